@@ -58,3 +58,13 @@ pub fn get_storage_var_address(storage_var_name: &str, args: &[StarkFelt]) -> St
 pub fn get_fee_token_var_address(contract_address: ContractAddress) -> StorageKey {
     get_storage_var_address("ERC20_balances", &[*contract_address.0.key()])
 }
+
+/// Returns the storage key inside the fee token corresponding to the first storage cell where the
+/// allowance the owner granted the spender is stored (in two consecutive storage cells, as the
+/// balance).
+pub fn get_fee_token_allowance_var_address(
+    owner: ContractAddress,
+    spender: ContractAddress,
+) -> StorageKey {
+    get_storage_var_address("ERC20_allowances", &[*owner.0.key(), *spender.0.key()])
+}
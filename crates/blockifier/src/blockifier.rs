@@ -1,4 +1,10 @@
 pub mod block;
+pub mod call_sandbox;
 pub mod config;
+pub mod hooks;
+pub mod priority;
+pub mod profile;
 pub mod stateful_validator;
 pub mod transaction_executor;
+pub mod validate_simulation;
+pub mod view_call;
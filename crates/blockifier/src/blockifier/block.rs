@@ -1,4 +1,6 @@
+use std::fmt::Debug;
 use std::num::NonZeroU128;
+use std::sync::Arc;
 
 use starknet_api::block::{BlockHash, BlockNumber, BlockTimestamp};
 use starknet_api::core::ContractAddress;
@@ -6,7 +8,9 @@ use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
 
 use crate::abi::constants;
-use crate::context::{BlockContext, ChainInfo};
+use crate::context::{BlockContext, ChainInfo, ExecutionPolicy, ExtensionHooks, FeePolicy};
+use crate::fee::gas_usage::{select_da_mode, L1DaMode, StateDiffSize};
+use crate::state::cached_state::StateChangesCount;
 use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateResult};
 use crate::transaction::objects::FeeType;
@@ -27,12 +31,32 @@ pub struct BlockInfo {
     pub use_kzg_da: bool,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct GasPrices {
     pub eth_l1_gas_price: NonZeroU128,       // In wei.
     pub strk_l1_gas_price: NonZeroU128,      // In fri.
     pub eth_l1_data_gas_price: NonZeroU128,  // In wei.
     pub strk_l1_data_gas_price: NonZeroU128, // In fri.
+    pub eth_l2_gas_price: NonZeroU128,       // In wei.
+    pub strk_l2_gas_price: NonZeroU128,      // In fri.
+}
+
+impl BlockInfo {
+    /// The mode the state diff of the block is published in on L1, which its transactions are
+    /// priced in.
+    pub fn da_mode(&self) -> L1DaMode {
+        L1DaMode::from_use_kzg_da(self.use_kzg_da)
+    }
+
+    /// Sets the DA mode of the block to the cheaper one for a state diff of the given expected size
+    /// (e.g., that of the previous block) at the block's gas prices, and returns it. Must be done
+    /// before the block is executed, as its transactions are priced in its mode.
+    pub fn select_da_mode(&mut self, expected_state_diff_size: &StateDiffSize) -> L1DaMode {
+        let state_changes_count = StateChangesCount::from(*expected_state_diff_size);
+        let da_mode = select_da_mode(&state_changes_count, &self.gas_prices);
+        self.use_kzg_da = da_mode.use_kzg_da();
+        da_mode
+    }
 }
 
 impl GasPrices {
@@ -49,11 +73,27 @@ impl GasPrices {
             FeeType::Eth => self.eth_l1_data_gas_price,
         }
     }
+
+    pub fn get_l2_gas_price_by_fee_type(&self, fee_type: &FeeType) -> NonZeroU128 {
+        match fee_type {
+            FeeType::Strk => self.strk_l2_gas_price,
+            FeeType::Eth => self.eth_l2_gas_price,
+        }
+    }
+}
+
+/// A source of gas prices, queried at the start of each block; e.g., a live L1 price feed.
+pub trait GasPriceOracle: Debug + Send + Sync {
+    /// Returns the gas prices for the given block. Implementations should not fail; if the
+    /// underlying feed is unavailable, they are expected to fall back to the last known prices.
+    fn gas_prices(&self, block_number: BlockNumber) -> GasPrices;
 }
 
 // Block pre-processing.
 // Writes the hash of the (current_block_number - N) block under its block number in the dedicated
 // contract state, where N=STORED_BLOCK_HASH_BUFFER.
+// If a gas price oracle is given, the gas prices of the block are taken from it, overriding the
+// ones in the given block info.
 // NOTE: This function must remain idempotent since full nodes can call it for an already updated
 // block hash table.
 pub fn pre_process_block(
@@ -63,6 +103,7 @@ pub fn pre_process_block(
     chain_info: ChainInfo,
     versioned_constants: VersionedConstants,
     concurrency_mode: bool,
+    gas_price_oracle: Option<Arc<dyn GasPriceOracle>>,
 ) -> StateResult<BlockContext> {
     let should_block_hash_be_provided =
         block_info.block_number >= BlockNumber(constants::STORED_BLOCK_HASH_BUFFER);
@@ -81,7 +122,18 @@ pub fn pre_process_block(
         return Err(StateError::OldBlockHashNotProvided);
     }
 
-    Ok(BlockContext { block_info, chain_info, versioned_constants, concurrency_mode })
+    let mut block_context = BlockContext {
+        block_info,
+        chain_info,
+        versioned_constants,
+        concurrency_mode,
+        fee_policy: FeePolicy::default(),
+        execution_policy: ExecutionPolicy::default(),
+        hooks: ExtensionHooks { gas_price_oracle, ..Default::default() },
+    };
+    block_context.refresh_gas_prices();
+
+    Ok(block_context)
 }
 
 pub struct BlockNumberHashPair {
@@ -1,15 +1,21 @@
+use std::num::NonZeroU128;
+use std::sync::Arc;
+
 use starknet_api::block::BlockNumber;
 use starknet_api::core::ContractAddress;
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
 
 use crate::abi::constants;
-use crate::blockifier::block::{pre_process_block, BlockInfo, BlockNumberHashPair};
+use crate::blockifier::block::{
+    pre_process_block, BlockInfo, BlockNumberHashPair, GasPriceOracle, GasPrices,
+};
 use crate::context::ChainInfo;
+use crate::fee::gas_usage::{L1DaMode, StateDiffSize};
 use crate::state::state_api::StateReader;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
-use crate::test_utils::{CairoVersion, BALANCE};
+use crate::test_utils::{CairoVersion, BALANCE, DEFAULT_ETH_L1_GAS_PRICE};
 use crate::versioned_constants::VersionedConstants;
 
 #[test]
@@ -30,6 +36,7 @@ fn test_pre_process_block() {
         ChainInfo::default(),
         VersionedConstants::default(),
         false,
+        None,
     )
     .unwrap();
 
@@ -43,17 +50,16 @@ fn test_pre_process_block() {
     // block number interval.
     let mut block_info = BlockInfo::create_for_testing();
     block_info.block_number = BlockNumber(constants::STORED_BLOCK_HASH_BUFFER - 1);
-    assert!(
-        pre_process_block(
-            &mut state,
-            None,
-            block_info,
-            ChainInfo::default(),
-            VersionedConstants::default(),
-            false,
-        )
-        .is_ok()
-    );
+    assert!(pre_process_block(
+        &mut state,
+        None,
+        block_info,
+        ChainInfo::default(),
+        VersionedConstants::default(),
+        false,
+        None,
+    )
+    .is_ok());
 
     let mut block_info = BlockInfo::create_for_testing();
     block_info.block_number = BlockNumber(constants::STORED_BLOCK_HASH_BUFFER);
@@ -64,6 +70,7 @@ fn test_pre_process_block() {
         ChainInfo::default(),
         VersionedConstants::default(),
         false,
+        None,
     );
     assert_eq!(
         format!(
@@ -73,3 +80,68 @@ fn test_pre_process_block() {
         format!("{}", error.unwrap_err())
     );
 }
+
+/// An oracle whose prices are derived from the block number.
+#[derive(Debug)]
+struct BlockNumberGasPriceOracle;
+
+impl GasPriceOracle for BlockNumberGasPriceOracle {
+    fn gas_prices(&self, block_number: BlockNumber) -> GasPrices {
+        let price = NonZeroU128::new(u128::from(block_number.0) + 1).unwrap();
+        GasPrices {
+            eth_l1_gas_price: price,
+            strk_l1_gas_price: price,
+            eth_l1_data_gas_price: price,
+            strk_l1_data_gas_price: price,
+            eth_l2_gas_price: price,
+            strk_l2_gas_price: price,
+        }
+    }
+}
+
+#[test]
+fn test_pre_process_block_with_gas_price_oracle() {
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[]);
+    let oracle = BlockNumberGasPriceOracle;
+
+    let mut block_info = BlockInfo::create_for_testing();
+    block_info.block_number = BlockNumber(1);
+    let static_gas_prices = block_info.gas_prices.clone();
+    let mut block_context = pre_process_block(
+        &mut state,
+        None,
+        block_info,
+        ChainInfo::default(),
+        VersionedConstants::default(),
+        false,
+        Some(Arc::new(BlockNumberGasPriceOracle)),
+    )
+    .unwrap();
+    assert_ne!(block_context.block_info().gas_prices, static_gas_prices);
+    assert_eq!(block_context.block_info().gas_prices, oracle.gas_prices(BlockNumber(1)));
+    assert_eq!(block_context.estimation_gas_prices(), oracle.gas_prices(BlockNumber(1)));
+
+    // Without an oracle, the static gas prices are used.
+    block_context.hooks_mut().gas_price_oracle = None;
+    block_context.block_info.gas_prices = static_gas_prices.clone();
+    block_context.refresh_gas_prices();
+    assert_eq!(block_context.block_info().gas_prices, static_gas_prices);
+    assert_eq!(block_context.estimation_gas_prices(), static_gas_prices);
+}
+
+#[test]
+fn test_select_da_mode() {
+    let state_diff_size =
+        StateDiffSize { n_modified_contracts: 3, n_storage_updates: 10, ..Default::default() };
+    let mut block_info = BlockInfo::create_for_testing_with_kzg(false);
+    assert_eq!(block_info.select_da_mode(&state_diff_size), L1DaMode::Blob);
+    assert!(block_info.use_kzg_da);
+
+    // Calldata is cheaper once blob space is in high demand.
+    block_info.gas_prices.eth_l1_data_gas_price =
+        NonZeroU128::new(100 * DEFAULT_ETH_L1_GAS_PRICE).unwrap();
+    assert_eq!(block_info.select_da_mode(&state_diff_size), L1DaMode::Calldata);
+    assert_eq!(block_info.da_mode(), L1DaMode::Calldata);
+    assert!(!block_info.use_kzg_da);
+}
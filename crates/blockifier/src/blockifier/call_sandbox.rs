@@ -0,0 +1,110 @@
+use std::thread;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+
+use crate::blockifier::view_call::{call_entry_point_with_overrides, ViewCall};
+use crate::context::BlockContext;
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point::{EntryPointExecutionResult, ExecutionInfoOverrides};
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::StateReader;
+
+#[cfg(test)]
+#[path = "call_sandbox_test.rs"]
+pub mod test;
+
+/// The results of a batch of view calls, in the order of the calls.
+#[derive(Debug, Default)]
+pub struct MulticallOutput {
+    pub results: Vec<EntryPointExecutionResult<CallInfo>>,
+    /// The VM resources consumed by the successful calls, including those of their inner calls.
+    pub resources: ExecutionResources,
+    /// The gas consumed by the successful calls.
+    pub gas_consumed: u64,
+}
+
+impl MulticallOutput {
+    fn new(results: Vec<EntryPointExecutionResult<CallInfo>>) -> Self {
+        let mut resources = ExecutionResources::default();
+        let mut gas_consumed = 0;
+        for call_info in results.iter().flatten() {
+            resources += &call_info.resources;
+            gas_consumed += call_info.execution.gas_consumed;
+        }
+
+        Self { results, resources, gas_consumed }
+    }
+}
+
+/// Executes view calls off-chain, on top of a fixed state snapshot and block context; e.g., for
+/// serving batches of `starknet_call` requests without the block-building machinery.
+/// The calls are independent: each runs on its own layer over the snapshot, and the changes it
+/// makes are discarded, so no call observes the writes of another.
+pub struct CallSandbox<S: StateReader> {
+    state: S,
+    block_context: BlockContext,
+}
+
+impl<S: StateReader> CallSandbox<S> {
+    pub fn new(state: S, block_context: BlockContext) -> Self {
+        Self { state, block_context }
+    }
+
+    pub fn block_context(&self) -> &BlockContext {
+        &self.block_context
+    }
+
+    /// Executes the given call; see [`crate::blockifier::view_call::call_entry_point`].
+    pub fn call(&self, call: ViewCall) -> EntryPointExecutionResult<CallInfo> {
+        self.call_with_overrides(
+            call,
+            self.block_context.execution_policy.execution_info_overrides.clone(),
+        )
+    }
+
+    /// Executes the given call, with the execution info seen by the executed contracts
+    /// overridden as given.
+    pub fn call_with_overrides(
+        &self,
+        call: ViewCall,
+        execution_info_overrides: ExecutionInfoOverrides,
+    ) -> EntryPointExecutionResult<CallInfo> {
+        let mut call_state = CachedState::new(&self.state);
+        call_entry_point_with_overrides(
+            &mut call_state,
+            &self.block_context,
+            call,
+            execution_info_overrides,
+        )
+    }
+
+    /// Executes the given calls one after the other.
+    pub fn multicall(&self, calls: Vec<ViewCall>) -> MulticallOutput {
+        MulticallOutput::new(calls.into_iter().map(|call| self.call(call)).collect())
+    }
+}
+
+impl<S: StateReader + Sync> CallSandbox<S> {
+    /// Executes the given calls in parallel, split evenly among the given number of worker
+    /// threads. The output is the same as that of [`Self::multicall`].
+    pub fn multicall_parallel(&self, calls: Vec<ViewCall>, n_workers: usize) -> MulticallOutput {
+        assert!(n_workers > 0, "At least one worker is required.");
+        let chunk_size = calls.len().div_ceil(n_workers).max(1);
+
+        let results: Vec<_> = thread::scope(|scope| {
+            let handles: Vec<_> = calls
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk.iter().map(|call| self.call(call.clone())).collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("View call execution panicked."))
+                .collect()
+        });
+        MulticallOutput::new(results)
+    }
+}
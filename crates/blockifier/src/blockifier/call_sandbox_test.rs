@@ -0,0 +1,71 @@
+use pretty_assertions::assert_eq;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::blockifier::call_sandbox::CallSandbox;
+use crate::blockifier::view_call::ViewCall;
+use crate::context::{BlockContext, ChainInfo};
+use crate::execution::call_info::Retdata;
+use crate::retdata;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state_reader;
+use crate::test_utils::{CairoVersion, BALANCE};
+
+#[test]
+fn test_multicall() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let state_reader = test_state_reader(chain_info, BALANCE, &[(test_contract, 1)]);
+    let sandbox = CallSandbox::new(state_reader, BlockContext::create_for_testing());
+
+    // All calls write to the same key; none observes the writes of the others.
+    let key = stark_felt!(1234_u16);
+    let storage_call = |value: StarkFelt| ViewCall {
+        contract_address: test_contract.get_instance_address(0),
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        calldata: calldata![key, value],
+    };
+    let failing_call = ViewCall {
+        entry_point_selector: selector_from_name("no_such_entry_point"),
+        ..storage_call(StarkFelt::ZERO)
+    };
+    let calls = vec![
+        storage_call(stark_felt!(1_u8)),
+        failing_call,
+        storage_call(stark_felt!(2_u8)),
+        storage_call(stark_felt!(3_u8)),
+    ];
+
+    let output = sandbox.multicall(calls.clone());
+    let retdata: Vec<_> = output
+        .results
+        .iter()
+        .map(|result| result.as_ref().ok().map(|call_info| call_info.execution.retdata.clone()))
+        .collect();
+    assert_eq!(
+        retdata,
+        vec![
+            Some(retdata![stark_felt!(1_u8)]),
+            None,
+            Some(retdata![stark_felt!(2_u8)]),
+            Some(retdata![stark_felt!(3_u8)])
+        ]
+    );
+
+    // The aggregates cover the successful calls.
+    let single_call_info = sandbox.call(storage_call(stark_felt!(1_u8))).unwrap();
+    assert_eq!(output.resources.n_steps, 3 * single_call_info.resources.n_steps);
+    assert_eq!(output.gas_consumed, 3 * single_call_info.execution.gas_consumed);
+
+    for n_workers in [1, 2, 8] {
+        let parallel_output = sandbox.multicall_parallel(calls.clone(), n_workers);
+        assert_eq!(parallel_output.results.len(), calls.len());
+        for (parallel_result, result) in parallel_output.results.iter().zip(&output.results) {
+            assert_eq!(parallel_result.as_ref().ok(), result.as_ref().ok());
+        }
+        assert_eq!(parallel_output.resources, output.resources);
+        assert_eq!(parallel_output.gas_consumed, output.gas_consumed);
+    }
+}
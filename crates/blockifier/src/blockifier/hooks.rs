@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use starknet_api::transaction::Fee;
+
+use crate::context::TransactionContext;
+use crate::execution::call_info::CallInfo;
+use crate::state::state_api::StateReader;
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
+use crate::transaction::transaction_execution::Transaction;
+
+/// Hooks invoked along the transaction execution path, e.g., for custom accounting,
+/// allow-listing, or metrics. The state passed to the hooks is the transactional state of the
+/// transaction, unless stated otherwise.
+/// Under partitioned and concurrent execution, the hooks are deferred until the transaction is
+/// committed, and are then invoked once, against the committed state (see `DeferredEffect`).
+/// All hooks are no-ops by default.
+pub trait TransactionExecutorHooks: Send + Sync {
+    /// Invoked before the validation of an account transaction, after the pre-validation checks.
+    /// Returning an error (e.g., `TransactionRejectedByHooks`) rejects the transaction.
+    fn before_validate(
+        &self,
+        _tx_context: &TransactionContext,
+        _state: &dyn StateReader,
+    ) -> TransactionExecutionResult<()> {
+        Ok(())
+    }
+
+    /// Invoked after the execution of an account transaction; `execute_call_info` is [None] if
+    /// the execution was reverted, or if the transaction has no execution (e.g., `Declare`).
+    fn after_execute(
+        &self,
+        _tx_context: &TransactionContext,
+        _state: &dyn StateReader,
+        _execute_call_info: Option<&CallInfo>,
+    ) {
+    }
+
+    /// Invoked before the fee transfer of an account transaction, with the fee to be charged.
+    fn before_fee_transfer(
+        &self,
+        _tx_context: &TransactionContext,
+        _state: &dyn StateReader,
+        _actual_fee: Fee,
+    ) {
+    }
+
+    /// Invoked after the state changes of a transaction were committed; the given state is the
+    /// state of the executor. `execution_duration` is the wall-clock duration of the transaction's
+    /// execution, including validation and fee transfer.
+    fn after_commit(
+        &self,
+        _tx: &Transaction,
+        _state: &dyn StateReader,
+        _tx_execution_info: &TransactionExecutionInfo,
+        _execution_duration: Duration,
+    ) {
+    }
+}
+
+/// Hooks that do nothing; used when no hooks are set.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopHooks;
+
+impl TransactionExecutorHooks for NoopHooks {}
@@ -0,0 +1,52 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use starknet_api::core::ContractAddress;
+
+use crate::transaction::objects::TransactionInfoCreator;
+use crate::transaction::transaction_execution::Transaction;
+
+/// The priority class of a transaction in block building; transactions of higher classes are
+/// executed first.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum TransactionPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// Returns the order in which the given transactions should be executed (as indices): by priority
+/// class, and then by their original order. The relative order of the transactions of each sender
+/// is preserved, so that their nonces are not reordered; hence, a transaction is not executed
+/// before lower-priority transactions that precede it and share its sender.
+pub fn priority_execution_order(
+    txs: &[Transaction],
+    priorities: &[TransactionPriority],
+) -> Vec<usize> {
+    assert_eq!(txs.len(), priorities.len(), "Each transaction must have a priority.");
+    let senders: Vec<ContractAddress> =
+        txs.iter().map(|tx| tx.create_tx_info().sender_address()).collect();
+    let mut sender_queues = HashMap::<ContractAddress, VecDeque<usize>>::new();
+    for (tx_index, sender_address) in senders.iter().enumerate() {
+        sender_queues.entry(*sender_address).or_default().push_back(tx_index);
+    }
+
+    // The next transaction of each sender, ordered by priority and then by index.
+    let mut next_txs: BinaryHeap<_> = sender_queues
+        .values_mut()
+        .filter_map(VecDeque::pop_front)
+        .map(|tx_index| (priorities[tx_index], Reverse(tx_index)))
+        .collect();
+    let mut execution_order = Vec::with_capacity(txs.len());
+    while let Some((_, Reverse(tx_index))) = next_txs.pop() {
+        execution_order.push(tx_index);
+        let sender_queue =
+            sender_queues.get_mut(&senders[tx_index]).expect("Sender queue must exist.");
+        if let Some(next_tx_index) = sender_queue.pop_front() {
+            next_txs.push((priorities[next_tx_index], Reverse(next_tx_index)));
+        }
+    }
+
+    execution_order
+}
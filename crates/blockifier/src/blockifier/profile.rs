@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use starknet_api::core::{ClassHash, EntryPointSelector};
+
+use crate::execution::call_info::CallInfo;
+use crate::transaction::objects::TransactionExecutionInfo;
+
+#[cfg(test)]
+#[path = "profile_test.rs"]
+pub mod test;
+
+/// The resources consumed by a set of calls, exclusive of their inner calls.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CallsUsage {
+    pub n_calls: usize,
+    pub n_steps: usize,
+    pub sierra_gas: u64,
+}
+
+impl CallsUsage {
+    fn add_call(&mut self, call_info: &CallInfo) {
+        self.n_calls += 1;
+        self.n_steps += call_info.exclusive_resources().n_steps;
+        self.sierra_gas += call_info.exclusive_gas_consumed();
+    }
+}
+
+/// The resources consumed by the calls to a class, overall and by entry point.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ClassProfile {
+    pub usage: CallsUsage,
+    pub usage_by_selector: HashMap<EntryPointSelector, CallsUsage>,
+}
+
+impl ClassProfile {
+    /// Returns the (at most) `n` entry points of the class that consumed the most steps, in
+    /// descending order.
+    pub fn top_selectors(&self, n: usize) -> Vec<(EntryPointSelector, CallsUsage)> {
+        let mut selectors: Vec<_> =
+            self.usage_by_selector.iter().map(|(selector, usage)| (*selector, *usage)).collect();
+        selectors.sort_by(|(_, left), (_, right)| right.n_steps.cmp(&left.n_steps));
+        selectors.truncate(n);
+        selectors
+    }
+}
+
+/// Aggregates the resources consumed by the executed calls of a block by class hash; e.g., for
+/// sequencer operators to find the contracts that consume their block space. Each call is
+/// accounted for exclusive of its inner calls, so that the resources of the classes add up.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BlockProfile {
+    /// The profiles of the executed classes; calls that were not executed (e.g., mocked calls)
+    /// are under [None].
+    pub classes: HashMap<Option<ClassHash>, ClassProfile>,
+}
+
+impl BlockProfile {
+    pub fn new<'a>(
+        tx_execution_infos: impl IntoIterator<Item = &'a TransactionExecutionInfo>,
+    ) -> Self {
+        let mut profile = Self::default();
+        for tx_execution_info in tx_execution_infos {
+            profile.add_tx(tx_execution_info);
+        }
+        profile
+    }
+
+    /// Adds the calls of the given transaction, including its validation and fee transfer, to the
+    /// profile.
+    pub fn add_tx(&mut self, tx_execution_info: &TransactionExecutionInfo) {
+        for call_info in tx_execution_info.non_optional_call_infos().flat_map(CallInfo::iter) {
+            let class_profile = self.classes.entry(call_info.call.class_hash).or_default();
+            class_profile.usage.add_call(call_info);
+            class_profile
+                .usage_by_selector
+                .entry(call_info.call.entry_point_selector)
+                .or_default()
+                .add_call(call_info);
+        }
+    }
+
+    /// Returns the (at most) `n` classes that consumed the most steps, in descending order.
+    pub fn top_classes(&self, n: usize) -> Vec<(Option<ClassHash>, &ClassProfile)> {
+        let mut classes: Vec<_> =
+            self.classes.iter().map(|(class_hash, profile)| (*class_hash, profile)).collect();
+        classes.sort_by(|(_, left), (_, right)| right.usage.n_steps.cmp(&left.usage.n_steps));
+        classes.truncate(n);
+        classes
+    }
+}
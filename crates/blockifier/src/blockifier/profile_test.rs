@@ -0,0 +1,72 @@
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use starknet_api::core::{ClassHash, EntryPointSelector};
+use starknet_api::hash::StarkHash;
+
+use crate::blockifier::profile::{BlockProfile, CallsUsage};
+use crate::execution::call_info::{CallExecution, CallInfo};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::transaction::objects::TransactionExecutionInfo;
+
+fn call_info(
+    class_hash: u8,
+    selector: u8,
+    n_steps: usize,
+    gas_consumed: u64,
+    inner_calls: Vec<CallInfo>,
+) -> CallInfo {
+    CallInfo {
+        call: CallEntryPoint {
+            class_hash: Some(ClassHash(StarkHash::from(class_hash))),
+            entry_point_selector: EntryPointSelector(StarkHash::from(selector)),
+            ..Default::default()
+        },
+        execution: CallExecution { gas_consumed, ..Default::default() },
+        resources: ExecutionResources { n_steps, ..Default::default() },
+        inner_calls,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_block_profile() {
+    // The account (class 1) validates, and calls a contract (class 2) twice; its calls consume 100
+    // steps and 1000 gas exclusive of the inner calls.
+    let tx_execution_info = TransactionExecutionInfo {
+        validate_call_info: Some(call_info(1, 1, 50, 500, vec![])),
+        execute_call_info: Some(call_info(
+            1,
+            2,
+            350,
+            3500,
+            vec![call_info(2, 3, 200, 2000, vec![]), call_info(2, 4, 100, 1000, vec![])],
+        )),
+        ..Default::default()
+    };
+
+    let profile = BlockProfile::new([&tx_execution_info, &tx_execution_info]);
+
+    let top_classes = profile.top_classes(1);
+    assert_eq!(top_classes.len(), 1);
+    let (class_hash, class_profile) = top_classes[0];
+    assert_eq!(class_hash, Some(ClassHash(StarkHash::from(2_u8))));
+    assert_eq!(class_profile.usage, CallsUsage { n_calls: 4, n_steps: 600, sierra_gas: 6000 });
+    assert_eq!(
+        class_profile.top_selectors(2),
+        vec![
+            (
+                EntryPointSelector(StarkHash::from(3_u8)),
+                CallsUsage { n_calls: 2, n_steps: 400, sierra_gas: 4000 }
+            ),
+            (
+                EntryPointSelector(StarkHash::from(4_u8)),
+                CallsUsage { n_calls: 2, n_steps: 200, sierra_gas: 2000 }
+            ),
+        ]
+    );
+
+    let account_profile = &profile.classes[&Some(ClassHash(StarkHash::from(1_u8)))];
+    assert_eq!(account_profile.usage, CallsUsage { n_calls: 4, n_steps: 200, sierra_gas: 2000 });
+    assert_eq!(account_profile.usage_by_selector.len(), 2);
+    assert_eq!(profile.classes.len(), 2);
+}
@@ -1,4 +1,7 @@
-use starknet_api::core::Nonce;
+use std::sync::Arc;
+
+use cairo_felt::Felt252;
+use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
 use starknet_api::transaction::TransactionHash;
 use thiserror::Error;
@@ -8,14 +11,18 @@ use crate::blockifier::transaction_executor::{TransactionExecutor, TransactionEx
 use crate::bouncer::BouncerConfig;
 use crate::context::{BlockContext, TransactionContext};
 use crate::execution::call_info::CallInfo;
+use crate::execution::execution_utils::stark_felt_to_felt;
 use crate::fee::actual_cost::TransactionReceipt;
 use crate::fee::fee_checks::PostValidationReport;
 use crate::state::cached_state::CachedState;
 use crate::state::errors::StateError;
 use crate::state::state_api::StateReader;
 use crate::transaction::account_transaction::AccountTransaction;
-use crate::transaction::errors::{TransactionExecutionError, TransactionPreValidationError};
+use crate::transaction::errors::{
+    OutsideExecutionError, TransactionExecutionError, TransactionPreValidationError,
+};
 use crate::transaction::objects::TransactionInfo;
+use crate::transaction::outside_execution::OutsideExecution;
 use crate::transaction::transaction_execution::Transaction;
 
 #[cfg(test)]
@@ -24,6 +31,18 @@ pub mod stateful_validator_test;
 
 #[derive(Debug, Error)]
 pub enum StatefulValidatorError {
+    #[error(
+        "Transaction nonce of contract at address {address:?} is too far ahead. Account nonce: \
+         {account_nonce:?}; got: {incoming_tx_nonce:?}; max gap: {max_gap}."
+    )]
+    NonceGapTooLarge {
+        address: ContractAddress,
+        account_nonce: Nonce,
+        incoming_tx_nonce: Nonce,
+        max_gap: u64,
+    },
+    #[error(transparent)]
+    OutsideExecutionError(#[from] OutsideExecutionError),
     #[error(transparent)]
     StateError(#[from] StateError),
     #[error(transparent)]
@@ -36,10 +55,23 @@ pub enum StatefulValidatorError {
 
 pub type StatefulValidatorResult<T> = Result<T, StatefulValidatorError>;
 
+/// The status of a transaction that passed the stateful validations.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ValidationStatus {
+    /// The transaction nonce is the account nonce; the transaction can be executed now.
+    Executable,
+    /// The transaction nonce is ahead of the account nonce; the transaction can only be executed
+    /// after the preceding transactions of the account.
+    Future,
+}
+
 /// Manages state related transaction validations for pre-execution flows.
 pub struct StatefulValidator<S: StateReader> {
     tx_executor: TransactionExecutor<S>,
     max_nonce_for_validation_skip: Nonce,
+    // The maximal distance of a future transaction nonce from the account nonce; [None] for no
+    // limit.
+    max_future_nonce_gap: Option<u64>,
 }
 
 impl<S: StateReader> StatefulValidator<S> {
@@ -47,6 +79,7 @@ impl<S: StateReader> StatefulValidator<S> {
         state: CachedState<S>,
         block_context: BlockContext,
         max_nonce_for_validation_skip: Nonce,
+        max_future_nonce_gap: Option<u64>,
         bouncer_config: BouncerConfig,
     ) -> Self {
         let tx_executor = TransactionExecutor::new(
@@ -55,26 +88,27 @@ impl<S: StateReader> StatefulValidator<S> {
             bouncer_config,
             TransactionExecutorConfig::default(),
         );
-        Self { tx_executor, max_nonce_for_validation_skip }
+        Self { tx_executor, max_nonce_for_validation_skip, max_future_nonce_gap }
     }
 
     pub fn perform_validations(
         &mut self,
         tx: AccountTransaction,
         deploy_account_tx_hash: Option<TransactionHash>,
-    ) -> StatefulValidatorResult<()> {
+    ) -> StatefulValidatorResult<ValidationStatus> {
         // Deploy account transactions should be fully executed, since the constructor must run
         // before `__validate_deploy__`. The execution already includes all necessary validations,
         // so they are skipped here.
         if let AccountTransaction::DeployAccount(_) = tx {
             self.execute(tx)?;
-            return Ok(());
+            return Ok(ValidationStatus::Executable);
         }
 
         // First, we check if the transaction should be skipped due to the deploy account not being
         // processed. It is done before the pre-validations checks because, in these checks, we
         // change the state (more precisely, we increment the nonce).
         let tx_context = self.tx_executor.block_context.to_tx_context(&tx);
+        let status = self.get_validation_status(&tx_context.tx_info)?;
         let skip_validate = self.skip_validate_due_to_unprocessed_deploy_account(
             &tx_context.tx_info,
             deploy_account_tx_hash,
@@ -82,18 +116,46 @@ impl<S: StateReader> StatefulValidator<S> {
         self.perform_pre_validation_stage(&tx, &tx_context)?;
 
         if skip_validate {
-            return Ok(());
+            return Ok(status);
         }
 
         // `__validate__` call.
-        let versioned_constants = &tx_context.block_context.versioned_constants();
-        let (_optional_call_info, actual_cost) =
-            self.validate(&tx, versioned_constants.tx_initial_gas())?;
+        let (_optional_call_info, actual_cost) = self.validate(&tx, tx_context.initial_gas())?;
 
         // Post validations.
         PostValidationReport::verify(&tx_context, &actual_cost)?;
 
-        Ok(())
+        self.validate_outside_executions(&tx, tx_context)?;
+
+        Ok(status)
+    }
+
+    /// Re-checks a previously validated transaction against the current state: its nonce, the
+    /// viability of its resource bounds and the sender's ability to pay for them.
+    /// Unlike [`Self::perform_validations`], `__validate__` is not run again and the state is not
+    /// modified; intended for periodic re-validation of pending mempool transactions.
+    pub fn revalidate(
+        &mut self,
+        tx: &AccountTransaction,
+    ) -> StatefulValidatorResult<ValidationStatus> {
+        let tx_context = self.tx_executor.block_context.to_tx_context(tx);
+        let status = self.get_validation_status(&tx_context.tx_info)?;
+
+        // The pre-validation stage increments the account nonce; discard its effects.
+        let mut transactional_state =
+            CachedState::create_transactional(&mut self.tx_executor.state);
+        let strict_nonce_check = false;
+        let charge_fee = true;
+        let pre_validation_result = tx.perform_pre_validation_stage(
+            &mut transactional_state,
+            &tx_context,
+            charge_fee,
+            strict_nonce_check,
+        );
+        transactional_state.abort();
+        pre_validation_result?;
+
+        Ok(status)
     }
 
     fn execute(&mut self, tx: AccountTransaction) -> StatefulValidatorResult<()> {
@@ -119,6 +181,37 @@ impl<S: StateReader> StatefulValidator<S> {
         Ok(())
     }
 
+    /// Classifies the transaction by its nonce, relative to the account nonce. Must be called
+    /// before the pre-validation stage, which increments the account nonce.
+    /// Transactions that are ahead of the account nonce by more than the configured gap are
+    /// rejected; stale nonces are left for the pre-validation stage to reject.
+    fn get_validation_status(
+        &mut self,
+        tx_info: &TransactionInfo,
+    ) -> StatefulValidatorResult<ValidationStatus> {
+        let address = tx_info.sender_address();
+        let account_nonce = self.tx_executor.state.get_nonce_at(address)?;
+        let incoming_tx_nonce = tx_info.nonce();
+        if incoming_tx_nonce <= account_nonce {
+            return Ok(ValidationStatus::Executable);
+        }
+
+        if let Some(max_gap) = self.max_future_nonce_gap {
+            let nonce_gap =
+                stark_felt_to_felt(incoming_tx_nonce.0) - stark_felt_to_felt(account_nonce.0);
+            if nonce_gap > Felt252::from(max_gap) {
+                return Err(StatefulValidatorError::NonceGapTooLarge {
+                    address,
+                    account_nonce,
+                    incoming_tx_nonce,
+                    max_gap,
+                });
+            }
+        }
+
+        Ok(ValidationStatus::Future)
+    }
+
     // Check if deploy account was submitted but not processed yet. If so, then skip
     // `__validate__` method for subsequent transactions for a better user experience.
     // (they will otherwise fail solely because the deploy account hasn't been processed yet).
@@ -143,6 +236,37 @@ impl<S: StateReader> StatefulValidator<S> {
         Ok(skip_validate)
     }
 
+    /// Validates the outside execution (SNIP-9) requests relayed by the transaction, on behalf of
+    /// signers other than the sender. Their nonces are checked against the signers' outside
+    /// execution nonce channel, which is independent of the protocol nonce.
+    fn validate_outside_executions(
+        &mut self,
+        tx: &AccountTransaction,
+        tx_context: TransactionContext,
+    ) -> StatefulValidatorResult<()> {
+        let AccountTransaction::Invoke(invoke_tx) = tx else {
+            return Ok(());
+        };
+        let outside_executions = OutsideExecution::extract_from_calldata(&invoke_tx.calldata());
+        if outside_executions.is_empty() {
+            return Ok(());
+        }
+
+        let relayer_address = tx_context.tx_info.sender_address();
+        let block_timestamp = tx_context.block_context.block_info().block_timestamp;
+        let tx_context = Arc::new(tx_context);
+        // Nonce checks run view calls on the signer accounts; discard their effects.
+        let mut transactional_state =
+            CachedState::create_transactional(&mut self.tx_executor.state);
+        for outside_execution in outside_executions {
+            outside_execution.verify_context(relayer_address, block_timestamp)?;
+            outside_execution.verify_nonce(&mut transactional_state, tx_context.clone())?;
+        }
+        transactional_state.abort();
+
+        Ok(())
+    }
+
     fn validate(
         &mut self,
         tx: &AccountTransaction,
@@ -1,16 +1,20 @@
+use assert_matches::assert_matches;
 use rstest::rstest;
 use starknet_api::transaction::{Fee, TransactionVersion};
 
-use crate::blockifier::stateful_validator::StatefulValidator;
+use crate::blockifier::stateful_validator::{
+    StatefulValidator, StatefulValidatorError, ValidationStatus,
+};
 use crate::bouncer::BouncerConfig;
 use crate::context::BlockContext;
 use crate::nonce;
+use crate::state::state_api::State;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::{fund_account, test_state};
 use crate::test_utils::{CairoVersion, NonceManager, BALANCE};
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::test_utils::{
-    block_context, create_account_tx_for_validate_test, FaultyAccountTxCreatorArgs, VALID,
+    block_context, create_account_tx_for_validate_test, FaultyAccountTxCreatorArgs, INVALID, VALID,
 };
 use crate::transaction::transaction_types::TransactionType;
 
@@ -69,9 +73,109 @@ fn test_transaction_validator(
         state,
         block_context,
         nonce!(0_u32),
+        None,
         BouncerConfig::create_for_testing(),
     );
 
     let reuslt = stateful_validator.perform_validations(tx, None);
     assert!(reuslt.is_ok(), "Validation failed: {:?}", reuslt.unwrap_err());
 }
+
+#[rstest]
+#[case::executable(0, Some(ValidationStatus::Executable))]
+#[case::future(2, Some(ValidationStatus::Future))]
+#[case::nonce_gap_too_large(3, None)]
+fn test_future_nonce_validation(
+    #[case] nonce_gap: u64,
+    #[case] expected_status: Option<ValidationStatus>,
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let max_future_nonce_gap = 2;
+    let faulty_account = FeatureContract::FaultyAccount(cairo_version);
+    let sender_address = faulty_account.get_instance_address(0);
+    let state = test_state(&block_context.chain_info, BALANCE, &[(faulty_account, 1)]);
+
+    // Skip the nonces in the gap.
+    let nonce_manager = &mut NonceManager::default();
+    for _ in 0..nonce_gap {
+        nonce_manager.next(sender_address);
+    }
+    let tx = create_account_tx_for_validate_test(
+        nonce_manager,
+        FaultyAccountTxCreatorArgs {
+            scenario: VALID,
+            sender_address,
+            max_fee: Fee(BALANCE),
+            ..Default::default()
+        },
+    );
+
+    let mut stateful_validator = StatefulValidator::create(
+        state,
+        block_context,
+        nonce!(0_u32),
+        Some(max_future_nonce_gap),
+        BouncerConfig::create_for_testing(),
+    );
+
+    let result = stateful_validator.perform_validations(tx, None);
+    match expected_status {
+        Some(expected_status) => assert_eq!(result.unwrap(), expected_status),
+        None => assert_matches!(
+            result.unwrap_err(),
+            StatefulValidatorError::NonceGapTooLarge { max_gap, .. }
+            if max_gap == max_future_nonce_gap
+        ),
+    }
+}
+
+#[rstest]
+#[case::executable(0, BALANCE, None)]
+#[case::stale_nonce(1, BALANCE, Some("Invalid transaction nonce"))]
+#[case::insufficient_balance(0, BALANCE - 1, Some("exceeds balance"))]
+fn test_revalidate(
+    #[case] account_nonce: u64,
+    #[case] account_balance: u128,
+    #[case] expected_error: Option<&str>,
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let faulty_account = FeatureContract::FaultyAccount(cairo_version);
+    let sender_address = faulty_account.get_instance_address(0);
+    let mut state = test_state(&block_context.chain_info, account_balance, &[(faulty_account, 1)]);
+    for _ in 0..account_nonce {
+        state.increment_nonce(sender_address).unwrap();
+    }
+
+    // `__validate__` is not run, hence, a transaction failing it passes re-validation.
+    let tx = create_account_tx_for_validate_test(
+        &mut NonceManager::default(),
+        FaultyAccountTxCreatorArgs {
+            tx_version: TransactionVersion::ONE,
+            scenario: INVALID,
+            sender_address,
+            max_fee: Fee(BALANCE),
+            ..Default::default()
+        },
+    );
+
+    let mut stateful_validator = StatefulValidator::create(
+        state,
+        block_context,
+        nonce!(0_u32),
+        None,
+        BouncerConfig::create_for_testing(),
+    );
+
+    // Re-validation does not modify the state, hence, it can be repeated.
+    for _ in 0..2 {
+        let result = stateful_validator.revalidate(&tx);
+        match expected_error {
+            None => assert_eq!(result.unwrap(), ValidationStatus::Executable),
+            Some(expected_error) => {
+                assert!(result.unwrap_err().to_string().contains(expected_error))
+            }
+        }
+    }
+}
@@ -1,24 +1,43 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use itertools::FoldWhile::{Continue, Done};
 use itertools::Itertools;
 use starknet_api::core::ClassHash;
+#[cfg(feature = "concurrency")]
+use starknet_api::core::ContractAddress;
+use starknet_api::transaction::Fee;
 use thiserror::Error;
 
 use crate::blockifier::config::TransactionExecutorConfig;
+use crate::blockifier::hooks::{NoopHooks, TransactionExecutorHooks};
+use crate::blockifier::priority::{priority_execution_order, TransactionPriority};
 use crate::bouncer::{Bouncer, BouncerConfig};
+#[cfg(feature = "concurrency")]
+use crate::concurrency::fee_utils::complete_fee_transfer;
+#[cfg(feature = "concurrency")]
+use crate::concurrency::partitioned_execution::{
+    execute_partitions, partition_by_footprint, replay_deferred_effects, DeferredEffect,
+    PartitionedTxOutput,
+};
 use crate::context::BlockContext;
 use crate::execution::call_info::CallInfo;
 use crate::fee::actual_cost::TransactionReceipt;
+use crate::fee::gas_usage::L1DaMode;
+#[cfg(feature = "concurrency")]
+use crate::state::cached_state::MutRefState;
 use crate::state::cached_state::{CachedState, CommitmentStateDiff};
 use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateReader};
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::errors::TransactionExecutionError;
-use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::objects::{
+    FeeType, GasVector, HasRelatedFeeType, TransactionExecutionInfo, TransactionInfoCreator,
+    TransactionMetadata,
+};
 use crate::transaction::transaction_execution::Transaction;
-use crate::transaction::transactions::{ExecutableTransaction, ValidatableTransaction};
+use crate::transaction::transactions::ValidatableTransaction;
 
 #[cfg(test)]
 #[path = "transaction_executor_test.rs"]
@@ -37,12 +56,73 @@ pub enum TransactionExecutorError {
 pub type TransactionExecutorResult<T> = Result<T, TransactionExecutorError>;
 pub type VisitedSegmentsMapping = Vec<(ClassHash, Vec<usize>)>;
 
+/// The outcome of a block execution.
+#[derive(Debug)]
+pub struct BlockExecutionSummary {
+    pub state_diff: CommitmentStateDiff,
+    pub visited_segments: VisitedSegmentsMapping,
+    /// The estimated VM resources for the OS to verify the compiled class hashes of the executed
+    /// Cairo 1 classes, of which only the visited segments are loaded.
+    pub casm_hash_computation_resources: ExecutionResources,
+    /// The mode the block's state diff is published in on L1, which its transactions were priced
+    /// in; see [`crate::blockifier::block::BlockInfo::select_da_mode`].
+    pub da_mode: L1DaMode,
+    pub block_totals: BlockTotals,
+}
+
+/// The fees collected and the gas consumed by the transactions of a block.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BlockTotals {
+    /// The fees collected in ETH, in Wei.
+    pub eth_fee: Fee,
+    /// The fees collected in STRK, in Fri.
+    pub strk_fee: Fee,
+    pub gas: GasVector,
+}
+
+impl BlockTotals {
+    /// Returns the totals including the given transaction; saturates on overflow.
+    pub fn add_tx(&self, fee_type: &FeeType, tx_execution_info: &TransactionExecutionInfo) -> Self {
+        let add_fee = |total: Fee| Fee(total.0.saturating_add(tx_execution_info.actual_fee.0));
+        let (eth_fee, strk_fee) = match fee_type {
+            FeeType::Eth => (add_fee(self.eth_fee), self.strk_fee),
+            FeeType::Strk => (self.eth_fee, add_fee(self.strk_fee)),
+        };
+        let tx_gas = tx_execution_info.gas;
+        let gas = GasVector {
+            l1_gas: self.gas.l1_gas.saturating_add(tx_gas.l1_gas),
+            l1_data_gas: self.gas.l1_data_gas.saturating_add(tx_gas.l1_data_gas),
+            l2_gas: self.gas.l2_gas.saturating_add(tx_gas.l2_gas),
+        };
+
+        Self { eth_fee, strk_fee, gas }
+    }
+
+    /// Returns whether each of the totals is within the corresponding total of the given cap.
+    pub fn is_within(&self, cap: &Self) -> bool {
+        self.eth_fee <= cap.eth_fee
+            && self.strk_fee <= cap.strk_fee
+            && self.gas.l1_gas <= cap.gas.l1_gas
+            && self.gas.l1_data_gas <= cap.gas.l1_data_gas
+            && self.gas.l2_gas <= cap.gas.l2_gas
+    }
+}
+
 // TODO(Gilad): make this hold TransactionContext instead of BlockContext.
 pub struct TransactionExecutor<S: StateReader> {
     pub block_context: BlockContext,
     pub bouncer: Bouncer,
     // Note: this config must not affect the execution result (e.g. state diff and traces).
     pub config: TransactionExecutorConfig,
+    pub hooks: Arc<dyn TransactionExecutorHooks>,
+    // The number of transactions committed so far; i.e., the index of the next transaction in the
+    // block.
+    pub n_committed_txs: usize,
+    // The fees and gas of the transactions committed so far.
+    pub block_totals: BlockTotals,
+    // If set, transactions that would bring the block totals over the cap are not included in the
+    // block.
+    pub block_totals_cap: Option<BlockTotals>,
 
     // State-related fields.
     pub state: CachedState<S>,
@@ -58,8 +138,16 @@ impl<S: StateReader> TransactionExecutor<S> {
         log::debug!("Initializing Transaction Executor...");
         // Note: the state might not be empty even at this point; it is the creator's
         // responsibility to tune the bouncer according to pre and post block process.
-        let tx_executor =
-            Self { block_context, bouncer: Bouncer::new(bouncer_config), config, state };
+        let tx_executor = Self {
+            block_context,
+            bouncer: Bouncer::new(bouncer_config),
+            config,
+            hooks: Arc::new(NoopHooks),
+            n_committed_txs: 0,
+            block_totals: BlockTotals::default(),
+            block_totals_cap: None,
+            state,
+        };
         log::debug!("Initialized Transaction Executor.");
 
         tx_executor
@@ -67,25 +155,55 @@ impl<S: StateReader> TransactionExecutor<S> {
 
     /// Executes the given transaction on the state maintained by the executor.
     /// Returns the execution result (info or error) if there is room for the transaction;
-    /// Otherwise, returns BlockFull error.
+    /// Otherwise, returns BlockFull error. The transaction is not executed if the block cannot fit
+    /// any transaction.
     pub fn execute(
         &mut self,
         tx: &Transaction,
         charge_fee: bool,
     ) -> TransactionExecutorResult<TransactionExecutionInfo> {
+        if self.bouncer.is_full() {
+            log::debug!(
+                "Block is full; no transaction can fit it. Block weights: {:?}.",
+                self.bouncer.get_accumulated_weights()
+            );
+            return Err(TransactionExecutorError::BlockFull);
+        }
+
         let mut transactional_state = CachedState::create_transactional(&mut self.state);
         let validate = true;
 
-        let tx_execution_result =
-            tx.execute_raw(&mut transactional_state, &self.block_context, charge_fee, validate);
+        let execution_start = Instant::now();
+        let tx_execution_result = tx.execute_raw_with_hooks(
+            &mut transactional_state,
+            &self.block_context,
+            charge_fee,
+            validate,
+            self.hooks.as_ref(),
+        );
+        let execution_duration = execution_start.elapsed();
         match tx_execution_result {
-            Ok(tx_execution_info) => {
+            Ok(mut tx_execution_info) => {
+                let block_totals = Self::block_totals_with(
+                    &self.block_totals,
+                    self.block_totals_cap.as_ref(),
+                    tx,
+                    &tx_execution_info,
+                )?;
                 self.bouncer.try_update(
                     &mut transactional_state,
                     &tx_execution_info.summarize(),
                     &tx_execution_info.actual_resources,
                 )?;
                 transactional_state.commit();
+                self.block_totals = block_totals;
+                tx_execution_info.metadata = Some(TransactionMetadata {
+                    tx_hash: tx.create_tx_info().transaction_hash(),
+                    tx_index: self.n_committed_txs,
+                    execution_duration,
+                });
+                self.n_committed_txs += 1;
+                self.hooks.after_commit(tx, &self.state, &tx_execution_info, execution_duration);
                 Ok(tx_execution_info)
             }
             Err(error) => {
@@ -146,6 +264,47 @@ impl<S: StateReader> TransactionExecutor<S> {
         results
     }
 
+    /// Executes the given transactions by their priority classes (see
+    /// [`priority_execution_order`]), preserving the nonce order of each sender.
+    /// Stops if and when there is no more room in the block, and returns the executed transactions'
+    /// results in execution order, along with their indices in the given transactions.
+    pub fn execute_txs_by_priority(
+        &mut self,
+        txs: &[Transaction],
+        priorities: &[TransactionPriority],
+        charge_fee: bool,
+    ) -> Vec<(usize, TransactionExecutorResult<TransactionExecutionInfo>)> {
+        let mut results = Vec::new();
+        for tx_index in priority_execution_order(txs, priorities) {
+            match self.execute(&txs[tx_index], charge_fee) {
+                Err(TransactionExecutorError::BlockFull) => break,
+                result => results.push((tx_index, result)),
+            }
+        }
+        results
+    }
+
+    /// Returns the block totals including the given executed transaction; fails if they exceed
+    /// the cap.
+    // Takes the totals and the cap rather than `self`, as it is called while the state is borrowed
+    // by a transactional state.
+    fn block_totals_with(
+        block_totals: &BlockTotals,
+        block_totals_cap: Option<&BlockTotals>,
+        tx: &Transaction,
+        tx_execution_info: &TransactionExecutionInfo,
+    ) -> TransactionExecutorResult<BlockTotals> {
+        let fee_type = tx.create_tx_info().fee_type();
+        let new_block_totals = block_totals.add_tx(&fee_type, tx_execution_info);
+        match block_totals_cap {
+            Some(cap) if !new_block_totals.is_within(cap) => {
+                log::debug!("Block totals cap reached. Block totals: {:?}.", block_totals);
+                Err(TransactionExecutorError::BlockFull)
+            }
+            _ => Ok(new_block_totals),
+        }
+    }
+
     pub fn validate(
         &mut self,
         account_tx: &AccountTransaction,
@@ -188,20 +347,173 @@ impl<S: StateReader> TransactionExecutor<S> {
     pub fn finalize(
         &mut self,
     ) -> TransactionExecutorResult<(CommitmentStateDiff, VisitedSegmentsMapping)> {
+        let BlockExecutionSummary { state_diff, visited_segments, .. } =
+            self.finalize_with_summary()?;
+        Ok((state_diff, visited_segments))
+    }
+
+    /// Same as `finalize`, also returning a summary of the block.
+    pub fn finalize_with_summary(&mut self) -> TransactionExecutorResult<BlockExecutionSummary> {
         // Get the visited segments of each contract class.
         // This is done by taking all the visited PCs of each contract, and compress them to one
         // representative for each visited segment.
-        let visited_segments = self
-            .state
-            .visited_pcs
-            .iter()
-            .map(|(class_hash, class_visited_pcs)| -> TransactionExecutorResult<_> {
-                let contract_class = self.state.get_compiled_contract_class(*class_hash)?;
-                Ok((*class_hash, contract_class.get_visited_segments(class_visited_pcs)?))
-            })
-            .collect::<TransactionExecutorResult<_>>()?;
+        let mut visited_segments = Vec::new();
+        let mut casm_hash_computation_resources = ExecutionResources::default();
+        for (class_hash, class_visited_pcs) in &self.state.visited_pcs {
+            let contract_class = self.state.get_compiled_contract_class(*class_hash)?;
+            visited_segments
+                .push((*class_hash, contract_class.get_visited_segments(class_visited_pcs)?));
+            casm_hash_computation_resources +=
+                &contract_class.estimate_visited_casm_hash_computation_resources(class_visited_pcs)?;
+        }
 
         log::debug!("Final block weights: {:?}.", self.bouncer.get_accumulated_weights());
-        Ok((self.state.to_state_diff(), visited_segments))
+        Ok(BlockExecutionSummary {
+            state_diff: self.state.to_state_diff(),
+            visited_segments,
+            casm_hash_computation_resources,
+            da_mode: self.block_context.block_info.da_mode(),
+            block_totals: self.block_totals,
+        })
+    }
+}
+
+#[cfg(feature = "concurrency")]
+impl<S: StateReader + Send> TransactionExecutor<S> {
+    /// Executes the given transactions, given that transactions with disjoint footprints are
+    /// independent, where the footprint of a transaction is its sender along with its declared
+    /// targets, i.e., the contracts it is expected to access. Independent partitions of the
+    /// transactions are executed fully in parallel, bypassing the optimistic scheduler, and their
+    /// results are committed in order.
+    /// The independence of the partitions is verified against their actual state accesses; if it
+    /// does not hold, the transactions are executed sequentially instead. Likewise, if a
+    /// transaction that was executed within its partition fails to be committed (e.g., it is
+    /// rejected by the hooks or by the bouncer), the later transactions, which may have been
+    /// executed on top of its changes, are executed sequentially.
+    /// The hooks and the event sink only observe the committed execution of each transaction.
+    pub fn execute_txs_partitioned(
+        &mut self,
+        txs: &[Transaction],
+        declared_targets: &[Vec<ContractAddress>],
+        charge_fee: bool,
+    ) -> Vec<TransactionExecutorResult<TransactionExecutionInfo>> {
+        assert_eq!(txs.len(), declared_targets.len(), "Each transaction must declare its targets.");
+        let partitions = partition_by_footprint(txs, declared_targets);
+        if partitions.len() <= 1 {
+            return self.execute_txs_sequentially(txs, charge_fee);
+        }
+
+        let block_context = BlockContext { concurrency_mode: true, ..self.block_context.clone() };
+        let Some(tx_outputs) = execute_partitions(
+            MutRefState::new(&mut self.state),
+            txs,
+            &partitions,
+            &block_context,
+            charge_fee,
+        ) else {
+            log::debug!("Transaction partitions are dependent; executing sequentially.");
+            return self.execute_txs_sequentially(txs, charge_fee);
+        };
+
+        let mut results = Vec::new();
+        for (tx_index, (tx, tx_output)) in txs.iter().zip(tx_outputs).enumerate() {
+            let executed = tx_output.result.is_ok();
+            match self.commit_partitioned_tx(tx, tx_output) {
+                Ok(tx_execution_info) => results.push(Ok(tx_execution_info)),
+                Err(TransactionExecutorError::BlockFull) => break,
+                Err(error) => {
+                    results.push(Err(error));
+                    if executed {
+                        log::debug!(
+                            "Partitioned transaction {tx_index} was not committed; executing the \
+                             rest sequentially."
+                        );
+                        results.extend(
+                            self.execute_txs_sequentially(&txs[tx_index + 1..], charge_fee),
+                        );
+                        break;
+                    }
+                }
+            }
+        }
+        results
+    }
+
+    fn commit_partitioned_tx(
+        &mut self,
+        tx: &Transaction,
+        tx_output: PartitionedTxOutput,
+    ) -> TransactionExecutorResult<TransactionExecutionInfo> {
+        if self.bouncer.is_full() {
+            return Err(TransactionExecutorError::BlockFull);
+        }
+
+        let PartitionedTxOutput {
+            writes,
+            class_hash_to_class,
+            visited_pcs,
+            execution_duration,
+            deferred_effects,
+            result,
+        } = tx_output;
+        let mut tx_context = self.block_context.to_tx_context(tx);
+        // The hooks of the transaction were deferred to its commit (see [`DeferredEffect`]);
+        // `before_validate` is invoked against the state preceding the transaction.
+        if deferred_effects.first() == Some(&DeferredEffect::BeforeValidate) {
+            self.hooks.before_validate(&tx_context, &self.state)?;
+        }
+        let mut transactional_state = CachedState::create_transactional(&mut self.state);
+        transactional_state.update_cache(writes);
+        transactional_state.update_contract_class_cache(class_hash_to_class);
+        transactional_state.update_visited_pcs_cache(&visited_pcs);
+        replay_deferred_effects(
+            deferred_effects,
+            &tx_context,
+            self.hooks.as_ref(),
+            &transactional_state,
+            result
+                .as_ref()
+                .ok()
+                .and_then(|tx_execution_info| tx_execution_info.execute_call_info.as_ref()),
+        );
+        let mut tx_execution_info = result?;
+
+        // The partitions were executed in concurrency mode; complete the fee transfers that were
+        // executed accordingly.
+        tx_context.block_context.concurrency_mode = true;
+        let fee_recipient = tx_context.fee_recipient();
+        if let Some(fee_transfer_call_info) = &mut tx_execution_info.fee_transfer_call_info {
+            if tx_context.is_concurrent_fee_transfer() {
+                complete_fee_transfer(
+                    &mut transactional_state,
+                    fee_transfer_call_info,
+                    tx_context.fee_token_address(),
+                    fee_recipient,
+                    tx_execution_info.actual_fee,
+                )?;
+            }
+        }
+
+        let block_totals = Self::block_totals_with(
+            &self.block_totals,
+            self.block_totals_cap.as_ref(),
+            tx,
+            &tx_execution_info,
+        )?;
+        self.bouncer.try_update(
+            &mut transactional_state,
+            &tx_execution_info.summarize(),
+            &tx_execution_info.actual_resources,
+        )?;
+        transactional_state.commit();
+        self.block_totals = block_totals;
+        tx_execution_info.metadata = Some(TransactionMetadata {
+            tx_hash: tx_context.tx_info.transaction_hash(),
+            tx_index: self.n_committed_txs,
+            execution_duration,
+        });
+        self.n_committed_txs += 1;
+        self.hooks.after_commit(tx, &self.state, &tx_execution_info, execution_duration);
+        Ok(tx_execution_info)
     }
 }
@@ -1,14 +1,25 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
 use assert_matches::assert_matches;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
+use starknet_api::core::ContractAddress;
 use starknet_api::hash::StarkFelt;
 use starknet_api::stark_felt;
-use starknet_api::transaction::{Fee, TransactionVersion};
+use starknet_api::transaction::{Fee, TransactionHash, TransactionVersion};
 
 use crate::blockifier::config::TransactionExecutorConfig;
-use crate::blockifier::transaction_executor::{TransactionExecutor, TransactionExecutorError};
+use crate::blockifier::hooks::TransactionExecutorHooks;
+use crate::blockifier::priority::TransactionPriority;
+use crate::blockifier::transaction_executor::{
+    BlockTotals, TransactionExecutor, TransactionExecutorError,
+};
 use crate::bouncer::{Bouncer, BouncerConfig, BouncerWeights};
-use crate::context::BlockContext;
+use crate::context::{BlockContext, TransactionContext};
+use crate::execution::call_info::CallInfo;
+use crate::fee::gas_usage::L1DaMode;
 use crate::state::cached_state::CachedState;
 use crate::state::state_api::StateReader;
 use crate::test_utils::contracts::FeatureContract;
@@ -16,10 +27,12 @@ use crate::test_utils::declare::declare_tx;
 use crate::test_utils::deploy_account::deploy_account_tx;
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{
-    create_calldata, CairoVersion, NonceManager, BALANCE, DEFAULT_STRK_L1_GAS_PRICE,
+    create_calldata, create_trivial_calldata, CairoVersion, NonceManager, BALANCE,
+    DEFAULT_STRK_L1_GAS_PRICE, MAX_FEE, MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE,
 };
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::{FeeType, TransactionExecutionInfo, TransactionExecutionResult};
 use crate::transaction::test_utils::{
     account_invoke_tx, block_context, calculate_class_info_for_testing, create_test_init_data,
     emit_n_events_tx, l1_resource_bounds, TestInitData,
@@ -353,3 +366,338 @@ fn test_execute_txs_bouncing(block_context: BlockContext) {
     assert!(remaining_tx_results[1].is_ok());
     assert_eq!(tx_executor.state.get_nonce_at(account_address).unwrap(), nonce!(4_u32));
 }
+
+#[rstest]
+fn test_block_full_early_abort(block_context: BlockContext, #[values(true, false)] is_full: bool) {
+    let TestInitData { state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+    let max_n_events_in_block = 10;
+    let bouncer_config = BouncerConfig {
+        block_max_capacity: BouncerWeights {
+            n_events: max_n_events_in_block,
+            ..BouncerWeights::max(false)
+        },
+        min_tx_weights: BouncerWeights { n_events: 1, ..Default::default() },
+        ..BouncerConfig::default()
+    };
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        bouncer_config,
+        TransactionExecutorConfig::default(),
+    );
+    let n_events_in_block = if is_full { max_n_events_in_block } else { max_n_events_in_block - 1 };
+    tx_executor.bouncer.set_accumulated_weights(BouncerWeights {
+        n_events: n_events_in_block,
+        ..Default::default()
+    });
+    let hooks = Arc::new(CountingHooks::default());
+    tx_executor.hooks = hooks.clone();
+
+    let tx = Transaction::AccountTransaction(emit_n_events_tx(
+        1,
+        account_address,
+        contract_address,
+        nonce_manager.next(account_address),
+    ));
+    let results = tx_executor.execute_txs(&[tx], true);
+
+    if is_full {
+        // The transaction is not executed at all.
+        assert!(results.is_empty());
+        assert_eq!(hooks.counts(), [0, 0, 0, 0]);
+    } else {
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_ok());
+    }
+    // Either way, the block is now full.
+    assert!(tx_executor.bouncer.is_full());
+}
+
+/// Hooks that count their invocations, and reject the transactions of a given sender.
+#[derive(Default)]
+pub(crate) struct CountingHooks {
+    pub(crate) rejected_sender: Option<ContractAddress>,
+    pub(crate) n_before_validate: AtomicUsize,
+    pub(crate) n_after_execute: AtomicUsize,
+    pub(crate) n_before_fee_transfer: AtomicUsize,
+    pub(crate) n_after_commit: AtomicUsize,
+}
+
+impl CountingHooks {
+    pub(crate) fn counts(&self) -> [usize; 4] {
+        [
+            self.n_before_validate.load(Ordering::Relaxed),
+            self.n_after_execute.load(Ordering::Relaxed),
+            self.n_before_fee_transfer.load(Ordering::Relaxed),
+            self.n_after_commit.load(Ordering::Relaxed),
+        ]
+    }
+}
+
+impl TransactionExecutorHooks for CountingHooks {
+    fn before_validate(
+        &self,
+        tx_context: &TransactionContext,
+        _state: &dyn StateReader,
+    ) -> TransactionExecutionResult<()> {
+        self.n_before_validate.fetch_add(1, Ordering::Relaxed);
+        if self.rejected_sender == Some(tx_context.tx_info.sender_address()) {
+            return Err(TransactionExecutionError::TransactionRejectedByHooks {
+                reason: "Sender is not allowed.".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn after_execute(
+        &self,
+        _tx_context: &TransactionContext,
+        _state: &dyn StateReader,
+        _execute_call_info: Option<&CallInfo>,
+    ) {
+        self.n_after_execute.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn before_fee_transfer(
+        &self,
+        _tx_context: &TransactionContext,
+        _state: &dyn StateReader,
+        _actual_fee: Fee,
+    ) {
+        self.n_before_fee_transfer.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn after_commit(
+        &self,
+        _tx: &Transaction,
+        _state: &dyn StateReader,
+        _tx_execution_info: &TransactionExecutionInfo,
+        _execution_duration: Duration,
+    ) {
+        self.n_after_commit.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[rstest]
+fn test_hooks(block_context: BlockContext, #[values(false, true)] reject: bool) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let account_contract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let sender_address = account_contract.get_instance_address(0);
+    let state = test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(test_contract, 1), (account_contract, 1)],
+    );
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::create_for_testing(),
+        TransactionExecutorConfig::default(),
+    );
+    let hooks = Arc::new(CountingHooks {
+        rejected_sender: if reject { Some(sender_address) } else { None },
+        ..Default::default()
+    });
+    tx_executor.hooks = hooks.clone();
+
+    let tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        sender_address,
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        version: TransactionVersion::THREE,
+    }));
+    let result = tx_executor.execute(&tx, true);
+
+    if reject {
+        assert_matches!(
+            result.unwrap_err(),
+            TransactionExecutorError::TransactionExecutionError(
+                TransactionExecutionError::TransactionRejectedByHooks { .. }
+            )
+        );
+        // Rejected transactions leave no trace in the state.
+        assert_eq!(tx_executor.state.get_nonce_at(sender_address).unwrap(), nonce!(0_u8));
+        assert_eq!(hooks.counts(), [1, 0, 0, 0]);
+    } else {
+        assert!(!result.unwrap().is_reverted());
+        assert_eq!(hooks.counts(), [1, 1, 1, 1]);
+    }
+}
+
+#[rstest]
+fn test_tx_metadata(block_context: BlockContext) {
+    let cairo_version = CairoVersion::Cairo1;
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let account_contract = FeatureContract::AccountWithoutValidations(cairo_version);
+    let sender_address = account_contract.get_instance_address(0);
+    let state = test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(test_contract, 1), (account_contract, 1)],
+    );
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::create_for_testing(),
+        TransactionExecutorConfig::default(),
+    );
+
+    let invoke_tx = |nonce| {
+        Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+            sender_address,
+            calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+            version: TransactionVersion::THREE,
+            nonce,
+        }))
+    };
+    let l1_handler_tx = L1HandlerTransaction {
+        tx_hash: TransactionHash(stark_felt!(7_u8)),
+        ..L1HandlerTransaction::create_for_testing(
+            Fee(1908000000000000),
+            test_contract.get_instance_address(0),
+        )
+    };
+    let txs = [
+        invoke_tx(nonce!(0_u8)),
+        // Invalid nonce; the transaction is not included in the block.
+        invoke_tx(nonce!(5_u8)),
+        Transaction::L1HandlerTransaction(l1_handler_tx),
+    ];
+    let results = tx_executor.execute_txs(&txs, true);
+
+    assert_eq!(results.len(), 3);
+    let first_metadata = results[0].as_ref().unwrap().metadata.unwrap();
+    assert_eq!(first_metadata.tx_hash, TransactionHash::default());
+    assert_eq!(first_metadata.tx_index, 0);
+    assert!(results[1].is_err());
+    let last_metadata = results[2].as_ref().unwrap().metadata.unwrap();
+    assert_eq!(last_metadata.tx_hash, TransactionHash(stark_felt!(7_u8)));
+    assert_eq!(last_metadata.tx_index, 1);
+    assert_eq!(tx_executor.n_committed_txs, 2);
+}
+
+#[rstest]
+fn test_execute_txs_by_priority(block_context: BlockContext) {
+    let cairo_version = CairoVersion::Cairo1;
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let account_contract = FeatureContract::AccountWithoutValidations(cairo_version);
+    let state = test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(test_contract, 1), (account_contract, 2)],
+    );
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::create_for_testing(),
+        TransactionExecutorConfig::default(),
+    );
+
+    let invoke_tx = |account_index, nonce| {
+        Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+            sender_address: account_contract.get_instance_address(account_index),
+            calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+            version: TransactionVersion::THREE,
+            nonce,
+        }))
+    };
+    let txs = [
+        invoke_tx(0, nonce!(0_u8)),
+        invoke_tx(1, nonce!(0_u8)),
+        invoke_tx(0, nonce!(1_u8)),
+        invoke_tx(1, nonce!(1_u8)),
+    ];
+    let priorities = [
+        TransactionPriority::Low,
+        TransactionPriority::Normal,
+        // Must not precede the preceding transaction of the same sender.
+        TransactionPriority::High,
+        TransactionPriority::High,
+    ];
+
+    let results = tx_executor.execute_txs_by_priority(&txs, &priorities, true);
+    let execution_order: Vec<usize> = results.iter().map(|(tx_index, _)| *tx_index).collect();
+    assert_eq!(execution_order, vec![1, 3, 0, 2]);
+    for (_, result) in results {
+        assert!(result.is_ok());
+    }
+}
+
+#[rstest]
+fn test_block_totals(block_context: BlockContext) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    // Only the Cairo 1 test contract has an L1 handler.
+    let l1_handler_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let account_contract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let sender_address = account_contract.get_instance_address(0);
+    let state = test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(test_contract, 1), (l1_handler_contract, 1), (account_contract, 1)],
+    );
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::create_for_testing(),
+        TransactionExecutorConfig::default(),
+    );
+
+    let invoke_tx = |version, nonce| {
+        Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+            sender_address,
+            calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+            max_fee: Fee(MAX_FEE),
+            resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE),
+            version,
+            nonce,
+        }))
+    };
+    let txs = [
+        invoke_tx(TransactionVersion::ONE, nonce!(0_u8)),
+        invoke_tx(TransactionVersion::THREE, nonce!(1_u8)),
+        Transaction::L1HandlerTransaction(L1HandlerTransaction::create_for_testing(
+            Fee(1908000000000000),
+            l1_handler_contract.get_instance_address(0),
+        )),
+    ];
+    let mut expected_block_totals = BlockTotals::default();
+    for (tx, fee_type) in txs.iter().zip([FeeType::Eth, FeeType::Strk, FeeType::Eth]) {
+        let tx_execution_info = tx_executor.execute(tx, true).unwrap();
+        expected_block_totals = expected_block_totals.add_tx(&fee_type, &tx_execution_info);
+    }
+    assert_eq!(tx_executor.block_totals, expected_block_totals);
+    assert!(expected_block_totals.eth_fee > Fee(0) && expected_block_totals.strk_fee > Fee(0));
+
+    // Transactions that would exceed the cap are not included.
+    tx_executor.block_totals_cap = Some(expected_block_totals);
+    assert_matches!(
+        tx_executor.execute(&invoke_tx(TransactionVersion::THREE, nonce!(2_u8)), true),
+        Err(TransactionExecutorError::BlockFull)
+    );
+    assert_eq!(tx_executor.n_committed_txs, txs.len());
+    assert_eq!(tx_executor.finalize_with_summary().unwrap().block_totals, expected_block_totals);
+}
+
+#[rstest]
+fn test_summary_da_mode(#[values(true, false)] use_kzg_da: bool) {
+    let block_context = BlockContext::create_for_account_testing_with_kzg(use_kzg_da);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let state = test_state(&block_context.chain_info, BALANCE, &[(test_contract, 1)]);
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::create_for_testing(),
+        TransactionExecutorConfig::default(),
+    );
+    let tx = Transaction::L1HandlerTransaction(L1HandlerTransaction::create_for_testing(
+        Fee(1908000000000000),
+        test_contract.get_instance_address(0),
+    ));
+    let tx_execution_info = tx_executor.execute(&tx, true).unwrap();
+    assert_eq!(tx_execution_info.da_gas.l1_data_gas > 0, use_kzg_da);
+
+    // The block is published in the mode its transactions were priced in, even if the other one
+    // is cheaper for its state diff.
+    let da_mode = tx_executor.finalize_with_summary().unwrap().da_mode;
+    assert_eq!(da_mode, L1DaMode::from_use_kzg_da(use_kzg_da));
+}
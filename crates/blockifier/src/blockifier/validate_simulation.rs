@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+
+use crate::context::BlockContext;
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point::EntryPointExecutionContext;
+use crate::state::cached_state::{CachedState, MutRefState};
+use crate::state::state_api::State;
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::objects::TransactionExecutionResult;
+use crate::transaction::transactions::{Executable, ValidatableTransaction};
+
+#[cfg(test)]
+#[path = "validate_simulation_test.rs"]
+pub mod test;
+
+/// The result of a simulated validation of an account transaction.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct ValidateSimulation {
+    /// The `__validate__` call; [None] for transactions that are not validated (version 0).
+    pub validate_call_info: Option<CallInfo>,
+    /// The constructor call of a deploy account transaction, run before the validation.
+    pub constructor_call_info: Option<CallInfo>,
+    /// The VM resources consumed by the validation (and the constructor run before it), including
+    /// those of their syscalls.
+    pub resources: ExecutionResources,
+    pub gas_consumed: u64,
+}
+
+/// Runs only the validation entry point of the given transaction's account on top of the given
+/// state, under the restrictions of the validation stage (e.g., forbidden syscalls and step
+/// limits); intended for mempools vetting transactions. The changes the validation makes to the
+/// state are discarded, and no fee is charged.
+/// As in a transaction run, the account of a deploy account transaction is deployed, and its
+/// constructor run under the validation restrictions, before its validation; the deployment is
+/// discarded along with the other changes.
+pub fn simulate_validate(
+    state: &mut dyn State,
+    block_context: &BlockContext,
+    tx: &AccountTransaction,
+) -> TransactionExecutionResult<ValidateSimulation> {
+    let tx_context = Arc::new(block_context.to_tx_context(tx));
+
+    // Run on top of a layer that is dropped afterwards, to leave the given state intact.
+    let mut validate_state = CachedState::new(MutRefState::new(state));
+    // As in a transaction run, the account nonce is incremented before the validation.
+    validate_state.increment_nonce(tx_context.tx_info.sender_address())?;
+
+    let mut resources = ExecutionResources::default();
+    let initial_gas = tx_context.initial_gas();
+    let mut remaining_gas = initial_gas;
+    let limit_steps_by_resources = true;
+    let constructor_call_info = match tx {
+        AccountTransaction::DeployAccount(deploy_account_tx) => {
+            let mut context = EntryPointExecutionContext::new_validate(
+                tx_context.clone(),
+                limit_steps_by_resources,
+            )?;
+            deploy_account_tx.run_execute(
+                &mut validate_state,
+                &mut resources,
+                &mut context,
+                &mut remaining_gas,
+            )?
+        }
+        AccountTransaction::Declare(_) | AccountTransaction::Invoke(_) => None,
+    };
+    let validate_call_info = tx.validate_tx(
+        &mut validate_state,
+        &mut resources,
+        tx_context,
+        &mut remaining_gas,
+        limit_steps_by_resources,
+    )?;
+
+    Ok(ValidateSimulation {
+        validate_call_info,
+        constructor_call_info,
+        resources,
+        gas_consumed: initial_gas - remaining_gas,
+    })
+}
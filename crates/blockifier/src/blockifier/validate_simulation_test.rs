@@ -0,0 +1,89 @@
+use rstest::rstest;
+use starknet_api::core::ClassHash;
+use starknet_api::transaction::{Fee, TransactionVersion};
+
+use crate::blockifier::validate_simulation::simulate_validate;
+use crate::context::BlockContext;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{CairoVersion, NonceManager, BALANCE};
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::test_utils::{
+    block_context, create_account_tx_for_validate_test, FaultyAccountTxCreatorArgs, GET_BLOCK_HASH,
+    VALID,
+};
+use crate::transaction::transaction_types::TransactionType;
+use crate::{check_transaction_execution_error_for_custom_hint, nonce};
+
+#[rstest]
+fn test_simulate_validate(block_context: BlockContext) {
+    let faulty_account = FeatureContract::FaultyAccount(CairoVersion::Cairo1);
+    let sender_address = faulty_account.get_instance_address(0);
+    let mut state = test_state(&block_context.chain_info, BALANCE, &[(faulty_account, 1)]);
+    let validate_test_tx = |scenario| {
+        create_account_tx_for_validate_test(
+            &mut NonceManager::default(),
+            FaultyAccountTxCreatorArgs {
+                tx_type: TransactionType::InvokeFunction,
+                tx_version: TransactionVersion::THREE,
+                scenario,
+                sender_address,
+                max_fee: Fee(BALANCE),
+                ..Default::default()
+            },
+        )
+    };
+
+    let tx = validate_test_tx(VALID);
+    let simulation = simulate_validate(&mut state, &block_context, &tx).unwrap();
+    let validate_call_info = simulation.validate_call_info.unwrap();
+    assert_eq!(validate_call_info.call.storage_address, sender_address);
+    assert!(simulation.resources.n_steps > 0);
+    assert_eq!(simulation.gas_consumed, validate_call_info.execution.gas_consumed);
+
+    // The nonce increment preceding the validation is discarded.
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), nonce!(0_u8));
+
+    // Syscalls forbidden in the validation stage fail.
+    let tx = validate_test_tx(GET_BLOCK_HASH);
+    let error = simulate_validate(&mut state, &block_context, &tx).unwrap_err();
+    check_transaction_execution_error_for_custom_hint!(
+        &error,
+        "Unauthorized syscall get_block_hash in execution mode Validate.",
+        false,
+    );
+}
+
+#[rstest]
+fn test_simulate_validate_deploy_account(block_context: BlockContext) {
+    let faulty_account = FeatureContract::FaultyAccount(CairoVersion::Cairo1);
+    // The account class is declared, but not deployed.
+    let mut state = test_state(&block_context.chain_info, BALANCE, &[(faulty_account, 0)]);
+    let tx = create_account_tx_for_validate_test(
+        &mut NonceManager::default(),
+        FaultyAccountTxCreatorArgs {
+            tx_type: TransactionType::DeployAccount,
+            tx_version: TransactionVersion::THREE,
+            scenario: VALID,
+            class_hash: faulty_account.get_class_hash(),
+            max_fee: Fee(BALANCE),
+            ..Default::default()
+        },
+    );
+    let AccountTransaction::DeployAccount(deploy_account_tx) = &tx else {
+        panic!("Expected a deploy account transaction.");
+    };
+    let account_address = deploy_account_tx.contract_address;
+
+    // The account is deployed, and its constructor run, before the validation.
+    let simulation = simulate_validate(&mut state, &block_context, &tx).unwrap();
+    let constructor_call_info = simulation.constructor_call_info.unwrap();
+    assert_eq!(constructor_call_info.call.storage_address, account_address);
+    let validate_call_info = simulation.validate_call_info.unwrap();
+    assert_eq!(validate_call_info.call.storage_address, account_address);
+    assert!(!validate_call_info.execution.failed);
+
+    // The deployment is discarded.
+    assert_eq!(state.get_class_hash_at(account_address).unwrap(), ClassHash::default());
+}
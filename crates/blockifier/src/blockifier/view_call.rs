@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use starknet_api::core::{ContractAddress, EntryPointSelector};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::transaction::Calldata;
+
+use crate::context::{BlockContext, TransactionContext};
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point::{
+    CallEntryPoint, CallType, EntryPointExecutionContext, EntryPointExecutionResult,
+    ExecutionInfoOverrides,
+};
+use crate::state::cached_state::{CachedState, MutRefState};
+use crate::state::state_api::State;
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
+
+#[cfg(test)]
+#[path = "view_call_test.rs"]
+pub mod test;
+
+/// A call to an external entry point of a deployed contract, outside of any transaction; e.g., for
+/// serving `starknet_call` requests.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ViewCall {
+    pub contract_address: ContractAddress,
+    pub entry_point_selector: EntryPointSelector,
+    pub calldata: Calldata,
+}
+
+/// Executes the given call on top of the given state and returns its call info; the changes the
+/// call makes to the state are discarded.
+/// The call has no caller, starts with the initial gas of a transaction, and is limited to the
+/// steps of an invoke transaction (unless the block context overrides the step limit).
+pub fn call_entry_point(
+    state: &mut dyn State,
+    block_context: &BlockContext,
+    call: ViewCall,
+) -> EntryPointExecutionResult<CallInfo> {
+    let execution_info_overrides = block_context.execution_policy.execution_info_overrides.clone();
+    call_entry_point_with_overrides(state, block_context, call, execution_info_overrides)
+}
+
+/// As [`call_entry_point`], with the execution info seen by the executed contracts overridden as
+/// given, instead of by the overrides of the block context.
+pub fn call_entry_point_with_overrides(
+    state: &mut dyn State,
+    block_context: &BlockContext,
+    call: ViewCall,
+    execution_info_overrides: ExecutionInfoOverrides,
+) -> EntryPointExecutionResult<CallInfo> {
+    let ViewCall { contract_address, entry_point_selector, calldata } = call;
+    let entry_point = CallEntryPoint {
+        class_hash: None,
+        code_address: None,
+        entry_point_type: EntryPointType::External,
+        entry_point_selector,
+        calldata,
+        storage_address: contract_address,
+        caller_address: ContractAddress::default(),
+        call_type: CallType::Call,
+        initial_gas: block_context.versioned_constants.tx_initial_gas(),
+    };
+
+    let mut block_context = block_context.clone();
+    block_context.execution_policy.execution_info_overrides = execution_info_overrides;
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let limit_steps_by_resources = false;
+    let mut context =
+        EntryPointExecutionContext::new_invoke(Arc::new(tx_context), limit_steps_by_resources)
+            .expect("The step limit of a call not limited by its resources is infallible.");
+
+    // Run on top of a layer that is dropped afterwards, to leave the given state intact.
+    let mut call_state = CachedState::new(MutRefState::new(state));
+    entry_point.execute(&mut call_state, &mut ExecutionResources::default(), &mut context)
+}
@@ -0,0 +1,85 @@
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{Calldata, TransactionVersion};
+use starknet_api::{calldata, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::blockifier::view_call::{call_entry_point, call_entry_point_with_overrides, ViewCall};
+use crate::context::{BlockContext, ChainInfo};
+use crate::execution::call_info::Retdata;
+use crate::execution::entry_point::ExecutionInfoOverrides;
+use crate::execution::errors::{EntryPointExecutionError, PreExecutionError};
+use crate::retdata;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{CairoVersion, BALANCE};
+
+#[test]
+fn test_call_entry_point() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let block_context = BlockContext::create_for_testing();
+
+    let contract_address = test_contract.get_instance_address(0);
+    let (key, value) = (stark_felt!(1234_u16), stark_felt!(18_u8));
+    let call = ViewCall {
+        contract_address,
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        calldata: calldata![key, value],
+    };
+    let call_info = call_entry_point(&mut state, &block_context, call.clone()).unwrap();
+    assert_eq!(call_info.execution.retdata, retdata![value]);
+    assert_eq!(call_info.call.storage_address, contract_address);
+
+    // The write of the call is discarded.
+    assert_eq!(
+        state.get_storage_at(contract_address, StorageKey::try_from(key).unwrap()).unwrap(),
+        StarkFelt::ZERO
+    );
+
+    let missing_entry_point_call =
+        ViewCall { entry_point_selector: selector_from_name("no_such_entry_point"), ..call };
+    assert!(matches!(
+        call_entry_point(&mut state, &block_context, missing_entry_point_call),
+        Err(EntryPointExecutionError::PreExecutionError(PreExecutionError::EntryPointNotFound(_)))
+    ));
+}
+
+#[test]
+fn test_call_entry_point_with_overrides() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let block_context = BlockContext::create_for_testing();
+    let execution_info_overrides = ExecutionInfoOverrides {
+        block_number: Some(BlockNumber(5678)),
+        block_timestamp: Some(BlockTimestamp(1234)),
+        tx_version: Some(TransactionVersion::THREE),
+        ..Default::default()
+    };
+
+    for (entry_point_name, expected_value) in [
+        ("test_get_block_number", 5678_u16),
+        ("test_get_block_timestamp", 1234),
+        ("test_tx_version", 3),
+    ] {
+        let call = ViewCall {
+            contract_address: test_contract.get_instance_address(0),
+            entry_point_selector: selector_from_name(entry_point_name),
+            calldata: calldata![stark_felt!(expected_value)],
+        };
+        call_entry_point_with_overrides(
+            &mut state,
+            &block_context,
+            call.clone(),
+            execution_info_overrides.clone(),
+        )
+        .unwrap();
+        // The block context is not affected.
+        assert!(call_entry_point(&mut state, &block_context, call).is_err());
+    }
+}
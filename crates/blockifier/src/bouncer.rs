@@ -40,6 +40,9 @@ pub type HashMapWrapper = HashMap<String, usize>;
 pub struct BouncerConfig {
     pub block_max_capacity: BouncerWeights,
     pub block_max_capacity_with_keccak: BouncerWeights,
+    // A lower bound on the weights of any transaction; once the remaining block capacity cannot
+    // fit it, the block is considered full.
+    pub min_tx_weights: BouncerWeights,
 }
 
 impl BouncerConfig {
@@ -47,6 +50,7 @@ impl BouncerConfig {
         Self {
             block_max_capacity_with_keccak: BouncerWeights::max(true),
             block_max_capacity: BouncerWeights::max(false),
+            min_tx_weights: BouncerWeights::default(),
         }
     }
 }
@@ -165,6 +169,8 @@ pub struct Bouncer {
     // Additional info; maintained and used to calculate the residual contribution of a transaction
     // to the accumulated weights.
     pub executed_class_hashes: HashSet<ClassHash>,
+    // The visited PCs of the executed Cairo 1 classes, whose visited segments were loaded.
+    pub visited_pcs: HashMap<ClassHash, HashSet<usize>>,
     pub visited_storage_entries: HashSet<StorageEntry>,
     pub state_changes_keys: StateChangesKeys,
 
@@ -182,15 +188,30 @@ impl Bouncer {
         &self.accumulated_weights
     }
 
+    /// Returns whether no transaction can fit the remaining block capacity.
+    pub fn is_full(&self) -> bool {
+        let max_capacity = if self.accumulated_weights.builtin_count.keccak > 0 {
+            self.bouncer_config.block_max_capacity_with_keccak
+        } else {
+            self.bouncer_config.block_max_capacity
+        };
+
+        !max_capacity.has_room(self.accumulated_weights + self.bouncer_config.min_tx_weights)
+    }
+
     fn _update(
         &mut self,
         tx_weights: BouncerWeights,
         tx_execution_summary: &ExecutionSummary,
+        tx_visited_pcs: &HashMap<ClassHash, HashSet<usize>>,
         state_changes_keys: &StateChangesKeys,
     ) {
         self.accumulated_weights += tx_weights;
         self.visited_storage_entries.extend(&tx_execution_summary.visited_storage_entries);
         self.executed_class_hashes.extend(&tx_execution_summary.executed_class_hashes);
+        for (class_hash, class_visited_pcs) in tx_visited_pcs {
+            self.visited_pcs.entry(*class_hash).or_default().extend(class_visited_pcs);
+        }
         // Note: cancelling writes (0 -> 1 -> 0) will not be removed, but it's fine since fee was
         // charged for them.
         self.state_changes_keys.extend(state_changes_keys);
@@ -230,7 +251,7 @@ impl Bouncer {
             Err(TransactionExecutorError::BlockFull)?
         }
 
-        self._update(tx_weights, tx_execution_summary, &state_changes_keys);
+        self._update(tx_weights, tx_execution_summary, &state.visited_pcs, &state_changes_keys);
 
         Ok(())
     }
@@ -248,6 +269,7 @@ impl Bouncer {
         let mut additional_os_resources = get_casm_hash_calculation_resources(
             state,
             &self.executed_class_hashes,
+            &self.visited_pcs,
             &tx_execution_summary.executed_class_hashes,
         )?;
         additional_os_resources += &get_particia_update_resources(
@@ -256,12 +278,16 @@ impl Bouncer {
         );
 
         let vm_resources = &additional_os_resources + &tx_resources.vm_resources;
+        // Calls metered by Sierra gas are counted by their gas, in terms of steps.
+        let n_steps = (&additional_os_resources + &tx_resources.charged_vm_resources())
+            .total_n_steps()
+            + tx_resources.sierra_gas_metering.n_steps;
 
         Ok(BouncerWeights {
             gas: gas_usage,
             message_segment_length,
             n_events: tx_resources.starknet_resources.n_events,
-            n_steps: vm_resources.total_n_steps(),
+            n_steps,
             builtin_count: BuiltinCount::from(vm_resources.prover_builtins()),
             state_diff_size: get_onchain_data_segment_length(&state_changes_keys.count()),
         })
@@ -281,21 +307,41 @@ impl Bouncer {
     }
 }
 
-/// Returns the estimated VM resources for Casm hash calculation (done by the OS), of the newly
-/// executed classes by the current transaction.
+/// Returns the estimated VM resources for Casm hash calculation (done by the OS), of the bytecode
+/// segments newly loaded by the current transaction. Only the visited segments of a Cairo 1 class
+/// are loaded; a class whose visited PCs are not tracked in the state is loaded as a whole.
 pub fn get_casm_hash_calculation_resources<S: StateReader>(
     state: &mut TransactionalState<'_, S>,
     block_executed_class_hashes: &HashSet<ClassHash>,
+    block_visited_pcs: &HashMap<ClassHash, HashSet<usize>>,
     tx_executed_class_hashes: &HashSet<ClassHash>,
 ) -> TransactionExecutorResult<ExecutionResources> {
-    let newly_executed_class_hashes: HashSet<&ClassHash> =
-        tx_executed_class_hashes.difference(block_executed_class_hashes).collect();
-
     let mut casm_hash_computation_resources = ExecutionResources::default();
 
-    for class_hash in newly_executed_class_hashes {
+    for class_hash in tx_executed_class_hashes {
+        let executed_in_block = block_executed_class_hashes.contains(class_hash);
+        let tx_visited_pcs = state.visited_pcs.get(class_hash).cloned();
         let class = state.get_compiled_contract_class(*class_hash)?;
-        casm_hash_computation_resources += &class.estimate_casm_hash_computation_resources();
+        match (tx_visited_pcs, block_visited_pcs.get(class_hash)) {
+            (None, _) | (Some(_), None) if executed_in_block => {}
+            (None, _) => {
+                casm_hash_computation_resources +=
+                    &class.estimate_casm_hash_computation_resources();
+            }
+            (Some(tx_visited_pcs), None) => {
+                casm_hash_computation_resources +=
+                    &class.estimate_visited_casm_hash_computation_resources(&tx_visited_pcs)?;
+            }
+            // Only the segments not loaded by previous transactions are charged.
+            (Some(tx_visited_pcs), Some(block_visited_pcs)) => {
+                let visited_pcs = block_visited_pcs.union(&tx_visited_pcs).copied().collect();
+                casm_hash_computation_resources += &(&class
+                    .estimate_visited_casm_hash_computation_resources(&visited_pcs)?
+                    - &class
+                        .estimate_visited_casm_hash_computation_resources(block_visited_pcs)?)
+                    .filter_unused_builtins();
+            }
+        }
     }
 
     Ok(casm_hash_computation_resources)
@@ -1,6 +1,8 @@
 use std::collections::{HashMap, HashSet};
 
+use cairo_lang_starknet_classes::NestedIntList;
 use cairo_vm::serde::deserialize_program::BuiltinName;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use rstest::rstest;
 use starknet_api::core::{ClassHash, ContractAddress, PatriciaKey};
 use starknet_api::hash::StarkHash;
@@ -13,10 +15,15 @@ use crate::blockifier::transaction_executor::{
 use crate::bouncer::{Bouncer, BouncerWeights, BuiltinCount};
 use crate::context::BlockContext;
 use crate::execution::call_info::ExecutionSummary;
+use crate::execution::contract_class::ContractClass;
+use crate::execution::execution_utils::poseidon_hash_many_cost;
 use crate::state::cached_state::{CachedState, StateChangesKeys};
 use crate::storage_key;
+use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::CairoVersion;
 use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::{ExecutionResourcesTraits, TransactionResources};
 
 #[test]
 fn test_block_weights_has_room() {
@@ -80,6 +87,7 @@ fn test_block_weights_has_room() {
 #[case::empty_initial_bouncer(Bouncer::new(BouncerConfig::default()))]
 #[case::non_empty_initial_bouncer(Bouncer {
     executed_class_hashes: HashSet::from([class_hash!(0_u128)]),
+    visited_pcs: HashMap::from([(class_hash!(0_u128), HashSet::from([0, 1]))]),
     visited_storage_entries: HashSet::from([(
         contract_address!(0_u128),
         storage_key!(0_u128),
@@ -135,10 +143,16 @@ fn test_bouncer_update(#[case] initial_bouncer: Bouncer) {
     let state_changes_keys_to_update =
         StateChangesKeys::create_for_testing(HashSet::from([ContractAddress::from(1_u128)]));
 
+    let visited_pcs_to_update = HashMap::from([
+        (class_hash!(0_u128), HashSet::from([1, 2])),
+        (class_hash!(1_u128), HashSet::from([0])),
+    ]);
+
     let mut updated_bouncer = initial_bouncer.clone();
     updated_bouncer._update(
         weights_to_update,
         &execution_summary_to_update,
+        &visited_pcs_to_update,
         &state_changes_keys_to_update,
     );
 
@@ -146,6 +160,9 @@ fn test_bouncer_update(#[case] initial_bouncer: Bouncer) {
     expected_bouncer
         .executed_class_hashes
         .extend(&execution_summary_to_update.executed_class_hashes);
+    for (class_hash, class_visited_pcs) in &visited_pcs_to_update {
+        expected_bouncer.visited_pcs.entry(*class_hash).or_default().extend(class_visited_pcs);
+    }
     expected_bouncer
         .visited_storage_entries
         .extend(&execution_summary_to_update.visited_storage_entries);
@@ -191,10 +208,6 @@ fn test_bouncer_try_update(
     #[case] added_keccak: usize,
     #[case] expected_result: TransactionExecutorResult<()>,
 ) {
-    use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
-
-    use crate::transaction::objects::TransactionResources;
-
     let state = &mut test_state(&BlockContext::create_for_account_testing().chain_info, 0, &[]);
     let mut transactional_state = CachedState::create_transactional(state);
 
@@ -217,7 +230,11 @@ fn test_bouncer_try_update(
     };
     let mut block_max_capacity_with_keccak = block_max_capacity;
     block_max_capacity_with_keccak.builtin_count.keccak = 1;
-    let bouncer_config = BouncerConfig { block_max_capacity, block_max_capacity_with_keccak };
+    let bouncer_config = BouncerConfig {
+        block_max_capacity,
+        block_max_capacity_with_keccak,
+        min_tx_weights: BouncerWeights::default(),
+    };
 
     let accumulated_weights = BouncerWeights {
         builtin_count: BuiltinCount {
@@ -263,3 +280,70 @@ fn test_bouncer_try_update(
     // TODO(yael 27/3/24): compare the results without using string comparison.
     assert_eq!(format!("{:?}", result), format!("{:?}", expected_result));
 }
+
+#[test]
+fn test_bouncer_casm_hash_weights_of_visited_segments() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let class_hash = test_contract.get_class_hash();
+    let class = test_contract.get_class();
+    let ContractClass::V1(class_v1) = &class else { panic!("Expected a Cairo 1 class.") };
+    let NestedIntList::Node(segments) = class_v1.bytecode_segment_lengths() else {
+        panic!("Expected a segmented bytecode.")
+    };
+    let [NestedIntList::Leaf(first_length), NestedIntList::Leaf(second_length), ..] =
+        segments.as_slice()
+    else {
+        panic!("Expected the first segments to be leaves.")
+    };
+
+    let state = &mut test_state(
+        &BlockContext::create_for_account_testing().chain_info,
+        0,
+        &[(test_contract, 1)],
+    );
+    let mut bouncer = Bouncer::new(BouncerConfig::max());
+    let execution_summary = ExecutionSummary {
+        executed_class_hashes: HashSet::from([class_hash]),
+        ..Default::default()
+    };
+    let mut add_tx = |visited_pcs: HashSet<usize>| {
+        let mut transactional_state = CachedState::create_transactional(state);
+        transactional_state.visited_pcs.insert(class_hash, visited_pcs);
+        let tx_resources = TransactionResources::default();
+        let tx_weights = bouncer
+            .get_tx_weights(
+                &mut transactional_state,
+                &execution_summary,
+                &tx_resources,
+                &StateChangesKeys::default(),
+            )
+            .unwrap();
+        bouncer.try_update(&mut transactional_state, &execution_summary, &tx_resources).unwrap();
+        (tx_weights.n_steps, tx_weights.builtin_count.poseidon)
+    };
+    let as_weights = |resources: ExecutionResources| {
+        (
+            resources.total_n_steps(),
+            resources.builtin_instance_counter[BuiltinName::poseidon.name()],
+        )
+    };
+
+    // Only the visited (first) segment is hashed.
+    let visited_pcs = HashSet::from([0]);
+    let expected_resources =
+        class.estimate_visited_casm_hash_computation_resources(&visited_pcs).unwrap();
+    assert!(
+        expected_resources.total_n_steps()
+            < class.estimate_casm_hash_computation_resources().total_n_steps()
+    );
+    assert_eq!(add_tx(visited_pcs.clone()), as_weights(expected_resources));
+
+    // Segments loaded by previous transactions are not charged again.
+    assert_eq!(add_tx(visited_pcs), (0, 0));
+
+    // A newly visited segment is charged by its hash.
+    assert_eq!(
+        add_tx(HashSet::from([*first_length])),
+        as_weights(poseidon_hash_many_cost(*second_length))
+    );
+}
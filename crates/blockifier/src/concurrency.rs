@@ -1,4 +1,5 @@
 pub mod fee_utils;
+pub mod partitioned_execution;
 pub mod scheduler;
 #[cfg(any(feature = "testing", test))]
 pub mod test_utils;
@@ -1,6 +1,13 @@
+use cairo_felt::Felt252;
+use starknet_api::core::ContractAddress;
 use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Fee;
 
+use crate::abi::sierra_types::felt_to_u128;
 use crate::execution::call_info::CallInfo;
+use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+use crate::fee::fee_utils::get_sequencer_balance_keys;
+use crate::state::state_api::{State, StateResult};
 #[cfg(test)]
 #[path = "fee_utils_test.rs"]
 mod test;
@@ -28,3 +35,42 @@ pub fn fill_sequencer_balance_reads(
     storage_read_values[low_index] = sequencer_balance_low;
     storage_read_values[high_index] = sequencer_balance_high;
 }
+
+// Completes a fee transfer executed in concurrency mode, given the state the transaction is
+// committed to: fills the sequencer balance reads of the call info, and adds the fee to the
+// sequencer balance.
+pub fn complete_fee_transfer(
+    state: &mut dyn State,
+    fee_transfer_call_info: &mut CallInfo,
+    fee_token_address: ContractAddress,
+    sequencer_address: ContractAddress,
+    actual_fee: Fee,
+) -> StateResult<()> {
+    let (sequencer_balance_key_low, sequencer_balance_key_high) =
+        get_sequencer_balance_keys(sequencer_address);
+    let sequencer_balance_low =
+        state.get_storage_at(fee_token_address, sequencer_balance_key_low)?;
+    let sequencer_balance_high =
+        state.get_storage_at(fee_token_address, sequencer_balance_key_high)?;
+    fill_sequencer_balance_reads(
+        fee_transfer_call_info,
+        sequencer_balance_low,
+        sequencer_balance_high,
+    );
+
+    let (new_balance_low, carry) = felt_to_u128(&stark_felt_to_felt(sequencer_balance_low))
+        .expect("The low part of a Uint256 balance must fit in 128 bits.")
+        .overflowing_add(actual_fee.0);
+    let new_balance_high =
+        stark_felt_to_felt(sequencer_balance_high) + Felt252::from(u8::from(carry));
+    state.set_storage_at(
+        fee_token_address,
+        sequencer_balance_key_low,
+        StarkFelt::from(new_balance_low),
+    )?;
+    state.set_storage_at(
+        fee_token_address,
+        sequencer_balance_key_high,
+        felt_to_stark_felt(&new_balance_high),
+    )
+}
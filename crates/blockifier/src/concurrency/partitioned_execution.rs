@@ -0,0 +1,369 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::iter;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use starknet_api::core::{ClassHash, ContractAddress};
+use starknet_api::transaction::Fee;
+
+use crate::blockifier::hooks::TransactionExecutorHooks;
+use crate::concurrency::versioned_state_proxy::{ThreadSafeVersionedState, VersionedState};
+use crate::concurrency::TxIndex;
+use crate::context::{BlockContext, TransactionContext};
+use crate::execution::call_info::{CallInfo, EmittedEvent};
+use crate::execution::event_sink::EventSink;
+use crate::fee::fee_utils::get_sequencer_balance_keys;
+use crate::state::cached_state::{CachedState, ContractClassMapping, StateMaps, StorageEntry};
+use crate::state::state_api::StateReader;
+use crate::transaction::objects::{
+    TransactionExecutionInfo, TransactionExecutionResult, TransactionInfo, TransactionInfoCreator,
+};
+use crate::transaction::transaction_execution::Transaction;
+
+#[cfg(test)]
+#[path = "partitioned_execution_test.rs"]
+mod test;
+
+/// The outputs of a transaction executed within its partition; to be committed to the block state
+/// in the original order of the transactions.
+#[derive(Debug)]
+pub struct PartitionedTxOutput {
+    pub writes: StateMaps,
+    pub class_hash_to_class: ContractClassMapping,
+    pub visited_pcs: HashMap<ClassHash, HashSet<usize>>,
+    pub execution_duration: Duration,
+    /// The hook invocations and events of the transaction, in order; see [`DeferredEffect`].
+    pub deferred_effects: Vec<DeferredEffect>,
+    pub result: TransactionExecutionResult<TransactionExecutionInfo>,
+}
+
+/// A hook invocation, or an emitted event, of a transaction executed within its partition, or by
+/// a concurrent worker (see [`crate::concurrency::worker_logic::WorkerExecutor`]).
+/// These are not reported while the transactions are executed, as the execution may be discarded;
+/// they are replayed when the transaction's outputs are committed, so that the hooks and the event
+/// sink only observe the committed execution of each transaction.
+#[derive(Debug, Eq, PartialEq)]
+pub enum DeferredEffect {
+    BeforeValidate,
+    Event { caller_address: ContractAddress, event: EmittedEvent },
+    AfterExecute,
+    BeforeFeeTransfer { actual_fee: Fee },
+}
+
+/// Records the hook invocations and events of the transactions of a partition, or of a
+/// concurrently executed transaction, in place of the executor's hooks and event sink.
+#[derive(Debug, Default)]
+pub(crate) struct EffectRecorder(Mutex<Vec<DeferredEffect>>);
+
+impl EffectRecorder {
+    fn record(&self, effect: DeferredEffect) {
+        self.0.lock().expect("Effect recorder is poisoned.").push(effect);
+    }
+
+    pub(crate) fn take(&self) -> Vec<DeferredEffect> {
+        std::mem::take(&mut *self.0.lock().expect("Effect recorder is poisoned."))
+    }
+}
+
+impl EventSink for EffectRecorder {
+    fn on_event(
+        &self,
+        _tx_info: &TransactionInfo,
+        caller_address: ContractAddress,
+        event: &EmittedEvent,
+    ) {
+        self.record(DeferredEffect::Event { caller_address, event: event.clone() });
+    }
+}
+
+impl TransactionExecutorHooks for EffectRecorder {
+    fn before_validate(
+        &self,
+        _tx_context: &TransactionContext,
+        _state: &dyn StateReader,
+    ) -> TransactionExecutionResult<()> {
+        self.record(DeferredEffect::BeforeValidate);
+        Ok(())
+    }
+
+    fn after_execute(
+        &self,
+        _tx_context: &TransactionContext,
+        _state: &dyn StateReader,
+        _execute_call_info: Option<&CallInfo>,
+    ) {
+        self.record(DeferredEffect::AfterExecute);
+    }
+
+    fn before_fee_transfer(
+        &self,
+        _tx_context: &TransactionContext,
+        _state: &dyn StateReader,
+        actual_fee: Fee,
+    ) {
+        self.record(DeferredEffect::BeforeFeeTransfer { actual_fee });
+    }
+}
+
+/// Replays the deferred effects of a committed transaction, other than `before_validate`, which
+/// is to be invoked by the caller against the state preceding the transaction.
+/// `after_execute` is passed the execute call info of the transaction's execution info; [None] if
+/// the transaction failed.
+pub(crate) fn replay_deferred_effects(
+    deferred_effects: impl IntoIterator<Item = DeferredEffect>,
+    tx_context: &TransactionContext,
+    hooks: &dyn TransactionExecutorHooks,
+    state: &dyn StateReader,
+    execute_call_info: Option<&CallInfo>,
+) {
+    for effect in deferred_effects {
+        match effect {
+            DeferredEffect::BeforeValidate => {}
+            DeferredEffect::Event { caller_address, event } => {
+                if let Some(event_sink) = &tx_context.block_context.hooks.event_sink {
+                    event_sink.on_event(&tx_context.tx_info, caller_address, &event);
+                }
+            }
+            DeferredEffect::AfterExecute => {
+                hooks.after_execute(tx_context, state, execute_call_info);
+            }
+            DeferredEffect::BeforeFeeTransfer { actual_fee } => {
+                hooks.before_fee_transfer(tx_context, state, actual_fee);
+            }
+        }
+    }
+}
+
+/// The state entries accessed by a partition.
+struct PartitionAccesses {
+    reads: StateMaps,
+    writes: StateMaps,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum StateEntry {
+    Storage(StorageEntry),
+    Nonce(ContractAddress),
+    ClassHash(ContractAddress),
+    CompiledClassHash(ClassHash),
+    DeclaredContract(ClassHash),
+}
+
+fn state_entries(state_maps: &StateMaps) -> impl Iterator<Item = StateEntry> + '_ {
+    let storage = state_maps.storage.keys().map(|&entry| StateEntry::Storage(entry));
+    let nonces = state_maps.nonces.keys().map(|&address| StateEntry::Nonce(address));
+    let class_hashes =
+        state_maps.class_hashes.keys().map(|&address| StateEntry::ClassHash(address));
+    let compiled_class_hashes = state_maps
+        .compiled_class_hashes
+        .keys()
+        .map(|&class_hash| StateEntry::CompiledClassHash(class_hash));
+    let declared_contracts = state_maps
+        .declared_contracts
+        .keys()
+        .map(|&class_hash| StateEntry::DeclaredContract(class_hash));
+
+    storage.chain(nonces).chain(class_hashes).chain(compiled_class_hashes).chain(declared_contracts)
+}
+
+/// Splits the chunk into partitions of transactions with disjoint footprints, where the footprint
+/// of a transaction is its sender along with its declared targets, i.e., the contracts it is
+/// expected to access. The transactions keep their relative order within each partition.
+pub fn partition_by_footprint(
+    chunk: &[Transaction],
+    declared_targets: &[Vec<ContractAddress>],
+) -> Vec<Vec<TxIndex>> {
+    // Union-find over the transaction indices; transactions sharing an address are united.
+    let mut parents: Vec<TxIndex> = (0..chunk.len()).collect();
+    let mut address_owners = HashMap::<ContractAddress, TxIndex>::new();
+    for (tx_index, tx) in chunk.iter().enumerate() {
+        let sender_address = tx.create_tx_info().sender_address();
+        let targets = declared_targets.get(tx_index).into_iter().flatten();
+        for &address in iter::once(&sender_address).chain(targets) {
+            match address_owners.entry(address) {
+                Entry::Vacant(entry) => {
+                    entry.insert(tx_index);
+                }
+                Entry::Occupied(entry) => {
+                    let (owner_root, tx_root) =
+                        (find_root(&mut parents, *entry.get()), find_root(&mut parents, tx_index));
+                    parents[tx_root.max(owner_root)] = tx_root.min(owner_root);
+                }
+            }
+        }
+    }
+
+    let mut partitions = Vec::<Vec<TxIndex>>::new();
+    let mut root_to_partition = HashMap::<TxIndex, usize>::new();
+    for tx_index in 0..chunk.len() {
+        let root = find_root(&mut parents, tx_index);
+        let partition_index = *root_to_partition.entry(root).or_insert_with(|| {
+            partitions.push(Vec::new());
+            partitions.len() - 1
+        });
+        partitions[partition_index].push(tx_index);
+    }
+
+    partitions
+}
+
+fn find_root(parents: &mut [TxIndex], mut tx_index: TxIndex) -> TxIndex {
+    while parents[tx_index] != tx_index {
+        parents[tx_index] = parents[parents[tx_index]];
+        tx_index = parents[tx_index];
+    }
+    tx_index
+}
+
+/// Executes the partitions of the chunk fully in parallel, each on its own view of the given
+/// state, which is not modified.
+/// The block context must be in concurrency mode, so that fee transfers do not access the fee
+/// recipient balances; the fees should be added to these balances when committing the outputs.
+/// The hooks and events of the transactions are deferred to their commit; see [`DeferredEffect`].
+/// Returns [None] if the partitions turned out to be dependent, i.e., a partition accessed a state
+/// entry written by another one, or accessed the balance of a fee recipient.
+pub fn execute_partitions<S: StateReader + Send>(
+    state: S,
+    chunk: &[Transaction],
+    partitions: &[Vec<TxIndex>],
+    block_context: &BlockContext,
+    charge_fee: bool,
+) -> Option<Vec<PartitionedTxOutput>> {
+    assert!(block_context.concurrency_mode, "Partitioned execution requires concurrency mode.");
+    let versioned_state = ThreadSafeVersionedState::new(VersionedState::new(state));
+
+    let partition_outputs: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = partitions
+            .iter()
+            .map(|partition| {
+                let versioned_state = &versioned_state;
+                scope.spawn(move || {
+                    execute_partition(versioned_state, chunk, partition, block_context, charge_fee)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Partition execution panicked."))
+            .collect()
+    });
+
+    let fee_recipient_balances: HashSet<StorageEntry> = chunk
+        .iter()
+        .flat_map(|tx| {
+            let tx_context = block_context.to_tx_context(tx);
+            let (balance_key_low, balance_key_high) =
+                get_sequencer_balance_keys(tx_context.fee_recipient());
+            let fee_token_address = tx_context.fee_token_address();
+            [(fee_token_address, balance_key_low), (fee_token_address, balance_key_high)]
+        })
+        .collect();
+    let accesses: Vec<_> = partition_outputs.iter().map(|(accesses, _)| accesses).collect();
+    if !are_independent(&accesses, &fee_recipient_balances) {
+        return None;
+    }
+
+    let mut tx_outputs: Vec<Option<PartitionedTxOutput>> =
+        iter::repeat_with(|| None).take(chunk.len()).collect();
+    for (_, partition_tx_outputs) in partition_outputs {
+        for (tx_index, tx_output) in partition_tx_outputs {
+            tx_outputs[tx_index] = Some(tx_output);
+        }
+    }
+    Some(
+        tx_outputs
+            .into_iter()
+            .map(|tx_output| tx_output.expect("Every transaction belongs to a partition."))
+            .collect(),
+    )
+}
+
+fn execute_partition<S: StateReader>(
+    versioned_state: &ThreadSafeVersionedState<S>,
+    chunk: &[Transaction],
+    partition: &[TxIndex],
+    block_context: &BlockContext,
+    charge_fee: bool,
+) -> (PartitionAccesses, Vec<(TxIndex, PartitionedTxOutput)>) {
+    // All partitions read the state as it was before the chunk.
+    let mut partition_state = CachedState::new(versioned_state.pin_version(0));
+    let validate = true;
+    let recorder = Arc::new(EffectRecorder::default());
+    let mut block_context = block_context.clone();
+    if block_context.hooks.event_sink.is_some() {
+        block_context.hooks.event_sink = Some(recorder.clone());
+    }
+
+    let mut tx_outputs = Vec::with_capacity(partition.len());
+    for &tx_index in partition {
+        let mut transactional_state = CachedState::create_transactional(&mut partition_state);
+        let execution_start = Instant::now();
+        let result = chunk[tx_index].execute_raw_with_hooks(
+            &mut transactional_state,
+            &block_context,
+            charge_fee,
+            validate,
+            recorder.as_ref(),
+        );
+        let execution_duration = execution_start.elapsed();
+        let deferred_effects = recorder.take();
+
+        // In case of a failed transaction, we don't record its writes and visited pcs.
+        let tx_output = if result.is_ok() {
+            let tx_output = PartitionedTxOutput {
+                writes: transactional_state.cache.borrow().writes.clone(),
+                class_hash_to_class: transactional_state.class_hash_to_class.borrow().clone(),
+                visited_pcs: transactional_state.visited_pcs.clone(),
+                execution_duration,
+                deferred_effects,
+                result,
+            };
+            transactional_state.commit();
+            tx_output
+        } else {
+            transactional_state.abort();
+            PartitionedTxOutput {
+                writes: StateMaps::default(),
+                class_hash_to_class: ContractClassMapping::default(),
+                visited_pcs: HashMap::default(),
+                execution_duration,
+                deferred_effects,
+                result,
+            }
+        };
+        tx_outputs.push((tx_index, tx_output));
+    }
+
+    let cache = partition_state.cache.into_inner();
+    (PartitionAccesses { reads: cache.initial_reads, writes: cache.writes }, tx_outputs)
+}
+
+/// Returns whether no partition accessed a state entry written by another partition, or one of
+/// the reserved storage entries.
+fn are_independent(
+    accesses: &[&PartitionAccesses],
+    reserved_storage: &HashSet<StorageEntry>,
+) -> bool {
+    let mut writers = HashMap::<StateEntry, usize>::new();
+    for (partition_index, partition_accesses) in accesses.iter().enumerate() {
+        for entry in state_entries(&partition_accesses.writes) {
+            writers.insert(entry, partition_index);
+        }
+    }
+
+    accesses.iter().enumerate().all(|(partition_index, partition_accesses)| {
+        state_entries(&partition_accesses.reads)
+            .chain(state_entries(&partition_accesses.writes))
+            .all(|entry| {
+                let is_reserved = matches!(
+                    entry, StateEntry::Storage(storage_entry)
+                    if reserved_storage.contains(&storage_entry)
+                );
+                let written_by_other = writers
+                    .get(&entry)
+                    .is_some_and(|&writer_index| writer_index != partition_index);
+                !is_reserved && !written_by_other
+            })
+    })
+}
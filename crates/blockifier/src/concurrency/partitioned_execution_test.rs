@@ -0,0 +1,203 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::ContractAddress;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::{Calldata, Fee};
+
+use crate::blockifier::config::TransactionExecutorConfig;
+use crate::blockifier::transaction_executor::transaction_executor_test::CountingHooks;
+use crate::blockifier::transaction_executor::{TransactionExecutor, TransactionExecutorError};
+use crate::bouncer::BouncerConfig;
+use crate::concurrency::partitioned_execution::partition_by_footprint;
+use crate::context::BlockContext;
+use crate::execution::call_info::EmittedEvent;
+use crate::execution::event_sink::EventSink;
+use crate::invoke_tx_args;
+use crate::state::cached_state::CachedState;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, create_trivial_calldata, CairoVersion, BALANCE};
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::TransactionInfo;
+use crate::transaction::test_utils::{account_invoke_tx, block_context};
+use crate::transaction::transaction_execution::Transaction;
+
+const N_ACCOUNTS: u16 = 3;
+
+#[derive(Debug, Default)]
+struct CountingEventSink(AtomicUsize);
+
+impl EventSink for CountingEventSink {
+    fn on_event(
+        &self,
+        _tx_info: &TransactionInfo,
+        _caller_address: ContractAddress,
+        _event: &EmittedEvent,
+    ) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn invoke_tx(sender_address: ContractAddress, calldata: Calldata) -> Transaction {
+    Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        sender_address,
+        calldata,
+        max_fee: Fee(BALANCE),
+    }))
+}
+
+/// Returns an executor with the given hooks, whose events are counted by the given sink.
+fn test_executor(
+    block_context: &BlockContext,
+    hooks: Arc<CountingHooks>,
+    event_sink: Arc<CountingEventSink>,
+) -> TransactionExecutor<DictStateReader> {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state: CachedState<DictStateReader> = test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(account, N_ACCOUNTS), (test_contract, 1)],
+    );
+    let mut block_context = block_context.clone();
+    block_context.hooks_mut().event_sink = Some(event_sink);
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::create_for_testing(),
+        TransactionExecutorConfig::default(),
+    );
+    tx_executor.hooks = hooks;
+    tx_executor
+}
+
+#[rstest]
+fn test_partition_by_footprint() {
+    let addresses: Vec<ContractAddress> =
+        (1_u8..=4).map(|i| ContractAddress::from(u64::from(i))).collect();
+    let txs: Vec<_> =
+        [0, 1, 0, 2, 3].into_iter().map(|i| invoke_tx(addresses[i], Calldata::default())).collect();
+    // The fourth transaction targets the sender of the second one.
+    let declared_targets = vec![vec![], vec![], vec![], vec![addresses[1]], vec![]];
+
+    assert_eq!(
+        partition_by_footprint(&txs, &declared_targets),
+        vec![vec![0, 2], vec![1, 3], vec![4]]
+    );
+}
+
+#[rstest]
+#[case::independent(false)]
+#[case::dependent(true)]
+fn test_execute_txs_partitioned(block_context: BlockContext, #[case] write_shared_storage: bool) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract_address =
+        FeatureContract::TestContract(CairoVersion::Cairo0).get_instance_address(0);
+    // Unless writing to the shared storage, the test contract is only read from.
+    let calldata = if write_shared_storage {
+        create_calldata(
+            test_contract_address,
+            "test_storage_read_write",
+            &[stark_felt!(1_u8), stark_felt!(2_u8)],
+        )
+    } else {
+        create_trivial_calldata(test_contract_address)
+    };
+    let txs: Vec<_> = (0..N_ACCOUNTS)
+        .map(|i| invoke_tx(account.get_instance_address(i), calldata.clone()))
+        .collect();
+    let declared_targets = vec![vec![]; txs.len()];
+
+    let expected_hooks = Arc::new(CountingHooks::default());
+    let expected_event_sink = Arc::new(CountingEventSink::default());
+    let mut sequential_executor = test_executor(
+        &block_context,
+        Arc::clone(&expected_hooks),
+        Arc::clone(&expected_event_sink),
+    );
+    let expected_results = sequential_executor.execute_txs_sequentially(&txs, true);
+    let hooks = Arc::new(CountingHooks::default());
+    let event_sink = Arc::new(CountingEventSink::default());
+    let mut partitioned_executor =
+        test_executor(&block_context, Arc::clone(&hooks), Arc::clone(&event_sink));
+    let results = partitioned_executor.execute_txs_partitioned(&txs, &declared_targets, true);
+
+    assert_eq!(results.len(), expected_results.len());
+    for (result, expected_result) in results.into_iter().zip(expected_results) {
+        let (mut tx_execution_info, mut expected_tx_execution_info) =
+            (result.unwrap(), expected_result.unwrap());
+        // Execution durations differ between the runs.
+        tx_execution_info.metadata.as_mut().unwrap().execution_duration = Default::default();
+        expected_tx_execution_info.metadata.as_mut().unwrap().execution_duration =
+            Default::default();
+        assert_eq!(tx_execution_info, expected_tx_execution_info);
+    }
+    assert_eq!(
+        partitioned_executor.bouncer.get_accumulated_weights(),
+        sequential_executor.bouncer.get_accumulated_weights()
+    );
+    let (state_diff, _) = partitioned_executor.finalize().unwrap();
+    let (expected_state_diff, _) = sequential_executor.finalize().unwrap();
+    assert_eq!(state_diff, expected_state_diff);
+
+    // The hooks and the event sink only observe the committed execution of each transaction.
+    let n_txs = usize::from(N_ACCOUNTS);
+    assert_eq!(expected_hooks.counts(), [n_txs; 4]);
+    assert_eq!(hooks.counts(), expected_hooks.counts());
+    assert!(expected_event_sink.0.load(Ordering::Relaxed) > 0);
+    assert_eq!(event_sink.0.load(Ordering::Relaxed), expected_event_sink.0.load(Ordering::Relaxed));
+}
+
+/// A transaction that is executed within its partition, but then fails to be committed, causes the
+/// later transactions to be executed sequentially.
+#[rstest]
+fn test_partitioned_tx_commit_failure(block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract_address =
+        FeatureContract::TestContract(CairoVersion::Cairo0).get_instance_address(0);
+    let txs: Vec<_> = (0..N_ACCOUNTS)
+        .map(|i| {
+            invoke_tx(
+                account.get_instance_address(i),
+                create_trivial_calldata(test_contract_address),
+            )
+        })
+        .collect();
+    let declared_targets = vec![vec![]; txs.len()];
+    // The first transaction is rejected by the hooks, which are only invoked on commit.
+    let new_hooks = || {
+        Arc::new(CountingHooks {
+            rejected_sender: Some(account.get_instance_address(0)),
+            ..Default::default()
+        })
+    };
+
+    let expected_hooks = new_hooks();
+    let mut sequential_executor =
+        test_executor(&block_context, Arc::clone(&expected_hooks), Default::default());
+    let expected_results = sequential_executor.execute_txs_sequentially(&txs, true);
+    let hooks = new_hooks();
+    let mut partitioned_executor =
+        test_executor(&block_context, Arc::clone(&hooks), Default::default());
+    let results = partitioned_executor.execute_txs_partitioned(&txs, &declared_targets, true);
+
+    assert_eq!(results.len(), expected_results.len());
+    assert_matches!(
+        &results[0],
+        Err(TransactionExecutorError::TransactionExecutionError(
+            TransactionExecutionError::TransactionRejectedByHooks { .. }
+        ))
+    );
+    assert!(results[1..].iter().all(Result::is_ok));
+    assert_eq!(hooks.counts(), expected_hooks.counts());
+    assert_eq!(partitioned_executor.n_committed_txs, sequential_executor.n_committed_txs);
+    let (state_diff, _) = partitioned_executor.finalize().unwrap();
+    let (expected_state_diff, _) = sequential_executor.finalize().unwrap();
+    assert_eq!(state_diff, expected_state_diff);
+}
@@ -55,6 +55,9 @@ impl Scheduler {
         let index_to_execute = self.execution_index.load(Ordering::Acquire);
 
         if min(index_to_validate, index_to_execute) >= self.chunk_size {
+            // All transactions may have been executed and validated; the chunk is done once no
+            // task is active.
+            self.check_done();
             return Task::NoTask;
         }
 
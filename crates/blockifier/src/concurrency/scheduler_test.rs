@@ -118,6 +118,23 @@ fn test_next_task(
     assert_eq!(scheduler.n_active_tasks.load(Ordering::Acquire), expected_n_active_tasks);
 }
 
+#[rstest]
+#[case::done(0, true)]
+#[case::active_tasks(1, false)]
+fn test_next_task_checks_done(#[case] n_active_tasks: usize, #[case] expected_done: bool) {
+    // All transactions were executed and validated.
+    let scheduler = default_scheduler!(
+        chunk_size: DEFAULT_CHUNK_SIZE,
+        execution_index: DEFAULT_CHUNK_SIZE,
+        validation_index: DEFAULT_CHUNK_SIZE,
+        n_active_tasks: n_active_tasks
+    );
+    assert_eq!(scheduler.next_task(), Task::NoTask);
+    assert_eq!(scheduler.done_marker.load(Ordering::Acquire), expected_done);
+    let expected_next_task = if expected_done { Task::Done } else { Task::NoTask };
+    assert_eq!(scheduler.next_task(), expected_next_task);
+}
+
 #[rstest]
 #[case::happy_flow(TransactionStatus::Executing)]
 #[should_panic(expected = "Only executing transactions can gain status executed. Transaction 0 \
@@ -27,6 +27,8 @@ pub struct VersionedState<S: StateReader> {
     nonces: VersionedStorage<ContractAddress, Nonce>,
     class_hashes: VersionedStorage<ContractAddress, ClassHash>,
     compiled_class_hashes: VersionedStorage<ClassHash, CompiledClassHash>,
+    /// Each class is read from the initial state (and thus deserialized, for readers that store
+    /// raw classes) at most once per chunk; all workers share the program of the read class.
     compiled_contract_classes: VersionedStorage<ClassHash, ContractClass>,
 }
 
@@ -237,6 +239,8 @@ impl<S: StateReader> StateReader for VersionedStateProxy<S> {
         match state.compiled_contract_classes.read(self.tx_index, class_hash) {
             Some(value) => Ok(value),
             None => {
+                // The state is locked while the class is read, so concurrent readers of the same
+                // class wait for it rather than read it again.
                 let initial_value = state.initial_state.get_compiled_contract_class(class_hash)?;
                 state
                     .compiled_contract_classes
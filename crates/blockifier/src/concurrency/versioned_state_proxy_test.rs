@@ -1,10 +1,14 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 
 use rstest::{fixture, rstest};
-use starknet_api::core::{calculate_contract_address, ClassHash, ContractAddress, PatriciaKey};
+use starknet_api::core::{
+    calculate_contract_address, ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey,
+};
 use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
 use starknet_api::transaction::{Calldata, ContractAddressSalt, Fee, TransactionVersion};
 use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
 
@@ -14,8 +18,9 @@ use crate::concurrency::versioned_state_proxy::{
     ThreadSafeVersionedState, VersionedState, VersionedStateProxy,
 };
 use crate::context::BlockContext;
+use crate::execution::contract_class::ContractClass;
 use crate::state::cached_state::{CachedState, StateMaps};
-use crate::state::state_api::{State, StateReader};
+use crate::state::state_api::{State, StateReader, StateResult};
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::deploy_account::deploy_account_tx;
 use crate::test_utils::dict_state_reader::DictStateReader;
@@ -371,3 +376,79 @@ fn test_apply_writes_reexecute_scenario(
     // The class hash should be updated.
     assert!(transactional_states[1].get_class_hash_at(contract_address).unwrap() == class_hash_0);
 }
+
+/// A state reader that counts the contract classes read from it.
+#[derive(Default)]
+struct ClassReadCountingReader {
+    state_reader: DictStateReader,
+    n_class_reads: AtomicUsize,
+}
+
+impl StateReader for ClassReadCountingReader {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        self.state_reader.get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.state_reader.get_nonce_at(contract_address)
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.state_reader.get_class_hash_at(contract_address)
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.n_class_reads.fetch_add(1, Ordering::Relaxed);
+        self.state_reader.get_compiled_contract_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.state_reader.get_compiled_class_hash(class_hash)
+    }
+}
+
+#[rstest]
+fn test_contract_class_shared_across_threads(class_hash: ClassHash) {
+    let contract_class = FeatureContract::TestContract(CairoVersion::Cairo1).get_class();
+    let state_reader = ClassReadCountingReader {
+        state_reader: DictStateReader {
+            class_hash_to_class: HashMap::from([(class_hash, contract_class)]),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let safe_versioned_state = ThreadSafeVersionedState::new(VersionedState::new(state_reader));
+
+    let n_threads = 4;
+    let contract_classes: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..n_threads)
+            .map(|tx_index| {
+                let safe_versioned_state = &safe_versioned_state;
+                scope.spawn(move || {
+                    let state = CachedState::from(safe_versioned_state.pin_version(tx_index));
+                    state.get_compiled_contract_class(class_hash).unwrap()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+
+    // The class is read once, and its program is shared by all threads.
+    assert_eq!(
+        safe_versioned_state.0.lock().unwrap().initial_state.n_class_reads.load(Ordering::Relaxed),
+        1
+    );
+    let ContractClass::V1(first_class) = &contract_classes[0] else {
+        panic!("Expected a Cairo 1 class.");
+    };
+    for contract_class in &contract_classes {
+        let ContractClass::V1(contract_class) = contract_class else {
+            panic!("Expected a Cairo 1 class.");
+        };
+        assert!(Arc::ptr_eq(&contract_class.0, &first_class.0));
+    }
+}
@@ -1,9 +1,15 @@
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use starknet_api::core::ClassHash;
 
+use crate::blockifier::hooks::TransactionExecutorHooks;
+use crate::concurrency::partitioned_execution::{
+    replay_deferred_effects, DeferredEffect, EffectRecorder,
+};
 use crate::concurrency::scheduler::{Scheduler, Task};
 use crate::concurrency::utils::lock_mutex_in_array;
 use crate::concurrency::versioned_state_proxy::ThreadSafeVersionedState;
@@ -11,15 +17,24 @@ use crate::concurrency::TxIndex;
 use crate::context::BlockContext;
 use crate::state::cached_state::{CachedState, StateMaps};
 use crate::state::state_api::StateReader;
-use crate::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
+use crate::transaction::objects::{
+    TransactionExecutionInfo, TransactionExecutionResult, TransactionMetadata,
+};
 use crate::transaction::transaction_execution::Transaction;
-use crate::transaction::transactions::ExecutableTransaction;
+
+#[cfg(test)]
+#[path = "worker_logic_test.rs"]
+mod test;
 
 #[derive(Debug)]
 pub struct ExecutionTaskOutput {
     pub reads: StateMaps,
     pub writes: StateMaps,
     pub visited_pcs: HashMap<ClassHash, HashSet<usize>>,
+    /// The hook invocations and events of the execution, in order; reported once the transaction
+    /// is committed (see [`WorkerExecutor::commit_tx_effects`]).
+    pub deferred_effects: Vec<DeferredEffect>,
+    pub execution_duration: Duration,
     pub result: TransactionExecutionResult<TransactionExecutionInfo>,
 }
 
@@ -59,9 +74,27 @@ impl<S: StateReader> WorkerExecutor<S> {
         let mut transactional_state = CachedState::create_transactional(&mut tx_state);
         let validate = true;
         let charge_fee = true;
+        // A transaction may be executed more than once; its hooks and events are deferred to its
+        // commit, and the effects of aborted executions are discarded along with their outputs.
+        let recorder = Arc::new(EffectRecorder::default());
+        let block_context = match self.block_context.hooks.event_sink {
+            Some(_) => {
+                let mut block_context = self.block_context.clone();
+                block_context.hooks.event_sink = Some(recorder.clone());
+                Cow::Owned(block_context)
+            }
+            None => Cow::Borrowed(&self.block_context),
+        };
 
-        let execution_result =
-            tx.execute_raw(&mut transactional_state, &self.block_context, charge_fee, validate);
+        let execution_start = Instant::now();
+        let execution_result = tx.execute_raw_with_hooks(
+            &mut transactional_state,
+            &block_context,
+            charge_fee,
+            validate,
+            recorder.as_ref(),
+        );
+        let execution_duration = execution_start.elapsed();
 
         if execution_result.is_ok() {
             let class_hash_to_class = transactional_state.class_hash_to_class.borrow();
@@ -84,11 +117,98 @@ impl<S: StateReader> WorkerExecutor<S> {
             reads: tx_reads_writes.initial_reads,
             writes,
             visited_pcs,
+            deferred_effects: recorder.take(),
+            execution_duration,
             result: execution_result,
         });
     }
 
-    fn validate(&self, _tx_index: TxIndex) -> Task {
-        todo!();
+    /// Commits the transactions of the chunk in order, once all of them were executed and
+    /// validated (i.e., once [`Self::run`] returned): reports their deferred effects (see
+    /// [`Self::commit_tx_effects`]) and returns their results. `n_committed_txs` is the number of
+    /// transactions committed to the block before the chunk.
+    /// Stops after the first executed transaction that is rejected by the hooks, as the later
+    /// transactions may have been executed on top of its changes.
+    pub fn commit_chunk(
+        &self,
+        mut n_committed_txs: usize,
+        hooks: &dyn TransactionExecutorHooks,
+    ) -> Vec<TransactionExecutionResult<TransactionExecutionInfo>> {
+        let mut results = Vec::new();
+        for tx_index in 0..self.chunk.len() {
+            let commit_result = self.commit_tx_effects(tx_index, n_committed_txs, hooks);
+            let execution_output = lock_mutex_in_array(&self.execution_outputs, tx_index)
+                .take()
+                .expect("Output must be ready before commit.");
+            match (commit_result, execution_output.result) {
+                (Ok(()), Ok(tx_execution_info)) => {
+                    n_committed_txs += 1;
+                    results.push(Ok(tx_execution_info));
+                }
+                (Ok(()), Err(error)) => results.push(Err(error)),
+                (Err(error), executed) => {
+                    results.push(Err(error));
+                    if executed.is_ok() {
+                        break;
+                    }
+                }
+            }
+        }
+        results
+    }
+
+    /// Reports the deferred hook invocations and events of the given transaction (see
+    /// [`DeferredEffect`]), and sets its metadata, given the number of transactions committed to
+    /// the block before it; to be called exactly once per transaction, when it is committed.
+    /// `before_validate` is invoked against the state preceding the transaction, and may reject
+    /// it, in which case no other hook is invoked; the other hooks are invoked against the state
+    /// following it.
+    pub fn commit_tx_effects(
+        &self,
+        tx_index: TxIndex,
+        n_committed_txs: usize,
+        hooks: &dyn TransactionExecutorHooks,
+    ) -> TransactionExecutionResult<()> {
+        let tx = &self.chunk[tx_index];
+        let tx_context = self.block_context.to_tx_context(tx);
+        let mut execution_output = lock_mutex_in_array(&self.execution_outputs, tx_index);
+        let execution_output =
+            execution_output.as_mut().expect("Output must be ready before commit.");
+        let deferred_effects = std::mem::take(&mut execution_output.deferred_effects);
+        if deferred_effects.first() == Some(&DeferredEffect::BeforeValidate) {
+            hooks.before_validate(&tx_context, &self.state.pin_version(tx_index))?;
+        }
+
+        let committed_state = self.state.pin_version(tx_index + 1);
+        let execution_duration = execution_output.execution_duration;
+        let tx_execution_info = execution_output.result.as_mut().ok();
+        replay_deferred_effects(
+            deferred_effects,
+            &tx_context,
+            hooks,
+            &committed_state,
+            tx_execution_info
+                .as_ref()
+                .and_then(|tx_execution_info| tx_execution_info.execute_call_info.as_ref()),
+        );
+        if let Some(tx_execution_info) = tx_execution_info {
+            tx_execution_info.metadata = Some(TransactionMetadata {
+                tx_hash: tx_context.tx_info.transaction_hash(),
+                tx_index: n_committed_txs,
+                execution_duration,
+            });
+            hooks.after_commit(tx, &committed_state, tx_execution_info, execution_duration);
+        }
+        Ok(())
+    }
+
+    fn validate(&self, tx_index: TxIndex) -> Task {
+        let tx_versioned_state = self.state.pin_version(tx_index);
+        let execution_output = lock_mutex_in_array(&self.execution_outputs, tx_index);
+        let reads =
+            &execution_output.as_ref().expect("Output must be ready before validation.").reads;
+        let reads_valid = tx_versioned_state.validate_reads(reads);
+        let aborted = !reads_valid && self.scheduler.try_validation_abort(tx_index);
+        self.scheduler.finish_validation(tx_index, aborted)
     }
 }
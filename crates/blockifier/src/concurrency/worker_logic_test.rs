@@ -0,0 +1,168 @@
+use std::sync::Mutex;
+
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::transaction::Fee;
+
+use crate::blockifier::transaction_executor::transaction_executor_test::CountingHooks;
+use crate::concurrency::scheduler::{Scheduler, Task};
+use crate::concurrency::test_utils::safe_versioned_state_for_testing;
+use crate::concurrency::worker_logic::WorkerExecutor;
+use crate::context::BlockContext;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state_reader;
+use crate::test_utils::{create_trivial_calldata, CairoVersion, BALANCE, MAX_FEE};
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::test_utils::account_invoke_tx;
+use crate::transaction::transaction_execution::Transaction;
+use crate::{invoke_tx_args, nonce};
+
+#[rstest]
+fn test_commit_tx_effects(#[values(false, true)] reject: bool) {
+    let block_context = BlockContext::create_for_account_testing_with_concurrency_mode(true);
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state =
+        test_state_reader(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let sender_address = account.get_instance_address(0);
+    let tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        sender_address,
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        max_fee: Fee(BALANCE),
+    }));
+    let worker_executor = WorkerExecutor {
+        scheduler: Scheduler::new(1),
+        state: safe_versioned_state_for_testing(state),
+        chunk: Box::new([tx]),
+        execution_outputs: Box::new([Mutex::new(None)]),
+        block_context,
+    };
+    let hooks = CountingHooks {
+        rejected_sender: if reject { Some(sender_address) } else { None },
+        ..Default::default()
+    };
+
+    // The hooks are not invoked by the executions, and only the last one is reported on commit.
+    worker_executor.execute_tx(0);
+    worker_executor.execute_tx(0);
+    assert_eq!(hooks.counts(), [0, 0, 0, 0]);
+
+    let result = worker_executor.commit_tx_effects(0, 0, &hooks);
+    if reject {
+        assert_matches!(
+            result.unwrap_err(),
+            TransactionExecutionError::TransactionRejectedByHooks { .. }
+        );
+        assert_eq!(hooks.counts(), [1, 0, 0, 0]);
+    } else {
+        result.unwrap();
+        assert_eq!(hooks.counts(), [1, 1, 1, 1]);
+    }
+}
+
+#[test]
+fn test_validate() {
+    let block_context = BlockContext::create_for_account_testing_with_concurrency_mode(true);
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state =
+        test_state_reader(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let sender_address = account.get_instance_address(0);
+    let chunk: Box<[Transaction]> = (0_u8..2)
+        .map(|nonce| {
+            Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+                sender_address,
+                calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+                max_fee: Fee(MAX_FEE),
+                nonce: nonce!(nonce),
+            }))
+        })
+        .collect();
+    let worker_executor = WorkerExecutor {
+        scheduler: Scheduler::new(2),
+        state: safe_versioned_state_for_testing(state),
+        chunk,
+        execution_outputs: std::iter::repeat_with(|| Mutex::new(None)).take(2).collect(),
+        block_context,
+    };
+
+    // The second transaction is executed before the first one, reading the initial nonce.
+    assert_eq!(worker_executor.scheduler.next_task(), Task::ExecutionTask(0));
+    assert_eq!(worker_executor.scheduler.next_task(), Task::ExecutionTask(1));
+    worker_executor.execute(1);
+    worker_executor.execute(0);
+    assert_eq!(worker_executor.scheduler.next_task(), Task::ValidationTask(0));
+    assert_eq!(worker_executor.validate(0), Task::NoTask);
+
+    // Its read set is invalidated by the first transaction; it is aborted and re-executed.
+    assert_eq!(worker_executor.scheduler.next_task(), Task::ValidationTask(1));
+    assert_eq!(worker_executor.validate(1), Task::ExecutionTask(1));
+    worker_executor.execute(1);
+    assert_eq!(worker_executor.scheduler.next_task(), Task::ValidationTask(1));
+    assert_eq!(worker_executor.validate(1), Task::NoTask);
+
+    // Both transactions are now executed successfully and validated; the chunk is done.
+    for tx_index in 0..2 {
+        let execution_output = worker_executor.execution_outputs[tx_index].lock().unwrap();
+        assert!(execution_output.as_ref().unwrap().result.is_ok());
+    }
+    assert_eq!(worker_executor.scheduler.next_task(), Task::NoTask);
+    assert_eq!(worker_executor.scheduler.next_task(), Task::Done);
+}
+
+#[rstest]
+fn test_run_and_commit_chunk(#[values(false, true)] reject: bool) {
+    let block_context = BlockContext::create_for_account_testing_with_concurrency_mode(true);
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state =
+        test_state_reader(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let sender_address = account.get_instance_address(0);
+    let chunk: Box<[Transaction]> = (0_u8..3)
+        .map(|nonce| {
+            Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+                sender_address,
+                calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+                max_fee: Fee(MAX_FEE),
+                nonce: nonce!(nonce),
+            }))
+        })
+        .collect();
+    let chunk_size = chunk.len();
+    let worker_executor = WorkerExecutor {
+        scheduler: Scheduler::new(chunk_size),
+        state: safe_versioned_state_for_testing(state),
+        chunk,
+        execution_outputs: std::iter::repeat_with(|| Mutex::new(None)).take(chunk_size).collect(),
+        block_context,
+    };
+    let hooks = CountingHooks {
+        rejected_sender: if reject { Some(sender_address) } else { None },
+        ..Default::default()
+    };
+
+    // The hooks are only invoked on commit, once per transaction.
+    worker_executor.run();
+    assert_eq!(hooks.counts(), [0, 0, 0, 0]);
+
+    let n_committed_txs = 5;
+    let results = worker_executor.commit_chunk(n_committed_txs, &hooks);
+    if reject {
+        // The later transactions were executed on top of the rejected one, and are not committed.
+        assert_eq!(results.len(), 1);
+        assert_matches!(
+            results[0].as_ref().unwrap_err(),
+            TransactionExecutionError::TransactionRejectedByHooks { .. }
+        );
+        assert_eq!(hooks.counts(), [1, 0, 0, 0]);
+    } else {
+        assert_eq!(results.len(), chunk_size);
+        for (tx_index, result) in results.into_iter().enumerate() {
+            let metadata = result.unwrap().metadata.unwrap();
+            assert_eq!(metadata.tx_index, n_committed_txs + tx_index);
+            assert!(!metadata.execution_duration.is_zero());
+        }
+        assert_eq!(hooks.counts(), [chunk_size; 4]);
+    }
+}
@@ -1,10 +1,31 @@
-use starknet_api::core::{ChainId, ContractAddress};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
-use crate::blockifier::block::BlockInfo;
+use starknet_api::core::{ChainId, ClassHash, ContractAddress};
+use starknet_api::transaction::Fee;
+
+use crate::blockifier::block::{BlockInfo, GasPriceOracle, GasPrices};
+use crate::execution::call_info::TrackedResource;
+use crate::execution::custom_hints::{CustomHint, CustomHints};
+use crate::execution::entry_point::ExecutionInfoOverrides;
+use crate::execution::errors::{CustomHintError, TraceTruncation};
+use crate::execution::event_sink::EventSink;
+#[cfg(feature = "native_execution")]
+use crate::execution::native_execution::NativeExecutor;
+use crate::execution::syscall_interceptor::SyscallInterceptor;
+use crate::execution::visited_pcs::VisitedPcsObserver;
+#[cfg(feature = "vm_tracer")]
+use crate::execution::vm_tracer::VmTracer;
+use crate::fee::fee_checks::{FeeChargeFailurePolicy, RevertedTxFeePolicy};
+use crate::fee::fee_estimation::FeeEstimateMargin;
+use crate::fee::fee_utils::Rounding;
+use crate::transaction::errors::SizeLimitError;
 use crate::transaction::objects::{
     FeeType, HasRelatedFeeType, TransactionInfo, TransactionInfoCreator,
 };
-use crate::versioned_constants::VersionedConstants;
+use crate::versioned_constants::{
+    EventLimits, ResourceCost, VersionedConstants, VersionedConstantsError,
+};
 
 /// Create via [`crate::blockifier::block::pre_process_block`] to ensure correctness.
 #[derive(Clone, Debug)]
@@ -17,6 +38,47 @@ impl TransactionContext {
     pub fn fee_token_address(&self) -> ContractAddress {
         self.block_context.chain_info.fee_token_address(&self.tx_info.fee_type())
     }
+
+    /// The address the transaction's fee is transferred to.
+    pub fn fee_recipient(&self) -> ContractAddress {
+        self.tx_info.fee_recipient().unwrap_or(self.block_context.block_info.sequencer_address)
+    }
+
+    /// The sponsor paying the transaction's fee, if its sender is sponsored; see
+    /// [`FeePaymentMode::TransferFrom`].
+    pub fn fee_sponsor(&self) -> Option<ContractAddress> {
+        self.block_context.fee_policy.fee_payment_mode.sponsor_of(self.tx_info.sender_address())
+    }
+
+    /// The address the transaction's fee is transferred from.
+    pub fn fee_payer(&self) -> ContractAddress {
+        self.fee_sponsor().unwrap_or(self.tx_info.sender_address())
+    }
+
+    /// Whether the fee transfer is executed in concurrency mode, i.e., without updating the
+    /// balance of the fee recipient, which is completed at the commit stage.
+    /// Transfers from a sponsor are executed as is, as their storage accesses differ.
+    pub fn is_concurrent_fee_transfer(&self) -> bool {
+        self.block_context.concurrency_mode
+            && self.fee_recipient() != self.tx_info.sender_address()
+            && self.fee_sponsor().is_none()
+    }
+
+    /// The Sierra gas the transaction starts with: the initial gas defined by the versioned
+    /// constants, capped by the L2 gas limit of transactions with all resource bounds.
+    pub fn initial_gas(&self) -> u64 {
+        let initial_gas = self.block_context.versioned_constants.tx_initial_gas();
+        self.tx_info.l2_gas_limit().map_or(initial_gas, |limit| limit.min(initial_gas))
+    }
+
+    /// Returns the L2 gas to charge for the given Sierra gas consumption; only transactions with
+    /// all resource bounds are charged for L2 gas.
+    pub fn charged_l2_gas(&self, consumed_gas: u64) -> u64 {
+        match self.tx_info.l2_gas_limit() {
+            Some(_) => consumed_gas,
+            None => 0,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -25,6 +87,9 @@ pub struct BlockContext {
     pub(crate) chain_info: ChainInfo,
     pub(crate) versioned_constants: VersionedConstants,
     pub(crate) concurrency_mode: bool,
+    pub(crate) fee_policy: FeePolicy,
+    pub(crate) execution_policy: ExecutionPolicy,
+    pub(crate) hooks: ExtensionHooks,
 }
 
 impl BlockContext {
@@ -41,6 +106,9 @@ impl BlockContext {
             chain_info: chain_info.clone(),
             versioned_constants: versioned_constants.clone(),
             concurrency_mode: false,
+            fee_policy: FeePolicy::default(),
+            execution_policy: ExecutionPolicy::default(),
+            hooks: ExtensionHooks::default(),
         }
     }
 
@@ -59,6 +127,104 @@ impl BlockContext {
     pub fn concurrency_mode(&self) -> bool {
         self.concurrency_mode
     }
+
+    pub fn fee_policy(&self) -> &FeePolicy {
+        &self.fee_policy
+    }
+
+    pub fn fee_policy_mut(&mut self) -> &mut FeePolicy {
+        &mut self.fee_policy
+    }
+
+    pub fn execution_policy(&self) -> &ExecutionPolicy {
+        &self.execution_policy
+    }
+
+    pub fn execution_policy_mut(&mut self) -> &mut ExecutionPolicy {
+        &mut self.execution_policy
+    }
+
+    pub fn hooks(&self) -> &ExtensionHooks {
+        &self.hooks
+    }
+
+    pub fn hooks_mut(&mut self) -> &mut ExtensionHooks {
+        &mut self.hooks
+    }
+
+    /// The event limits in effect: the overridden ones if set, or those of the versioned
+    /// constants.
+    pub fn event_limits(&self) -> EventLimits {
+        self.execution_policy
+            .size_limits
+            .event_limits
+            .unwrap_or(self.versioned_constants.tx_event_limits)
+    }
+
+    /// Registers an implementation of the given hint, executed instead of the implementation of
+    /// the hint processors; the hint must be allowed by the chain.
+    pub fn register_custom_hint(
+        &mut self,
+        hint_code: String,
+        custom_hint: Arc<dyn CustomHint>,
+    ) -> Result<(), CustomHintError> {
+        if !self.chain_info.allowed_custom_hints.contains(&hint_code) {
+            return Err(CustomHintError::HintNotAllowed { hint_code });
+        }
+
+        self.hooks.custom_hints.insert(hint_code, custom_hint);
+        Ok(())
+    }
+
+    /// Overrides the fee costs of the given builtins, e.g., for provers that price builtins
+    /// differently; the overridden builtins must be priced by the block's versioned constants.
+    pub fn set_builtin_gas_cost_overrides(
+        &mut self,
+        overrides: &HashMap<String, ResourceCost>,
+    ) -> Result<(), VersionedConstantsError> {
+        self.versioned_constants =
+            self.versioned_constants.with_builtin_gas_cost_overrides(overrides)?;
+        Ok(())
+    }
+
+    /// Overrides the gas costs of the given syscalls (or other OS gas costs, by their names in
+    /// [`GasCosts`](crate::versioned_constants::GasCosts)), e.g., for experimenting with repricing
+    /// without forking the constants.
+    pub fn set_gas_cost_overrides(
+        &mut self,
+        overrides: &HashMap<String, u64>,
+    ) -> Result<(), VersionedConstantsError> {
+        self.versioned_constants = self.versioned_constants.with_gas_cost_overrides(overrides)?;
+        Ok(())
+    }
+
+    /// Overrides the maximum entry point recursion depth of the block's versioned constants; e.g.,
+    /// simulation services may allow deeper recursion than block production.
+    pub fn set_max_recursion_depth(&mut self, max_recursion_depth: usize) {
+        self.versioned_constants.max_recursion_depth = max_recursion_depth;
+    }
+
+    /// Overrides the gas prices of the block with the ones of the gas price oracle, if set.
+    /// Called at the start of the block; must not be called mid-block, as fees of executed
+    /// transactions were computed by the previous prices.
+    pub fn refresh_gas_prices(&mut self) {
+        if let Some(gas_prices) = self.oracle_gas_prices() {
+            self.block_info.gas_prices = gas_prices;
+        }
+    }
+
+    /// The gas prices to estimate fees by: the current prices of the gas price oracle, if set,
+    /// and the prices of the block otherwise.
+    pub fn estimation_gas_prices(&self) -> GasPrices {
+        self.oracle_gas_prices().unwrap_or_else(|| self.block_info.gas_prices.clone())
+    }
+
+    fn oracle_gas_prices(&self) -> Option<GasPrices> {
+        self.hooks
+            .gas_price_oracle
+            .as_ref()
+            .map(|oracle| oracle.gas_prices(self.block_info.block_number))
+    }
 }
 
 impl BlockContext {
@@ -73,10 +239,299 @@ impl BlockContext {
     }
 }
 
+/// The pricing and charging of transaction fees.
+#[derive(Clone, Debug, Default)]
+pub struct FeePolicy {
+    pub fee_charge_failure_policy: FeeChargeFailurePolicy,
+    pub reverted_tx_fee_policy: RevertedTxFeePolicy,
+    /// If set, deprecated transactions whose actual fee exceeds their max fee do not fail; the
+    /// actual fee is computed even if the max fee is zero, and the charged fee is clamped to the
+    /// max fee. Intended for fee estimation and debugging flows.
+    pub relax_max_fee_check: bool,
+    pub gas_metering_mode: GasMeteringMode,
+    /// The minimal fee and gas price transactions are accepted with; transactions priced below
+    /// the floor fail pre-validation, when their fee is charged.
+    pub fee_floor: FeeFloor,
+    pub fee_exemptions: FeeExemptions,
+    pub fee_payment_mode: FeePaymentMode,
+    /// The rounding of fractional gas amounts: the L1 gas Cairo resources and Sierra gas are
+    /// converted to, and the L1 gas equivalent of data gas (by which tips and minimal gas amounts
+    /// are computed). Defaults to rounding up.
+    pub fee_rounding: Rounding,
+    /// The safety margin of fee estimates; see [`crate::fee::fee_estimation::estimate_fee`].
+    pub fee_estimate_margin: FeeEstimateMargin,
+}
+
+/// The limits and policies of the validation and execution of transactions.
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionPolicy {
+    /// Overrides the step and gas limits of the validation phase, e.g., to use looser limits for
+    /// fee estimation than for block building.
+    pub validation_limits: ValidationLimits,
+    pub undeployed_sender_policy: UndeployedSenderPolicy,
+    /// Overrides the calldata, signature and event size limits, e.g., to mirror the policies of a
+    /// specific sequencer.
+    pub size_limits: SizeLimits,
+    /// The operator allowed to send (fee-exempt) system transactions, and how they are validated.
+    pub system_tx_config: SystemTransactionConfig,
+    /// Overrides the step limit of executions, regardless of the versioned constants and the
+    /// transactions' resource bounds; e.g., for trace generation and debugging.
+    pub max_steps_override: Option<StepLimitOverride>,
+    pub reentrancy_policy: ReentrancyPolicy,
+    /// The default tracking of visited PCs of the executions in the block; it may be selected per
+    /// execution by
+    /// [`crate::execution::entry_point::EntryPointExecutionContext::visited_pcs_tracking`].
+    pub visited_pcs_tracking: VisitedPcsTracking,
+    /// If set, the gas charged for each syscall invocation of Cairo 1 calls, along with the
+    /// remaining gas before and after it, is recorded in the call infos; e.g., for contract
+    /// developers chasing gas regressions.
+    pub syscall_gas_audit: bool,
+    /// If set, the Sierra classes of declare transactions of version 2 and above are recompiled,
+    /// and the transactions fail unless the declared compiled class hash matches; see
+    /// [`crate::transaction::transactions::DeclareTransaction::with_sierra_contract_class`].
+    pub verify_sierra_compilation: bool,
+    /// The truncation of the error traces in the revert reasons of reverted transactions; e.g.,
+    /// to keep the innermost frames when debugging deep failures.
+    pub trace_truncation: TraceTruncation,
+    /// Overrides of the execution info seen by the executed contracts; e.g., for simulating
+    /// transactions at another block number or version, on a clone of the block context.
+    pub execution_info_overrides: ExecutionInfoOverrides,
+}
+
+/// The external components plugged into the execution.
+#[derive(Clone, Debug, Default)]
+pub struct ExtensionHooks {
+    /// The oracle the gas prices are taken from; see [`BlockContext::refresh_gas_prices`].
+    pub gas_price_oracle: Option<Arc<dyn GasPriceOracle>>,
+    /// The backend Cairo 1 classes are executed by, where supported; see [`NativeExecutor`].
+    #[cfg(feature = "native_execution")]
+    pub native_executor: Option<Arc<dyn NativeExecutor>>,
+    /// The interceptor the syscalls of executed contracts are reported to; see
+    /// [`SyscallInterceptor`].
+    pub syscall_interceptor: Option<Arc<dyn SyscallInterceptor>>,
+    /// Registered via [`BlockContext::register_custom_hint`].
+    pub(crate) custom_hints: CustomHints,
+    /// The sink the events of executed contracts are streamed to; see [`EventSink`].
+    pub event_sink: Option<Arc<dyn EventSink>>,
+    /// The observer of the steps of the Cairo VM; see [`VmTracer`].
+    #[cfg(feature = "vm_tracer")]
+    pub vm_tracer: Option<Arc<dyn VmTracer>>,
+}
+
+impl ExtensionHooks {
+    pub fn custom_hints(&self) -> &CustomHints {
+        &self.custom_hints
+    }
+}
+
+/// A limit on a resource consumed by the validation phase of a transaction.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ValidateLimit {
+    /// The limit defined by the versioned constants.
+    #[default]
+    Default,
+    Bounded(u64),
+    /// No limit beyond the transaction's own bounds; intended for simulation.
+    Unlimited,
+}
+
+/// A step limit overriding the one derived from the versioned constants and the transaction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StepLimitOverride {
+    Bounded(usize),
+    Unlimited,
+}
+
+impl StepLimitOverride {
+    pub fn max_steps(&self) -> usize {
+        match self {
+            Self::Bounded(max_steps) => *max_steps,
+            Self::Unlimited => usize::MAX,
+        }
+    }
+}
+
+/// The handling of reentrant calls; i.e., calls to a contract that is already executing up the
+/// call stack (directly, or via library calls).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ReentrancyPolicy {
+    /// Reentrant calls are not detected.
+    #[default]
+    Allow,
+    /// Reentrant calls are executed, and marked as such in their call info.
+    Record,
+    /// Reentrant calls fail.
+    Forbid,
+}
+
+/// The collection of the PCs visited by Cairo 1 executions. Only the full collection allows
+/// computing the visited bytecode segments of the block, required for proving it; the others save
+/// the memory and time it takes, when nothing consumes it.
+#[derive(Clone, Debug, Default)]
+pub enum VisitedPcsTracking {
+    /// The visited PCs of each class are collected into the state.
+    #[default]
+    Full,
+    /// The visited PCs of each call are reported to the given observer, and not retained; e.g., to
+    /// a [`crate::execution::visited_pcs::VisitedPcsSketch`], estimating the distinct PCs visited
+    /// in each class in bounded memory.
+    Observe(Arc<dyn VisitedPcsObserver>),
+    /// Visited PCs are not collected, and the VM trace they are read from is not recorded.
+    Disabled,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ValidationLimits {
+    pub max_n_steps: ValidateLimit,
+    pub max_gas: ValidateLimit,
+}
+
+impl ValidationLimits {
+    pub fn unlimited() -> Self {
+        Self { max_n_steps: ValidateLimit::Unlimited, max_gas: ValidateLimit::Unlimited }
+    }
+}
+
+/// Determines how invoke transactions whose sender is not deployed are handled.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum UndeployedSenderPolicy {
+    /// The transaction fails; the sender must be deployed first.
+    #[default]
+    Reject,
+    /// The transaction is validated and executed counterfactually, by running the account entry
+    /// points of the given class at the sender address. Intended for account abstraction
+    /// experiments.
+    Counterfactual(ClassHash),
+}
+
+/// The minimal pricing of transactions a sequencer accepts; a zero value imposes no floor.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FeeFloor {
+    /// The minimal fee offered: the max fee of deprecated transactions, or the fee committed to
+    /// by the resource bounds of current ones.
+    pub min_fee: Fee,
+    /// The minimal max L1 gas price; only applies to transactions with resource bounds, as
+    /// deprecated ones are charged by the gas price of the block.
+    pub min_l1_gas_price: u128,
+}
+
+/// The transactions exempt from fee charging; e.g., the ones of subsidized system contracts of
+/// appchains. Exempt transactions are neither checked against their fee bounds nor charged, and
+/// their fee is reported as zero.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FeeExemptions {
+    /// Account transactions sent by, and L1 handlers targeting, these addresses are exempt.
+    pub addresses: HashSet<ContractAddress>,
+    /// If set, all L1 handlers are exempt; i.e., they need not pay a fee on L1.
+    pub l1_handlers: bool,
+}
+
+impl FeeExemptions {
+    pub fn is_exempt_address(&self, address: ContractAddress) -> bool {
+        self.addresses.contains(&address)
+    }
+}
+
+/// How the fees of account transactions are transferred to the fee recipient.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum FeePaymentMode {
+    /// The sender transfers the fee (via `transfer`).
+    #[default]
+    Transfer,
+    /// The fees of sponsored accounts, which need not hold the fee token, are transferred from
+    /// their sponsors by the fee recipient (via `transferFrom`), against an allowance the sponsor
+    /// granted the fee recipient. The sponsors are keyed by the sponsored account address; other
+    /// senders transfer their fees.
+    TransferFrom { sponsors: HashMap<ContractAddress, ContractAddress> },
+}
+
+impl FeePaymentMode {
+    pub fn sponsor_of(&self, sender_address: ContractAddress) -> Option<ContractAddress> {
+        match self {
+            Self::Transfer => None,
+            Self::TransferFrom { sponsors } => sponsors.get(&sender_address).copied(),
+        }
+    }
+}
+
+/// Determines the resource the computation of transactions is metered by.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GasMeteringMode {
+    /// Calls are metered by the VM resources they consume; for transactions charged for L2 gas,
+    /// these are converted to Sierra gas and charged as L2 gas.
+    #[default]
+    VmResources,
+    /// Cairo 1 calls are metered by the Sierra gas they consume, and the VM resources of Cairo 0
+    /// calls are converted to Sierra gas; the VM resources of the calls are not charged for.
+    SierraGas,
+    /// Calls are metered and charged as in [`Self::VmResources`], while Cairo 1 calls record their
+    /// Sierra gas alongside the Sierra gas their VM resources convert to; for validating Sierra
+    /// gas metering before charging by it (see [`crate::execution::call_info::DualGasMetering`]).
+    Dual,
+}
+
+impl GasMeteringMode {
+    /// The resource Cairo 1 calls are metered by; Cairo 0 calls are always metered by steps.
+    pub fn cairo1_tracked_resource(&self) -> TrackedResource {
+        match self {
+            Self::VmResources | Self::Dual => TrackedResource::CairoSteps,
+            Self::SierraGas => TrackedResource::SierraGas,
+        }
+    }
+}
+
+/// Limits on the size of transactions and of the events they emit; a [None] limit falls back to
+/// the default: calldata and signature lengths are not limited, and event limits are defined by
+/// the versioned constants.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SizeLimits {
+    /// Limits the calldata of the transaction, and that of every entry point call it makes.
+    pub max_calldata_length: Option<usize>,
+    pub max_signature_length: Option<usize>,
+    pub event_limits: Option<EventLimits>,
+}
+
+impl SizeLimits {
+    pub fn check_calldata_length(&self, calldata_length: usize) -> Result<(), SizeLimitError> {
+        match self.max_calldata_length {
+            Some(max_calldata_length) if calldata_length > max_calldata_length => {
+                Err(SizeLimitError::CalldataTooLong { calldata_length, max_calldata_length })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn check_signature_length(&self, signature_length: usize) -> Result<(), SizeLimitError> {
+        match self.max_signature_length {
+            Some(max_signature_length) if signature_length > max_signature_length => {
+                Err(SizeLimitError::SignatureTooLong { signature_length, max_signature_length })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The policy for system transactions; i.e., fee-exempt invoke transactions of the chain operator,
+/// used by appchains for maintenance calls.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SystemTransactionConfig {
+    /// The only account allowed to send system transactions; if unset, they are rejected.
+    pub operator_address: Option<ContractAddress>,
+    /// Overrides the block's validation limits for system transactions.
+    pub validation_limits: Option<ValidationLimits>,
+}
+
 #[derive(Clone, Debug)]
 pub struct ChainInfo {
     pub chain_id: ChainId,
     pub fee_token_addresses: FeeTokenAddresses,
+    /// The codes of the hints the chain allows to register custom implementations for; see
+    /// [`BlockContext::register_custom_hint`].
+    pub allowed_custom_hints: HashSet<String>,
+    /// Whether the deploy syscall may derive the deployed address from the zero address instead
+    /// of the deployer's; see
+    /// [`crate::execution::execution_utils::calculate_deployed_contract_address`].
+    pub allow_deploy_from_zero: bool,
 }
 
 impl ChainInfo {
@@ -93,6 +548,8 @@ impl Default for ChainInfo {
         ChainInfo {
             chain_id: ChainId("0x0".to_string()),
             fee_token_addresses: FeeTokenAddresses::default(),
+            allowed_custom_hints: HashSet::new(),
+            allow_deploy_from_zero: true,
         }
     }
 }
@@ -104,6 +561,12 @@ pub struct FeeTokenAddresses {
 }
 
 impl FeeTokenAddresses {
+    /// Returns the fee-token addresses of a chain with a single fee token, in which fees of both
+    /// types are paid.
+    pub fn single_token(fee_token_address: ContractAddress) -> Self {
+        Self { strk_fee_token_address: fee_token_address, eth_fee_token_address: fee_token_address }
+    }
+
     pub fn get_by_fee_type(&self, fee_type: &FeeType) -> ContractAddress {
         match fee_type {
             FeeType::Strk => self.strk_fee_token_address,
@@ -1,12 +1,23 @@
 pub mod call_info;
+#[cfg(feature = "cheatcodes")]
+pub mod cheatcodes;
 pub mod common_hints;
 pub mod contract_address;
 pub mod contract_class;
+pub mod custom_hints;
 pub mod deprecated_entry_point_execution;
 pub mod deprecated_syscalls;
 pub mod entry_point;
 pub mod entry_point_execution;
 pub mod errors;
+pub mod event_sink;
 pub mod execution_utils;
 pub mod hint_code;
+pub mod messages_to_l1;
+#[cfg(feature = "native_execution")]
+pub mod native_execution;
+pub mod syscall_interceptor;
 pub mod syscalls;
+pub mod visited_pcs;
+#[cfg(feature = "vm_tracer")]
+pub mod vm_tracer;
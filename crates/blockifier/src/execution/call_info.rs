@@ -1,18 +1,28 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::iter::Sum;
 use std::ops::Add;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
-use serde::{Deserialize, Serialize};
-use starknet_api::core::{ClassHash, ContractAddress, EthAddress, PatriciaKey};
+use serde::{Serialize, Serializer};
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, EthAddress, PatriciaKey};
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::patricia_key;
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{EventContent, L2ToL1Payload};
 
+use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
 use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::syscalls::SyscallSelector;
+use crate::fee::fee_utils::{calculate_l1_gas_by_vm_usage, calculate_sierra_gas_by_vm_usage};
 use crate::fee::gas_usage::get_message_segment_length;
 use crate::state::cached_state::StorageEntry;
+use crate::transaction::objects::TransactionFeeResult;
+use crate::transaction::receipt::rpc_builtin_counters;
+use crate::versioned_constants::VersionedConstants;
+
+#[cfg(test)]
+#[path = "call_info_test.rs"]
+pub mod test;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
 pub struct Retdata(pub Vec<StarkFelt>);
@@ -24,8 +34,7 @@ macro_rules! retdata {
     };
 }
 
-#[cfg_attr(test, derive(Clone))]
-#[derive(Debug, Default, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
 pub struct OrderedEvent {
     pub order: usize,
     pub event: EventContent,
@@ -38,6 +47,16 @@ pub struct MessageL1CostInfo {
 }
 
 impl MessageL1CostInfo {
+    pub fn new(
+        l2_to_l1_payload_lengths: Vec<usize>,
+        l1_handler_payload_size: Option<usize>,
+    ) -> Self {
+        let message_segment_length =
+            get_message_segment_length(&l2_to_l1_payload_lengths, l1_handler_payload_size);
+
+        Self { l2_to_l1_payload_lengths, message_segment_length }
+    }
+
     pub fn calculate<'a>(
         call_infos: impl Iterator<Item = &'a CallInfo>,
         l1_handler_payload_size: Option<usize>,
@@ -47,27 +66,44 @@ impl MessageL1CostInfo {
             l2_to_l1_payload_lengths.extend(call_info.get_l2_to_l1_payload_lengths());
         }
 
-        let message_segment_length =
-            get_message_segment_length(&l2_to_l1_payload_lengths, l1_handler_payload_size);
-
-        Self { l2_to_l1_payload_lengths, message_segment_length }
+        Self::new(l2_to_l1_payload_lengths, l1_handler_payload_size)
     }
 }
 
-#[cfg_attr(test, derive(Clone))]
-#[derive(Debug, Default, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
 pub struct MessageToL1 {
     pub to_address: EthAddress,
     pub payload: L2ToL1Payload,
 }
 
-#[cfg_attr(test, derive(Clone))]
-#[derive(Debug, Default, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
 pub struct OrderedL2ToL1Message {
     pub order: usize,
     pub message: MessageToL1,
 }
 
+/// An event emitted during an execution, along with the address of the emitting contract.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct EmittedEvent {
+    pub from_address: ContractAddress,
+    pub event: OrderedEvent,
+}
+
+/// An L2-to-L1 message sent during an execution, along with the address of the sending contract.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct SentL2ToL1Message {
+    pub from_address: ContractAddress,
+    pub message: OrderedL2ToL1Message,
+}
+
+/// The events and L2-to-L1 messages emitted during an execution, in emission order.
+/// Used to report the effects of an execution that was eventually reverted.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct EmittedEffects {
+    pub events: Vec<EmittedEvent>,
+    pub l2_to_l1_messages: Vec<SentL2ToL1Message>,
+}
+
 pub fn get_payload_lengths(l2_to_l1_messages: &[OrderedL2ToL1Message]) -> Vec<usize> {
     l2_to_l1_messages.iter().map(|message| message.message.payload.0.len()).collect()
 }
@@ -83,13 +119,27 @@ pub struct CallExecution {
     pub gas_consumed: u64,
 }
 
-// This struct is used to implement `serde` functionality in a remote `ExecutionResources` Struct.
-#[derive(Debug, Default, Deserialize, derive_more::From, Eq, PartialEq, Serialize)]
-#[serde(remote = "ExecutionResources")]
-struct ExecutionResourcesDef {
+/// The serialized form of the resources of a call: the VM resources, along with the usage of each
+/// builtin under its JSON-RPC counter name.
+#[derive(Serialize)]
+struct CallResourcesDef<'a> {
     n_steps: usize,
     n_memory_holes: usize,
-    builtin_instance_counter: HashMap<String, usize>,
+    builtin_instance_counter: &'a HashMap<String, usize>,
+    rpc_builtin_counters: BTreeMap<&'static str, usize>,
+}
+
+fn serialize_call_resources<S: Serializer>(
+    resources: &ExecutionResources,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    CallResourcesDef {
+        n_steps: resources.n_steps,
+        n_memory_holes: resources.n_memory_holes,
+        builtin_instance_counter: &resources.builtin_instance_counter,
+        rpc_builtin_counters: rpc_builtin_counters(resources),
+    }
+    .serialize(serializer)
 }
 
 #[derive(Default)]
@@ -170,18 +220,84 @@ impl TestExecutionSummary {
     }
 }
 
+/// The resource a call is metered by.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub enum TrackedResource {
+    #[default]
+    CairoSteps,
+    SierraGas,
+}
+
+/// The gas charged for a single syscall invocation, including its base cost and the gas consumed
+/// by the inner call it may invoke.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct SyscallGasRecord {
+    pub selector: SyscallSelector,
+    /// The remaining gas of the call before the syscall was charged.
+    pub gas_before: u64,
+    /// The remaining gas of the call after the syscall returned.
+    pub gas_after: u64,
+}
+
+impl SyscallGasRecord {
+    pub fn gas_charged(&self) -> u64 {
+        self.gas_before - self.gas_after
+    }
+}
+
+/// The Sierra gas of a Cairo 1 call under [`crate::context::GasMeteringMode::Dual`], exclusive of
+/// its inner calls: as consumed by the call, and as converted from the VM resources of the call.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct DualGasMetering {
+    pub sierra_gas: u64,
+    pub vm_resources_gas: u64,
+}
+
+impl DualGasMetering {
+    /// Returns whether the two meterings differ by more than the given percentage of the larger
+    /// one.
+    pub fn diverges(&self, tolerance_percent: u64) -> bool {
+        let divergence = u128::from(self.sierra_gas.abs_diff(self.vm_resources_gas));
+        let larger = u128::from(self.sierra_gas.max(self.vm_resources_gas));
+        divergence * 100 > larger * u128::from(tolerance_percent)
+    }
+}
+
 /// Represents the full effects of executing an entry point, including the inner calls it invoked.
 #[derive(Debug, Default, Eq, PartialEq, Serialize)]
 pub struct CallInfo {
     pub call: CallEntryPoint,
     pub execution: CallExecution,
-    #[serde(with = "ExecutionResourcesDef")]
+    #[serde(serialize_with = "serialize_call_resources")]
     pub resources: ExecutionResources,
+    /// The memory holes of the call's own VM run; i.e., exclusive of its inner calls and of the OS
+    /// resources of its syscalls, which are estimated rather than measured.
+    pub vm_memory_holes: usize,
     pub inner_calls: Vec<CallInfo>,
+    /// The resource the call is metered by; see [`crate::context::GasMeteringMode`].
+    pub tracked_resource: TrackedResource,
+    /// Recorded only under [`crate::context::GasMeteringMode::Dual`], for Cairo 1 calls.
+    pub dual_gas_metering: Option<DualGasMetering>,
+    /// The gas the caller was charged for this call, if other than the gas the call consumed; see
+    /// [`crate::execution::syscalls::hint_processor::inner_call_gas_charge`].
+    pub caller_gas_charge: Option<u64>,
 
     // Additional information gathered during execution.
     pub storage_read_values: Vec<StarkFelt>,
     pub accessed_storage_keys: HashSet<StorageKey>,
+    /// The number of invocations of each syscall by this call, exclusive of its inner calls.
+    /// Keccak is counted by the number of permutation rounds it is charged for.
+    pub syscall_counter: SyscallCounter,
+    /// The gas charged for each syscall invocation by this call, in order; recorded only under
+    /// syscall gas audit (see [`crate::context::ExecutionPolicy::syscall_gas_audit`]).
+    pub syscall_gas_records: Vec<SyscallGasRecord>,
+    /// Whether the call re-entered a contract that was already executing; only detected under
+    /// [`crate::context::ReentrancyPolicy::Record`].
+    pub reentrant: bool,
+    /// The position of the call in the pre-order traversal of its call tree (see
+    /// [`CallInfo::iter`]), and that of its parent; assigned once the top-level call completes.
+    pub call_index: usize,
+    pub parent_call_index: Option<usize>,
 }
 
 impl CallInfo {
@@ -190,6 +306,25 @@ impl CallInfo {
         CallInfoIter { call_infos }
     }
 
+    /// Assigns each call in the tree its pre-order index and the index of its parent, such that
+    /// the tree can be reconstructed from its flattened calls.
+    pub fn assign_call_indices(&mut self) {
+        self.assign_call_indices_from(None, &mut 0);
+    }
+
+    fn assign_call_indices_from(
+        &mut self,
+        parent_call_index: Option<usize>,
+        next_index: &mut usize,
+    ) {
+        self.call_index = *next_index;
+        self.parent_call_index = parent_call_index;
+        *next_index += 1;
+        for inner_call in &mut self.inner_calls {
+            inner_call.assign_call_indices_from(Some(self.call_index), next_index);
+        }
+    }
+
     pub fn get_l2_to_l1_payload_lengths(&self) -> Vec<usize> {
         self.iter().fold(Vec::new(), |mut acc, call_info| {
             acc.extend(get_payload_lengths(&call_info.execution.l2_to_l1_messages));
@@ -197,6 +332,33 @@ impl CallInfo {
         })
     }
 
+    /// Returns the events and L2-to-L1 messages emitted by this call and its inner calls, in
+    /// emission order.
+    pub fn emitted_effects(&self) -> EmittedEffects {
+        let mut effects = EmittedEffects::default();
+        for call_info in self.iter() {
+            let from_address = call_info.call.storage_address;
+            effects.events.extend(
+                call_info
+                    .execution
+                    .events
+                    .iter()
+                    .map(|event| EmittedEvent { from_address, event: event.clone() }),
+            );
+            effects.l2_to_l1_messages.extend(
+                call_info
+                    .execution
+                    .l2_to_l1_messages
+                    .iter()
+                    .map(|message| SentL2ToL1Message { from_address, message: message.clone() }),
+            );
+        }
+        effects.events.sort_by_key(|event| event.event.order);
+        effects.l2_to_l1_messages.sort_by_key(|message| message.message.order);
+
+        effects
+    }
+
     pub fn summarize(&self) -> ExecutionSummary {
         let mut executed_class_hashes: HashSet<ClassHash> = HashSet::new();
         let mut visited_storage_entries: HashSet<StorageEntry> = HashSet::new();
@@ -227,6 +389,143 @@ impl CallInfo {
             n_events,
         }
     }
+
+    /// Returns the VM resources of this call, exclusive of its inner calls.
+    pub fn exclusive_resources(&self) -> ExecutionResources {
+        let mut inner_calls_resources = ExecutionResources::default();
+        for inner_call in &self.inner_calls {
+            inner_calls_resources += &inner_call.resources;
+        }
+        (&self.resources - &inner_calls_resources).filter_unused_builtins()
+    }
+
+    /// Returns the number of invocations of each syscall by this call and its inner calls.
+    pub fn inclusive_syscall_counter(&self) -> SyscallCounter {
+        let mut syscall_counter = SyscallCounter::new();
+        for call_info in self.iter() {
+            for (selector, count) in &call_info.syscall_counter {
+                *syscall_counter.entry(*selector).or_default() += count;
+            }
+        }
+        syscall_counter
+    }
+
+    /// Returns the gas the caller was charged for this call.
+    pub fn charged_gas(&self) -> u64 {
+        self.caller_gas_charge.unwrap_or(self.execution.gas_consumed)
+    }
+
+    /// Returns the Sierra gas consumed by this call, exclusive of the gas it was charged for its
+    /// inner calls.
+    pub fn exclusive_gas_consumed(&self) -> u64 {
+        let inner_calls_gas: u64 = self.inner_calls.iter().map(CallInfo::charged_gas).sum();
+        self.execution.gas_consumed.saturating_sub(inner_calls_gas)
+    }
+
+    /// Returns the Sierra gas of this call and its inner calls, when metered by Sierra gas: calls
+    /// metered by Sierra gas are accounted for by the gas they consumed, and calls metered by steps
+    /// by the Sierra gas their VM resources convert to.
+    pub fn sierra_gas_consumed(&self, versioned_constants: &VersionedConstants) -> u64 {
+        self.iter().fold(0, |sierra_gas: u64, call| {
+            let call_sierra_gas = match call.tracked_resource {
+                TrackedResource::SierraGas => call.exclusive_gas_consumed(),
+                TrackedResource::CairoSteps => calculate_sierra_gas_by_vm_usage(
+                    versioned_constants,
+                    &call.exclusive_resources(),
+                ),
+            };
+            sierra_gas.saturating_add(call_sierra_gas)
+        })
+    }
+
+    /// Returns the calls in this call tree whose metering by Sierra gas diverges from their
+    /// metering by VM resources by more than the given percentage; see [`DualGasMetering`].
+    pub fn gas_metering_divergences(
+        &self,
+        tolerance_percent: u64,
+    ) -> impl Iterator<Item = &CallInfo> + '_ {
+        self.iter().filter(move |call| {
+            call.dual_gas_metering
+                .is_some_and(|dual_gas_metering| dual_gas_metering.diverges(tolerance_percent))
+        })
+    }
+
+    /// Attributes gas to this call and to each of its inner calls, exclusive of their children.
+    pub fn gas_attribution(
+        &self,
+        versioned_constants: &VersionedConstants,
+    ) -> TransactionFeeResult<CallGasAttribution> {
+        let exclusive_gas = CallGas {
+            sierra_gas: self.exclusive_gas_consumed(),
+            l1_gas: calculate_l1_gas_by_vm_usage(
+                versioned_constants,
+                &self.exclusive_resources(),
+                0,
+            )?
+            .l1_gas,
+        };
+        let inner_calls = self
+            .inner_calls
+            .iter()
+            .map(|inner_call| inner_call.gas_attribution(versioned_constants))
+            .collect::<TransactionFeeResult<_>>()?;
+
+        Ok(CallGasAttribution {
+            storage_address: self.call.storage_address,
+            class_hash: self.call.class_hash,
+            entry_point_selector: self.call.entry_point_selector,
+            exclusive_gas,
+            inner_calls,
+        })
+    }
+}
+
+#[derive(
+    derive_more::Add, derive_more::Sum, Clone, Copy, Debug, Default, Eq, PartialEq, Serialize,
+)]
+pub struct CallGas {
+    pub sierra_gas: u64,
+    /// The L1 gas the VM resources convert to, by the heaviest resource. Note that as the heaviest
+    /// resource is taken per call, the L1 gas of calls does not add up to the L1 gas charged for
+    /// the transaction; it is an attribution, not a breakdown.
+    pub l1_gas: u128,
+}
+
+/// The gas attributable to a call, exclusive of its inner calls, and the attribution of its inner
+/// calls.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct CallGasAttribution {
+    pub storage_address: ContractAddress,
+    pub class_hash: Option<ClassHash>,
+    pub entry_point_selector: EntryPointSelector,
+    pub exclusive_gas: CallGas,
+    pub inner_calls: Vec<CallGasAttribution>,
+}
+
+impl CallGasAttribution {
+    /// Returns the gas of the call, including its inner calls.
+    pub fn inclusive_gas(&self) -> CallGas {
+        self.exclusive_gas + self.inner_calls.iter().map(Self::inclusive_gas).sum()
+    }
+
+    /// Rolls up the exclusive gas of the call and of all of its inner calls by entry point; i.e.,
+    /// by class hash and selector.
+    pub fn rollup_by_entry_point(
+        &self,
+    ) -> HashMap<(Option<ClassHash>, EntryPointSelector), CallGas> {
+        let mut rollup = HashMap::new();
+        self.rollup_into(&mut rollup);
+        rollup
+    }
+
+    fn rollup_into(&self, rollup: &mut HashMap<(Option<ClassHash>, EntryPointSelector), CallGas>) {
+        let entry_point_gas =
+            rollup.entry((self.class_hash, self.entry_point_selector)).or_default();
+        *entry_point_gas = *entry_point_gas + self.exclusive_gas;
+        for inner_call in &self.inner_calls {
+            inner_call.rollup_into(rollup);
+        }
+    }
 }
 
 pub struct CallInfoIter<'a> {
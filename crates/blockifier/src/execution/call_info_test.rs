@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use cairo_vm::vm::runners::builtin_runner::RANGE_CHECK_BUILTIN_NAME;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use serde_json::json;
+use starknet_api::core::{ClassHash, EntryPointSelector};
+use starknet_api::hash::StarkHash;
+
+use crate::execution::call_info::{CallExecution, CallGas, CallInfo, DualGasMetering};
+use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
+use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
+use crate::execution::entry_point::CallEntryPoint;
+use crate::fee::fee_utils::calculate_l1_gas_by_vm_usage;
+use crate::versioned_constants::VersionedConstants;
+
+fn call_info(
+    class_hash: u8,
+    n_steps: usize,
+    n_range_checks: usize,
+    gas_consumed: u64,
+    inner_calls: Vec<CallInfo>,
+) -> CallInfo {
+    CallInfo {
+        call: CallEntryPoint {
+            class_hash: Some(ClassHash(StarkHash::from(class_hash))),
+            entry_point_selector: EntryPointSelector(StarkHash::ONE),
+            ..Default::default()
+        },
+        execution: CallExecution { gas_consumed, ..Default::default() },
+        resources: ExecutionResources {
+            n_steps,
+            n_memory_holes: 0,
+            builtin_instance_counter: HashMap::from([(
+                RANGE_CHECK_BUILTIN_NAME.to_string(),
+                n_range_checks,
+            )]),
+        },
+        inner_calls,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_gas_attribution() {
+    let versioned_constants = VersionedConstants::create_for_account_testing();
+    let l1_gas = |n_steps: usize, n_range_checks: usize| {
+        let resources = call_info(0, n_steps, n_range_checks, 0, vec![]).resources;
+        calculate_l1_gas_by_vm_usage(&versioned_constants, &resources, 0).unwrap().l1_gas
+    };
+
+    // The inner calls are of the same entry point; the resources and gas of a call include the
+    // ones of its inner calls.
+    let inner_call = || call_info(2, 100, 10, 1000, vec![]);
+    let outer_call = call_info(1, 500, 20, 5000, vec![inner_call(), inner_call()]);
+
+    let exclusive_resources = outer_call.exclusive_resources();
+    assert_eq!(exclusive_resources.n_steps, 300);
+    assert_eq!(exclusive_resources.builtin_instance_counter.len(), 0);
+    assert_eq!(outer_call.exclusive_gas_consumed(), 3000);
+
+    let attribution = outer_call.gas_attribution(&versioned_constants).unwrap();
+    let inner_call_gas = CallGas { sierra_gas: 1000, l1_gas: l1_gas(100, 10) };
+    assert_eq!(attribution.exclusive_gas, CallGas { sierra_gas: 3000, l1_gas: l1_gas(300, 0) });
+    assert_eq!(attribution.inner_calls[0].exclusive_gas, inner_call_gas);
+    assert_eq!(attribution.inclusive_gas().sierra_gas, outer_call.execution.gas_consumed);
+
+    let rollup = attribution.rollup_by_entry_point();
+    assert_eq!(rollup.len(), 2);
+    assert_eq!(
+        rollup[&(inner_call().call.class_hash, EntryPointSelector(StarkHash::ONE))],
+        inner_call_gas + inner_call_gas
+    );
+}
+
+#[test]
+fn test_call_indices() {
+    // The tree is constructed as follows (by class hash):
+    //          1
+    //        /   \
+    //       2     4
+    //       |
+    //       3
+    let inner_node = call_info(2, 0, 0, 0, vec![call_info(3, 0, 0, 0, vec![])]);
+    let mut root = call_info(1, 0, 0, 0, vec![inner_node, call_info(4, 0, 0, 0, vec![])]);
+    root.assign_call_indices();
+
+    let flattened_calls: Vec<_> = root
+        .iter()
+        .map(|call_info| {
+            (call_info.call.class_hash.unwrap(), call_info.call_index, call_info.parent_call_index)
+        })
+        .collect();
+    assert_eq!(
+        flattened_calls,
+        vec![
+            (ClassHash(StarkHash::from(1_u8)), 0, None),
+            (ClassHash(StarkHash::from(2_u8)), 1, Some(0)),
+            (ClassHash(StarkHash::from(3_u8)), 2, Some(1)),
+            (ClassHash(StarkHash::from(4_u8)), 3, Some(0)),
+        ]
+    );
+}
+
+#[test]
+fn test_gas_metering_divergences() {
+    let with_dual_gas_metering = |call_info: CallInfo, sierra_gas, vm_resources_gas| CallInfo {
+        dual_gas_metering: Some(DualGasMetering { sierra_gas, vm_resources_gas }),
+        ..call_info
+    };
+    // The calls diverge by 1/11 and 1/2 of their larger metering, respectively; the Cairo 0 call
+    // has no dual metering.
+    let inner_call = with_dual_gas_metering(call_info(2, 0, 0, 0, vec![]), 1000, 2000);
+    let cairo0_call = call_info(3, 0, 0, 0, vec![]);
+    let root =
+        with_dual_gas_metering(call_info(1, 0, 0, 0, vec![inner_call, cairo0_call]), 1100, 1000);
+
+    let diverging_class_hashes = |tolerance_percent| {
+        root.gas_metering_divergences(tolerance_percent)
+            .map(|call_info| call_info.call.class_hash.unwrap())
+            .collect::<Vec<_>>()
+    };
+    let class_hash = |class_hash: u8| ClassHash(StarkHash::from(class_hash));
+    assert_eq!(diverging_class_hashes(5), vec![class_hash(1), class_hash(2)]);
+    assert_eq!(diverging_class_hashes(10), vec![class_hash(2)]);
+    assert_eq!(diverging_class_hashes(50), vec![]);
+}
+
+#[test]
+fn test_inclusive_syscall_counter() {
+    let inner_call = |syscall_counter: SyscallCounter| CallInfo {
+        syscall_counter,
+        ..call_info(2, 100, 10, 1000, vec![])
+    };
+    let outer_call = CallInfo {
+        syscall_counter: HashMap::from([(DeprecatedSyscallSelector::CallContract, 2)]),
+        ..call_info(
+            1,
+            500,
+            20,
+            5000,
+            vec![
+                inner_call(HashMap::from([(DeprecatedSyscallSelector::StorageRead, 1)])),
+                inner_call(HashMap::from([
+                    (DeprecatedSyscallSelector::StorageRead, 2),
+                    (DeprecatedSyscallSelector::EmitEvent, 1),
+                ])),
+            ],
+        )
+    };
+
+    assert_eq!(
+        outer_call.inclusive_syscall_counter(),
+        HashMap::from([
+            (DeprecatedSyscallSelector::CallContract, 2),
+            (DeprecatedSyscallSelector::StorageRead, 3),
+            (DeprecatedSyscallSelector::EmitEvent, 1),
+        ])
+    );
+}
+
+#[test]
+fn test_serialized_call_resources() {
+    let outer_call = call_info(1, 500, 20, 5000, vec![call_info(2, 100, 0, 1000, vec![])]);
+
+    let serialized_call = serde_json::to_value(&outer_call).unwrap();
+
+    // The builtin usage of each call is also reported under the JSON-RPC counter names, omitting
+    // zero counters.
+    assert_eq!(
+        serialized_call["resources"],
+        json!({
+            "n_steps": 500,
+            "n_memory_holes": 0,
+            "builtin_instance_counter": {RANGE_CHECK_BUILTIN_NAME: 20},
+            "rpc_builtin_counters": {"range_check_builtin_applications": 20},
+        })
+    );
+    assert_eq!(serialized_call["inner_calls"][0]["resources"]["rpc_builtin_counters"], json!({}));
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
+
+use crate::execution::call_info::{CallExecution, Retdata};
+use crate::execution::entry_point::{CallEntryPoint, CallType, ExecutionInfoOverrides};
+use crate::execution::syscall_interceptor::SyscallInterceptor;
+
+#[cfg(test)]
+#[path = "cheatcodes_test.rs"]
+pub mod test;
+
+/// Cheatcodes manipulating the environment executed contracts observe, for contract testing
+/// tools; set on the execution info overrides of the execution context.
+impl ExecutionInfoOverrides {
+    /// Sets the block timestamp seen by contracts.
+    pub fn warp(&mut self, block_timestamp: BlockTimestamp) {
+        self.block_timestamp = Some(block_timestamp);
+    }
+
+    /// Sets the block number seen by contracts.
+    pub fn roll(&mut self, block_number: BlockNumber) {
+        self.block_number = Some(block_number);
+    }
+
+    /// Sets the caller address seen by the given contract.
+    pub fn start_prank(
+        &mut self,
+        contract_address: ContractAddress,
+        caller_address: ContractAddress,
+    ) {
+        self.caller_addresses.insert(contract_address, caller_address);
+    }
+
+    pub fn stop_prank(&mut self, contract_address: ContractAddress) {
+        self.caller_addresses.remove(&contract_address);
+    }
+}
+
+/// Mocks of entry points, for contract testing tools; installed as the syscall interceptor of
+/// the block context (see [`crate::context::ExtensionHooks::syscall_interceptor`]). Calls to a
+/// mocked entry point are not executed, and return the mocked data.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CallMocks {
+    /// The return data of mocked entry points of contracts, keyed by contract address and
+    /// selector; applies to contract calls.
+    pub mocked_calls: HashMap<(ContractAddress, EntryPointSelector), Retdata>,
+    /// The return data of mocked entry points of classes, keyed by class hash and selector;
+    /// applies to library calls.
+    pub mocked_library_calls: HashMap<(ClassHash, EntryPointSelector), Retdata>,
+}
+
+impl CallMocks {
+    /// Mocks the given entry point of the given contract; calls to it return the given data.
+    pub fn mock_call(
+        &mut self,
+        contract_address: ContractAddress,
+        entry_point_selector: EntryPointSelector,
+        retdata: Retdata,
+    ) {
+        self.mocked_calls.insert((contract_address, entry_point_selector), retdata);
+    }
+
+    pub fn clear_mock_call(
+        &mut self,
+        contract_address: ContractAddress,
+        entry_point_selector: EntryPointSelector,
+    ) {
+        self.mocked_calls.remove(&(contract_address, entry_point_selector));
+    }
+
+    /// Mocks the given entry point of the given class; library calls to it return the given data.
+    pub fn mock_library_call(
+        &mut self,
+        class_hash: ClassHash,
+        entry_point_selector: EntryPointSelector,
+        retdata: Retdata,
+    ) {
+        self.mocked_library_calls.insert((class_hash, entry_point_selector), retdata);
+    }
+
+    pub fn clear_mock_library_call(
+        &mut self,
+        class_hash: ClassHash,
+        entry_point_selector: EntryPointSelector,
+    ) {
+        self.mocked_library_calls.remove(&(class_hash, entry_point_selector));
+    }
+}
+
+impl SyscallInterceptor for CallMocks {
+    fn intercept_call(&self, call: &CallEntryPoint) -> Option<CallExecution> {
+        let retdata = match call.call_type {
+            CallType::Call => {
+                self.mocked_calls.get(&(call.code_address?, call.entry_point_selector))
+            }
+            CallType::Delegate => {
+                self.mocked_library_calls.get(&(call.class_hash?, call.entry_point_selector))
+            }
+        }?;
+        Some(CallExecution { retdata: retdata.clone(), ..Default::default() })
+    }
+}
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, contract_address, patricia_key, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
+use crate::execution::call_info::Retdata;
+use crate::execution::cheatcodes::CallMocks;
+use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
+use crate::retdata;
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
+
+fn execution_context() -> EntryPointExecutionContext {
+    let tx_context = TransactionContext {
+        block_context: BlockContext::create_for_testing(),
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap()
+}
+
+fn test_contract_state(test_contract: FeatureContract) -> CachedState<DictStateReader> {
+    test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)])
+}
+
+#[test]
+fn test_warp_and_roll() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state = test_contract_state(test_contract);
+    let mut context = execution_context();
+    context.execution_info_overrides.warp(BlockTimestamp(1234));
+    context.execution_info_overrides.roll(BlockNumber(5678));
+
+    for (entry_point_name, expected_value) in
+        [("test_get_block_timestamp", 1234_u16), ("test_get_block_number", 5678_u16)]
+    {
+        let entry_point_call = CallEntryPoint {
+            entry_point_selector: selector_from_name(entry_point_name),
+            calldata: calldata![stark_felt!(expected_value)],
+            ..trivial_external_entry_point_new(test_contract)
+        };
+        entry_point_call
+            .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_prank() {
+    let mut context = execution_context();
+    let contract_address = contract_address!("0x10");
+    let caller_address = contract_address!("0x20");
+    let pranked_caller_address = contract_address!("0x30");
+
+    context.execution_info_overrides.start_prank(contract_address, pranked_caller_address);
+    assert_eq!(
+        context.caller_address_seen_by(contract_address, caller_address),
+        pranked_caller_address
+    );
+    assert_eq!(context.caller_address_seen_by(caller_address, contract_address), contract_address);
+
+    context.execution_info_overrides.stop_prank(contract_address);
+    assert_eq!(context.caller_address_seen_by(contract_address, caller_address), caller_address);
+}
+
+/// Returns an execution context whose block context has the given mocks installed.
+fn execution_context_with_mocks(call_mocks: CallMocks) -> EntryPointExecutionContext {
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.hooks_mut().syscall_interceptor = Some(Arc::new(call_mocks));
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap()
+}
+
+#[rstest]
+fn test_mock_call(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let test_contract_address = test_contract.get_instance_address(0);
+    let mut state = test_contract_state(test_contract);
+    let mocked_selector = selector_from_name("test_storage_read_write");
+    let mut call_mocks = CallMocks::default();
+    call_mocks.mock_call(test_contract_address, mocked_selector, retdata![stark_felt!(99_u8)]);
+
+    let key = stark_felt!(405_u16);
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_call_contract"),
+        calldata: create_calldata(
+            test_contract_address,
+            "test_storage_read_write",
+            &[key, stark_felt!(48_u8)],
+        ),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let call_info = entry_point_call
+        .clone()
+        .execute(
+            &mut state,
+            &mut ExecutionResources::default(),
+            &mut execution_context_with_mocks(call_mocks.clone()),
+        )
+        .unwrap();
+
+    // The mocked entry point is not executed.
+    assert_eq!(call_info.execution.retdata, retdata![stark_felt!(99_u8)]);
+    let storage_key = StorageKey::try_from(key).unwrap();
+    assert_eq!(state.get_storage_at(test_contract_address, storage_key).unwrap(), StarkFelt::ZERO);
+
+    call_mocks.clear_mock_call(test_contract_address, mocked_selector);
+    let call_info = entry_point_call
+        .execute(
+            &mut state,
+            &mut ExecutionResources::default(),
+            &mut execution_context_with_mocks(call_mocks),
+        )
+        .unwrap();
+    assert_eq!(call_info.execution.retdata, retdata![stark_felt!(48_u8)]);
+}
+
+#[test]
+fn test_mock_library_call() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let test_contract_address = test_contract.get_instance_address(0);
+    let mut state = test_contract_state(test_contract);
+    let mocked_selector = selector_from_name("test_storage_read_write");
+    let key = stark_felt!(405_u16);
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_library_call"),
+        calldata: calldata![
+            test_contract.get_class_hash().0, // Class hash.
+            mocked_selector.0,                // Function selector.
+            stark_felt!(2_u8),                // Calldata length.
+            key,                              // Calldata: address.
+            stark_felt!(48_u8)                // Calldata: value.
+        ],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+
+    // Library calls are mocked by class hash; mocking the entry point of the contract does not
+    // apply to them.
+    let mut call_mocks = CallMocks::default();
+    call_mocks.mock_call(test_contract_address, mocked_selector, retdata![stark_felt!(98_u8)]);
+    call_mocks.mock_library_call(
+        test_contract.get_class_hash(),
+        mocked_selector,
+        retdata![stark_felt!(99_u8)],
+    );
+    let call_info = entry_point_call
+        .execute(
+            &mut state,
+            &mut ExecutionResources::default(),
+            &mut execution_context_with_mocks(call_mocks),
+        )
+        .unwrap();
+
+    assert_eq!(call_info.execution.retdata, retdata![stark_felt!(99_u8)]);
+    let storage_key = StorageKey::try_from(key).unwrap();
+    assert_eq!(state.get_storage_at(test_contract_address, storage_key).unwrap(), StarkFelt::ZERO);
+}
@@ -1,11 +1,13 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::Deref;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use cairo_felt::Felt252;
 use cairo_lang_casm;
 use cairo_lang_casm::hints::Hint;
 use cairo_lang_starknet_classes::casm_contract_class::{CasmContractClass, CasmContractEntryPoint};
+use cairo_lang_starknet_classes::compiler_version::current_compiler_version_id;
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
 use cairo_lang_starknet_classes::NestedIntList;
 use cairo_vm::serde::deserialize_program::{
     ApTracking, FlowTrackingData, HintParams, ReferenceManager,
@@ -23,6 +25,8 @@ use starknet_api::deprecated_contract_class::{
     ContractClass as DeprecatedContractClass, EntryPoint, EntryPointOffset, EntryPointType,
     Program as DeprecatedProgram,
 };
+use starknet_api::hash::StarkFelt;
+use starknet_crypto::{poseidon_hash_many, FieldElement};
 
 use super::execution_utils::poseidon_hash_many_cost;
 use crate::abi::abi_utils::selector_from_name;
@@ -32,6 +36,8 @@ use crate::execution::errors::{ContractClassError, PreExecutionError};
 use crate::execution::execution_utils::{felt_to_stark_felt, sn_api_to_cairo_vm_program};
 use crate::fee::eth_gas_constants;
 use crate::transaction::errors::TransactionExecutionError;
+use crate::utils::u128_from_usize;
+use crate::versioned_constants::VersionedConstants;
 
 #[cfg(test)]
 #[path = "contract_class_test.rs"]
@@ -65,6 +71,20 @@ impl ContractClass {
         }
     }
 
+    /// Returns the estimated VM resources required for computing Casm hash, where only the bytecode
+    /// segments containing any of the given visited PCs are loaded and hashed by the OS.
+    pub fn estimate_visited_casm_hash_computation_resources(
+        &self,
+        visited_pcs: &HashSet<usize>,
+    ) -> Result<ExecutionResources, TransactionExecutionError> {
+        match self {
+            ContractClass::V0(class) => Ok(class.estimate_casm_hash_computation_resources()),
+            ContractClass::V1(class) => {
+                class.estimate_visited_casm_hash_computation_resources(visited_pcs)
+            }
+        }
+    }
+
     pub fn get_visited_segments(
         &self,
         visited_pcs: &HashSet<usize>,
@@ -142,8 +162,54 @@ pub struct ContractClassV0Inner {
     #[serde(deserialize_with = "deserialize_program")]
     pub program: Program,
     pub entry_points_by_type: HashMap<EntryPointType, Vec<EntryPoint>>,
+    #[serde(skip)]
+    pub entry_point_index: EntryPointIndex<usize>,
 }
 
+/// The entry points of a class by type and selector, built on the first entry point resolution of
+/// the class and reused by its later calls; the class is shared by class hash through the class
+/// caches, so repeated setups of the same class resolve their entry point with a single lookup.
+/// Selectors appearing more than once are not indexed, and are resolved (and reported) by scanning
+/// the entry points of the class.
+#[derive(Clone, Debug, Default)]
+pub struct EntryPointIndex<T>(OnceLock<HashMap<(EntryPointType, EntryPointSelector), T>>);
+
+impl<T> EntryPointIndex<T> {
+    /// Returns the indexed entry point of the call, building the index from the given entry points
+    /// if this is the first resolution.
+    pub fn get(
+        &self,
+        call: &CallEntryPoint,
+        entry_points: impl FnOnce() -> Vec<((EntryPointType, EntryPointSelector), T)>,
+    ) -> Option<&T> {
+        let index = self.0.get_or_init(|| {
+            let mut index = HashMap::new();
+            let mut duplicated_keys = HashSet::new();
+            for (key, entry_point) in entry_points() {
+                if index.insert(key, entry_point).is_some() {
+                    duplicated_keys.insert(key);
+                }
+            }
+            index.retain(|key, _| !duplicated_keys.contains(key));
+            index
+        });
+        index.get(&(call.entry_point_type, call.entry_point_selector))
+    }
+
+    pub fn is_built(&self) -> bool {
+        self.0.get().is_some()
+    }
+}
+
+// The index is derived from the entry points of the class, and does not take part in its equality.
+impl<T> PartialEq for EntryPointIndex<T> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<T> Eq for EntryPointIndex<T> {}
+
 impl TryFrom<DeprecatedContractClass> for ContractClassV0 {
     type Error = ProgramError;
 
@@ -151,6 +217,7 @@ impl TryFrom<DeprecatedContractClass> for ContractClassV0 {
         Ok(Self(Arc::new(ContractClassV0Inner {
             program: sn_api_to_cairo_vm_program(class.program)?,
             entry_points_by_type: class.entry_points_by_type,
+            entry_point_index: Default::default(),
         })))
     }
 }
@@ -189,6 +256,19 @@ impl ContractClassV1 {
             return Err(PreExecutionError::InvalidConstructorEntryPointName);
         }
 
+        let indexed_entry_point = self.0.entry_point_index.get(call, || {
+            self.0
+                .entry_points_by_type
+                .iter()
+                .flat_map(|(typ, entry_points)| {
+                    entry_points.iter().map(|ep| ((*typ, ep.selector), ep.clone()))
+                })
+                .collect()
+        });
+        if let Some(entry_point) = indexed_entry_point {
+            return Ok(entry_point.clone());
+        }
+
         let entry_points_of_same_type = &self.0.entry_points_by_type[&call.entry_point_type];
         let filtered_entry_points: Vec<_> = entry_points_of_same_type
             .iter()
@@ -222,6 +302,31 @@ impl ContractClassV1 {
         get_visited_segments(&self.bytecode_segment_lengths, &mut reversed_visited_pcs, &mut 0)
     }
 
+    /// Returns the estimated VM resources required for computing Casm hash, where only the
+    /// top-level segments containing any of the given visited PCs are loaded and hashed by the OS.
+    fn estimate_visited_casm_hash_computation_resources(
+        &self,
+        visited_pcs: &HashSet<usize>,
+    ) -> Result<ExecutionResources, TransactionExecutionError> {
+        let NestedIntList::Node(segments) = &self.bytecode_segment_lengths else {
+            // The entire contract is a single segment, which is loaded as a whole.
+            return Ok(self.estimate_casm_hash_computation_resources());
+        };
+
+        // The visited segments are given in ascending order.
+        let mut visited_segments = self.get_visited_segments(visited_pcs)?.into_iter().peekable();
+        let mut bytecode_offset = 0;
+        Ok(estimate_segmented_casm_hash_computation_resources(segments.iter().map(|segment| {
+            let length = segment_lengths_sum(segment);
+            bytecode_offset += length;
+            let mut is_loaded = false;
+            while visited_segments.next_if(|pc| *pc < bytecode_offset).is_some() {
+                is_loaded = true;
+            }
+            (length, is_loaded)
+        })))
+    }
+
     pub fn try_from_json_string(raw_contract_class: &str) -> Result<ContractClassV1, ProgramError> {
         let casm_contract_class: CasmContractClass = serde_json::from_str(raw_contract_class)?;
         let contract_class: ContractClassV1 = casm_contract_class.try_into()?;
@@ -229,6 +334,43 @@ impl ContractClassV1 {
         Ok(contract_class)
     }
 
+    /// Compiles the given Sierra class to CASM by the compiler version pinned by the given
+    /// versioned constants. Fails if the compiler linked into the Blockifier is of another version,
+    /// rather than run a class compiled differently than the network would have.
+    pub fn try_from_sierra(
+        sierra_contract_class: SierraContractClass,
+        versioned_constants: &VersionedConstants,
+    ) -> ContractClassResult<ContractClassV1> {
+        let compiler_constants = versioned_constants
+            .sierra_compiler
+            .as_ref()
+            .ok_or(ContractClassError::SierraCompilerVersionNotPinned)?;
+        let linked_version = current_compiler_version_id().to_string();
+        if linked_version != compiler_constants.compiler_version {
+            return Err(ContractClassError::SierraCompilerVersionMismatch {
+                required_version: compiler_constants.compiler_version.clone(),
+                linked_version,
+            });
+        }
+
+        let add_pythonic_hints = false;
+        let casm_contract_class = CasmContractClass::from_contract_class(
+            sierra_contract_class,
+            add_pythonic_hints,
+            compiler_constants.max_bytecode_size,
+        )?;
+        Ok(casm_contract_class.try_into()?)
+    }
+
+    pub fn try_from_sierra_json_string(
+        raw_sierra_contract_class: &str,
+        versioned_constants: &VersionedConstants,
+    ) -> ContractClassResult<ContractClassV1> {
+        let sierra_contract_class: SierraContractClass =
+            serde_json::from_str(raw_sierra_contract_class)?;
+        Self::try_from_sierra(sierra_contract_class, versioned_constants)
+    }
+
     /// Returns an empty contract class for testing purposes.
     #[cfg(any(feature = "testing", test))]
     pub fn empty_for_testing() -> Self {
@@ -237,6 +379,21 @@ impl ContractClassV1 {
             entry_points_by_type: Default::default(),
             hints: Default::default(),
             bytecode_segment_lengths: NestedIntList::Leaf(0),
+            sierra_debug_info: None,
+            entry_point_index: Default::default(),
+        }))
+    }
+
+    pub fn sierra_debug_info(&self) -> Option<&SierraDebugInfo> {
+        self.sierra_debug_info.as_ref()
+    }
+
+    /// Returns a copy of the class carrying the given Sierra debug info; errors raised in the class
+    /// are then attributed to the Sierra functions they were raised in.
+    pub fn with_sierra_debug_info(&self, sierra_debug_info: SierraDebugInfo) -> Self {
+        Self(Arc::new(ContractClassV1Inner {
+            sierra_debug_info: Some(sierra_debug_info),
+            ..self.0.as_ref().clone()
         }))
     }
 }
@@ -261,28 +418,161 @@ pub fn estimate_casm_hash_computation_resources(
         }
         NestedIntList::Node(segments) => {
             // The contract code is segmented by its functions.
-            let mut execution_resources = ExecutionResources {
-                n_steps: 491,
-                n_memory_holes: 0,
-                builtin_instance_counter: HashMap::from([(POSEIDON_BUILTIN_NAME.to_string(), 11)]),
-            };
-            let base_segment_cost = ExecutionResources {
-                n_steps: 24,
-                n_memory_holes: 1,
-                builtin_instance_counter: HashMap::from([(POSEIDON_BUILTIN_NAME.to_string(), 1)]),
-            };
-            for segment in segments {
+            estimate_segmented_casm_hash_computation_resources(segments.iter().map(|segment| {
                 let NestedIntList::Leaf(length) = segment else {
                     panic!(
                         "Estimating hash cost is only supported for segmentation depth at most 1."
                     );
                 };
-                execution_resources += &poseidon_hash_many_cost(*length);
-                execution_resources += &base_segment_cost;
+                (*length, true)
+            }))
+        }
+    }
+}
+
+/// Returns the estimated VM resources required for computing the Casm hash of a segmented
+/// bytecode, given the length of each segment and whether it is loaded; the OS only hashes the
+/// loaded segments, and is given the hashes of the others.
+fn estimate_segmented_casm_hash_computation_resources(
+    segments: impl Iterator<Item = (usize, bool)>,
+) -> ExecutionResources {
+    let mut execution_resources = ExecutionResources {
+        n_steps: 491,
+        n_memory_holes: 0,
+        builtin_instance_counter: HashMap::from([(POSEIDON_BUILTIN_NAME.to_string(), 11)]),
+    };
+    let base_segment_cost = ExecutionResources {
+        n_steps: 24,
+        n_memory_holes: 1,
+        builtin_instance_counter: HashMap::from([(POSEIDON_BUILTIN_NAME.to_string(), 1)]),
+    };
+    for (length, is_loaded) in segments {
+        if is_loaded {
+            execution_resources += &poseidon_hash_many_cost(length);
+        }
+        execution_resources += &base_segment_cost;
+    }
+    execution_resources
+}
+
+/// The bytecode of a Cairo 1 class, structured by the segments it is hashed by.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BytecodeSegmentStructure {
+    Leaf(Vec<StarkFelt>),
+    Node(Vec<BytecodeSegment>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BytecodeSegment {
+    /// Whether any of the PCs of the segment was visited; only used segments are loaded by the OS.
+    pub is_used: bool,
+    pub structure: BytecodeSegmentStructure,
+}
+
+impl BytecodeSegmentStructure {
+    /// Structures the given bytecode by the given segment lengths, marking the segments that
+    /// contain any of the given visited PCs as used. Fails if the segments do not cover the
+    /// bytecode exactly.
+    pub fn new(
+        bytecode: &[StarkFelt],
+        bytecode_segment_lengths: &NestedIntList,
+        visited_pcs: &HashSet<usize>,
+    ) -> Result<Self, TransactionExecutionError> {
+        let segments_length = segment_lengths_sum(bytecode_segment_lengths);
+        if segments_length != bytecode.len() {
+            return Err(TransactionExecutionError::InvalidBytecodeSegmentLengths {
+                segments_length,
+                bytecode_length: bytecode.len(),
+            });
+        }
+
+        Ok(Self::new_at_offset(bytecode, bytecode_segment_lengths, visited_pcs, &mut 0))
+    }
+
+    fn new_at_offset(
+        bytecode: &[StarkFelt],
+        segment_lengths: &NestedIntList,
+        visited_pcs: &HashSet<usize>,
+        bytecode_offset: &mut usize,
+    ) -> Self {
+        match segment_lengths {
+            NestedIntList::Leaf(length) => {
+                let segment = *bytecode_offset..*bytecode_offset + length;
+                *bytecode_offset += length;
+                Self::Leaf(bytecode[segment].to_vec())
             }
-            execution_resources
+            NestedIntList::Node(segments) => Self::Node(
+                segments
+                    .iter()
+                    .map(|segment_lengths| {
+                        let segment_start = *bytecode_offset;
+                        let structure = Self::new_at_offset(
+                            bytecode,
+                            segment_lengths,
+                            visited_pcs,
+                            bytecode_offset,
+                        );
+                        let segment = segment_start..*bytecode_offset;
+                        BytecodeSegment {
+                            is_used: visited_pcs.iter().any(|pc| segment.contains(pc)),
+                            structure,
+                        }
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        match self {
+            Self::Leaf(bytecode) => bytecode.len(),
+            Self::Node(segments) => segments.iter().map(|segment| segment.structure.length()).sum(),
+        }
+    }
+
+    /// Returns the length of the bytecode loaded by the OS; i.e., of the used segments.
+    pub fn loaded_length(&self) -> usize {
+        match self {
+            Self::Leaf(bytecode) => bytecode.len(),
+            Self::Node(segments) => segments
+                .iter()
+                .filter(|segment| segment.is_used)
+                .map(|segment| segment.structure.loaded_length())
+                .sum(),
         }
     }
+
+    /// Returns the hash of the bytecode, as in the compiled class hash: the Poseidon hash of the
+    /// bytecode of a leaf, or one plus the Poseidon hash of the lengths and hashes of the segments
+    /// of a node.
+    pub fn hash(&self) -> StarkFelt {
+        let hash = match self {
+            Self::Leaf(bytecode) => {
+                let bytecode: Vec<_> =
+                    bytecode.iter().map(|felt| FieldElement::from(*felt)).collect();
+                poseidon_hash_many(&bytecode)
+            }
+            Self::Node(segments) => {
+                let segments_data: Vec<_> = segments
+                    .iter()
+                    .flat_map(|segment| {
+                        let length = StarkFelt::from(u128_from_usize(segment.structure.length()));
+                        [FieldElement::from(length), FieldElement::from(segment.structure.hash())]
+                    })
+                    .collect();
+                poseidon_hash_many(&segments_data) + FieldElement::ONE
+            }
+        };
+        StarkFelt::from(hash)
+    }
+}
+
+/// Returns the total length of the segments of the given segment structure.
+fn segment_lengths_sum(segment_lengths: &NestedIntList) -> usize {
+    match segment_lengths {
+        NestedIntList::Leaf(length) => *length,
+        NestedIntList::Node(segments) => segments.iter().map(segment_lengths_sum).sum(),
+    }
 }
 
 // Returns the set of segments that were visited according to the given visited PCs and segment
@@ -338,6 +628,29 @@ pub struct ContractClassV1Inner {
     pub entry_points_by_type: HashMap<EntryPointType, Vec<EntryPointV1>>,
     pub hints: HashMap<String, Hint>,
     bytecode_segment_lengths: NestedIntList,
+    sierra_debug_info: Option<SierraDebugInfo>,
+    pub entry_point_index: EntryPointIndex<EntryPointV1>,
+}
+
+/// Maps the bytecode of a Cairo 1 class to the Sierra functions it was compiled from, for
+/// attributing errors to functions. Built by the compiler of the class, from the Sierra debug info
+/// and the code offsets of the Sierra statements.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SierraDebugInfo {
+    /// The names of the Sierra functions, by the pc their code starts at.
+    function_names_by_pc: BTreeMap<usize, String>,
+}
+
+impl SierraDebugInfo {
+    pub fn new(function_names_by_pc: BTreeMap<usize, String>) -> Self {
+        Self { function_names_by_pc }
+    }
+
+    /// Returns the name of the function whose code contains the given pc; i.e., the last function
+    /// starting at or before it.
+    pub fn function_name_at(&self, pc: usize) -> Option<&str> {
+        self.function_names_by_pc.range(..=pc).next_back().map(|(_, name)| name.as_str())
+    }
 }
 
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
@@ -414,12 +727,22 @@ impl TryFrom<CasmContractClass> for ContractClassV1 {
         let bytecode_segment_lengths = class
             .bytecode_segment_lengths
             .unwrap_or_else(|| NestedIntList::Leaf(program.data_len()));
+        let segments_length = segment_lengths_sum(&bytecode_segment_lengths);
+        if segments_length != program.data_len() {
+            return Err(ProgramError::Parse(serde_json::Error::custom(format!(
+                "Bytecode segment lengths sum to {segments_length}, but the bytecode is of length \
+                 {}.",
+                program.data_len()
+            ))));
+        }
 
         Ok(Self(Arc::new(ContractClassV1Inner {
             program,
             entry_points_by_type,
             hints: string_to_hint,
             bytecode_segment_lengths,
+            sierra_debug_info: None,
+            entry_point_index: Default::default(),
         })))
     }
 }
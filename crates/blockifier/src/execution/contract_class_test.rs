@@ -1,12 +1,40 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
 use assert_matches::assert_matches;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::compiler_version::current_compiler_version_id;
 use cairo_lang_starknet_classes::NestedIntList;
+use cairo_vm::types::errors::program_errors::ProgramError;
 use rstest::rstest;
+use starknet_api::core::{ClassHash, ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
+use starknet_crypto::{poseidon_hash_many, FieldElement};
 
-use crate::execution::contract_class::{ContractClassV1, ContractClassV1Inner};
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::ChainInfo;
+use crate::execution::call_info::Retdata;
+use crate::execution::contract_class::{
+    estimate_casm_hash_computation_resources, BytecodeSegment, BytecodeSegmentStructure,
+    ContractClass, ContractClassV1, ContractClassV1Inner, SierraDebugInfo,
+};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::errors::ContractClassError;
+use crate::execution::execution_utils::poseidon_hash_many_cost;
+use crate::retdata;
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{
+    get_raw_contract_class, trivial_external_entry_point_new,
+    trivial_external_entry_point_with_address, CairoVersion, MINIMAL_CONTRACT_SIERRA_PATH,
+};
 use crate::transaction::errors::TransactionExecutionError;
+use crate::versioned_constants::{SierraCompilerConstants, VersionedConstants};
 
 #[rstest]
 fn test_get_visited_segments() {
@@ -26,6 +54,8 @@ fn test_get_visited_segments() {
             ])]),
             NestedIntList::Leaf(162),
         ]),
+        sierra_debug_info: None,
+        entry_point_index: Default::default(),
     }));
 
     assert_eq!(
@@ -42,3 +72,215 @@ fn test_get_visited_segments() {
         TransactionExecutionError::InvalidSegmentStructure(907, 807)
     );
 }
+
+#[test]
+fn test_sierra_debug_info_function_name_at() {
+    let debug_info =
+        SierraDebugInfo::new(BTreeMap::from([(10, "foo".to_string()), (25, "bar".to_string())]));
+
+    assert_eq!(debug_info.function_name_at(0), None);
+    assert_eq!(debug_info.function_name_at(10), Some("foo"));
+    assert_eq!(debug_info.function_name_at(24), Some("foo"));
+    assert_eq!(debug_info.function_name_at(25), Some("bar"));
+    assert_eq!(debug_info.function_name_at(1000), Some("bar"));
+}
+
+#[test]
+fn test_bytecode_segment_structure() {
+    let bytecode: Vec<_> = (1_u8..=6).map(|value| stark_felt!(value)).collect();
+    let segment_lengths = NestedIntList::Node(vec![
+        NestedIntList::Leaf(2),
+        NestedIntList::Leaf(3),
+        NestedIntList::Leaf(1),
+    ]);
+
+    // Only the first and last segments are visited.
+    let structure =
+        BytecodeSegmentStructure::new(&bytecode, &segment_lengths, &HashSet::from([1, 5])).unwrap();
+    let segment = |range: std::ops::Range<usize>, is_used| BytecodeSegment {
+        is_used,
+        structure: BytecodeSegmentStructure::Leaf(bytecode[range].to_vec()),
+    };
+    assert_eq!(
+        structure,
+        BytecodeSegmentStructure::Node(vec![
+            segment(0..2, true),
+            segment(2..5, false),
+            segment(5..6, true)
+        ])
+    );
+    assert_eq!(structure.length(), 6);
+    assert_eq!(structure.loaded_length(), 3);
+
+    // The hash of a node is one plus the hash of the lengths and hashes of its segments.
+    let hash = |felts: &[StarkFelt]| {
+        poseidon_hash_many(&felts.iter().map(|felt| FieldElement::from(*felt)).collect::<Vec<_>>())
+    };
+    let expected_hash = hash(&[
+        stark_felt!(2_u8),
+        StarkFelt::from(hash(&bytecode[0..2])),
+        stark_felt!(3_u8),
+        StarkFelt::from(hash(&bytecode[2..5])),
+        stark_felt!(1_u8),
+        StarkFelt::from(hash(&bytecode[5..6])),
+    ]) + FieldElement::ONE;
+    assert_eq!(structure.hash(), StarkFelt::from(expected_hash));
+    let unsegmented_structure =
+        BytecodeSegmentStructure::new(&bytecode, &NestedIntList::Leaf(6), &HashSet::new()).unwrap();
+    assert_eq!(unsegmented_structure.hash(), StarkFelt::from(hash(&bytecode)));
+
+    // The segments must cover the bytecode exactly.
+    assert_matches!(
+        BytecodeSegmentStructure::new(&bytecode, &NestedIntList::Leaf(7), &HashSet::new()),
+        Err(TransactionExecutionError::InvalidBytecodeSegmentLengths {
+            segments_length: 7,
+            bytecode_length: 6
+        })
+    );
+}
+
+#[test]
+fn test_estimate_visited_casm_hash_computation_resources() {
+    let segment_lengths = NestedIntList::Node(vec![
+        NestedIntList::Leaf(2),
+        NestedIntList::Leaf(3),
+        NestedIntList::Leaf(1),
+    ]);
+    let class = ContractClassV1(Arc::new(ContractClassV1Inner {
+        program: Default::default(),
+        entry_points_by_type: Default::default(),
+        hints: Default::default(),
+        bytecode_segment_lengths: segment_lengths.clone(),
+        sierra_debug_info: None,
+        entry_point_index: Default::default(),
+    }));
+
+    // The unvisited segment is not hashed.
+    assert_eq!(
+        &class.estimate_visited_casm_hash_computation_resources(&HashSet::from([0, 1, 5])).unwrap()
+            + &poseidon_hash_many_cost(3),
+        estimate_casm_hash_computation_resources(&segment_lengths)
+    );
+
+    // Each visited segment must have its starting PC visited.
+    assert_matches!(
+        class.estimate_visited_casm_hash_computation_resources(&HashSet::from([0, 3])),
+        Err(TransactionExecutionError::InvalidSegmentStructure(3, 2))
+    );
+}
+
+#[test]
+fn test_bytecode_segment_lengths_validation() {
+    let raw_class = FeatureContract::TestContract(CairoVersion::Cairo1).get_raw_class();
+    let mut casm_contract_class: CasmContractClass = serde_json::from_str(&raw_class).unwrap();
+    let bytecode_length = casm_contract_class.bytecode.len();
+    casm_contract_class.bytecode_segment_lengths =
+        Some(NestedIntList::Node(vec![NestedIntList::Leaf(bytecode_length - 1)]));
+
+    assert_matches!(
+        ContractClassV1::try_from(casm_contract_class),
+        Err(ProgramError::Parse(error))
+        if error.to_string().contains("Bytecode segment lengths sum to")
+    );
+}
+
+#[test]
+fn test_sierra_compiler_version_pinning() {
+    // The latest constants pin the compiler linked into the Blockifier.
+    let latest_constants = VersionedConstants::latest_constants();
+    let compiler_constants = latest_constants.sierra_compiler.clone().unwrap();
+    assert_eq!(compiler_constants.compiler_version, current_compiler_version_id().to_string());
+
+    // Classes are not compiled by a compiler other than the pinned one.
+    let raw_sierra_contract_class = r#"{
+        "sierra_program": [],
+        "contract_class_version": "0.1.0",
+        "entry_points_by_type": {"EXTERNAL": [], "L1_HANDLER": [], "CONSTRUCTOR": []}
+    }"#;
+    let compile = |versioned_constants: &VersionedConstants| {
+        ContractClassV1::try_from_sierra_json_string(raw_sierra_contract_class, versioned_constants)
+    };
+    let mut versioned_constants = latest_constants.clone();
+    versioned_constants.sierra_compiler = Some(SierraCompilerConstants {
+        compiler_version: "0.0.0".to_string(),
+        ..compiler_constants
+    });
+    assert_matches!(
+        compile(&versioned_constants),
+        Err(ContractClassError::SierraCompilerVersionMismatch { required_version, .. })
+        if required_version == "0.0.0"
+    );
+
+    versioned_constants.sierra_compiler = None;
+    assert_matches!(
+        compile(&versioned_constants),
+        Err(ContractClassError::SierraCompilerVersionNotPinned)
+    );
+}
+
+#[test]
+fn test_sierra_compilation() {
+    let raw_sierra_contract_class = get_raw_contract_class(MINIMAL_CONTRACT_SIERRA_PATH);
+    let contract_class = ContractClassV1::try_from_sierra_json_string(
+        &raw_sierra_contract_class,
+        VersionedConstants::latest_constants(),
+    )
+    .unwrap();
+
+    // The compiled class is executable.
+    let class_hash = class_hash!("0x1357");
+    let contract_address = contract_address!("0x2468");
+    let mut state = CachedState::from(DictStateReader {
+        address_to_class_hash: HashMap::from([(contract_address, class_hash)]),
+        class_hash_to_class: HashMap::from([(class_hash, ContractClass::V1(contract_class))]),
+        ..Default::default()
+    });
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("empty"),
+        ..trivial_external_entry_point_with_address(contract_address)
+    };
+    let call_info = entry_point_call.execute_directly(&mut state).unwrap();
+    assert!(!call_info.execution.failed);
+    assert_eq!(call_info.execution.retdata, retdata![]);
+}
+
+#[rstest]
+fn test_entry_point_index_reuse(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let chain_info = ChainInfo::create_for_testing();
+    let mut state = test_state(&chain_info, 0, &[(test_contract, 1)]);
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        calldata: calldata![stark_felt!(15_u8), stark_felt!(7_u8)],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let entry_point_index_is_built = |class: &ContractClass| match class {
+        ContractClass::V0(class) => class.entry_point_index.is_built(),
+        ContractClass::V1(class) => class.entry_point_index.is_built(),
+    };
+
+    // The index is built by the first call to the class.
+    let class_hash = test_contract.get_class_hash();
+    assert!(!entry_point_index_is_built(&state.get_compiled_contract_class(class_hash).unwrap()));
+    entry_point_call.clone().execute_directly(&mut state).unwrap();
+    let class = state.get_compiled_contract_class(class_hash).unwrap();
+    assert!(entry_point_index_is_built(&class));
+
+    // Later calls resolve their entry point from the same index, shared by the cached class.
+    let call_info = entry_point_call.clone().execute_directly(&mut state).unwrap();
+    assert_eq!(call_info.execution.retdata, retdata![stark_felt!(7_u8)]);
+    let cached_class = state.get_compiled_contract_class(class_hash).unwrap();
+    match (&class, &cached_class) {
+        (ContractClass::V0(class), ContractClass::V0(cached_class)) => assert!(std::ptr::eq(
+            class.entry_point_index.get(&entry_point_call, Vec::new).unwrap(),
+            cached_class.entry_point_index.get(&entry_point_call, Vec::new).unwrap(),
+        )),
+        (ContractClass::V1(class), ContractClass::V1(cached_class)) => assert!(std::ptr::eq(
+            class.entry_point_index.get(&entry_point_call, Vec::new).unwrap(),
+            cached_class.entry_point_index.get(&entry_point_call, Vec::new).unwrap(),
+        )),
+        _ => panic!("The class version changed."),
+    }
+}
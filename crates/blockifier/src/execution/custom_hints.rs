@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use cairo_felt::Felt252;
+use cairo_lang_casm::hints::Hint;
+use cairo_vm::hint_processor::hint_processor_definition::HintReference;
+use cairo_vm::serde::deserialize_program::ApTracking;
+use cairo_vm::types::exec_scope::ExecutionScopes;
+use cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm::vm::vm_core::VirtualMachine;
+
+use crate::execution::common_hints::HintExecutionResult;
+
+#[cfg(test)]
+#[path = "custom_hints_test.rs"]
+pub mod test;
+
+/// An implementation of a hint registered by a chain, e.g., for precompile-like functionality of
+/// appchains; takes precedence over the implementation of the hint processors.
+/// Only the hints allowed by the chain may be registered (see `ChainInfo::allowed_custom_hints`).
+pub trait CustomHint: std::fmt::Debug + Send + Sync {
+    /// Executes the hint in a Cairo 0 run, given the references of its variables.
+    fn execute_deprecated(
+        &self,
+        _vm: &mut VirtualMachine,
+        _exec_scopes: &mut ExecutionScopes,
+        _ids_data: &HashMap<String, HintReference>,
+        _ap_tracking: &ApTracking,
+        _constants: &HashMap<String, Felt252>,
+    ) -> HintExecutionResult {
+        Err(HintError::UnknownHint("The custom hint does not support Cairo 0.".into()))
+    }
+
+    /// Executes the hint in a Cairo 1 run.
+    fn execute(
+        &self,
+        _vm: &mut VirtualMachine,
+        _exec_scopes: &mut ExecutionScopes,
+        _hint: &Hint,
+    ) -> HintExecutionResult {
+        Err(HintError::UnknownHint("The custom hint does not support Cairo 1.".into()))
+    }
+}
+
+/// The registered custom hints, keyed by the hint code; the code of a Cairo 1 hint is its JSON
+/// serialization.
+pub type CustomHints = HashMap<String, Arc<dyn CustomHint>>;
+
+/// A Cairo 1 hint compiled with its registered implementation.
+pub struct CompiledCustomHint {
+    pub hint: Hint,
+    pub custom_hint: Arc<dyn CustomHint>,
+}
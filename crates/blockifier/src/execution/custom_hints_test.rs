@@ -0,0 +1,111 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use cairo_felt::Felt252;
+use cairo_lang_casm::hints::Hint;
+use cairo_vm::hint_processor::hint_processor_definition::HintReference;
+use cairo_vm::serde::deserialize_program::ApTracking;
+use cairo_vm::types::exec_scope::ExecutionScopes;
+use cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use cairo_vm::vm::vm_core::VirtualMachine;
+use rstest::rstest;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::check_entry_point_execution_error_for_custom_hint;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
+use crate::execution::common_hints::HintExecutionResult;
+use crate::execution::contract_class::ContractClass;
+use crate::execution::custom_hints::CustomHint;
+use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
+use crate::execution::errors::CustomHintError;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
+
+const CUSTOM_HINT_ERROR: &str = "Executed by the custom hint.";
+const STORAGE_READ_HINT: &str =
+    "syscall_handler.storage_read(segments=segments, syscall_ptr=ids.syscall_ptr)";
+
+/// Fails the run, to indicate the custom hint was executed.
+#[derive(Debug)]
+struct FailingHint;
+
+impl CustomHint for FailingHint {
+    fn execute_deprecated(
+        &self,
+        _vm: &mut VirtualMachine,
+        _exec_scopes: &mut ExecutionScopes,
+        _ids_data: &HashMap<String, HintReference>,
+        _ap_tracking: &ApTracking,
+        _constants: &HashMap<String, Felt252>,
+    ) -> HintExecutionResult {
+        Err(HintError::Internal(VirtualMachineError::Other(anyhow::anyhow!(CUSTOM_HINT_ERROR))))
+    }
+
+    fn execute(
+        &self,
+        _vm: &mut VirtualMachine,
+        _exec_scopes: &mut ExecutionScopes,
+        _hint: &Hint,
+    ) -> HintExecutionResult {
+        Err(HintError::Internal(VirtualMachineError::Other(anyhow::anyhow!(CUSTOM_HINT_ERROR))))
+    }
+}
+
+/// Returns the codes of the hints that invoke syscalls in the given contract.
+fn syscall_hint_codes(contract: FeatureContract) -> HashSet<String> {
+    match contract.get_class() {
+        ContractClass::V0(_) => HashSet::from([STORAGE_READ_HINT.to_string()]),
+        ContractClass::V1(class) => {
+            class.hints.keys().filter(|code| code.contains("SystemCall")).cloned().collect()
+        }
+    }
+}
+
+#[test]
+fn test_register_disallowed_custom_hint() {
+    let mut block_context = BlockContext::create_for_testing();
+    let hint_code = "custom_hint()".to_string();
+
+    assert_matches!(
+        block_context.register_custom_hint(hint_code.clone(), Arc::new(FailingHint)),
+        Err(CustomHintError::HintNotAllowed { hint_code: code }) if code == hint_code
+    );
+    assert!(block_context.hooks().custom_hints().is_empty());
+}
+
+#[rstest]
+fn test_custom_hint(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let hint_codes = syscall_hint_codes(test_contract);
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.chain_info.allowed_custom_hints.clone_from(&hint_codes);
+    for hint_code in hint_codes {
+        block_context.register_custom_hint(hint_code, Arc::new(FailingHint)).unwrap();
+    }
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let error = entry_point_call
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap_err();
+    check_entry_point_execution_error_for_custom_hint!(&error, CUSTOM_HINT_ERROR);
+}
@@ -8,7 +8,7 @@ use starknet_api::hash::StarkHash;
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants::{CONSTRUCTOR_ENTRY_POINT_NAME, DEFAULT_ENTRY_POINT_SELECTOR};
-use crate::execution::call_info::{CallExecution, CallInfo};
+use crate::execution::call_info::{CallExecution, CallInfo, TrackedResource};
 use crate::execution::contract_class::ContractClassV0;
 use crate::execution::deprecated_syscalls::hint_processor::DeprecatedSyscallHintProcessor;
 use crate::execution::entry_point::{
@@ -16,8 +16,10 @@ use crate::execution::entry_point::{
 };
 use crate::execution::errors::{PostExecutionError, PreExecutionError};
 use crate::execution::execution_utils::{
-    read_execution_retdata, stark_felt_to_felt, Args, ReadOnlySegments,
+    read_execution_retdata, stark_felt_to_felt, Args, ReadOnlySegments, ResourcesSnapshot,
 };
+#[cfg(feature = "vm_tracer")]
+use crate::execution::vm_tracer::trace_vm_steps;
 use crate::state::state_api::State;
 
 pub struct VmExecutionContext<'a> {
@@ -53,10 +55,19 @@ pub fn execute_entry_point_call(
     let n_total_args = args.len();
 
     // Fix the VM resources, in order to calculate the usage of this run at the end.
-    let previous_resources = syscall_handler.resources.clone();
+    let previous_resources = ResourcesSnapshot::take(syscall_handler.resources);
 
     // Execute.
-    run_entry_point(&mut vm, &mut runner, &mut syscall_handler, entry_point_pc, args)?;
+    let n_available_steps = syscall_handler.context.n_remaining_steps();
+    run_entry_point(&mut vm, &mut runner, &mut syscall_handler, entry_point_pc, args)
+        .map_err(|error| error.with_available_steps(n_available_steps))?;
+
+    #[cfg(feature = "vm_tracer")]
+    if syscall_handler.context.tx_context.block_context.hooks.vm_tracer.is_some() {
+        // Relocate the trace as in Cairo 1 executions.
+        vm.relocate_trace(&[1, 1 + runner.get_program().data_len()])?;
+        trace_vm_steps(&vm, &call, previous_resources.resources(), syscall_handler.context)?;
+    }
 
     Ok(finalize_execution(
         vm,
@@ -83,7 +94,11 @@ pub fn initialize_execution_context<'a>(
     let proof_mode = false;
     let mut runner = CairoRunner::new(&contract_class.program, "starknet", proof_mode)?;
 
+    // The trace is only needed for tracing the steps of the VM.
+    #[cfg(not(feature = "vm_tracer"))]
     let trace_enabled = false;
+    #[cfg(feature = "vm_tracer")]
+    let trace_enabled = context.tx_context.block_context.hooks.vm_tracer.is_some();
     let mut vm = VirtualMachine::new(trace_enabled);
 
     runner.initialize_builtins(&mut vm)?;
@@ -113,6 +128,19 @@ pub fn resolve_entry_point_pc(
         return Err(PreExecutionError::InvalidConstructorEntryPointName);
     }
 
+    let indexed_pc = contract_class.entry_point_index.get(call, || {
+        contract_class
+            .entry_points_by_type
+            .iter()
+            .flat_map(|(typ, entry_points)| {
+                entry_points.iter().map(|ep| ((*typ, ep.selector), ep.offset.0))
+            })
+            .collect()
+    });
+    if let Some(pc) = indexed_pc {
+        return Ok(*pc);
+    }
+
     let entry_points_of_same_type = &contract_class.entry_points_by_type[&call.entry_point_type];
     let filtered_entry_points: Vec<_> = entry_points_of_same_type
         .iter()
@@ -212,7 +240,7 @@ pub fn finalize_execution(
     runner: CairoRunner,
     syscall_handler: DeprecatedSyscallHintProcessor<'_>,
     call: CallEntryPoint,
-    previous_resources: ExecutionResources,
+    previous_resources: ResourcesSnapshot,
     implicit_args: Vec<MaybeRelocatable>,
     n_total_args: usize,
 ) -> Result<CallInfo, PostExecutionError> {
@@ -243,7 +271,7 @@ pub fn finalize_execution(
     *syscall_handler.resources += &versioned_constants
         .get_additional_os_syscall_resources(&syscall_handler.syscall_counter)?;
 
-    let full_call_resources = &*syscall_handler.resources - &previous_resources;
+    let full_call_resources = previous_resources.consumed_since(syscall_handler.resources);
     Ok(CallInfo {
         call,
         execution: CallExecution {
@@ -253,10 +281,22 @@ pub fn finalize_execution(
             failed: false,
             gas_consumed: 0,
         },
-        resources: full_call_resources.filter_unused_builtins(),
+        resources: full_call_resources,
+        vm_memory_holes: vm_resources_without_inner_calls.n_memory_holes,
         inner_calls: syscall_handler.inner_calls,
+        tracked_resource: TrackedResource::CairoSteps,
+        dual_gas_metering: None,
+        // Assigned by the caller.
+        caller_gas_charge: None,
         storage_read_values: syscall_handler.read_values,
         accessed_storage_keys: syscall_handler.accessed_keys,
+        syscall_counter: syscall_handler.syscall_counter,
+        // Deprecated syscalls are not charged gas.
+        syscall_gas_records: vec![],
+        reentrant: false,
+        // Assigned by the top-level call.
+        call_index: 0,
+        parent_call_index: None,
     })
 }
 
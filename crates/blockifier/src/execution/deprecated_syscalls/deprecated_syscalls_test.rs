@@ -1,12 +1,18 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use cairo_felt::Felt252;
+use cairo_vm::hint_processor::hint_processor_definition::HintReference;
+use cairo_vm::serde::deserialize_program::ApTracking;
+use cairo_vm::types::relocatable::MaybeRelocatable;
 use cairo_vm::vm::runners::builtin_runner::RANGE_CHECK_BUILTIN_NAME;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use cairo_vm::vm::vm_core::VirtualMachine;
 use num_traits::Pow;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
-use starknet_api::core::{calculate_contract_address, ChainId, PatriciaKey};
+use starknet_api::core::{calculate_contract_address, ChainId, ContractAddress, PatriciaKey};
+use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{
@@ -17,13 +23,17 @@ use starknet_api::{calldata, stark_felt};
 use test_case::test_case;
 
 use crate::abi::abi_utils::selector_from_name;
-use crate::context::ChainInfo;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
 use crate::execution::call_info::{CallExecution, CallInfo, OrderedEvent, Retdata};
 use crate::execution::common_hints::ExecutionMode;
+use crate::execution::deprecated_syscalls::hint_processor::DeprecatedSyscallHintProcessor;
 use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
-use crate::execution::entry_point::{CallEntryPoint, CallType};
+use crate::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
 use crate::execution::errors::EntryPointExecutionError;
-use crate::execution::execution_utils::felt_to_stark_felt;
+use crate::execution::execution_utils::{
+    felt_to_stark_felt, stark_felt_from_ptr, stark_felt_to_felt, write_felt,
+    write_maybe_relocatable, write_stark_felt,
+};
 use crate::execution::syscalls::hint_processor::EmitEventError;
 use crate::state::state_api::StateReader;
 use crate::test_utils::contracts::FeatureContract;
@@ -148,6 +158,12 @@ fn test_nested_library_call() {
         resources: storage_entry_point_resources.clone(),
         storage_read_values: vec![stark_felt!(value + 1)],
         accessed_storage_keys: HashSet::from([storage_key!(key + 1)]),
+        syscall_counter: HashMap::from([
+            (DeprecatedSyscallSelector::StorageWrite, 1),
+            (DeprecatedSyscallSelector::StorageRead, 1),
+        ]),
+        call_index: 2,
+        parent_call_index: Some(1),
         ..Default::default()
     };
     let mut library_call_resources = &get_syscall_resources(DeprecatedSyscallSelector::LibraryCall)
@@ -162,6 +178,9 @@ fn test_nested_library_call() {
         execution: CallExecution::from_retdata(retdata![stark_felt!(value + 1)]),
         resources: library_call_resources.clone(),
         inner_calls: vec![nested_storage_call_info],
+        syscall_counter: HashMap::from([(DeprecatedSyscallSelector::LibraryCall, 1)]),
+        call_index: 1,
+        parent_call_index: Some(0),
         ..Default::default()
     };
     let storage_call_info = CallInfo {
@@ -170,6 +189,12 @@ fn test_nested_library_call() {
         resources: storage_entry_point_resources.clone(),
         storage_read_values: vec![stark_felt!(value)],
         accessed_storage_keys: HashSet::from([storage_key!(key)]),
+        syscall_counter: HashMap::from([
+            (DeprecatedSyscallSelector::StorageWrite, 1),
+            (DeprecatedSyscallSelector::StorageRead, 1),
+        ]),
+        call_index: 3,
+        parent_call_index: Some(0),
         ..Default::default()
     };
 
@@ -186,6 +211,7 @@ fn test_nested_library_call() {
         execution: CallExecution::from_retdata(retdata![stark_felt!(0_u8)]),
         resources: main_call_resources,
         inner_calls: vec![library_call_info, storage_call_info],
+        syscall_counter: HashMap::from([(DeprecatedSyscallSelector::LibraryCall, 2)]),
         ..Default::default()
     };
 
@@ -235,6 +261,12 @@ fn test_call_contract() {
         },
         storage_read_values: vec![stark_felt!(value)],
         accessed_storage_keys: HashSet::from([storage_key!(key)]),
+        syscall_counter: HashMap::from([
+            (DeprecatedSyscallSelector::StorageWrite, 1),
+            (DeprecatedSyscallSelector::StorageRead, 1),
+        ]),
+        call_index: 1,
+        parent_call_index: Some(0),
         ..Default::default()
     };
     let expected_call_info = CallInfo {
@@ -255,6 +287,7 @@ fn test_call_contract() {
                     3,
                 )]),
             },
+        syscall_counter: HashMap::from([(DeprecatedSyscallSelector::CallContract, 1)]),
         ..Default::default()
     };
 
@@ -440,6 +473,49 @@ fn test_block_info_syscalls(
     }
 }
 
+#[test]
+fn test_validate_forbidden_syscalls() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state = test_state(&ChainInfo::create_for_testing(), 0, &[(test_contract, 1)]);
+    let mut versioned_constants = VersionedConstants::create_for_testing();
+    versioned_constants.validate_forbidden_syscalls =
+        HashSet::from([DeprecatedSyscallSelector::GetBlockNumber]);
+
+    // Relaxed syscall.
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_get_sequencer_address"),
+        calldata: calldata![stark_felt!(TEST_SEQUENCER_ADDRESS)],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    assert_eq!(
+        entry_point_call
+            .execute_directly_in_validate_mode_given_versioned_constants(
+                &mut state,
+                versioned_constants.clone()
+            )
+            .unwrap()
+            .execution,
+        CallExecution::from_retdata(retdata![])
+    );
+
+    // Tightened syscall.
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_get_block_number"),
+        calldata: calldata![stark_felt!(CURRENT_BLOCK_NUMBER_FOR_VALIDATE)],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let error = entry_point_call
+        .execute_directly_in_validate_mode_given_versioned_constants(
+            &mut state,
+            versioned_constants,
+        )
+        .unwrap_err();
+    check_entry_point_execution_error_for_custom_hint!(
+        &error,
+        "Unauthorized syscall get_block_number in execution mode Validate.",
+    );
+}
+
 #[rstest]
 fn test_tx_info(#[values(false, true)] only_query: bool) {
     let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
@@ -567,3 +643,117 @@ fn emit_events(
 
     entry_point_call.execute_directly(&mut state)
 }
+
+/// Invokes the delegate and L1 handler library call syscalls through the syscall dispatch, as a
+/// contract does: the request is written to the syscall segment, whose pointer is passed to the
+/// syscall hint. None of the Cairo 0 test contracts invokes these syscalls.
+#[test]
+fn test_delegate_and_l1_handler_library_calls() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    // The Cairo 0 test contracts have no L1 handlers.
+    let l1_handler_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let mut state = test_state(
+        &ChainInfo::create_for_testing(),
+        0,
+        &[(test_contract, 2), (l1_handler_contract, 1)],
+    );
+    let tx_context = TransactionContext {
+        block_context: BlockContext::create_for_testing(),
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+    let mut resources = ExecutionResources::default();
+    let mut vm = VirtualMachine::new(false);
+    // The program and execution segments, followed by the syscall segment.
+    vm.add_memory_segment();
+    vm.add_memory_segment();
+    let syscall_ptr = vm.add_memory_segment();
+    let storage_address = test_contract.get_instance_address(0);
+    let mut syscall_handler = DeprecatedSyscallHintProcessor::new(
+        &mut state,
+        &mut resources,
+        &mut context,
+        syscall_ptr,
+        storage_address,
+        ContractAddress::default(),
+    );
+
+    let (key, value) = (stark_felt!(1234_u16), stark_felt!(18_u8));
+    let from_address = stark_felt!(1_u8);
+    let l1_handler_selector = selector_from_name("l1_handler_set_value");
+    // The selector and the request of each syscall: the callee (a contract address, or a class
+    // hash), the function selector and the calldata.
+    let syscalls = [
+        (
+            DeprecatedSyscallSelector::DelegateCall,
+            "DelegateCall",
+            *test_contract.get_instance_address(1).0.key(),
+            selector_from_name("test_storage_read_write"),
+            vec![key, value],
+        ),
+        (
+            DeprecatedSyscallSelector::DelegateL1Handler,
+            "DelegateL1Handler",
+            *l1_handler_contract.get_instance_address(0).0.key(),
+            l1_handler_selector,
+            vec![from_address, key, value],
+        ),
+        (
+            DeprecatedSyscallSelector::LibraryCallL1Handler,
+            "LibraryCallL1Handler",
+            l1_handler_contract.get_class_hash().0,
+            l1_handler_selector,
+            vec![from_address, key, value],
+        ),
+    ];
+    for (fp_offset, (_, selector_name, callee, function_selector, calldata)) in
+        syscalls.iter().enumerate()
+    {
+        let calldata: Vec<MaybeRelocatable> =
+            calldata.iter().map(|&felt| stark_felt_to_felt(felt).into()).collect();
+        let calldata_ptr = vm.add_memory_segment();
+        vm.load_data(calldata_ptr, &calldata).unwrap();
+
+        let mut request_ptr = syscall_handler.syscall_ptr;
+        vm.insert_value((vm.get_fp() + fp_offset).unwrap(), request_ptr).unwrap();
+        write_felt(&mut vm, &mut request_ptr, Felt252::from_bytes_be(selector_name.as_bytes()))
+            .unwrap();
+        let calldata_length = StarkFelt::from(u64::try_from(calldata.len()).unwrap());
+        for felt in [*callee, function_selector.0, calldata_length] {
+            write_stark_felt(&mut vm, &mut request_ptr, felt).unwrap();
+        }
+        write_maybe_relocatable(&mut vm, &mut request_ptr, calldata_ptr).unwrap();
+        let ids_data = HashMap::from([(
+            "syscall_ptr".to_string(),
+            HintReference::new_simple(i32::try_from(fp_offset).unwrap()),
+        )]);
+        syscall_handler.execute_next_syscall(&mut vm, &ids_data, &ApTracking::default()).unwrap();
+
+        // The response follows the request: the return data length and pointer.
+        let mut response_ptr = request_ptr;
+        assert_eq!(stark_felt_from_ptr(&vm, &mut response_ptr).unwrap(), stark_felt!(1_u8));
+        let mut retdata_ptr = vm.get_relocatable(response_ptr).unwrap();
+        assert_eq!(stark_felt_from_ptr(&vm, &mut retdata_ptr).unwrap(), value);
+        assert_eq!(syscall_handler.syscall_ptr, (response_ptr + 1_usize).unwrap());
+    }
+
+    // The calls run in the context of the caller.
+    let entry_point_types: Vec<EntryPointType> = syscall_handler
+        .inner_calls
+        .iter()
+        .map(|inner_call| {
+            assert_eq!(inner_call.call.call_type, CallType::Delegate);
+            assert_eq!(inner_call.call.storage_address, storage_address);
+            inner_call.call.entry_point_type
+        })
+        .collect();
+    assert_eq!(
+        entry_point_types,
+        vec![EntryPointType::External, EntryPointType::L1Handler, EntryPointType::L1Handler]
+    );
+    for (selector, ..) in syscalls {
+        assert_eq!(syscall_handler.syscall_counter[&selector], 1);
+    }
+    let storage_key = StorageKey::try_from(key).unwrap();
+    assert_eq!(state.get_storage_at(storage_address, storage_key).unwrap(), value);
+}
@@ -45,6 +45,7 @@ use crate::execution::execution_utils::{
     ReadOnlySegment, ReadOnlySegments,
 };
 use crate::execution::hint_code;
+use crate::execution::syscall_interceptor::execute_intercepted_call;
 use crate::execution::syscalls::hint_processor::EmitEventError;
 use crate::state::errors::StateError;
 use crate::state::state_api::State;
@@ -68,6 +69,8 @@ pub enum DeprecatedSyscallExecutionError {
     },
     #[error(transparent)]
     EmitEventError(#[from] EmitEventError),
+    #[error("Deploying from zero is not allowed on this chain.")]
+    ForbiddenDeployFromZero,
     #[error("{error}")]
     LibraryCallExecutionError {
         class_hash: ClassHash,
@@ -201,11 +204,17 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         self.execution_mode() == ExecutionMode::Validate
     }
 
-    /// Returns an error if the syscall is run in validate mode.
-    pub fn verify_not_in_validate_mode(&self, syscall_name: &str) -> DeprecatedSyscallResult<()> {
-        if self.is_validate_mode() {
+    /// Returns an error if the syscall is run in validate mode, and is forbidden in this mode by
+    /// the versioned constants.
+    pub fn verify_syscall_allowed(
+        &self,
+        selector: &DeprecatedSyscallSelector,
+    ) -> DeprecatedSyscallResult<()> {
+        if self.is_validate_mode()
+            && self.context.versioned_constants().validate_forbidden_syscalls.contains(selector)
+        {
             return Err(DeprecatedSyscallExecutionError::InvalidSyscallInExecutionMode {
-                syscall_name: syscall_name.to_string(),
+                syscall_name: selector.to_string(),
                 execution_mode: self.execution_mode(),
             });
         }
@@ -236,6 +245,11 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         self.verify_syscall_ptr(initial_syscall_ptr)?;
 
         let selector = DeprecatedSyscallSelector::try_from(self.read_next_syscall_selector(vm)?)?;
+        self.verify_syscall_allowed(&selector)?;
+        let block_context = &self.context.tx_context.block_context;
+        if let Some(syscall_interceptor) = &block_context.hooks.syscall_interceptor {
+            syscall_interceptor.on_syscall(selector, self.storage_address);
+        }
         self.increment_syscall_count(&selector);
 
         match selector {
@@ -361,7 +375,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         let TransactionContext { block_context, tx_info } = self.context.tx_context.as_ref();
         let tx_signature_length = tx_info.signature().0.len();
         let tx_info: Vec<MaybeRelocatable> = vec![
-            stark_felt_to_felt(tx_info.signed_version().0).into(),
+            stark_felt_to_felt(self.context.tx_version().0).into(),
             stark_felt_to_felt(*tx_info.sender_address().0.key()).into(),
             max_fee_for_execution_info(tx_info).into(),
             tx_signature_length.into(),
@@ -429,6 +443,16 @@ impl HintProcessorLogic for DeprecatedSyscallHintProcessor<'_> {
         constants: &HashMap<String, Felt252>,
     ) -> HintExecutionResult {
         let hint = hint_data.downcast_ref::<HintProcessorData>().ok_or(HintError::WrongHintData)?;
+        let custom_hints = &self.context.tx_context.block_context.hooks.custom_hints;
+        if let Some(custom_hint) = custom_hints.get(&hint.code) {
+            return custom_hint.execute_deprecated(
+                vm,
+                exec_scopes,
+                &hint.ids_data,
+                &hint.ap_tracking,
+                constants,
+            );
+        }
         if hint_code::SYSCALL_HINTS.contains(hint.code.as_str()) {
             return self.execute_next_syscall(vm, &hint.ids_data, &hint.ap_tracking);
         }
@@ -474,8 +498,12 @@ pub fn execute_inner_call(
     vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<ReadOnlySegment> {
-    let call_info =
-        call.execute(syscall_handler.state, syscall_handler.resources, syscall_handler.context)?;
+    let call_info = execute_intercepted_call(
+        call,
+        syscall_handler.state,
+        syscall_handler.resources,
+        syscall_handler.context,
+    )?;
     let retdata = &call_info.execution.retdata.0;
     let retdata: Vec<MaybeRelocatable> =
         retdata.iter().map(|&x| MaybeRelocatable::from(stark_felt_to_felt(x))).collect();
@@ -1,30 +1,30 @@
 use cairo_felt::Felt252;
 use cairo_vm::types::relocatable::Relocatable;
 use cairo_vm::vm::vm_core::VirtualMachine;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockNumber, BlockTimestamp};
-use starknet_api::core::{
-    calculate_contract_address, ClassHash, ContractAddress, EntryPointSelector, EthAddress,
-};
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, EthAddress};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{
     Calldata, ContractAddressSalt, EventContent, EventData, EventKey, L2ToL1Payload,
 };
-use strum_macros::EnumIter;
+use strum_macros::{Display, EnumIter};
 
 use self::hint_processor::{
     execute_inner_call, execute_library_call, felt_to_bool, read_call_params, read_calldata,
     read_felt_array, DeprecatedSyscallExecutionError, DeprecatedSyscallHintProcessor,
 };
-use super::syscalls::exceeds_event_size_limit;
-use crate::execution::call_info::{MessageToL1, OrderedEvent, OrderedL2ToL1Message};
+use super::syscalls::{event_payload_size, exceeds_event_size_limit};
+use crate::execution::call_info::{
+    EmittedEvent, MessageToL1, OrderedEvent, OrderedL2ToL1Message, SentL2ToL1Message,
+};
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::entry_point::{CallEntryPoint, CallType, ConstructorContext};
 use crate::execution::execution_utils::{
-    execute_deployment, stark_felt_from_ptr, write_maybe_relocatable, write_stark_felt,
-    ReadOnlySegment,
+    calculate_deployed_contract_address, execute_deployment, stark_felt_from_ptr,
+    write_maybe_relocatable, write_stark_felt, ReadOnlySegment,
 };
 
 #[cfg(test)]
@@ -35,7 +35,8 @@ pub mod hint_processor;
 pub type DeprecatedSyscallResult<T> = Result<T, DeprecatedSyscallExecutionError>;
 pub type WriteResponseResult = DeprecatedSyscallResult<()>;
 
-#[derive(Clone, Copy, Debug, Deserialize, EnumIter, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Display, EnumIter, Eq, Hash, PartialEq, Serialize)]
+#[strum(serialize_all = "snake_case")]
 pub enum DeprecatedSyscallSelector {
     CallContract,
     DelegateCall,
@@ -46,6 +47,7 @@ pub enum DeprecatedSyscallSelector {
     GetBlockNumber,
     GetBlockTimestamp,
     GetCallerAddress,
+    GetClassHashAt,
     GetContractAddress,
     GetExecutionInfo,
     GetSequencerAddress,
@@ -87,6 +89,7 @@ impl TryFrom<StarkFelt> for DeprecatedSyscallSelector {
             b"GetBlockNumber" => Ok(Self::GetBlockNumber),
             b"GetBlockTimestamp" => Ok(Self::GetBlockTimestamp),
             b"GetCallerAddress" => Ok(Self::GetCallerAddress),
+            b"GetClassHashAt" => Ok(Self::GetClassHashAt),
             b"GetContractAddress" => Ok(Self::GetContractAddress),
             b"GetExecutionInfo" => Ok(Self::GetExecutionInfo),
             b"GetSequencerAddress" => Ok(Self::GetSequencerAddress),
@@ -312,15 +315,17 @@ pub fn deploy(
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<DeployResponse> {
     let deployer_address = syscall_handler.storage_address;
-    let deployer_address_for_calculation = match request.deploy_from_zero {
-        true => ContractAddress::default(),
-        false => deployer_address,
-    };
-    let deployed_contract_address = calculate_contract_address(
+    if request.deploy_from_zero
+        && !syscall_handler.context.tx_context.block_context.chain_info.allow_deploy_from_zero
+    {
+        return Err(DeprecatedSyscallExecutionError::ForbiddenDeployFromZero);
+    }
+    let deployed_contract_address = calculate_deployed_contract_address(
         request.contract_address_salt,
         request.class_hash,
         &request.constructor_calldata,
-        deployer_address_for_calculation,
+        deployer_address,
+        request.deploy_from_zero,
     )?;
 
     let ctor_context = ConstructorContext {
@@ -372,16 +377,28 @@ pub fn emit_event(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<EmitEventResponse> {
+    let from_address = syscall_handler.storage_address;
+    let caller_address = syscall_handler.caller_address;
     let execution_context = &mut syscall_handler.context;
+    let total_payload_size =
+        execution_context.emitted_events_payload_size + event_payload_size(&request.content);
     exceeds_event_size_limit(
-        execution_context.versioned_constants(),
+        execution_context.tx_context.block_context.event_limits(),
         execution_context.n_emitted_events + 1,
+        total_payload_size,
         &request.content,
     )?;
     let ordered_event =
         OrderedEvent { order: execution_context.n_emitted_events, event: request.content };
+    let emitted_event = EmittedEvent { from_address, event: ordered_event.clone() };
+    let tx_context = &execution_context.tx_context;
+    if let Some(event_sink) = &tx_context.block_context.hooks.event_sink {
+        event_sink.on_event(&tx_context.tx_info, caller_address, &emitted_event);
+    }
+    execution_context.emitted_effects.events.push(emitted_event);
     syscall_handler.events.push(ordered_event);
     execution_context.n_emitted_events += 1;
+    execution_context.emitted_events_payload_size = total_payload_size;
 
     Ok(EmitEventResponse {})
 }
@@ -408,7 +425,7 @@ pub fn get_block_number(
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<GetBlockNumberResponse> {
     let versioned_constants = syscall_handler.context.versioned_constants();
-    let block_number = syscall_handler.get_block_info().block_number;
+    let block_number = syscall_handler.context.block_number();
     let block_number = match syscall_handler.execution_mode() {
         ExecutionMode::Validate => {
             let validate_block_number_rounding =
@@ -444,7 +461,7 @@ pub fn get_block_timestamp(
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<GetBlockTimestampResponse> {
     let versioned_constants = syscall_handler.context.versioned_constants();
-    let block_timestamp = syscall_handler.get_block_info().block_timestamp;
+    let block_timestamp = syscall_handler.context.block_timestamp();
     let block_timestamp = match syscall_handler.execution_mode() {
         ExecutionMode::Validate => {
             let validate_timestamp_rounding = versioned_constants.get_validate_timestamp_rounding();
@@ -467,7 +484,10 @@ pub fn get_caller_address(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<GetCallerAddressResponse> {
-    Ok(GetCallerAddressResponse { address: syscall_handler.caller_address })
+    let address = syscall_handler
+        .context
+        .caller_address_seen_by(syscall_handler.storage_address, syscall_handler.caller_address);
+    Ok(GetCallerAddressResponse { address })
 }
 
 // GetContractAddress syscall.
@@ -504,7 +524,6 @@ pub fn get_sequencer_address(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<GetSequencerAddressResponse> {
-    syscall_handler.verify_not_in_validate_mode("get_sequencer_address")?;
     Ok(GetSequencerAddressResponse { address: syscall_handler.get_block_info().sequencer_address })
 }
 
@@ -671,11 +690,16 @@ pub fn send_message_to_l1(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<SendMessageToL1Response> {
+    let from_address = syscall_handler.storage_address;
     let execution_context = &mut syscall_handler.context;
     let ordered_message_to_l1 = OrderedL2ToL1Message {
         order: execution_context.n_sent_messages_to_l1,
         message: request.message,
     };
+    execution_context
+        .emitted_effects
+        .l2_to_l1_messages
+        .push(SentL2ToL1Message { from_address, message: ordered_message_to_l1.clone() });
     syscall_handler.l2_to_l1_messages.push(ordered_message_to_l1);
     execution_context.n_sent_messages_to_l1 += 1;
 
@@ -1,10 +1,12 @@
 use std::cell::RefCell;
 use std::cmp::min;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use cairo_vm::vm::runners::cairo_runner::{ExecutionResources, ResourceTracker, RunResources};
 use num_traits::{Inv, Zero};
 use serde::Serialize;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
@@ -12,16 +14,24 @@ use starknet_api::transaction::{Calldata, TransactionVersion};
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants;
-use crate::context::{BlockContext, TransactionContext};
-use crate::execution::call_info::CallInfo;
+use crate::context::{
+    BlockContext, ReentrancyPolicy, StepLimitOverride, TransactionContext, ValidateLimit,
+    VisitedPcsTracking,
+};
+use crate::execution::call_info::{CallInfo, EmittedEffects};
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::errors::{
-    ConstructorEntryPointExecutionError, EntryPointExecutionError, PreExecutionError,
+    ConstructorEntryPointExecutionError, EntryPointExecutionError, ExhaustedResource,
+    PostExecutionError, PreExecutionError,
 };
 use crate::execution::execution_utils::execute_entry_point_call;
+use crate::execution::syscalls::secp::SecpPointCaches;
 use crate::state::state_api::State;
-use crate::transaction::objects::{HasRelatedFeeType, TransactionExecutionResult, TransactionInfo};
+use crate::transaction::objects::{
+    ExecutionResourcesTraits, HasRelatedFeeType, TransactionExecutionResult, TransactionInfo,
+};
 use crate::transaction::transaction_types::TransactionType;
+use crate::transaction::transaction_utils::to_query_version;
 use crate::utils::{u128_from_usize, usize_from_u128};
 use crate::versioned_constants::{GasCosts, VersionedConstants};
 
@@ -75,9 +85,22 @@ impl CallEntryPoint {
         );
         decrement_when_dropped.try_increment_and_check_depth()?;
 
-        // Validate contract is deployed.
+        tx_context
+            .block_context
+            .execution_policy
+            .size_limits
+            .check_calldata_length(self.calldata.0.len())
+            .map_err(|error| PreExecutionError::SizeLimitExceeded {
+                storage_address: self.storage_address,
+                selector: self.entry_point_selector,
+                error,
+            },
+        )?;
+
+        // Validate contract is deployed, unless the class to run is given explicitly (e.g., for the
+        // entry points of a counterfactual account).
         let storage_class_hash = state.get_class_hash_at(self.storage_address)?;
-        if storage_class_hash == ClassHash::default() {
+        if storage_class_hash == ClassHash::default() && self.class_hash.is_none() {
             return Err(PreExecutionError::UninitializedStorageAddress(self.storage_address).into());
         }
 
@@ -98,7 +121,42 @@ impl CallEntryPoint {
         self.class_hash = Some(class_hash);
         let contract_class = state.get_compiled_contract_class(class_hash)?;
 
-        execute_entry_point_call(self, contract_class, state, resources, context)
+        // Library calls execute in the context of their caller, and thus do not re-enter it.
+        let reentrancy_policy = context.tx_context.block_context.execution_policy.reentrancy_policy;
+        let reentrant = reentrancy_policy != ReentrancyPolicy::Allow
+            && self.call_type == CallType::Call
+            && context.active_storage_addresses.contains(&self.storage_address);
+        if reentrant && reentrancy_policy == ReentrancyPolicy::Forbid {
+            return Err(EntryPointExecutionError::ReentrancyDetected {
+                storage_address: self.storage_address,
+                selector: self.entry_point_selector,
+            });
+        }
+
+        context.active_storage_addresses.push(self.storage_address);
+        let execution_result =
+            execute_entry_point_call(self, contract_class, state, resources, context);
+        context.active_storage_addresses.pop();
+        let mut call_info = execution_result?;
+        call_info.reentrant = reentrant;
+        if context.active_storage_addresses.is_empty() {
+            call_info.assign_call_indices();
+        }
+        let entry_point_limits = context.versioned_constants().entry_point_limits;
+        if let Some(max_retdata_length) = entry_point_limits.max_retdata_length {
+            let retdata_length = call_info.execution.retdata.0.len();
+            if retdata_length > max_retdata_length {
+                return Err(PostExecutionError::RetdataTooLong {
+                    storage_address: call_info.call.storage_address,
+                    selector: call_info.call.entry_point_selector,
+                    retdata_length,
+                    max_retdata_length,
+                }
+                .into());
+            }
+        }
+
+        Ok(call_info)
     }
 }
 
@@ -110,6 +168,21 @@ pub struct ConstructorContext {
     pub caller_address: ContractAddress,
 }
 
+/// Overrides of the execution info seen by executed contracts; e.g., for simulating calls on
+/// behalf of another account. Set on the block context to apply to executed transactions (see
+/// [`crate::context::ExecutionPolicy::execution_info_overrides`]), or on an execution context to
+/// apply to a single run. Only the values seen by contracts are affected, not the ones the
+/// execution is charged by.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExecutionInfoOverrides {
+    pub block_number: Option<BlockNumber>,
+    pub block_timestamp: Option<BlockTimestamp>,
+    /// The transaction version; still marked as a query version for query transactions.
+    pub tx_version: Option<TransactionVersion>,
+    /// The caller address seen by each given contract, instead of the actual one.
+    pub caller_addresses: HashMap<ContractAddress, ContractAddress>,
+}
+
 #[derive(Debug)]
 pub struct EntryPointExecutionContext {
     // We use `Arc` to avoid the clone of this potentially large object, as inner calls
@@ -119,13 +192,33 @@ pub struct EntryPointExecutionContext {
     pub vm_run_resources: RunResources,
     /// Used for tracking events order during the current execution.
     pub n_emitted_events: usize,
+    /// The total size, in bytes, of the payloads of the events emitted during the current
+    /// execution.
+    pub emitted_events_payload_size: usize,
     /// Used for tracking L2-to-L1 messages order during the current execution.
     pub n_sent_messages_to_l1: usize,
+    /// Events and L2-to-L1 messages emitted so far, across all calls of the current execution.
+    pub emitted_effects: EmittedEffects,
     // Managed by dedicated guard object.
     current_recursion_depth: Arc<RefCell<usize>>,
+    /// The storage addresses of the calls currently executing, outermost first.
+    active_storage_addresses: Vec<ContractAddress>,
 
     // The execution mode affects the behavior of the hint processor.
     pub execution_mode: ExecutionMode,
+    /// The secp points constructed so far, across all calls of the current execution.
+    pub(crate) secp_point_caches: SecpPointCaches,
+    /// The remaining gas of the executing Cairo 1 call, as of its start or its latest syscall.
+    remaining_gas: Option<u64>,
+    /// The latest syscall that was not executed for lack of gas, since the start of the latest
+    /// Cairo 1 call.
+    pub(crate) out_of_gas_syscall: Option<ExhaustedResource>,
+    /// Overrides of the execution info seen by executed contracts; initialized from the block
+    /// context.
+    pub execution_info_overrides: ExecutionInfoOverrides,
+    /// The collection of the PCs visited by the Cairo 1 calls of the current execution;
+    /// initialized from the block context.
+    pub visited_pcs_tracking: VisitedPcsTracking,
 }
 
 impl EntryPointExecutionContext {
@@ -138,10 +231,26 @@ impl EntryPointExecutionContext {
         Ok(Self {
             vm_run_resources: RunResources::new(max_steps),
             n_emitted_events: 0,
+            emitted_events_payload_size: 0,
             n_sent_messages_to_l1: 0,
+            emitted_effects: EmittedEffects::default(),
             tx_context: tx_context.clone(),
             current_recursion_depth: Default::default(),
+            active_storage_addresses: Vec::new(),
             execution_mode: mode,
+            secp_point_caches: SecpPointCaches::default(),
+            remaining_gas: None,
+            out_of_gas_syscall: None,
+            execution_info_overrides: tx_context
+                .block_context
+                .execution_policy
+                .execution_info_overrides
+                .clone(),
+            visited_pcs_tracking: tx_context
+                .block_context
+                .execution_policy
+                .visited_pcs_tracking
+                .clone(),
         })
     }
 
@@ -162,20 +271,30 @@ impl EntryPointExecutionContext {
     /// Returns the maximum number of cairo steps allowed, given the max fee, gas price and the
     /// execution mode.
     /// If fee is disabled, returns the global maximum.
+    /// If the block context overrides the step limit, returns the override.
     fn max_steps(
         tx_context: &TransactionContext,
         mode: &ExecutionMode,
         limit_steps_by_resources: bool,
     ) -> TransactionExecutionResult<usize> {
         let TransactionContext { block_context, tx_info } = tx_context;
-        let BlockContext { block_info, versioned_constants, .. } = block_context;
+        if let Some(max_steps_override) = block_context.execution_policy.max_steps_override {
+            return Ok(max_steps_override.max_steps());
+        }
+        let BlockContext { block_info, versioned_constants, execution_policy, .. } = block_context;
         let block_upper_bound = match mode {
-            // TODO(Ori, 1/2/2024): Write an indicative expect message explaining why the conversion
-            // works.
-            ExecutionMode::Validate => versioned_constants
-                .validate_max_n_steps
-                .try_into()
-                .expect("Failed to convert validate_max_n_steps (u32) to usize."),
+            ExecutionMode::Validate => match execution_policy.validation_limits.max_n_steps {
+                // TODO(Ori, 1/2/2024): Write an indicative expect message explaining why the
+                // conversion works.
+                ValidateLimit::Default => versioned_constants
+                    .validate_max_n_steps
+                    .try_into()
+                    .expect("Failed to convert validate_max_n_steps (u32) to usize."),
+                ValidateLimit::Bounded(max_n_steps) => {
+                    usize::try_from(max_n_steps).unwrap_or(usize::MAX)
+                }
+                ValidateLimit::Unlimited => usize::MAX,
+            },
             ExecutionMode::Execute => versioned_constants
                 .invoke_tx_max_n_steps
                 .try_into()
@@ -242,6 +361,22 @@ impl EntryPointExecutionContext {
         self.vm_run_resources.get_n_steps().expect("The number of steps must be initialized.")
     }
 
+    /// Returns the remaining gas of the executing Cairo 1 call. The gas counter is only observed
+    /// by the hint processor at syscalls, so this is the gas as of the start of the call or its
+    /// latest syscall; [None] if no Cairo 1 call was executed.
+    pub fn remaining_gas(&self) -> Option<u64> {
+        self.remaining_gas
+    }
+
+    pub(crate) fn set_remaining_gas(&mut self, remaining_gas: u64) {
+        self.remaining_gas = Some(remaining_gas);
+    }
+
+    /// Overrides the steps available for the rest of this execution.
+    pub fn set_max_steps(&mut self, max_steps_override: StepLimitOverride) {
+        self.vm_run_resources = RunResources::new(max_steps_override.max_steps());
+    }
+
     /// Subtracts the given number of steps from the currently available run resources.
     /// Used for limiting the number of steps available during the execution stage, to leave enough
     /// steps available for the fee transfer stage.
@@ -261,7 +396,8 @@ impl EntryPointExecutionContext {
     }
 
     /// From the total amount of steps available for execution, deduct the steps consumed during
-    /// validation and the overhead steps required for fee transfer.
+    /// validation and the overhead steps required for fee transfer. From the versions that charge
+    /// them, the steps of the segment arena usage of the validation are deducted as well.
     /// Returns the remaining steps (after the subtraction).
     pub fn subtract_validation_and_overhead_steps(
         &mut self,
@@ -269,13 +405,22 @@ impl EntryPointExecutionContext {
         tx_type: &TransactionType,
         calldata_length: usize,
     ) -> usize {
+        let versioned_constants = self.versioned_constants();
         let validate_steps = validate_call_info
             .as_ref()
-            .map(|call_info| call_info.resources.n_steps)
+            .map(|call_info| {
+                let segment_arena_steps = if versioned_constants.charge_validate_segment_arena_steps
+                {
+                    call_info.resources.segment_arena_n_steps()
+                } else {
+                    0
+                };
+                call_info.resources.n_steps + segment_arena_steps
+            })
             .unwrap_or_default();
 
         let overhead_steps =
-            self.versioned_constants().os_resources_for_tx_type(tx_type, calldata_length).n_steps;
+            versioned_constants.os_resources_for_tx_type(tx_type, calldata_length).n_steps;
         self.subtract_steps(validate_steps + overhead_steps)
     }
 
@@ -283,6 +428,52 @@ impl EntryPointExecutionContext {
         &self.tx_context.block_context.versioned_constants
     }
 
+    /// The block number seen by executed contracts.
+    pub fn block_number(&self) -> BlockNumber {
+        self.execution_info_overrides
+            .block_number
+            .unwrap_or(self.tx_context.block_context.block_info.block_number)
+    }
+
+    /// The block timestamp seen by executed contracts.
+    pub fn block_timestamp(&self) -> BlockTimestamp {
+        self.execution_info_overrides
+            .block_timestamp
+            .unwrap_or(self.tx_context.block_context.block_info.block_timestamp)
+    }
+
+    /// The transaction version seen by executed contracts.
+    pub fn tx_version(&self) -> TransactionVersion {
+        let tx_info = &self.tx_context.tx_info;
+        match self.execution_info_overrides.tx_version {
+            Some(version) if tx_info.only_query() => to_query_version(version),
+            Some(version) => version,
+            None => tx_info.signed_version(),
+        }
+    }
+
+    /// Whether executed contracts see the transaction info layout of transactions with resource
+    /// bounds (version 3 onwards), following the overridden transaction version, if any.
+    pub fn sees_current_tx_info(&self) -> bool {
+        match self.execution_info_overrides.tx_version {
+            Some(version) => version >= TransactionVersion::THREE,
+            None => matches!(self.tx_context.tx_info, TransactionInfo::Current(_)),
+        }
+    }
+
+    /// The caller address seen by the given contract, called by the given caller.
+    pub fn caller_address_seen_by(
+        &self,
+        storage_address: ContractAddress,
+        caller_address: ContractAddress,
+    ) -> ContractAddress {
+        self.execution_info_overrides
+            .caller_addresses
+            .get(&storage_address)
+            .copied()
+            .unwrap_or(caller_address)
+    }
+
     pub fn gas_costs(&self) -> &GasCosts {
         &self.versioned_constants().os_constants.gas_costs
     }
@@ -371,7 +562,9 @@ impl RecursionDepthGuard {
     fn try_increment_and_check_depth(&mut self) -> EntryPointExecutionResult<()> {
         *self.current_depth.borrow_mut() += 1;
         if *self.current_depth.borrow() > self.max_depth {
-            return Err(EntryPointExecutionError::RecursionDepthExceeded);
+            return Err(EntryPointExecutionError::RecursionDepthExceeded {
+                max_recursion_depth: self.max_depth,
+            });
         }
         Ok(())
     }
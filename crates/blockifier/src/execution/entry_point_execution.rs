@@ -3,6 +3,7 @@ use std::collections::HashSet;
 use cairo_felt::Felt252;
 use cairo_vm::serde::deserialize_program::BuiltinName;
 use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
+use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
 use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
 use cairo_vm::vm::runners::builtin_runner::SEGMENT_ARENA_BUILTIN_NAME;
 use cairo_vm::vm::runners::cairo_runner::{CairoArg, CairoRunner, ExecutionResources};
@@ -11,7 +12,8 @@ use num_traits::ToPrimitive;
 use starknet_api::hash::StarkFelt;
 use starknet_api::stark_felt;
 
-use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
+use crate::context::{GasMeteringMode, VisitedPcsTracking};
+use crate::execution::call_info::{CallExecution, CallInfo, DualGasMetering, Retdata};
 use crate::execution::contract_class::{ContractClassV1, EntryPointV1};
 use crate::execution::entry_point::{
     CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
@@ -19,9 +21,12 @@ use crate::execution::entry_point::{
 use crate::execution::errors::{EntryPointExecutionError, PostExecutionError, PreExecutionError};
 use crate::execution::execution_utils::{
     read_execution_retdata, stark_felt_to_felt, write_maybe_relocatable, write_stark_felt, Args,
-    ReadOnlySegments,
+    ReadOnlySegments, ResourcesSnapshot,
 };
-use crate::execution::syscalls::hint_processor::SyscallHintProcessor;
+use crate::execution::syscalls::hint_processor::{SyscallHintProcessor, OUT_OF_GAS_ERROR};
+#[cfg(feature = "vm_tracer")]
+use crate::execution::vm_tracer::trace_vm_steps;
+use crate::fee::fee_utils::calculate_sierra_gas_by_vm_usage;
 use crate::state::state_api::State;
 
 // TODO(spapini): Try to refactor this file into a StarknetRunner struct.
@@ -54,6 +59,11 @@ pub fn execute_entry_point_call(
     let class_hash = call.class_hash.ok_or(EntryPointExecutionError::InternalError(
         "Class hash must not be None when executing an entry point.".into(),
     ))?;
+    let trace_enabled = is_trace_enabled(context);
+    let visited_pcs_tracking = context.visited_pcs_tracking.clone();
+    let n_available_steps = context.n_remaining_steps();
+    context.set_remaining_gas(call.initial_gas);
+    context.out_of_gas_syscall = None;
 
     let VmExecutionContext {
         mut runner,
@@ -74,7 +84,7 @@ pub fn execute_entry_point_call(
     let n_total_args = args.len();
 
     // Fix the resources, in order to calculate the usage of this run at the end.
-    let previous_resources = syscall_handler.resources.clone();
+    let previous_resources = ResourcesSnapshot::take(syscall_handler.resources);
 
     // Execute.
     let bytecode_length = contract_class.bytecode_length();
@@ -86,15 +96,34 @@ pub fn execute_entry_point_call(
         entry_point,
         args,
         program_segment_size,
-    )?;
+    )
+    .map_err(|error| {
+        attribute_error_to_function(error, &contract_class).with_available_steps(n_available_steps)
+    })?;
 
-    // Collect the set PC values that were visited during the entry point execution.
-    register_visited_pcs(
-        &mut vm,
-        syscall_handler.state,
-        class_hash,
-        program_segment_size,
-        bytecode_length,
+    if trace_enabled {
+        // Relocate the trace, putting the program segment at address 1 and the execution segment
+        // right after it.
+        // TODO(lior): Avoid unnecessary relocation once the VM has a non-relocated `get_trace()`
+        //   function.
+        vm.relocate_trace(&[1, 1 + program_segment_size])?;
+
+        // Collect the set PC values that were visited during the entry point execution.
+        register_visited_pcs(
+            &vm,
+            syscall_handler.state,
+            class_hash,
+            bytecode_length,
+            &visited_pcs_tracking,
+        )?;
+    }
+
+    #[cfg(feature = "vm_tracer")]
+    trace_vm_steps(
+        &vm,
+        &syscall_handler.call,
+        previous_resources.resources(),
+        syscall_handler.context,
     )?;
 
     let call_info = finalize_execution(
@@ -106,28 +135,66 @@ pub fn execute_entry_point_call(
         program_extra_data_length,
     )?;
     if call_info.execution.failed {
-        return Err(EntryPointExecutionError::ExecutionFailed {
-            error_data: call_info.execution.retdata.0,
+        let error_data = call_info.execution.retdata.0;
+        let out_of_gas = error_data.first() == Some(&stark_felt!(OUT_OF_GAS_ERROR));
+        let error = EntryPointExecutionError::ExecutionFailed { error_data };
+        return Err(match context.out_of_gas_syscall.take() {
+            Some(exhausted_resource) if out_of_gas => EntryPointExecutionError::OutOfResources {
+                error: Box::new(error),
+                exhausted_resource,
+            },
+            _ => error,
         });
     }
 
     Ok(call_info)
 }
 
-// Collects the set PC values that were visited during the entry point execution.
+/// Attributes a Cairo run error to the Sierra function it was raised in, if the class carries
+/// Sierra debug info.
+fn attribute_error_to_function(
+    error: EntryPointExecutionError,
+    contract_class: &ContractClassV1,
+) -> EntryPointExecutionError {
+    let function_name = match &error {
+        EntryPointExecutionError::CairoRunError(CairoRunError::VmException(vm_exception)) => {
+            contract_class
+                .sierra_debug_info()
+                .and_then(|debug_info| debug_info.function_name_at(vm_exception.pc))
+        }
+        _ => None,
+    };
+
+    match (error, function_name.map(str::to_string)) {
+        (EntryPointExecutionError::CairoRunError(error), Some(function_name)) => {
+            EntryPointExecutionError::CairoRunErrorInFunction { error, function_name }
+        }
+        (error, _) => error,
+    }
+}
+
+/// Returns whether the VM trace should be recorded; it is read for the visited PCs, and for tracing
+/// the steps of the VM.
+fn is_trace_enabled(context: &EntryPointExecutionContext) -> bool {
+    let tracking_visited_pcs =
+        !matches!(context.visited_pcs_tracking, VisitedPcsTracking::Disabled);
+    #[cfg(not(feature = "vm_tracer"))]
+    let tracing_vm_steps = false;
+    #[cfg(feature = "vm_tracer")]
+    let tracing_vm_steps = context.tx_context.block_context.hooks.vm_tracer.is_some();
+    tracking_visited_pcs || tracing_vm_steps
+}
+
+// Collects the set PC values that were visited during the entry point execution, from the
+// relocated trace.
 fn register_visited_pcs(
-    vm: &mut VirtualMachine,
+    vm: &VirtualMachine,
     state: &mut dyn State,
     class_hash: starknet_api::core::ClassHash,
-    program_segment_size: usize,
     bytecode_length: usize,
+    visited_pcs_tracking: &VisitedPcsTracking,
 ) -> EntryPointExecutionResult<()> {
     let mut class_visited_pcs = HashSet::new();
-    // Relocate the trace, putting the program segment at address 1 and the execution segment right
-    // after it.
-    // TODO(lior): Avoid unnecessary relocation once the VM has a non-relocated `get_trace()`
-    //   function.
-    vm.relocate_trace(&[1, 1 + program_segment_size])?;
     for trace_entry in vm.get_relocated_trace()? {
         let pc = trace_entry.pc;
         if pc < 1 {
@@ -142,7 +209,13 @@ fn register_visited_pcs(
             class_visited_pcs.insert(real_pc);
         }
     }
-    state.add_visited_pcs(class_hash, &class_visited_pcs);
+    match visited_pcs_tracking {
+        VisitedPcsTracking::Full => state.add_visited_pcs(class_hash, &class_visited_pcs),
+        VisitedPcsTracking::Observe(observer) => {
+            observer.on_visited_pcs(class_hash, &class_visited_pcs)
+        }
+        VisitedPcsTracking::Disabled => {}
+    }
     Ok(())
 }
 
@@ -159,7 +232,7 @@ pub fn initialize_execution_context<'a>(
     let proof_mode = false;
     let mut runner = CairoRunner::new(&contract_class.0.program, "starknet", proof_mode)?;
 
-    let trace_enabled = true;
+    let trace_enabled = is_trace_enabled(context);
     let mut vm = VirtualMachine::new(trace_enabled);
 
     // Initialize program with all builtins.
@@ -304,7 +377,7 @@ pub fn finalize_execution(
     mut vm: VirtualMachine,
     runner: CairoRunner,
     syscall_handler: SyscallHintProcessor<'_>,
-    previous_resources: ExecutionResources,
+    previous_resources: ResourcesSnapshot,
     n_total_args: usize,
     program_extra_data_length: usize,
 ) -> Result<CallInfo, PostExecutionError> {
@@ -337,8 +410,10 @@ pub fn finalize_execution(
     *syscall_handler.resources += &versioned_constants
         .get_additional_os_syscall_resources(&syscall_handler.syscall_counter)?;
 
-    let full_call_resources = &*syscall_handler.resources - &previous_resources;
-    Ok(CallInfo {
+    let full_call_resources = previous_resources.consumed_since(syscall_handler.resources);
+    let gas_metering_mode =
+        syscall_handler.context.tx_context.block_context.fee_policy.gas_metering_mode;
+    let mut call_info = CallInfo {
         call: syscall_handler.call,
         execution: CallExecution {
             retdata: call_result.retdata,
@@ -347,11 +422,33 @@ pub fn finalize_execution(
             failed: call_result.failed,
             gas_consumed: call_result.gas_consumed,
         },
-        resources: full_call_resources.filter_unused_builtins(),
+        resources: full_call_resources,
+        vm_memory_holes: vm_resources_without_inner_calls.n_memory_holes,
         inner_calls: syscall_handler.inner_calls,
+        tracked_resource: gas_metering_mode.cairo1_tracked_resource(),
+        dual_gas_metering: None,
+        // Assigned by the caller.
+        caller_gas_charge: None,
         storage_read_values: syscall_handler.read_values,
         accessed_storage_keys: syscall_handler.accessed_keys,
-    })
+        syscall_counter: syscall_handler.syscall_counter,
+        syscall_gas_records: syscall_handler.syscall_gas_records,
+        reentrant: false,
+        // Assigned by the top-level call.
+        call_index: 0,
+        parent_call_index: None,
+    };
+    if gas_metering_mode == GasMeteringMode::Dual {
+        call_info.dual_gas_metering = Some(DualGasMetering {
+            sierra_gas: call_info.exclusive_gas_consumed(),
+            vm_resources_gas: calculate_sierra_gas_by_vm_usage(
+                versioned_constants,
+                &call_info.exclusive_resources(),
+            ),
+        });
+    }
+
+    Ok(call_info)
 }
 
 fn get_call_result(
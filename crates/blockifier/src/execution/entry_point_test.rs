@@ -1,20 +1,32 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
 
+use assert_matches::assert_matches;
 use cairo_vm::serde::deserialize_program::BuiltinName;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use num_bigint::BigInt;
 use pretty_assertions::assert_eq;
 use regex::Regex;
 use rstest::rstest;
-use starknet_api::core::{EntryPointSelector, PatriciaKey};
+use starknet_api::core::{ContractAddress, EntryPointSelector, PatriciaKey};
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::transaction::{Calldata, TransactionVersion};
-use starknet_api::{calldata, stark_felt};
+use starknet_api::{calldata, contract_address, patricia_key, stark_felt};
 
 use crate::abi::abi_utils::{get_storage_var_address, selector_from_name};
-use crate::context::{BlockContext, ChainInfo};
+use crate::abi::constants::N_STEPS_PER_SEGMENT_ARENA_BUILTIN;
+use crate::context::{BlockContext, ChainInfo, ReentrancyPolicy, SizeLimits, TransactionContext};
 use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
-use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::contract_class::{ContractClass, SierraDebugInfo};
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, ExecutionInfoOverrides,
+};
+use crate::execution::errors::{
+    gen_transaction_execution_error_stack, EntryPointExecutionError, ErrorFrame,
+    PostExecutionError, PreExecutionError,
+};
 use crate::state::cached_state::CachedState;
+use crate::state::state_api::State;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::dict_state_reader::DictStateReader;
 use crate::test_utils::initial_test_state::test_state;
@@ -26,13 +38,19 @@ use crate::transaction::constants::{
     EXECUTE_ENTRY_POINT_NAME, VALIDATE_DECLARE_ENTRY_POINT_NAME, VALIDATE_DEPLOY_ENTRY_POINT_NAME,
     VALIDATE_ENTRY_POINT_NAME,
 };
+use crate::transaction::errors::SizeLimitError;
+use crate::transaction::objects::{
+    CommonAccountFields, DeprecatedTransactionInfo, ExecutionResourcesTraits, RevertFrame,
+    RevertReason, TransactionInfo,
+};
 use crate::transaction::test_utils::{
     block_context, create_account_tx_for_validate_test, run_invoke_tx, FaultyAccountTxCreatorArgs,
     INVALID,
 };
 use crate::transaction::transaction_types::TransactionType;
+use crate::transaction::transaction_utils::to_query_version;
 use crate::transaction::transactions::ExecutableTransaction;
-use crate::versioned_constants::VersionedConstants;
+use crate::versioned_constants::{EntryPointLimits, VersionedConstants};
 use crate::{invoke_tx_args, retdata, storage_key};
 
 const INNER_CALL_CONTRACT_IN_CALL_CHAIN_OFFSET: usize = 117;
@@ -127,6 +145,115 @@ fn test_long_retdata() {
     );
 }
 
+#[test]
+fn test_entry_point_size_limits() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state = test_state(&ChainInfo::create_for_testing(), 0, &[(test_contract, 1)]);
+    let execute_with_limits =
+        |state: &mut dyn State, selector: &str, calldata, size_limits, entry_point_limits| {
+            let mut block_context = BlockContext::create_for_testing();
+            block_context.execution_policy_mut().size_limits = size_limits;
+            block_context.versioned_constants.entry_point_limits = entry_point_limits;
+            let tx_context = TransactionContext {
+                block_context,
+                tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+            };
+            let mut context =
+                EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+            let entry_point_call = CallEntryPoint {
+                calldata,
+                entry_point_selector: selector_from_name(selector),
+                ..trivial_external_entry_point_new(test_contract)
+            };
+            entry_point_call.execute(state, &mut ExecutionResources::default(), &mut context)
+        };
+
+    // Calldata; limited by the same size limits as the calldata of transactions.
+    let calldata = || calldata![stark_felt!(25_u8)];
+    let limits = SizeLimits { max_calldata_length: Some(1), ..Default::default() };
+    execute_with_limits(&mut state, "with_arg", calldata(), limits, EntryPointLimits::default())
+        .unwrap();
+    let limits = SizeLimits { max_calldata_length: Some(0), ..Default::default() };
+    let error = execute_with_limits(
+        &mut state,
+        "with_arg",
+        calldata(),
+        limits,
+        EntryPointLimits::default(),
+    )
+    .unwrap_err();
+    assert_matches!(
+        error,
+        EntryPointExecutionError::PreExecutionError(PreExecutionError::SizeLimitExceeded {
+            error: SizeLimitError::CalldataTooLong { calldata_length: 1, max_calldata_length: 0 },
+            ..
+        })
+    );
+
+    // Return data; the entry point returns 5 values.
+    let execute_with_retdata_limits = |state: &mut dyn State, limits| {
+        execute_with_limits(state, "test_long_retdata", calldata![], SizeLimits::default(), limits)
+    };
+    let limits = EntryPointLimits { max_retdata_length: Some(5) };
+    execute_with_retdata_limits(&mut state, limits).unwrap();
+    let limits = EntryPointLimits { max_retdata_length: Some(4) };
+    let error = execute_with_retdata_limits(&mut state, limits).unwrap_err();
+    assert_matches!(
+        error,
+        EntryPointExecutionError::PostExecutionError(PostExecutionError::RetdataTooLong {
+            retdata_length: 5,
+            max_retdata_length: 4,
+            ..
+        })
+    );
+}
+
+#[rstest]
+fn test_reentrancy_policy(
+    #[values(ReentrancyPolicy::Allow, ReentrancyPolicy::Record, ReentrancyPolicy::Forbid)]
+    reentrancy_policy: ReentrancyPolicy,
+) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state = test_state(&ChainInfo::create_for_testing(), 0, &[(test_contract, 1)]);
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.execution_policy_mut().reentrancy_policy = reentrancy_policy;
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+
+    // The contract calls itself.
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_call_contract"),
+        calldata: calldata![
+            *test_contract.get_instance_address(0).0.key(), // Contract address.
+            selector_from_name("return_result").0,          // Function selector.
+            stark_felt!(1_u8),                              // Calldata length.
+            stark_felt!(25_u8)                              // Calldata.
+        ],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let result =
+        entry_point_call.execute(&mut state, &mut ExecutionResources::default(), &mut context);
+
+    match reentrancy_policy {
+        ReentrancyPolicy::Allow | ReentrancyPolicy::Record => {
+            let call_info = result.unwrap();
+            assert!(!call_info.reentrant);
+            assert_eq!(
+                call_info.inner_calls[0].reentrant,
+                reentrancy_policy == ReentrancyPolicy::Record
+            );
+        }
+        ReentrancyPolicy::Forbid => {
+            assert!(result.unwrap_err().to_string().contains("Reentrant call to entry point"));
+        }
+    }
+    // The stack of executing contracts is unwound in either case.
+    assert!(context.active_storage_addresses.is_empty());
+}
+
 #[test]
 fn test_entry_point_with_builtin() {
     let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
@@ -550,13 +677,57 @@ fn test_cairo1_entry_point_segment_arena() {
         ..trivial_external_entry_point_new(test_contract)
     };
 
-    assert!(
-        entry_point_call
-            .execute_directly(&mut state)
+    let resources = entry_point_call.execute_directly(&mut state).unwrap().resources;
+    let n_segment_arena_instances =
+        *resources.builtin_instance_counter.get(BuiltinName::segment_arena.name()).unwrap();
+    assert!(n_segment_arena_instances > 0);
+
+    // Segment arena instances are not proven; they are charged as steps.
+    assert_eq!(
+        resources.total_n_steps(),
+        resources.n_steps
+            + resources.n_memory_holes
+            + N_STEPS_PER_SEGMENT_ARENA_BUILTIN * n_segment_arena_instances
+    );
+    assert!(!resources.prover_builtins().contains_key(BuiltinName::segment_arena.name()));
+}
+
+#[rstest]
+fn test_validate_segment_arena_steps_budget(#[values(true, false)] charge_segment_arena: bool) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("segment_arena_builtin"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    // Use a call with segment arena usage as the validation call.
+    let validate_call_info = Some(entry_point_call.execute_directly(&mut state).unwrap());
+    let resources = &validate_call_info.as_ref().unwrap().resources;
+
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.versioned_constants.charge_validate_segment_arena_steps = charge_segment_arena;
+    let tx_context = Arc::new(TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    });
+    let remaining_execute_steps = |validate_call_info: &Option<CallInfo>| {
+        EntryPointExecutionContext::new_invoke(tx_context.clone(), false)
             .unwrap()
-            .resources
-            .builtin_instance_counter
-            .contains_key(BuiltinName::segment_arena.name())
+            .subtract_validation_and_overhead_steps(
+                validate_call_info,
+                &TransactionType::InvokeFunction,
+                0,
+            )
+    };
+
+    // Memory holes of the validation are not deducted from the execution step budget.
+    let expected_segment_arena_steps =
+        if charge_segment_arena { resources.segment_arena_n_steps() } else { 0 };
+    assert!(resources.segment_arena_n_steps() > 0);
+    assert_eq!(
+        remaining_execute_steps(&None) - remaining_execute_steps(&validate_call_info),
+        resources.n_steps + expected_segment_arena_steps
     );
 }
 
@@ -664,6 +835,114 @@ Execution failed. Failure reason: 0x6661696c ('fail').
     };
 
     assert_eq!(tx_execution_error.to_string(), expected_trace);
+
+    // The revert reason keeps the trace, and exposes the failing call chain and panic data.
+    let revert_reason = RevertReason::from(&tx_execution_error);
+    assert_eq!(revert_reason.to_string(), expected_trace);
+    let expected_frames = vec![
+        RevertFrame {
+            contract_address: account_address,
+            class_hash: account.get_class_hash(),
+            selector: Some(selector_from_name(EXECUTE_ENTRY_POINT_NAME)),
+        },
+        RevertFrame {
+            contract_address: test_contract_address,
+            class_hash: test_contract.get_class_hash(),
+            selector: Some(selector_from_name(call_contract_function_name)),
+        },
+        RevertFrame {
+            contract_address: test_contract_address_2,
+            class_hash: test_contract.get_class_hash(),
+            selector: Some(selector_from_name("fail")),
+        },
+    ];
+    assert_eq!(revert_reason.frames, expected_frames);
+    let expected_panic_data = match cairo_version {
+        CairoVersion::Cairo0 => vec![],
+        CairoVersion::Cairo1 => vec![stark_felt!("0x6661696c")],
+    };
+    assert_eq!(revert_reason.panic_data, expected_panic_data);
+
+    // The error stack is rendered as the trace, and exposes its frames.
+    let error_stack = gen_transaction_execution_error_stack(&tx_execution_error);
+    assert_eq!(format!("Transaction execution has failed:\n{error_stack}"), expected_trace);
+    let entry_point_frames: Vec<RevertFrame> =
+        error_stack.entry_point_frames().map(RevertFrame::from).collect();
+    assert_eq!(entry_point_frames, expected_frames);
+    assert!(error_stack.entry_point_frames().map(|frame| frame.depth).eq(0..3));
+    assert_eq!(error_stack.panic_data(), expected_panic_data.as_slice());
+    let n_vm_exception_frames = error_stack
+        .frames()
+        .iter()
+        .filter(|frame| matches!(frame, ErrorFrame::VmExceptionFrame(_)))
+        .count();
+    let expected_last_frame = match cairo_version {
+        CairoVersion::Cairo0 => {
+            assert_eq!(n_vm_exception_frames, 3);
+            ErrorFrame::StringFrame("An ASSERT_EQ instruction failed: 1 != 0.\n".to_string())
+        }
+        CairoVersion::Cairo1 => {
+            assert_eq!(n_vm_exception_frames, 2);
+            ErrorFrame::PanicFrame(expected_panic_data)
+        }
+    };
+    assert_eq!(error_stack.frames().last(), Some(&expected_last_frame));
+}
+
+#[rstest]
+fn test_stack_trace_with_sierra_debug_info(block_context: BlockContext) {
+    let chain_info = ChainInfo::create_for_testing();
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let mut state = test_state(&chain_info, BALANCE, &[(account, 1), (test_contract, 2)]);
+    let test_contract_address_2_felt = *test_contract.get_instance_address(1).0.key();
+
+    // Attribute all the code of the test contract to a single function.
+    let function_name = "test_contract::test_contract::TestContract::test_call_contract";
+    let ContractClass::V1(test_contract_class) = test_contract.get_class() else {
+        panic!("Expected a Cairo 1 class.");
+    };
+    let debug_info = SierraDebugInfo::new(BTreeMap::from([(0, function_name.to_string())]));
+    state
+        .set_contract_class(
+            test_contract.get_class_hash(),
+            test_contract_class.with_sierra_debug_info(debug_info).into(),
+        )
+        .unwrap();
+
+    // Nest calls: __execute__ -> test_call_contract -> fail.
+    let calldata = create_calldata(
+        test_contract.get_instance_address(0),
+        "test_call_contract",
+        &[
+            test_contract_address_2_felt, // Contract address.
+            selector_from_name("fail").0, // Function selector.
+            stark_felt!(0_u8),            // Innermost calldata length.
+        ],
+    );
+    let tx_execution_error = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            sender_address: account.get_instance_address(0),
+            calldata,
+            version: TransactionVersion::ZERO,
+        },
+    )
+    .unwrap_err();
+
+    // The exception in the account is not attributed, as its class carries no debug info.
+    let error_stack = gen_transaction_execution_error_stack(&tx_execution_error);
+    let vm_exception_function_names: Vec<Option<&str>> = error_stack
+        .frames()
+        .iter()
+        .filter_map(|frame| match frame {
+            ErrorFrame::VmExceptionFrame(frame) => Some(frame.function_name.as_deref()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(vm_exception_function_names, vec![None, Some(function_name)]);
+    assert!(tx_execution_error.to_string().contains(&format!(" (in {function_name}):")));
 }
 
 #[rstest]
@@ -1051,3 +1330,48 @@ Execution failed. Failure reason: 0x496e76616c6964207363656e6172696f ('Invalid s
     // Compare actual trace to the expected trace (sans pc locations).
     assert_eq!(cleaned_actual_error.to_string(), cleaned_expected_error.to_string());
 }
+
+#[test]
+fn test_execution_info_overrides() {
+    let execution_context = |only_query| {
+        let tx_info = TransactionInfo::Deprecated(DeprecatedTransactionInfo {
+            common_fields: CommonAccountFields {
+                version: TransactionVersion::ONE,
+                only_query,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let tx_context =
+            TransactionContext { block_context: BlockContext::create_for_testing(), tx_info };
+        EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap()
+    };
+    let contract_address = contract_address!("0x10");
+    let caller_address = contract_address!("0x20");
+    let overridden_caller_address = contract_address!("0x30");
+    let execution_info_overrides = ExecutionInfoOverrides {
+        tx_version: Some(TransactionVersion::THREE),
+        caller_addresses: HashMap::from([(contract_address, overridden_caller_address)]),
+        ..Default::default()
+    };
+
+    let mut context = execution_context(false);
+    assert_eq!(context.tx_version(), TransactionVersion::ONE);
+    assert!(!context.sees_current_tx_info());
+    assert_eq!(context.caller_address_seen_by(contract_address, caller_address), caller_address);
+
+    context.execution_info_overrides = execution_info_overrides.clone();
+    assert_eq!(context.tx_version(), TransactionVersion::THREE);
+    // The transaction info layout follows the overridden version.
+    assert!(context.sees_current_tx_info());
+    assert_eq!(
+        context.caller_address_seen_by(contract_address, caller_address),
+        overridden_caller_address
+    );
+    assert_eq!(context.caller_address_seen_by(caller_address, contract_address), contract_address);
+
+    // Query transactions see the overridden version as a query version.
+    let mut query_context = execution_context(true);
+    query_context.execution_info_overrides = execution_info_overrides;
+    assert_eq!(query_context.tx_version(), to_query_version(TransactionVersion::THREE));
+}
@@ -1,4 +1,8 @@
+use std::fmt;
+
+use cairo_lang_starknet_classes::casm_contract_class::StarknetSierraCompilationError;
 use cairo_vm::types::errors::math_errors::MathError;
+use cairo_vm::types::errors::program_errors::ProgramError;
 use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
 use cairo_vm::vm::errors::hint_errors::HintError;
 use cairo_vm::vm::errors::memory_errors::MemoryError;
@@ -7,6 +11,7 @@ use cairo_vm::vm::errors::trace_errors::TraceError;
 use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
 use cairo_vm::vm::errors::vm_exception::VmException;
 use num_bigint::{BigInt, TryFromBigIntError};
+use serde::Serialize;
 use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
@@ -14,13 +19,25 @@ use thiserror::Error;
 
 use super::deprecated_syscalls::hint_processor::DeprecatedSyscallExecutionError;
 use super::syscalls::hint_processor::SyscallExecutionError;
+use super::syscalls::SyscallSelector;
 use crate::execution::entry_point::ConstructorContext;
 use crate::execution::execution_utils::format_panic_data;
 use crate::state::errors::StateError;
-use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::errors::{SizeLimitError, TransactionExecutionError};
+use crate::transaction::objects::{RevertFrame, RevertReason};
+
+#[cfg(test)]
+#[path = "errors_test.rs"]
+pub mod test;
 
 // TODO(AlonH, 21/12/2022): Implement Display for all types that appear in errors.
 
+#[derive(Debug, Error)]
+pub enum CustomHintError {
+    #[error("Custom hint {hint_code:?} is not allowed by the chain.")]
+    HintNotAllowed { hint_code: String },
+}
+
 #[derive(Debug, Error)]
 pub enum PreExecutionError {
     #[error("Entry point {selector:?} of type {typ:?} is not unique.")]
@@ -40,9 +57,17 @@ pub enum PreExecutionError {
     #[error("No entry points of type {0:?} found in contract.")]
     NoEntryPointOfTypeFound(EntryPointType),
     #[error(transparent)]
-    ProgramError(#[from] cairo_vm::types::errors::program_errors::ProgramError),
+    ProgramError(#[from] ProgramError),
     #[error(transparent)]
     RunnerError(Box<RunnerError>),
+    #[error(
+        "Entry point {selector:?} of contract {storage_address:?} exceeds a size limit: {error}"
+    )]
+    SizeLimitExceeded {
+        storage_address: ContractAddress,
+        selector: EntryPointSelector,
+        error: SizeLimitError,
+    },
     #[error(transparent)]
     StateError(#[from] StateError),
     #[error("Requested contract address {0:?} is not deployed.")]
@@ -69,6 +94,16 @@ pub enum PostExecutionError {
     VirtualMachineError(#[from] VirtualMachineError),
     #[error("Malformed return data : {error_message}.")]
     MalformedReturnData { error_message: String },
+    #[error(
+        "Return data of entry point {selector:?} of contract {storage_address:?} is too long: \
+         {retdata_length} > {max_retdata_length}."
+    )]
+    RetdataTooLong {
+        storage_address: ContractAddress,
+        selector: EntryPointSelector,
+        retdata_length: usize,
+        max_retdata_length: usize,
+    },
 }
 
 impl From<RunnerError> for PostExecutionError {
@@ -81,6 +116,10 @@ impl From<RunnerError> for PostExecutionError {
 pub enum EntryPointExecutionError {
     #[error(transparent)]
     CairoRunError(#[from] CairoRunError),
+    /// A Cairo run error raised in a class carrying Sierra debug info; see
+    /// [`crate::execution::contract_class::SierraDebugInfo`].
+    #[error("In function {function_name}: {error}")]
+    CairoRunErrorInFunction { error: CairoRunError, function_name: String },
     #[error("Execution failed. Failure reason: {}.", format_panic_data(.error_data))]
     ExecutionFailed { error_data: Vec<StarkFelt> },
     #[error("Internal error: {0}")]
@@ -89,16 +128,82 @@ pub enum EntryPointExecutionError {
     InvalidExecutionInput { input_descriptor: String, info: String },
     #[error(transparent)]
     PostExecutionError(#[from] PostExecutionError),
+    /// A failure of a call that ran out of resources, along with the resources left to it.
+    #[error("{error}\n{exhausted_resource}")]
+    OutOfResources { error: Box<EntryPointExecutionError>, exhausted_resource: ExhaustedResource },
     #[error(transparent)]
     PreExecutionError(#[from] PreExecutionError),
-    #[error("Execution failed due to recursion depth exceeded.")]
-    RecursionDepthExceeded,
+    #[error(
+        "Execution failed due to recursion depth exceeded (max depth: {max_recursion_depth})."
+    )]
+    RecursionDepthExceeded { max_recursion_depth: usize },
+    #[error(
+        "Reentrant call to entry point {selector:?} of contract {storage_address:?}, which is \
+         already executing."
+    )]
+    ReentrancyDetected { storage_address: ContractAddress, selector: EntryPointSelector },
     #[error(transparent)]
     StateError(#[from] StateError),
     #[error(transparent)]
     TraceError(#[from] TraceError),
 }
 
+impl EntryPointExecutionError {
+    /// Annotates the failure of a call that ran out of steps with the steps available to it when
+    /// it started; other failures are returned as is.
+    pub fn with_available_steps(self, n_available_steps: usize) -> Self {
+        if !self.is_out_of_steps() {
+            return self;
+        }
+
+        Self::OutOfResources {
+            error: Box::new(self),
+            exhausted_resource: ExhaustedResource::Steps { n_available_steps },
+        }
+    }
+
+    fn is_out_of_steps(&self) -> bool {
+        let (Self::CairoRunError(error) | Self::CairoRunErrorInFunction { error, .. }) = self
+        else {
+            return false;
+        };
+        matches!(
+            error,
+            CairoRunError::VirtualMachine(VirtualMachineError::UnfinishedExecution)
+                | CairoRunError::VmException(VmException {
+                    inner_exc: VirtualMachineError::UnfinishedExecution,
+                    ..
+                })
+        )
+    }
+}
+
+/// The resource a call ran out of, and how much of it was left at the failure; distinguishes
+/// calls that almost fit their budget from calls that are far over it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExhaustedResource {
+    /// The call ran out of steps; no steps are left at the failure.
+    Steps { n_available_steps: usize },
+    /// A syscall of the call was not executed for lack of gas. The gas amounts exclude the base
+    /// syscall cost, which is charged before the syscall is invoked.
+    Gas { selector: SyscallSelector, remaining_gas: u64, required_gas: u64 },
+}
+
+impl fmt::Display for ExhaustedResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Steps { n_available_steps } => {
+                write!(f, "Out of steps; {n_available_steps} steps were available to the call.")
+            }
+            Self::Gas { selector, remaining_gas, required_gas } => write!(
+                f,
+                "Out of gas in syscall {selector}; remaining gas: {remaining_gas}, required gas: \
+                 {required_gas}."
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ConstructorEntryPointExecutionError {
     #[error(
@@ -139,31 +244,302 @@ pub enum ContractClassError {
         contract_class_version: u8,
         sierra_program_length: usize,
     },
+    #[error(transparent)]
+    ParseError(#[from] serde_json::Error),
+    #[error(transparent)]
+    ProgramError(#[from] ProgramError),
+    #[error(transparent)]
+    SierraCompilationError(#[from] StarknetSierraCompilationError),
+    #[error(
+        "Sierra classes must be compiled by compiler version {required_version}; the linked \
+         compiler is of version {linked_version}."
+    )]
+    SierraCompilerVersionMismatch { required_version: String, linked_version: String },
+    #[error("The versioned constants do not pin a Sierra compiler version.")]
+    SierraCompilerVersionNotPinned,
 }
 
 // A set of functions used to extract error trace from a recursive error object.
 
-type ErrorStack = Vec<String>;
+/// The kind of call an entry point frame was entered by.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreambleType {
+    CallContract,
+    LibraryCall,
+}
+
+impl PreambleType {
+    fn text(&self) -> &'static str {
+        match self {
+            Self::CallContract => "Error in the called contract",
+            Self::LibraryCall => "Error in a library call",
+        }
+    }
+}
+
+/// A call on the path to the failure.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct EntryPointErrorFrame {
+    /// The call depth, where the outermost call is of depth 0.
+    pub depth: usize,
+    pub preamble_type: PreambleType,
+    pub storage_address: ContractAddress,
+    pub class_hash: ClassHash,
+    /// [None] if the selector of the call is unknown.
+    pub selector: Option<EntryPointSelector>,
+}
+
+impl fmt::Display for EntryPointErrorFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} (contract address: {}, class hash: {}, selector: {}):",
+            self.depth,
+            self.preamble_type.text(),
+            self.storage_address.0.key(),
+            self.class_hash,
+            if let Some(selector) = self.selector {
+                format!("{}", selector.0)
+            } else {
+                "UNKNOWN".to_string()
+            }
+        )
+    }
+}
+
+/// A Cairo VM exception raised by a call.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct VmExceptionFrame {
+    pub pc: usize,
+    /// The Sierra function the exception was raised in, if the class carries Sierra debug info.
+    pub function_name: Option<String>,
+    /// The Cairo traceback of the exception, if available.
+    pub traceback: Option<String>,
+}
+
+impl fmt::Display for VmExceptionFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Error at pc=0:{}", self.pc)?;
+        if let Some(function_name) = &self.function_name {
+            write!(f, " (in {function_name})")?;
+        }
+        write!(f, ":")?;
+        if let Some(traceback) = &self.traceback {
+            write!(f, "\n{traceback}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A segment of the error stack of a failed transaction.
+/// Serialized as an object keyed by the frame kind, e.g., `{"panic_frame": [...]}`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorFrame {
+    EntryPointFrame(EntryPointErrorFrame),
+    VmExceptionFrame(VmExceptionFrame),
+    /// A syscall failure, other than a failure of the call it made.
+    SyscallFrame(String),
+    /// A failure of a Cairo 1 call; holds its panic data.
+    PanicFrame(Vec<StarkFelt>),
+    /// Any other error, as rendered in the trace.
+    StringFrame(String),
+}
+
+impl fmt::Display for ErrorFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EntryPointFrame(frame) => write!(f, "{frame}"),
+            Self::VmExceptionFrame(frame) => write!(f, "{frame}"),
+            Self::SyscallFrame(message) | Self::StringFrame(message) => write!(f, "{message}"),
+            Self::PanicFrame(panic_data) => {
+                writeln!(f, "Execution failed. Failure reason: {}.", format_panic_data(panic_data))
+            }
+        }
+    }
+}
+
+/// The error stack of a failed transaction, from the outermost call inwards.
+/// Its display is the (untrimmed) error trace.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct ErrorStack {
+    frames: Vec<ErrorFrame>,
+}
+
+impl ErrorStack {
+    pub fn frames(&self) -> &[ErrorFrame] {
+        &self.frames
+    }
+
+    /// The calls on the path to the failure, from the outermost call inwards.
+    pub fn entry_point_frames(&self) -> impl Iterator<Item = &EntryPointErrorFrame> {
+        self.frames.iter().filter_map(|frame| match frame {
+            ErrorFrame::EntryPointFrame(entry_point_frame) => Some(entry_point_frame),
+            _ => None,
+        })
+    }
+
+    /// The panic data of the failing call; empty if the failure was not a panic.
+    pub fn panic_data(&self) -> &[StarkFelt] {
+        self.frames
+            .iter()
+            .rev()
+            .find_map(|frame| match frame {
+                ErrorFrame::PanicFrame(panic_data) => Some(panic_data.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    fn push(&mut self, frame: ErrorFrame) {
+        self.frames.push(frame);
+    }
+
+    fn push_string(&mut self, trace_line: String) {
+        self.push(ErrorFrame::StringFrame(trace_line));
+    }
+
+    fn push_entry_point_frame(
+        &mut self,
+        depth: usize,
+        preamble_type: PreambleType,
+        storage_address: &ContractAddress,
+        class_hash: &ClassHash,
+        selector: Option<&EntryPointSelector>,
+    ) {
+        self.push(ErrorFrame::EntryPointFrame(EntryPointErrorFrame {
+            depth,
+            preamble_type,
+            storage_address: *storage_address,
+            class_hash: *class_hash,
+            selector: selector.copied(),
+        }));
+    }
+}
+
+impl fmt::Display for ErrorStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let trace: Vec<String> = self.frames.iter().map(ToString::to_string).collect();
+        write!(f, "{}", trace.join("\n"))
+    }
+}
+
+impl From<&EntryPointErrorFrame> for RevertFrame {
+    fn from(frame: &EntryPointErrorFrame) -> Self {
+        Self {
+            contract_address: frame.storage_address,
+            class_hash: frame.class_hash,
+            selector: frame.selector,
+        }
+    }
+}
+
+/// The part of an overly long error trace that is kept.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TraceTruncationStrategy {
+    /// Traces are kept whole, regardless of their length.
+    NoTruncation,
+    /// The beginning and the end of the trace are kept, half of the cap each.
+    #[default]
+    HeadAndTail,
+    /// The beginning of the trace is kept; i.e., the outermost calls.
+    HeadOnly,
+    /// The end of the trace is kept; i.e., the innermost calls and the failure itself.
+    TailOnly,
+}
+
+/// The truncation of error traces; the traces of failures deep in the call tree may be long.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TraceTruncation {
+    /// The length, in bytes, that long traces are truncated to.
+    pub length_cap: usize,
+    /// Traces are only truncated if they exceed the cap by more than this slack.
+    pub extra_chars_slack: usize,
+    pub strategy: TraceTruncationStrategy,
+}
 
-pub const TRACE_LENGTH_CAP: usize = 15000;
-pub const TRACE_EXTRA_CHARS_SLACK: usize = 100;
+impl Default for TraceTruncation {
+    fn default() -> Self {
+        Self {
+            length_cap: 15000,
+            extra_chars_slack: 100,
+            strategy: TraceTruncationStrategy::default(),
+        }
+    }
+}
 
-fn finalize_error_stack(error_stack: &ErrorStack) -> String {
-    let error_stack_str = error_stack.join("\n");
+impl TraceTruncation {
+    /// Truncates the given trace; the kept parts are cut at character boundaries, hence, may be
+    /// shorter than the cap.
+    pub fn truncate(&self, trace: String) -> String {
+        let Self { length_cap, extra_chars_slack, strategy } = *self;
+        if trace.len() <= length_cap.saturating_add(extra_chars_slack) {
+            return trace;
+        }
 
-    // When the trace string is too long, trim it in a way that keeps both the beginning and end.
-    if error_stack_str.len() > TRACE_LENGTH_CAP + TRACE_EXTRA_CHARS_SLACK {
-        error_stack_str[..(TRACE_LENGTH_CAP / 2)].to_string()
-            + "\n\n...\n\n"
-            + &error_stack_str[(error_stack_str.len() - TRACE_LENGTH_CAP / 2)..]
-    } else {
-        error_stack_str
+        const OMISSION_MARKER: &str = "\n\n...\n\n";
+        let head_end = |length: usize| floor_char_boundary(&trace, length);
+        let tail_start = |length: usize| ceil_char_boundary(&trace, trace.len() - length);
+        match strategy {
+            TraceTruncationStrategy::NoTruncation => trace,
+            TraceTruncationStrategy::HeadAndTail => {
+                trace[..head_end(length_cap / 2)].to_string()
+                    + OMISSION_MARKER
+                    + &trace[tail_start(length_cap / 2)..]
+            }
+            TraceTruncationStrategy::HeadOnly => {
+                trace[..head_end(length_cap)].to_string() + OMISSION_MARKER
+            }
+            TraceTruncationStrategy::TailOnly => {
+                OMISSION_MARKER.to_string() + &trace[tail_start(length_cap)..]
+            }
+        }
     }
 }
 
-/// Extracts the error trace from a `TransactionExecutionError`. This is a top level function.
-pub fn gen_transaction_execution_error_trace(error: &TransactionExecutionError) -> String {
-    let error_stack = match error {
+/// Returns the largest character boundary of the given string not after the given index.
+fn floor_char_boundary(string: &str, index: usize) -> usize {
+    (0..=index).rev().find(|&i| string.is_char_boundary(i)).unwrap_or_default()
+}
+
+/// Returns the smallest character boundary of the given string not before the given index.
+fn ceil_char_boundary(string: &str, index: usize) -> usize {
+    (index..string.len()).find(|&i| string.is_char_boundary(i)).unwrap_or(string.len())
+}
+
+/// Extracts the error trace from a `TransactionExecutionError`, truncated as given. This is a top
+/// level function.
+pub fn gen_transaction_execution_error_trace(
+    error: &TransactionExecutionError,
+    truncation: &TraceTruncation,
+) -> String {
+    truncation.truncate(gen_transaction_execution_error_stack(error).to_string())
+}
+
+/// Returns the revert reason of a transaction that failed with the given error, with its trace
+/// truncated as given.
+pub fn gen_revert_reason(
+    error: &TransactionExecutionError,
+    truncation: &TraceTruncation,
+) -> RevertReason {
+    let error_stack = gen_transaction_execution_error_stack(error);
+    RevertReason::new(
+        error.message_with_trace_truncation(truncation),
+        error_stack.entry_point_frames().map(RevertFrame::from).collect(),
+        error_stack.panic_data().to_vec(),
+    )
+}
+
+impl From<&TransactionExecutionError> for RevertReason {
+    fn from(error: &TransactionExecutionError) -> Self {
+        gen_revert_reason(error, &TraceTruncation::default())
+    }
+}
+
+/// Extracts the structured error stack from a `TransactionExecutionError`.
+pub fn gen_transaction_execution_error_stack(error: &TransactionExecutionError) -> ErrorStack {
+    match error {
         TransactionExecutionError::ExecutionError {
             error,
             class_hash,
@@ -191,11 +567,11 @@ pub fn gen_transaction_execution_error_trace(error: &TransactionExecutionError)
             Some(selector),
         ),
         _ => {
-            vec![error.to_string()]
+            let mut error_stack = ErrorStack::default();
+            error_stack.push_string(error.to_string());
+            error_stack
         }
-    };
-
-    finalize_error_stack(&error_stack)
+    }
 }
 
 /// Generate error stack from top-level entry point execution error.
@@ -205,46 +581,52 @@ fn gen_error_trace_from_entry_point_error(
     class_hash: &ClassHash,
     entry_point_selector: Option<&EntryPointSelector>,
 ) -> ErrorStack {
-    let mut error_stack: ErrorStack = ErrorStack::new();
+    let mut error_stack = ErrorStack::default();
     let depth = 0;
-    error_stack.push(frame_preamble(
+    error_stack.push_entry_point_frame(
         depth,
-        "Error in the called contract",
+        PreambleType::CallContract,
         storage_address,
         class_hash,
         entry_point_selector,
-    ));
+    );
     extract_entry_point_execution_error_into_stack_trace(&mut error_stack, depth + 1, error);
     error_stack
 }
 
 fn extract_cairo_run_error_into_stack_trace(
-    error_stack: &mut Vec<String>,
+    error_stack: &mut ErrorStack,
     depth: usize,
     error: &CairoRunError,
+    function_name: Option<&str>,
 ) {
     if let CairoRunError::VmException(vm_exception) = error {
-        return extract_vm_exception_into_stack_trace(error_stack, depth, vm_exception);
+        return extract_vm_exception_into_stack_trace(
+            error_stack,
+            depth,
+            vm_exception,
+            function_name,
+        );
     }
-    error_stack.push(error.to_string());
+    error_stack.push_string(error.to_string());
 }
 
 fn extract_vm_exception_into_stack_trace(
-    error_stack: &mut Vec<String>,
+    error_stack: &mut ErrorStack,
     depth: usize,
     vm_exception: &VmException,
+    function_name: Option<&str>,
 ) {
-    let vm_exception_preamble = format!("Error at pc=0:{}:", vm_exception.pc);
-    error_stack.push(vm_exception_preamble);
-
-    if let Some(traceback) = &vm_exception.traceback {
-        error_stack.push(traceback.to_string());
-    }
+    error_stack.push(ErrorFrame::VmExceptionFrame(VmExceptionFrame {
+        pc: vm_exception.pc,
+        function_name: function_name.map(str::to_string),
+        traceback: vm_exception.traceback.clone(),
+    }));
     extract_virtual_machine_error_into_stack_trace(error_stack, depth, &vm_exception.inner_exc)
 }
 
 fn extract_virtual_machine_error_into_stack_trace(
-    error_stack: &mut Vec<String>,
+    error_stack: &mut ErrorStack,
     depth: usize,
     vm_error: &VirtualMachineError,
 ) {
@@ -257,7 +639,7 @@ fn extract_virtual_machine_error_into_stack_trace(
                     internal_vm_error,
                 );
             }
-            error_stack.push(boxed_hint_error.1.to_string());
+            error_stack.push_string(boxed_hint_error.1.to_string());
         }
         VirtualMachineError::Other(anyhow_error) => {
             let syscall_exec_err = anyhow_error.downcast_ref::<SyscallExecutionError>();
@@ -280,58 +662,13 @@ fn extract_virtual_machine_error_into_stack_trace(
             }
         }
         _ => {
-            error_stack.push(format!("{}\n", vm_error));
+            error_stack.push_string(format!("{}\n", vm_error));
         }
     }
 }
 
-fn frame_preamble(
-    depth: usize,
-    preamble_text: &str,
-    storage_address: &ContractAddress,
-    class_hash: &ClassHash,
-    selector: Option<&EntryPointSelector>,
-) -> String {
-    format!(
-        "{}: {} (contract address: {}, class hash: {}, selector: {}):",
-        depth,
-        preamble_text,
-        storage_address.0.key(),
-        class_hash,
-        if let Some(selector) = selector {
-            format!("{}", selector.0)
-        } else {
-            "UNKNOWN".to_string()
-        }
-    )
-}
-
-fn call_contract_preamble(
-    depth: usize,
-    storage_address: &ContractAddress,
-    class_hash: &ClassHash,
-    selector: &EntryPointSelector,
-) -> String {
-    frame_preamble(
-        depth,
-        "Error in the called contract",
-        storage_address,
-        class_hash,
-        Some(selector),
-    )
-}
-
-fn library_call_preamble(
-    depth: usize,
-    storage_address: &ContractAddress,
-    class_hash: &ClassHash,
-    selector: &EntryPointSelector,
-) -> String {
-    frame_preamble(depth, "Error in a library call", storage_address, class_hash, Some(selector))
-}
-
 fn extract_syscall_execution_error_into_stack_trace(
-    error_stack: &mut Vec<String>,
+    error_stack: &mut ErrorStack,
     depth: usize,
     syscall_error: &SyscallExecutionError,
 ) {
@@ -342,7 +679,13 @@ fn extract_syscall_execution_error_into_stack_trace(
             selector,
             error,
         } => {
-            error_stack.push(call_contract_preamble(depth, storage_address, class_hash, selector));
+            error_stack.push_entry_point_frame(
+                depth,
+                PreambleType::CallContract,
+                storage_address,
+                class_hash,
+                Some(selector),
+            );
             extract_syscall_execution_error_into_stack_trace(error_stack, depth + 1, error)
         }
         SyscallExecutionError::LibraryCallExecutionError {
@@ -351,7 +694,13 @@ fn extract_syscall_execution_error_into_stack_trace(
             selector,
             error,
         } => {
-            error_stack.push(library_call_preamble(depth, storage_address, class_hash, selector));
+            error_stack.push_entry_point_frame(
+                depth,
+                PreambleType::LibraryCall,
+                storage_address,
+                class_hash,
+                Some(selector),
+            );
             extract_syscall_execution_error_into_stack_trace(error_stack, depth + 1, error);
         }
         SyscallExecutionError::EntryPointExecutionError(entry_point_error) => {
@@ -362,13 +711,13 @@ fn extract_syscall_execution_error_into_stack_trace(
             )
         }
         _ => {
-            error_stack.push(syscall_error.to_string());
+            error_stack.push(ErrorFrame::SyscallFrame(syscall_error.to_string()));
         }
     }
 }
 
 fn extract_deprecated_syscall_execution_error_into_stack_trace(
-    error_stack: &mut Vec<String>,
+    error_stack: &mut ErrorStack,
     depth: usize,
     syscall_error: &DeprecatedSyscallExecutionError,
 ) {
@@ -379,7 +728,13 @@ fn extract_deprecated_syscall_execution_error_into_stack_trace(
             selector,
             error,
         } => {
-            error_stack.push(call_contract_preamble(depth, storage_address, class_hash, selector));
+            error_stack.push_entry_point_frame(
+                depth,
+                PreambleType::CallContract,
+                storage_address,
+                class_hash,
+                Some(selector),
+            );
             extract_deprecated_syscall_execution_error_into_stack_trace(
                 error_stack,
                 depth + 1,
@@ -392,7 +747,13 @@ fn extract_deprecated_syscall_execution_error_into_stack_trace(
             selector,
             error,
         } => {
-            error_stack.push(library_call_preamble(depth, storage_address, class_hash, selector));
+            error_stack.push_entry_point_frame(
+                depth,
+                PreambleType::LibraryCall,
+                storage_address,
+                class_hash,
+                Some(selector),
+            );
             extract_deprecated_syscall_execution_error_into_stack_trace(
                 error_stack,
                 depth + 1,
@@ -406,19 +767,34 @@ fn extract_deprecated_syscall_execution_error_into_stack_trace(
                 entry_point_error,
             )
         }
-        _ => error_stack.push(syscall_error.to_string()),
+        _ => error_stack.push(ErrorFrame::SyscallFrame(syscall_error.to_string())),
     }
 }
 
 fn extract_entry_point_execution_error_into_stack_trace(
-    error_stack: &mut Vec<String>,
+    error_stack: &mut ErrorStack,
     depth: usize,
     entry_point_error: &EntryPointExecutionError,
 ) {
     match entry_point_error {
         EntryPointExecutionError::CairoRunError(cairo_run_error) => {
-            extract_cairo_run_error_into_stack_trace(error_stack, depth, cairo_run_error)
+            extract_cairo_run_error_into_stack_trace(error_stack, depth, cairo_run_error, None)
+        }
+        EntryPointExecutionError::CairoRunErrorInFunction { error, function_name } => {
+            extract_cairo_run_error_into_stack_trace(
+                error_stack,
+                depth,
+                error,
+                Some(function_name.as_str()),
+            )
+        }
+        EntryPointExecutionError::ExecutionFailed { error_data } => {
+            error_stack.push(ErrorFrame::PanicFrame(error_data.clone()))
+        }
+        EntryPointExecutionError::OutOfResources { error, exhausted_resource } => {
+            extract_entry_point_execution_error_into_stack_trace(error_stack, depth, error);
+            error_stack.push_string(exhausted_resource.to_string());
         }
-        _ => error_stack.push(format!("{}\n", entry_point_error)),
+        _ => error_stack.push_string(format!("{}\n", entry_point_error)),
     }
 }
@@ -0,0 +1,99 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use serde_json::json;
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
+
+use crate::execution::errors::{
+    EntryPointErrorFrame, ErrorFrame, ErrorStack, PreambleType, TraceTruncation,
+    TraceTruncationStrategy, VmExceptionFrame,
+};
+
+#[test]
+fn test_error_stack_serialization() {
+    let storage_address = contract_address!("0x100");
+    let class_hash = class_hash!("0x200");
+    let selector = EntryPointSelector(stark_felt!("0x300"));
+    let panic_data = vec![stark_felt!("0x6661696c")];
+    let error_stack = ErrorStack {
+        frames: vec![
+            ErrorFrame::EntryPointFrame(EntryPointErrorFrame {
+                depth: 0,
+                preamble_type: PreambleType::CallContract,
+                storage_address,
+                class_hash,
+                selector: Some(selector),
+            }),
+            ErrorFrame::VmExceptionFrame(VmExceptionFrame {
+                pc: 7,
+                function_name: Some("test_contract::fail".to_string()),
+                traceback: None,
+            }),
+            ErrorFrame::EntryPointFrame(EntryPointErrorFrame {
+                depth: 1,
+                preamble_type: PreambleType::LibraryCall,
+                storage_address,
+                class_hash,
+                selector: None,
+            }),
+            ErrorFrame::PanicFrame(panic_data.clone()),
+        ],
+    };
+
+    assert_eq!(
+        serde_json::to_value(error_stack).unwrap(),
+        json!({
+            "frames": [
+                {"entry_point_frame": {
+                    "depth": 0,
+                    "preamble_type": "call_contract",
+                    "storage_address": storage_address,
+                    "class_hash": class_hash,
+                    "selector": selector,
+                }},
+                {"vm_exception_frame": {
+                    "pc": 7,
+                    "function_name": "test_contract::fail",
+                    "traceback": null,
+                }},
+                {"entry_point_frame": {
+                    "depth": 1,
+                    "preamble_type": "library_call",
+                    "storage_address": storage_address,
+                    "class_hash": class_hash,
+                    "selector": null,
+                }},
+                {"panic_frame": panic_data},
+            ]
+        })
+    );
+}
+
+#[rstest]
+#[case::no_truncation(TraceTruncationStrategy::NoTruncation, "aaaaaaaaaabbbbbbbbbb")]
+#[case::head_and_tail(TraceTruncationStrategy::HeadAndTail, "aaaa\n\n...\n\nbbbb")]
+#[case::head_only(TraceTruncationStrategy::HeadOnly, "aaaaaaaa\n\n...\n\n")]
+#[case::tail_only(TraceTruncationStrategy::TailOnly, "\n\n...\n\nbbbbbbbb")]
+fn test_trace_truncation(#[case] strategy: TraceTruncationStrategy, #[case] expected_trace: &str) {
+    let truncation = TraceTruncation { length_cap: 8, extra_chars_slack: 2, strategy };
+    assert_eq!(truncation.truncate("a".repeat(10) + &"b".repeat(10)), expected_trace);
+
+    // Traces within the slack are kept whole.
+    let trace = "a".repeat(5) + &"b".repeat(5);
+    assert_eq!(truncation.truncate(trace.clone()), trace);
+}
+
+#[rstest]
+#[case::head_and_tail(TraceTruncationStrategy::HeadAndTail, "é\n\n...\n\né")]
+#[case::head_only(TraceTruncationStrategy::HeadOnly, "ééé\n\n...\n\n")]
+#[case::tail_only(TraceTruncationStrategy::TailOnly, "\n\n...\n\nééé")]
+fn test_trace_truncation_at_char_boundaries(
+    #[case] strategy: TraceTruncationStrategy,
+    #[case] expected_trace: &str,
+) {
+    // Every character takes two bytes; hence, all cuts fall inside characters, and are moved to
+    // keep whole characters only.
+    let truncation = TraceTruncation { length_cap: 7, extra_chars_slack: 0, strategy };
+    assert_eq!(truncation.truncate("é".repeat(10)), expected_trace);
+}
@@ -0,0 +1,22 @@
+use starknet_api::core::ContractAddress;
+
+use crate::execution::call_info::EmittedEvent;
+use crate::transaction::objects::TransactionInfo;
+
+#[cfg(test)]
+#[path = "event_sink_test.rs"]
+pub mod test;
+
+/// Receives the events of executed contracts as they are emitted, rather than once the execution
+/// ends; e.g., for indexers streaming the events of a block before it is sealed.
+/// Note that the events of calls that are later reverted are reported as well.
+pub trait EventSink: std::fmt::Debug + Send + Sync {
+    /// Invoked on every emitted event, with the info of the executing transaction and the caller
+    /// of the emitting contract.
+    fn on_event(
+        &self,
+        tx_info: &TransactionInfo,
+        caller_address: ContractAddress,
+        event: &EmittedEvent,
+    );
+}
@@ -0,0 +1,87 @@
+use std::sync::{Arc, Mutex};
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::ContractAddress;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::{Calldata, TransactionHash};
+use starknet_api::{calldata, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::{BlockContext, ChainInfo};
+use crate::execution::call_info::EmittedEvent;
+use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
+use crate::execution::event_sink::EventSink;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::transaction::objects::{
+    CommonAccountFields, DeprecatedTransactionInfo, TransactionInfo,
+};
+
+/// Records the reported events, along with the hash of the emitting transaction and the caller.
+#[derive(Debug, Default)]
+struct RecordingEventSink {
+    events: Mutex<Vec<(TransactionHash, ContractAddress, EmittedEvent)>>,
+}
+
+impl EventSink for RecordingEventSink {
+    fn on_event(
+        &self,
+        tx_info: &TransactionInfo,
+        caller_address: ContractAddress,
+        event: &EmittedEvent,
+    ) {
+        self.events.lock().unwrap().push((
+            tx_info.transaction_hash(),
+            caller_address,
+            event.clone(),
+        ));
+    }
+}
+
+#[rstest]
+fn test_event_sink(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let test_contract_address = test_contract.get_instance_address(0);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let event_sink = Arc::new(RecordingEventSink::default());
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.hooks_mut().event_sink = Some(event_sink.clone());
+    let tx_hash = TransactionHash(stark_felt!(7_u8));
+    let tx_info = TransactionInfo::Deprecated(DeprecatedTransactionInfo {
+        common_fields: CommonAccountFields { transaction_hash: tx_hash, ..Default::default() },
+        ..Default::default()
+    });
+    let mut context = EntryPointExecutionContext::create_for_testing(block_context, tx_info);
+
+    // Emit two events, with a single key and no data.
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_emit_events"),
+        calldata: calldata![
+            stark_felt!(2_u8),
+            stark_felt!(1_u8),
+            stark_felt!(2019_u16),
+            StarkFelt::ZERO
+        ],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let caller_address = entry_point_call.caller_address;
+    let call_info = entry_point_call
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap();
+
+    let expected_events: Vec<_> = call_info
+        .execution
+        .events
+        .into_iter()
+        .map(|event| {
+            (tx_hash, caller_address, EmittedEvent { from_address: test_contract_address, event })
+        })
+        .collect();
+    assert_eq!(expected_events.len(), 2);
+    assert_eq!(*event_sink.events.lock().unwrap(), expected_events);
+}
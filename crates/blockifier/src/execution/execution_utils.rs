@@ -14,10 +14,11 @@ use cairo_vm::vm::runners::builtin_runner::POSEIDON_BUILTIN_NAME;
 use cairo_vm::vm::runners::cairo_runner::{CairoArg, ExecutionResources};
 use cairo_vm::vm::vm_core::VirtualMachine;
 use num_bigint::BigUint;
-use starknet_api::core::ClassHash;
+use starknet_api::core::{calculate_contract_address, ClassHash, ContractAddress};
 use starknet_api::deprecated_contract_class::Program as DeprecatedProgram;
 use starknet_api::hash::StarkFelt;
-use starknet_api::transaction::Calldata;
+use starknet_api::transaction::{Calldata, ContractAddressSalt};
+use starknet_api::StarknetApiError;
 
 use super::entry_point::ConstructorEntryPointExecutionResult;
 use super::errors::ConstructorEntryPointExecutionError;
@@ -66,13 +67,26 @@ pub fn execute_entry_point_call(
                 context,
             )
         }
-        ContractClass::V1(contract_class) => entry_point_execution::execute_entry_point_call(
-            call,
-            contract_class,
-            state,
-            resources,
-            context,
-        ),
+        ContractClass::V1(contract_class) => {
+            #[cfg(feature = "native_execution")]
+            if let Some(result) = crate::execution::native_execution::try_execute_natively(
+                &call,
+                &contract_class,
+                state,
+                resources,
+                context,
+            ) {
+                return result;
+            }
+
+            entry_point_execution::execute_entry_point_call(
+                call,
+                contract_class,
+                state,
+                resources,
+                context,
+            )
+        }
     }
 }
 
@@ -214,6 +228,50 @@ impl ReadOnlySegments {
     }
 }
 
+/// A snapshot of the execution resources accumulated up to some point of the execution; e.g., right
+/// before running an entry point or invoking an inner call. Computes the resources consumed since.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ResourcesSnapshot(ExecutionResources);
+
+impl ResourcesSnapshot {
+    pub fn take(resources: &ExecutionResources) -> Self {
+        Self(resources.clone())
+    }
+
+    /// Returns the resources accumulated when the snapshot was taken.
+    pub fn resources(&self) -> &ExecutionResources {
+        &self.0
+    }
+
+    /// Returns the resources consumed since the snapshot was taken, given the resources
+    /// accumulated by now; unused builtins are omitted.
+    pub fn consumed_since(&self, resources: &ExecutionResources) -> ExecutionResources {
+        (resources - &self.0).filter_unused_builtins()
+    }
+}
+
+/// Returns the address the deploy syscall assigns to the contract it deploys, given the syscall
+/// arguments and the address of the deploying contract; deploying from zero derives the address
+/// as if the deployer were the zero address.
+pub fn calculate_deployed_contract_address(
+    contract_address_salt: ContractAddressSalt,
+    class_hash: ClassHash,
+    constructor_calldata: &Calldata,
+    deployer_address: ContractAddress,
+    deploy_from_zero: bool,
+) -> Result<ContractAddress, StarknetApiError> {
+    let deployer_address_for_calculation = match deploy_from_zero {
+        true => ContractAddress::default(),
+        false => deployer_address,
+    };
+    calculate_contract_address(
+        contract_address_salt,
+        class_hash,
+        constructor_calldata,
+        deployer_address_for_calculation,
+    )
+}
+
 /// Instantiates the given class and assigns it an address.
 /// Returns the call info of the deployed class' constructor execution.
 pub fn execute_deployment(
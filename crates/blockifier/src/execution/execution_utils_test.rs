@@ -1,12 +1,17 @@
+use std::collections::HashMap;
 use std::iter::zip;
 
 use cairo_felt::Felt252;
+use cairo_vm::vm::runners::builtin_runner::{HASH_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use num_bigint::BigUint;
 use num_traits::{One, Zero};
 use pretty_assertions::assert_eq;
 use starknet_api::hash::StarkFelt;
 
-use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+use crate::execution::execution_utils::{
+    felt_to_stark_felt, stark_felt_to_felt, ResourcesSnapshot,
+};
 
 fn starkfelt_to_felt_pairs() -> Vec<(StarkFelt, Felt252)> {
     // The STARK prime is 2 ^ 251 + 17 * 2 ^ 192 + 1.
@@ -45,3 +50,30 @@ fn test_felt_to_stark_felt() {
         assert_eq!(felt_to_stark_felt(&felt), equivalent_stark_felt);
     }
 }
+
+#[test]
+fn test_resources_snapshot() {
+    let mut resources = ExecutionResources {
+        n_steps: 10,
+        n_memory_holes: 1,
+        builtin_instance_counter: HashMap::from([(HASH_BUILTIN_NAME.to_string(), 2)]),
+    };
+    let snapshot = ResourcesSnapshot::take(&resources);
+
+    resources += &ExecutionResources {
+        n_steps: 5,
+        n_memory_holes: 0,
+        builtin_instance_counter: HashMap::from([(RANGE_CHECK_BUILTIN_NAME.to_string(), 3)]),
+    };
+
+    // Builtins unused since the snapshot are omitted.
+    assert_eq!(
+        snapshot.consumed_since(&resources),
+        ExecutionResources {
+            n_steps: 5,
+            n_memory_holes: 0,
+            builtin_instance_counter: HashMap::from([(RANGE_CHECK_BUILTIN_NAME.to_string(), 3)]),
+        }
+    );
+    assert_eq!(snapshot.resources().n_steps, 10);
+}
@@ -0,0 +1,58 @@
+use sha3::{Digest, Keccak256};
+use starknet_api::core::EthAddress;
+use starknet_api::hash::StarkFelt;
+
+use crate::execution::call_info::SentL2ToL1Message;
+use crate::utils::u128_from_usize;
+
+#[cfg(test)]
+#[path = "messages_to_l1_test.rs"]
+pub mod test;
+
+/// Returns the given message as encoded by the OS in the messages-to-L1 segment of its output:
+/// `from_address, to_address, payload_size, payload`.
+pub fn encode_message_to_l1(message: &SentL2ToL1Message) -> Vec<StarkFelt> {
+    let SentL2ToL1Message { from_address, message } = message;
+    let payload = &message.message.payload.0;
+    let mut encoded_message = vec![
+        *from_address.0.key(),
+        eth_address_to_felt(message.message.to_address),
+        StarkFelt::from(u128_from_usize(payload.len())),
+    ];
+    encoded_message.extend(payload.iter().copied());
+
+    encoded_message
+}
+
+/// Returns the messages-to-L1 segment of the OS output for the given messages, in the given order.
+/// Note that the size of the segment, which precedes it in the OS output, is not included.
+pub fn encode_messages_to_l1_segment(messages: &[SentL2ToL1Message]) -> Vec<StarkFelt> {
+    messages.iter().flat_map(encode_message_to_l1).collect()
+}
+
+/// Returns the hash of the given message, as computed by the Starknet core contract on L1 to
+/// consume it: the keccak256 of its encoding as 32-byte big-endian words.
+pub fn message_to_l1_hash(message: &SentL2ToL1Message) -> [u8; 32] {
+    keccak256_of_felts(&encode_message_to_l1(message))
+}
+
+/// Returns the keccak256 of the messages-to-L1 segment of the given messages, encoded as 32-byte
+/// big-endian words.
+pub fn messages_to_l1_segment_hash(messages: &[SentL2ToL1Message]) -> [u8; 32] {
+    keccak256_of_felts(&encode_messages_to_l1_segment(messages))
+}
+
+fn eth_address_to_felt(address: EthAddress) -> StarkFelt {
+    let mut bytes = [0_u8; 32];
+    bytes[12..].copy_from_slice(address.0.as_bytes());
+    StarkFelt::new(bytes).expect("An Ethereum address must fit in a felt.")
+}
+
+fn keccak256_of_felts(felts: &[StarkFelt]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    for felt in felts {
+        hasher.update(felt.bytes());
+    }
+
+    hasher.finalize().into()
+}
@@ -0,0 +1,50 @@
+use pretty_assertions::assert_eq;
+use starknet_api::core::{ContractAddress, EthAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::L2ToL1Payload;
+use starknet_api::{contract_address, patricia_key, stark_felt};
+
+use crate::execution::call_info::{MessageToL1, OrderedL2ToL1Message, SentL2ToL1Message};
+use crate::execution::messages_to_l1::{
+    encode_messages_to_l1_segment, message_to_l1_hash, messages_to_l1_segment_hash,
+};
+
+fn sent_message(from_address: u16, to_address: u8, payload: &[u8]) -> SentL2ToL1Message {
+    SentL2ToL1Message {
+        from_address: contract_address!(from_address),
+        message: OrderedL2ToL1Message {
+            order: 0,
+            message: MessageToL1 {
+                to_address: EthAddress::try_from(stark_felt!(to_address)).unwrap(),
+                payload: L2ToL1Payload(payload.iter().map(|felt| stark_felt!(*felt)).collect()),
+            },
+        },
+    }
+}
+
+#[test]
+fn test_messages_to_l1_segment() {
+    let messages = [sent_message(0x1234, 5, &[1, 2]), sent_message(0x20, 6, &[7])];
+
+    let expected_segment: Vec<StarkFelt> =
+        [0x1234_u16, 5, 2, 1, 2, 0x20, 6, 1, 7].into_iter().map(StarkFelt::from).collect();
+    assert_eq!(encode_messages_to_l1_segment(&messages), expected_segment);
+
+    // The hashes are the keccak256 of the encodings, as 32-byte big-endian words.
+    assert_eq!(
+        message_to_l1_hash(&messages[0]),
+        [
+            0x3e, 0xf6, 0x1a, 0xb8, 0x6d, 0x9a, 0xed, 0xcd, 0x3b, 0x4a, 0xfd, 0x89, 0xa8, 0x83,
+            0xcf, 0xe4, 0x8b, 0x4c, 0x84, 0x43, 0x38, 0x22, 0x07, 0x45, 0x4e, 0x0d, 0xa6, 0x83,
+            0xf1, 0x07, 0xb7, 0xcf
+        ]
+    );
+    assert_eq!(
+        messages_to_l1_segment_hash(&messages),
+        [
+            0x84, 0x3f, 0xae, 0xc4, 0x79, 0x50, 0x10, 0x3b, 0x2c, 0x3a, 0xbc, 0xd6, 0x4d, 0xe1,
+            0xd3, 0x1e, 0x3d, 0xe5, 0x68, 0x9e, 0xb5, 0x77, 0x3b, 0x9b, 0x8b, 0xdd, 0x00, 0xed,
+            0x00, 0xc2, 0xb6, 0x20
+        ]
+    );
+}
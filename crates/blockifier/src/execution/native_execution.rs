@@ -0,0 +1,57 @@
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+
+use crate::execution::call_info::CallInfo;
+use crate::execution::contract_class::ContractClassV1;
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
+};
+use crate::state::state_api::State;
+
+#[cfg(test)]
+#[path = "native_execution_test.rs"]
+pub mod test;
+
+/// An execution backend running Cairo 1 classes natively, i.e., compiled from Sierra to machine
+/// code, rather than by the Cairo VM; e.g., a Cairo Native based one, caching the compiled
+/// classes.
+/// The backend is selected per class: classes it does not support are executed by the VM.
+/// The Blockifier does not provide a backend, nor depend on a Sierra-to-native compiler, as such a
+/// compiler builds against LLVM; a backend is implemented and set on the block context (see
+/// [`ExtensionHooks::native_executor`](crate::context::ExtensionHooks::native_executor)) by the
+/// embedding node.
+pub trait NativeExecutor: std::fmt::Debug + Send + Sync {
+    /// Executes the given call natively, as the VM would have; i.e., with the same call info,
+    /// state changes and resource accounting.
+    /// Returns [None] if the class uses features the backend does not support, in which case the
+    /// call is executed by the VM; this must be decided before the state or the resources are
+    /// modified.
+    fn execute(
+        &self,
+        call: &CallEntryPoint,
+        contract_class: &ContractClassV1,
+        state: &mut dyn State,
+        resources: &mut ExecutionResources,
+        context: &mut EntryPointExecutionContext,
+    ) -> Option<EntryPointExecutionResult<CallInfo>>;
+}
+
+/// Executes the given call by the native executor of the block, if set and if it supports the
+/// class; returns [None] otherwise, in which case the call is to be executed by the VM.
+pub fn try_execute_natively(
+    call: &CallEntryPoint,
+    contract_class: &ContractClassV1,
+    state: &mut dyn State,
+    resources: &mut ExecutionResources,
+    context: &mut EntryPointExecutionContext,
+) -> Option<EntryPointExecutionResult<CallInfo>> {
+    let native_executor = context.tx_context.block_context.hooks.native_executor.clone()?;
+    let result = native_executor.execute(call, contract_class, state, resources, context);
+    if result.is_none() {
+        log::debug!(
+            "Class {:?} is not supported by the native executor; executing by the VM.",
+            call.class_hash
+        );
+    }
+
+    result
+}
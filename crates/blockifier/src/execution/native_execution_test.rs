@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::ClassHash;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::{BlockContext, ChainInfo};
+use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
+use crate::execution::contract_class::ContractClassV1;
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
+};
+use crate::execution::native_execution::NativeExecutor;
+use crate::retdata;
+use crate::state::state_api::State;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+
+/// Executes calls to the supported class by returning a constant retdata.
+#[derive(Debug)]
+struct ConstantNativeExecutor {
+    supported_class_hash: ClassHash,
+    retdata: Retdata,
+}
+
+impl NativeExecutor for ConstantNativeExecutor {
+    fn execute(
+        &self,
+        call: &CallEntryPoint,
+        _contract_class: &ContractClassV1,
+        _state: &mut dyn State,
+        _resources: &mut ExecutionResources,
+        _context: &mut EntryPointExecutionContext,
+    ) -> Option<EntryPointExecutionResult<CallInfo>> {
+        if call.class_hash != Some(self.supported_class_hash) {
+            return None;
+        }
+
+        Some(Ok(CallInfo {
+            call: call.clone(),
+            execution: CallExecution { retdata: self.retdata.clone(), ..Default::default() },
+            ..Default::default()
+        }))
+    }
+}
+
+#[rstest]
+fn test_native_execution(#[values(true, false)] is_class_supported: bool) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let native_retdata = retdata![stark_felt!(7_u8)];
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.hooks_mut().native_executor = Some(Arc::new(ConstantNativeExecutor {
+        supported_class_hash: if is_class_supported {
+            test_contract.get_class_hash()
+        } else {
+            ClassHash::default()
+        },
+        retdata: native_retdata.clone(),
+    }));
+
+    let value = stark_felt!(18_u8);
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(1234_u16), value],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let call_info =
+        entry_point_call.execute_directly_given_block_context(&mut state, block_context).unwrap();
+
+    // Unsupported classes are executed by the VM.
+    let expected_retdata = if is_class_supported { native_retdata } else { retdata![value] };
+    assert_eq!(call_info.execution.retdata, expected_retdata);
+}
@@ -0,0 +1,50 @@
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use starknet_api::core::ContractAddress;
+
+use crate::execution::call_info::{CallExecution, CallInfo};
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
+};
+use crate::execution::syscalls::SyscallSelector;
+use crate::state::state_api::State;
+
+#[cfg(test)]
+#[path = "syscall_interceptor_test.rs"]
+pub mod test;
+
+/// Observes the syscalls of executed contracts, and may replace the execution of the calls they
+/// invoke; e.g., for contract testing frameworks to mock contracts without deploying them.
+pub trait SyscallInterceptor: std::fmt::Debug + Send + Sync {
+    /// Invoked before the execution of every syscall, with the address of the contract invoking
+    /// it.
+    fn on_syscall(&self, _selector: SyscallSelector, _storage_address: ContractAddress) {}
+
+    /// Invoked before the execution of a call invoked by a syscall (e.g., `call_contract` or
+    /// `library_call`); if an execution is returned, the call is not executed, and the syscall
+    /// responds with the returned execution instead. Note that in Cairo 1, a failed execution
+    /// fails the syscall with the return data as the error data.
+    fn intercept_call(&self, _call: &CallEntryPoint) -> Option<CallExecution> {
+        None
+    }
+}
+
+/// Executes a call invoked by a syscall, unless the syscall interceptor of the block replaces its
+/// execution.
+pub fn execute_intercepted_call(
+    call: CallEntryPoint,
+    state: &mut dyn State,
+    resources: &mut ExecutionResources,
+    context: &mut EntryPointExecutionContext,
+) -> EntryPointExecutionResult<CallInfo> {
+    let intercepted_execution = context
+        .tx_context
+        .block_context
+        .hooks
+        .syscall_interceptor
+        .as_ref()
+        .and_then(|syscall_interceptor| syscall_interceptor.intercept_call(&call));
+    match intercepted_execution {
+        Some(execution) => Ok(CallInfo { call, execution, ..Default::default() }),
+        None => call.execute(state, resources, context),
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::{Arc, Mutex};
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::{ContractAddress, EntryPointSelector};
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::state::StorageKey;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::{BlockContext, ChainInfo};
+use crate::execution::call_info::{CallExecution, Retdata};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::syscall_interceptor::SyscallInterceptor;
+use crate::execution::syscalls::SyscallSelector;
+use crate::retdata;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, trivial_external_entry_point_new, CairoVersion, BALANCE};
+
+/// Records the syscalls, and mocks the calls to the given entry point.
+#[derive(Debug)]
+struct MockingInterceptor {
+    mocked_selector: EntryPointSelector,
+    mocked_retdata: Vec<StarkFelt>,
+    syscalls: Mutex<Vec<(SyscallSelector, ContractAddress)>>,
+}
+
+impl SyscallInterceptor for MockingInterceptor {
+    fn on_syscall(&self, selector: SyscallSelector, storage_address: ContractAddress) {
+        self.syscalls.lock().unwrap().push((selector, storage_address));
+    }
+
+    fn intercept_call(&self, call: &CallEntryPoint) -> Option<CallExecution> {
+        (call.entry_point_selector == self.mocked_selector).then(|| CallExecution {
+            retdata: Retdata(self.mocked_retdata.clone()),
+            ..Default::default()
+        })
+    }
+}
+
+#[rstest]
+fn test_mocked_call_contract(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let test_contract_address = test_contract.get_instance_address(0);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let interceptor = Arc::new(MockingInterceptor {
+        mocked_selector: selector_from_name("test_storage_read_write"),
+        mocked_retdata: vec![stark_felt!(99_u8)],
+        syscalls: Mutex::default(),
+    });
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.hooks_mut().syscall_interceptor = Some(interceptor.clone());
+
+    let key = stark_felt!(405_u16);
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_call_contract"),
+        calldata: create_calldata(
+            test_contract_address,
+            "test_storage_read_write",
+            &[key, stark_felt!(48_u8)],
+        ),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let call_info =
+        entry_point_call.execute_directly_given_block_context(&mut state, block_context).unwrap();
+
+    // The mocked call is not executed, and its caller gets the mocked return data.
+    assert_eq!(call_info.execution.retdata, retdata![stark_felt!(99_u8)]);
+    assert_eq!(call_info.inner_calls.len(), 1);
+    assert_eq!(call_info.inner_calls[0].execution.retdata, retdata![stark_felt!(99_u8)]);
+    assert_eq!(
+        state.get_storage_at(test_contract_address, StorageKey::try_from(key).unwrap()).unwrap(),
+        StarkFelt::ZERO
+    );
+    assert_eq!(
+        *interceptor.syscalls.lock().unwrap(),
+        vec![(SyscallSelector::CallContract, test_contract_address)]
+    );
+}
@@ -1,5 +1,6 @@
 use std::any::Any;
-use std::collections::{HashMap, HashSet};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use cairo_felt::Felt252;
 use cairo_lang_casm::hints::{Hint, StarknetHint};
@@ -17,37 +18,46 @@ use cairo_vm::vm::runners::cairo_runner::{ExecutionResources, ResourceTracker, R
 use cairo_vm::vm::vm_core::VirtualMachine;
 use num_traits::Zero;
 use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
+use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
-use starknet_api::transaction::{Calldata, Resource};
+use starknet_api::transaction::{
+    AccountDeploymentData, Calldata, PaymasterData, Resource, ResourceBoundsMapping, Tip,
+};
 use starknet_api::StarknetApiError;
 use thiserror::Error;
 
 use crate::abi::sierra_types::SierraTypeError;
-use crate::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message};
+use crate::context::GasMeteringMode;
+use crate::execution::call_info::{
+    CallInfo, OrderedEvent, OrderedL2ToL1Message, SyscallGasRecord, TrackedResource,
+};
 use crate::execution::common_hints::{ExecutionMode, HintExecutionResult};
+use crate::execution::custom_hints::CompiledCustomHint;
 use crate::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
-use crate::execution::errors::{ConstructorEntryPointExecutionError, EntryPointExecutionError};
+use crate::execution::errors::{
+    ConstructorEntryPointExecutionError, EntryPointExecutionError, ExhaustedResource,
+};
 use crate::execution::execution_utils::{
     felt_range_from_ptr, max_fee_for_execution_info, stark_felt_from_ptr, stark_felt_to_felt,
     write_maybe_relocatable, ReadOnlySegment, ReadOnlySegments,
 };
+use crate::execution::syscall_interceptor::execute_intercepted_call;
 use crate::execution::syscalls::secp::{
     secp256k1_add, secp256k1_get_point_from_x, secp256k1_get_xy, secp256k1_mul, secp256k1_new,
     secp256r1_add, secp256r1_get_point_from_x, secp256r1_get_xy, secp256r1_mul, secp256r1_new,
     SecpHintProcessor,
 };
 use crate::execution::syscalls::{
-    call_contract, deploy, emit_event, get_block_hash, get_execution_info, keccak, library_call,
-    library_call_l1_handler, replace_class, send_message_to_l1, storage_read, storage_write,
-    StorageReadResponse, StorageWriteResponse, SyscallRequest, SyscallRequestWrapper,
-    SyscallResponse, SyscallResponseWrapper, SyscallResult, SyscallSelector,
+    call_contract, deploy, emit_event, get_block_hash, get_class_hash_at, get_execution_info,
+    keccak, library_call, library_call_l1_handler, replace_class, send_message_to_l1, storage_read,
+    storage_write, StorageReadResponse, StorageWriteResponse, SyscallRequest,
+    SyscallRequestWrapper, SyscallResponse, SyscallResponseWrapper, SyscallResult, SyscallSelector,
 };
 use crate::state::errors::StateError;
 use crate::state::state_api::State;
 use crate::transaction::objects::{CurrentTransactionInfo, TransactionInfo};
-use crate::transaction::transaction_utils::update_remaining_gas;
 
 pub type SyscallCounter = HashMap<SyscallSelector, usize>;
 
@@ -59,6 +69,8 @@ pub enum SyscallExecutionError {
     EmitEventError(#[from] EmitEventError),
     #[error("Cannot replace V1 class hash with V0 class hash: {class_hash}.")]
     ForbiddenClassReplacement { class_hash: ClassHash },
+    #[error("Deploying from zero is not allowed on this chain.")]
+    ForbiddenDeployFromZero,
     #[error("Invalid address domain: {address_domain}.")]
     InvalidAddressDomain { address_domain: StarkFelt },
     #[error(transparent)]
@@ -85,6 +97,8 @@ pub enum SyscallExecutionError {
     InvalidSyscallSelector(StarkFelt),
     #[error("Unauthorized syscall {syscall_name} in execution mode {execution_mode}.")]
     InvalidSyscallInExecutionMode { syscall_name: String, execution_mode: ExecutionMode },
+    #[error("Syscall {selector:?} is not supported by the current versioned constants.")]
+    UnsupportedSyscall { selector: SyscallSelector },
     #[error(transparent)]
     MathError(#[from] cairo_vm::types::errors::math_errors::MathError),
     #[error(transparent)]
@@ -118,6 +132,11 @@ pub enum EmitEventError {
          events: {max_n_emitted_events}."
     )]
     ExceedsMaxNumberOfEmittedEvents { n_emitted_events: usize, max_n_emitted_events: usize },
+    #[error(
+        "Exceeded the maximum total size of event payloads, total payload size: \
+         {total_payload_size}, max total payload size: {max_total_payload_size}."
+    )]
+    ExceedsMaxTotalPayloadSize { total_payload_size: usize, max_total_payload_size: usize },
 }
 
 // Needed for custom hint implementations (in our case, syscall hints) which must comply with the
@@ -192,6 +211,8 @@ pub struct SyscallHintProcessor<'a> {
     pub events: Vec<OrderedEvent>,
     pub l2_to_l1_messages: Vec<OrderedL2ToL1Message>,
     pub syscall_counter: SyscallCounter,
+    /// The gas charged for each syscall invocation, in order; recorded under syscall gas audit.
+    pub syscall_gas_records: Vec<SyscallGasRecord>,
 
     // Fields needed for execution and validation.
     pub read_only_segments: ReadOnlySegments,
@@ -230,6 +251,7 @@ impl<'a> SyscallHintProcessor<'a> {
             events: vec![],
             l2_to_l1_messages: vec![],
             syscall_counter: SyscallCounter::default(),
+            syscall_gas_records: vec![],
             read_only_segments,
             syscall_ptr: initial_syscall_ptr,
             read_values: vec![],
@@ -261,6 +283,21 @@ impl<'a> SyscallHintProcessor<'a> {
         self.execution_mode() == ExecutionMode::Validate
     }
 
+    /// Returns an error if the syscall is run in validate mode, and is forbidden in this mode by
+    /// the versioned constants.
+    pub fn verify_syscall_allowed(&self, selector: &SyscallSelector) -> SyscallResult<()> {
+        if self.is_validate_mode()
+            && self.context.versioned_constants().validate_forbidden_syscalls.contains(selector)
+        {
+            return Err(SyscallExecutionError::InvalidSyscallInExecutionMode {
+                syscall_name: selector.to_string(),
+                execution_mode: self.execution_mode(),
+            });
+        }
+
+        Ok(())
+    }
+
     pub fn verify_syscall_ptr(&self, actual_ptr: Relocatable) -> SyscallResult<()> {
         if actual_ptr != self.syscall_ptr {
             return Err(SyscallExecutionError::BadSyscallPointer {
@@ -288,6 +325,11 @@ impl<'a> SyscallHintProcessor<'a> {
         self.verify_syscall_ptr(initial_syscall_ptr)?;
 
         let selector = SyscallSelector::try_from(self.read_next_syscall_selector(vm)?)?;
+        self.verify_syscall_allowed(&selector)?;
+        let block_context = &self.context.tx_context.block_context;
+        if let Some(syscall_interceptor) = &block_context.hooks.syscall_interceptor {
+            syscall_interceptor.on_syscall(selector, self.storage_address());
+        }
 
         // Keccak resource usage depends on the input length, so we increment the syscall count
         // in the syscall execution callback.
@@ -298,105 +340,133 @@ impl<'a> SyscallHintProcessor<'a> {
         match selector {
             SyscallSelector::CallContract => self.execute_syscall(
                 vm,
+                selector,
                 call_contract,
                 self.context.gas_costs().call_contract_gas_cost,
             ),
             SyscallSelector::Deploy => {
-                self.execute_syscall(vm, deploy, self.context.gas_costs().deploy_gas_cost)
-            }
-            SyscallSelector::EmitEvent => {
-                self.execute_syscall(vm, emit_event, self.context.gas_costs().emit_event_gas_cost)
+                self.execute_syscall(vm, selector, deploy, self.context.gas_costs().deploy_gas_cost)
             }
+            SyscallSelector::EmitEvent => self.execute_syscall(
+                vm,
+                selector,
+                emit_event,
+                self.context.gas_costs().emit_event_gas_cost,
+            ),
             SyscallSelector::GetBlockHash => self.execute_syscall(
                 vm,
+                selector,
                 get_block_hash,
                 self.context.gas_costs().get_block_hash_gas_cost,
             ),
+            SyscallSelector::GetClassHashAt => {
+                let Some(gas_cost) = self.context.gas_costs().get_class_hash_at_gas_cost else {
+                    return Err(SyscallExecutionError::UnsupportedSyscall { selector }.into());
+                };
+                self.execute_syscall(vm, selector, get_class_hash_at, gas_cost)
+            }
             SyscallSelector::GetExecutionInfo => self.execute_syscall(
                 vm,
+                selector,
                 get_execution_info,
                 self.context.gas_costs().get_execution_info_gas_cost,
             ),
             SyscallSelector::Keccak => {
-                self.execute_syscall(vm, keccak, self.context.gas_costs().keccak_gas_cost)
+                self.execute_syscall(vm, selector, keccak, self.context.gas_costs().keccak_gas_cost)
             }
             SyscallSelector::LibraryCall => self.execute_syscall(
                 vm,
+                selector,
                 library_call,
                 self.context.gas_costs().library_call_gas_cost,
             ),
             SyscallSelector::LibraryCallL1Handler => self.execute_syscall(
                 vm,
+                selector,
                 library_call_l1_handler,
                 self.context.gas_costs().library_call_gas_cost,
             ),
             SyscallSelector::ReplaceClass => self.execute_syscall(
                 vm,
+                selector,
                 replace_class,
                 self.context.gas_costs().replace_class_gas_cost,
             ),
             SyscallSelector::Secp256k1Add => self.execute_syscall(
                 vm,
+                selector,
                 secp256k1_add,
                 self.context.gas_costs().secp256k1_add_gas_cost,
             ),
             SyscallSelector::Secp256k1GetPointFromX => self.execute_syscall(
                 vm,
+                selector,
                 secp256k1_get_point_from_x,
                 self.context.gas_costs().secp256k1_get_point_from_x_gas_cost,
             ),
             SyscallSelector::Secp256k1GetXy => self.execute_syscall(
                 vm,
+                selector,
                 secp256k1_get_xy,
                 self.context.gas_costs().secp256k1_get_xy_gas_cost,
             ),
             SyscallSelector::Secp256k1Mul => self.execute_syscall(
                 vm,
+                selector,
                 secp256k1_mul,
                 self.context.gas_costs().secp256k1_mul_gas_cost,
             ),
             SyscallSelector::Secp256k1New => self.execute_syscall(
                 vm,
+                selector,
                 secp256k1_new,
                 self.context.gas_costs().secp256k1_new_gas_cost,
             ),
             SyscallSelector::Secp256r1Add => self.execute_syscall(
                 vm,
+                selector,
                 secp256r1_add,
                 self.context.gas_costs().secp256r1_add_gas_cost,
             ),
             SyscallSelector::Secp256r1GetPointFromX => self.execute_syscall(
                 vm,
+                selector,
                 secp256r1_get_point_from_x,
                 self.context.gas_costs().secp256r1_get_point_from_x_gas_cost,
             ),
             SyscallSelector::Secp256r1GetXy => self.execute_syscall(
                 vm,
+                selector,
                 secp256r1_get_xy,
                 self.context.gas_costs().secp256r1_get_xy_gas_cost,
             ),
             SyscallSelector::Secp256r1Mul => self.execute_syscall(
                 vm,
+                selector,
                 secp256r1_mul,
                 self.context.gas_costs().secp256r1_mul_gas_cost,
             ),
             SyscallSelector::Secp256r1New => self.execute_syscall(
                 vm,
+                selector,
                 secp256r1_new,
                 self.context.gas_costs().secp256r1_new_gas_cost,
             ),
             SyscallSelector::SendMessageToL1 => self.execute_syscall(
                 vm,
+                selector,
                 send_message_to_l1,
                 self.context.gas_costs().send_message_to_l1_gas_cost,
             ),
             SyscallSelector::StorageRead => self.execute_syscall(
                 vm,
+                selector,
                 storage_read,
                 self.context.gas_costs().storage_read_gas_cost,
             ),
             SyscallSelector::StorageWrite => self.execute_syscall(
                 vm,
+                selector,
                 storage_write,
                 self.context.gas_costs().storage_write_gas_cost,
             ),
@@ -451,6 +521,7 @@ impl<'a> SyscallHintProcessor<'a> {
     fn execute_syscall<Request, Response, ExecuteCallback>(
         &mut self,
         vm: &mut VirtualMachine,
+        selector: SyscallSelector,
         execute_callback: ExecuteCallback,
         syscall_gas_cost: u64,
     ) -> HintExecutionResult
@@ -470,13 +541,17 @@ impl<'a> SyscallHintProcessor<'a> {
         let SyscallRequestWrapper { gas_counter, request } =
             SyscallRequestWrapper::<Request>::read(vm, &mut self.syscall_ptr)?;
 
+        self.context.set_remaining_gas(gas_counter);
         if gas_counter < required_gas {
+            self.context.out_of_gas_syscall =
+                Some(ExhaustedResource::Gas { selector, remaining_gas: gas_counter, required_gas });
             //  Out of gas failure.
             let out_of_gas_error =
                 StarkFelt::try_from(OUT_OF_GAS_ERROR).map_err(SyscallExecutionError::from)?;
             let response: SyscallResponseWrapper<Response> =
                 SyscallResponseWrapper::Failure { gas_counter, error_data: vec![out_of_gas_error] };
             response.write(vm, &mut self.syscall_ptr)?;
+            self.record_syscall_gas(selector, gas_counter, gas_counter);
 
             return Ok(());
         }
@@ -484,6 +559,8 @@ impl<'a> SyscallHintProcessor<'a> {
         // Execute.
         let mut remaining_gas = gas_counter - required_gas;
         let original_response = execute_callback(request, vm, self, &mut remaining_gas);
+        self.record_syscall_gas(selector, gas_counter, remaining_gas);
+        self.context.set_remaining_gas(remaining_gas);
         let response = match original_response {
             Ok(response) => {
                 SyscallResponseWrapper::Success { gas_counter: remaining_gas, response }
@@ -499,6 +576,27 @@ impl<'a> SyscallHintProcessor<'a> {
         Ok(())
     }
 
+    /// Records the gas charged for a syscall invocation, under syscall gas audit. The given gas
+    /// counters are those of the request and the response; the base syscall cost was pre-charged
+    /// before the request.
+    fn record_syscall_gas(
+        &mut self,
+        selector: SyscallSelector,
+        gas_counter: u64,
+        remaining_gas: u64,
+    ) {
+        if !self.context.tx_context.block_context.execution_policy.syscall_gas_audit {
+            return;
+        }
+
+        let gas_before = gas_counter + self.context.gas_costs().syscall_base_gas_cost;
+        self.syscall_gas_records.push(SyscallGasRecord {
+            selector,
+            gas_before,
+            gas_after: remaining_gas,
+        });
+    }
+
     fn read_next_syscall_selector(&mut self, vm: &mut VirtualMachine) -> SyscallResult<StarkFelt> {
         let selector = stark_felt_from_ptr(vm, &mut self.syscall_ptr)?;
 
@@ -521,10 +619,12 @@ impl<'a> SyscallHintProcessor<'a> {
         let block_info_ptr = self.allocate_block_info_segment(vm)?;
         let tx_info_ptr = self.allocate_tx_info_segment(vm)?;
 
+        let caller_address =
+            self.context.caller_address_seen_by(self.storage_address(), self.caller_address());
         let additional_info: Vec<MaybeRelocatable> = vec![
             block_info_ptr.into(),
             tx_info_ptr.into(),
-            stark_felt_to_felt(*self.caller_address().0.key()).into(),
+            stark_felt_to_felt(*caller_address.0.key()).into(),
             stark_felt_to_felt(*self.storage_address().0.key()).into(),
             stark_felt_to_felt(self.entry_point_selector().0).into(),
         ];
@@ -539,8 +639,8 @@ impl<'a> SyscallHintProcessor<'a> {
         vm: &mut VirtualMachine,
     ) -> SyscallResult<Relocatable> {
         let block_info = &self.context.tx_context.block_context.block_info;
-        let block_timestamp = block_info.block_timestamp.0;
-        let block_number = block_info.block_number.0;
+        let block_timestamp = self.context.block_timestamp().0;
+        let block_number = self.context.block_number().0;
         let versioned_constants = self.context.versioned_constants();
         let block_data: Vec<StarkFelt> = if self.is_validate_mode() {
             // Round down to the nearest multiple of validate_block_number_rounding.
@@ -583,13 +683,21 @@ impl<'a> SyscallHintProcessor<'a> {
 
     fn allocate_tx_info_segment(&mut self, vm: &mut VirtualMachine) -> SyscallResult<Relocatable> {
         let tx_info = &self.context.tx_context.clone().tx_info;
+        // The layout follows the transaction version seen by the contract, which may be
+        // overridden.
+        let sees_current_tx_info = self.context.sees_current_tx_info();
         let (tx_signature_start_ptr, tx_signature_end_ptr) =
             &self.allocate_data_segment(vm, &tx_info.signature().0)?;
+        let max_fee = if sees_current_tx_info {
+            Felt252::zero()
+        } else {
+            max_fee_for_execution_info(tx_info)
+        };
 
         let mut tx_data: Vec<MaybeRelocatable> = vec![
-            stark_felt_to_felt(tx_info.signed_version().0).into(),
+            stark_felt_to_felt(self.context.tx_version().0).into(),
             stark_felt_to_felt(*tx_info.sender_address().0.key()).into(),
-            max_fee_for_execution_info(tx_info).into(),
+            max_fee.into(),
             tx_signature_start_ptr.into(),
             tx_signature_end_ptr.into(),
             stark_felt_to_felt((tx_info).transaction_hash().0).into(),
@@ -600,44 +708,55 @@ impl<'a> SyscallHintProcessor<'a> {
             stark_felt_to_felt((tx_info).nonce().0).into(),
         ];
 
-        match tx_info {
-            TransactionInfo::Current(context) => {
-                let (tx_resource_bounds_start_ptr, tx_resource_bounds_end_ptr) =
-                    &self.allocate_tx_resource_bounds_segment(vm, context)?;
-
-                let (tx_paymaster_data_start_ptr, tx_paymaster_data_end_ptr) =
-                    &self.allocate_data_segment(vm, &context.paymaster_data.0)?;
-
-                let (tx_account_deployment_data_start_ptr, tx_account_deployment_data_end_ptr) =
-                    &self.allocate_data_segment(vm, &context.account_deployment_data.0)?;
-
-                tx_data.extend_from_slice(&[
-                    tx_resource_bounds_start_ptr.into(),
-                    tx_resource_bounds_end_ptr.into(),
-                    Felt252::from(context.tip.0).into(),
-                    tx_paymaster_data_start_ptr.into(),
-                    tx_paymaster_data_end_ptr.into(),
-                    stark_felt_to_felt(context.nonce_data_availability_mode.into()).into(),
-                    stark_felt_to_felt(context.fee_data_availability_mode.into()).into(),
-                    tx_account_deployment_data_start_ptr.into(),
-                    tx_account_deployment_data_end_ptr.into(),
-                ]);
-            }
-            TransactionInfo::Deprecated(_) => {
-                let zero_felt: MaybeRelocatable = Felt252::zero().into();
-                tx_data.extend_from_slice(&[
-                    zero_felt.clone(), // Empty segment of resource bounds (start ptr).
-                    zero_felt.clone(), // Empty segment of resource bounds (end ptr).
-                    zero_felt.clone(), // Tip.
-                    zero_felt.clone(), // Empty segment of paymaster data (start ptr).
-                    zero_felt.clone(), // Empty segment of paymaster data (end ptr).
-                    zero_felt.clone(), // Nonce DA mode.
-                    zero_felt.clone(), // Fee DA mode.
-                    zero_felt.clone(), // Empty segment of account deployment data (start ptr).
-                    zero_felt,         // Empty segment of account deployment data (end ptr).
-                ]);
-            }
-        };
+        if sees_current_tx_info {
+            let context = match tx_info {
+                TransactionInfo::Current(context) => Cow::Borrowed(context),
+                // Seen with empty resource bounds and data.
+                TransactionInfo::Deprecated(context) => Cow::Owned(CurrentTransactionInfo {
+                    common_fields: context.common_fields.clone(),
+                    resource_bounds: ResourceBoundsMapping(BTreeMap::new()),
+                    tip: Tip::default(),
+                    nonce_data_availability_mode: DataAvailabilityMode::L1,
+                    fee_data_availability_mode: DataAvailabilityMode::L1,
+                    paymaster_data: PaymasterData::default(),
+                    account_deployment_data: AccountDeploymentData::default(),
+                }),
+            };
+            let context = context.as_ref();
+            let (tx_resource_bounds_start_ptr, tx_resource_bounds_end_ptr) =
+                &self.allocate_tx_resource_bounds_segment(vm, context)?;
+
+            let (tx_paymaster_data_start_ptr, tx_paymaster_data_end_ptr) =
+                &self.allocate_data_segment(vm, &context.paymaster_data.0)?;
+
+            let (tx_account_deployment_data_start_ptr, tx_account_deployment_data_end_ptr) =
+                &self.allocate_data_segment(vm, &context.account_deployment_data.0)?;
+
+            tx_data.extend_from_slice(&[
+                tx_resource_bounds_start_ptr.into(),
+                tx_resource_bounds_end_ptr.into(),
+                Felt252::from(context.tip.0).into(),
+                tx_paymaster_data_start_ptr.into(),
+                tx_paymaster_data_end_ptr.into(),
+                stark_felt_to_felt(context.nonce_data_availability_mode.into()).into(),
+                stark_felt_to_felt(context.fee_data_availability_mode.into()).into(),
+                tx_account_deployment_data_start_ptr.into(),
+                tx_account_deployment_data_end_ptr.into(),
+            ]);
+        } else {
+            let zero_felt: MaybeRelocatable = Felt252::zero().into();
+            tx_data.extend_from_slice(&[
+                zero_felt.clone(), // Empty segment of resource bounds (start ptr).
+                zero_felt.clone(), // Empty segment of resource bounds (end ptr).
+                zero_felt.clone(), // Tip.
+                zero_felt.clone(), // Empty segment of paymaster data (start ptr).
+                zero_felt.clone(), // Empty segment of paymaster data (end ptr).
+                zero_felt.clone(), // Nonce DA mode.
+                zero_felt.clone(), // Fee DA mode.
+                zero_felt.clone(), // Empty segment of account deployment data (start ptr).
+                zero_felt,         // Empty segment of account deployment data (end ptr).
+            ]);
+        }
 
         let tx_info_start_ptr = self.read_only_segments.allocate(vm, &tx_data)?;
         Ok(tx_info_start_ptr)
@@ -712,6 +831,12 @@ impl HintProcessorLogic for SyscallHintProcessor<'_> {
         hint_data: &Box<dyn Any>,
         _constants: &HashMap<String, Felt252>,
     ) -> HintExecutionResult {
+        if let Some(CompiledCustomHint { hint, custom_hint }) =
+            hint_data.downcast_ref::<CompiledCustomHint>()
+        {
+            return custom_hint.execute(vm, exec_scopes, hint);
+        }
+
         let hint = hint_data.downcast_ref::<Hint>().ok_or(HintError::WrongHintData)?;
         match hint {
             Hint::Core(hint) => execute_core_hint_base(vm, exec_scopes, hint),
@@ -727,7 +852,14 @@ impl HintProcessorLogic for SyscallHintProcessor<'_> {
         _reference_ids: &HashMap<String, usize>,
         _references: &[HintReference],
     ) -> Result<Box<dyn Any>, VirtualMachineError> {
-        Ok(Box::new(self.hints[hint_code].clone()))
+        let hint = self.hints[hint_code].clone();
+        if let Some(custom_hint) =
+            self.context.tx_context.block_context.hooks.custom_hints.get(hint_code)
+        {
+            return Ok(Box::new(CompiledCustomHint { hint, custom_hint: custom_hint.clone() }));
+        }
+
+        Ok(Box::new(hint))
     }
 }
 
@@ -755,14 +887,62 @@ pub fn read_call_params(
     Ok((function_selector, calldata))
 }
 
+/// Returns the gas charged to the caller for a call that returned, out of the gas forwarded to it
+/// (i.e., all of the caller's remaining gas); the rest of the forwarded gas is refunded to the
+/// caller. Calls are charged the gas they consumed, including that of their inner calls, except in
+/// Sierra gas metering mode: there, calls metered by steps (i.e., Cairo 0 calls), which do not
+/// consume the forwarded gas, are charged the Sierra gas of their VM resources and of their inner
+/// calls (see [`CallInfo::sierra_gas_consumed`]). Such a call fails with an out-of-gas error if
+/// its charge exceeds the forwarded gas.
+pub fn inner_call_gas_charge(
+    call_info: &CallInfo,
+    context: &EntryPointExecutionContext,
+) -> SyscallResult<u64> {
+    let gas_metering_mode = context.tx_context.block_context.fee_policy.gas_metering_mode;
+    if gas_metering_mode != GasMeteringMode::SierraGas
+        || call_info.tracked_resource == TrackedResource::SierraGas
+    {
+        return Ok(call_info.execution.gas_consumed);
+    }
+
+    let gas_charge = call_info.sierra_gas_consumed(context.versioned_constants());
+    if gas_charge > call_info.call.initial_gas {
+        let out_of_gas_error =
+            StarkFelt::try_from(OUT_OF_GAS_ERROR).map_err(SyscallExecutionError::from)?;
+        return Err(SyscallExecutionError::SyscallError { error_data: vec![out_of_gas_error] });
+    }
+    Ok(gas_charge)
+}
+
+/// Charges the caller for its last inner call, which returned (see [`inner_call_gas_charge`]), and
+/// records the charge in the call info if it differs from the gas the call consumed.
+/// Called once the errors of the inner call are wrapped, so that an out-of-gas charge is returned
+/// to the caller as a syscall failure.
+pub fn charge_inner_call_gas(
+    syscall_handler: &mut SyscallHintProcessor<'_>,
+    remaining_gas: &mut u64,
+) -> SyscallResult<()> {
+    let call_info =
+        syscall_handler.inner_calls.last_mut().expect("The inner call should be recorded.");
+    let gas_charge = inner_call_gas_charge(call_info, syscall_handler.context)?;
+    if gas_charge != call_info.execution.gas_consumed {
+        call_info.caller_gas_charge = Some(gas_charge);
+    }
+    *remaining_gas -= gas_charge;
+    Ok(())
+}
+
 pub fn execute_inner_call(
     call: CallEntryPoint,
     vm: &mut VirtualMachine,
     syscall_handler: &mut SyscallHintProcessor<'_>,
-    remaining_gas: &mut u64,
 ) -> SyscallResult<ReadOnlySegment> {
-    let call_info =
-        call.execute(syscall_handler.state, syscall_handler.resources, syscall_handler.context)?;
+    let call_info = execute_intercepted_call(
+        call,
+        syscall_handler.state,
+        syscall_handler.resources,
+        syscall_handler.context,
+    )?;
     let raw_retdata = &call_info.execution.retdata.0;
 
     if call_info.execution.failed {
@@ -772,7 +952,6 @@ pub fn execute_inner_call(
     }
 
     let retdata_segment = create_retdata_segment(vm, syscall_handler, raw_retdata)?;
-    update_remaining_gas(remaining_gas, &call_info);
 
     syscall_handler.inner_calls.push(call_info);
 
@@ -813,13 +992,17 @@ pub fn execute_library_call(
         initial_gas: *remaining_gas,
     };
 
-    execute_inner_call(entry_point, vm, syscall_handler, remaining_gas).map_err(|error| {
-        error.as_lib_call_execution_error(
-            class_hash,
-            syscall_handler.storage_address(),
-            entry_point_selector,
-        )
-    })
+    let retdata_segment =
+        execute_inner_call(entry_point, vm, syscall_handler).map_err(|error| {
+            error.as_lib_call_execution_error(
+                class_hash,
+                syscall_handler.storage_address(),
+                entry_point_selector,
+            )
+        })?;
+    charge_inner_call_gas(syscall_handler, remaining_gas)?;
+
+    Ok(retdata_segment)
 }
 
 pub fn read_felt_array<TErr>(
@@ -3,9 +3,7 @@ use cairo_vm::types::relocatable::Relocatable;
 use cairo_vm::vm::vm_core::VirtualMachine;
 use num_traits::ToPrimitive;
 use starknet_api::block::{BlockHash, BlockNumber};
-use starknet_api::core::{
-    calculate_contract_address, ClassHash, ContractAddress, EntryPointSelector, EthAddress,
-};
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, EthAddress};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
@@ -14,25 +12,27 @@ use starknet_api::transaction::{
 };
 
 use self::hint_processor::{
-    create_retdata_segment, execute_inner_call, execute_library_call, felt_to_bool,
-    read_call_params, read_calldata, read_felt_array, write_segment, EmitEventError,
+    charge_inner_call_gas, create_retdata_segment, execute_inner_call, execute_library_call,
+    felt_to_bool, read_call_params, read_calldata, read_felt_array, write_segment, EmitEventError,
     SyscallExecutionError, SyscallHintProcessor, BLOCK_NUMBER_OUT_OF_RANGE_ERROR,
 };
 use crate::abi::constants;
-use crate::execution::call_info::{MessageToL1, OrderedEvent, OrderedL2ToL1Message};
+use crate::execution::call_info::{
+    EmittedEvent, MessageToL1, OrderedEvent, OrderedL2ToL1Message, SentL2ToL1Message,
+};
 use crate::execution::contract_class::ContractClass;
 use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
 use crate::execution::entry_point::{CallEntryPoint, CallType, ConstructorContext};
 use crate::execution::execution_utils::{
-    execute_deployment, felt_from_ptr, felt_to_stark_felt, stark_felt_from_ptr, stark_felt_to_felt,
-    write_felt, write_maybe_relocatable, write_stark_felt, ReadOnlySegment,
+    calculate_deployed_contract_address, execute_deployment, felt_from_ptr, felt_to_stark_felt,
+    stark_felt_from_ptr, stark_felt_to_felt, write_felt, write_maybe_relocatable, write_stark_felt,
+    ReadOnlySegment,
 };
 use crate::execution::syscalls::hint_processor::{INVALID_INPUT_LENGTH_ERROR, OUT_OF_GAS_ERROR};
-use crate::transaction::transaction_utils::update_remaining_gas;
-use crate::versioned_constants::{EventLimits, VersionedConstants};
+use crate::versioned_constants::EventLimits;
 
 pub mod hint_processor;
-mod secp;
+pub(crate) mod secp;
 
 #[cfg(test)]
 #[path = "syscalls_test.rs"]
@@ -179,10 +179,11 @@ pub fn call_contract(
         call_type: CallType::Call,
         initial_gas: *remaining_gas,
     };
-    let retdata_segment = execute_inner_call(entry_point, vm, syscall_handler, remaining_gas)
-        .map_err(|error| {
+    let retdata_segment =
+        execute_inner_call(entry_point, vm, syscall_handler).map_err(|error| {
             error.as_call_contract_execution_error(class_hash, storage_address, selector)
         })?;
+    charge_inner_call_gas(syscall_handler, remaining_gas)?;
 
     Ok(CallContractResponse { segment: retdata_segment })
 }
@@ -236,15 +237,17 @@ pub fn deploy(
     remaining_gas: &mut u64,
 ) -> SyscallResult<DeployResponse> {
     let deployer_address = syscall_handler.storage_address();
-    let deployer_address_for_calculation = match request.deploy_from_zero {
-        true => ContractAddress::default(),
-        false => deployer_address,
-    };
-    let deployed_contract_address = calculate_contract_address(
+    if request.deploy_from_zero
+        && !syscall_handler.context.tx_context.block_context.chain_info.allow_deploy_from_zero
+    {
+        return Err(SyscallExecutionError::ForbiddenDeployFromZero);
+    }
+    let deployed_contract_address = calculate_deployed_contract_address(
         request.contract_address_salt,
         request.class_hash,
         &request.constructor_calldata,
-        deployer_address_for_calculation,
+        deployer_address,
+        request.deploy_from_zero,
     )?;
 
     let ctor_context = ConstructorContext {
@@ -264,9 +267,8 @@ pub fn deploy(
 
     let constructor_retdata =
         create_retdata_segment(vm, syscall_handler, &call_info.execution.retdata.0)?;
-    update_remaining_gas(remaining_gas, &call_info);
-
     syscall_handler.inner_calls.push(call_info);
+    charge_inner_call_gas(syscall_handler, remaining_gas)?;
 
     Ok(DeployResponse { contract_address: deployed_contract_address, constructor_retdata })
 }
@@ -291,13 +293,24 @@ impl SyscallRequest for EmitEventRequest {
 
 type EmitEventResponse = EmptyResponse;
 
+/// The size of the keys and data of the given event, in bytes.
+pub fn event_payload_size(event: &EventContent) -> usize {
+    const FELT_SIZE_IN_BYTES: usize = 32;
+    (event.keys.len() + event.data.0.len()) * FELT_SIZE_IN_BYTES
+}
+
 pub fn exceeds_event_size_limit(
-    versioned_constants: &VersionedConstants,
+    event_limits: EventLimits,
     n_emitted_events: usize,
+    total_payload_size: usize,
     event: &EventContent,
 ) -> Result<(), EmitEventError> {
-    let EventLimits { max_data_length, max_keys_length, max_n_emitted_events } =
-        versioned_constants.tx_event_limits;
+    let EventLimits {
+        max_data_length,
+        max_keys_length,
+        max_n_emitted_events,
+        max_total_payload_size,
+    } = event_limits;
     if n_emitted_events > max_n_emitted_events {
         return Err(EmitEventError::ExceedsMaxNumberOfEmittedEvents {
             n_emitted_events,
@@ -312,6 +325,12 @@ pub fn exceeds_event_size_limit(
     if data_length > max_data_length {
         return Err(EmitEventError::ExceedsMaxDataLength { data_length, max_data_length });
     }
+    if total_payload_size > max_total_payload_size {
+        return Err(EmitEventError::ExceedsMaxTotalPayloadSize {
+            total_payload_size,
+            max_total_payload_size,
+        });
+    }
 
     Ok(())
 }
@@ -322,16 +341,28 @@ pub fn emit_event(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<EmitEventResponse> {
+    let from_address = syscall_handler.storage_address();
+    let caller_address = syscall_handler.call.caller_address;
     let execution_context = &mut syscall_handler.context;
+    let total_payload_size =
+        execution_context.emitted_events_payload_size + event_payload_size(&request.content);
     exceeds_event_size_limit(
-        execution_context.versioned_constants(),
+        execution_context.tx_context.block_context.event_limits(),
         execution_context.n_emitted_events + 1,
+        total_payload_size,
         &request.content,
     )?;
     let ordered_event =
         OrderedEvent { order: execution_context.n_emitted_events, event: request.content };
+    let emitted_event = EmittedEvent { from_address, event: ordered_event.clone() };
+    let tx_context = &execution_context.tx_context;
+    if let Some(event_sink) = &tx_context.block_context.hooks.event_sink {
+        event_sink.on_event(&tx_context.tx_info, caller_address, &emitted_event);
+    }
+    execution_context.emitted_effects.events.push(emitted_event);
     syscall_handler.events.push(ordered_event);
     execution_context.n_emitted_events += 1;
+    execution_context.emitted_events_payload_size = total_payload_size;
 
     Ok(EmitEventResponse {})
 }
@@ -379,13 +410,6 @@ pub fn get_block_hash(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<GetBlockHashResponse> {
-    if syscall_handler.is_validate_mode() {
-        return Err(SyscallExecutionError::InvalidSyscallInExecutionMode {
-            syscall_name: "get_block_hash".to_string(),
-            execution_mode: syscall_handler.execution_mode(),
-        });
-    }
-
     let requested_block_number = request.block_number.0;
     let current_block_number =
         syscall_handler.context.tx_context.block_context.block_info.block_number.0;
@@ -406,6 +430,44 @@ pub fn get_block_hash(
     Ok(GetBlockHashResponse { block_hash })
 }
 
+// GetClassHashAt syscall.
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct GetClassHashAtRequest {
+    pub contract_address: ContractAddress,
+}
+
+impl SyscallRequest for GetClassHashAtRequest {
+    fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<GetClassHashAtRequest> {
+        let contract_address = ContractAddress::try_from(stark_felt_from_ptr(vm, ptr)?)?;
+        Ok(GetClassHashAtRequest { contract_address })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct GetClassHashAtResponse {
+    pub class_hash: ClassHash,
+}
+
+impl SyscallResponse for GetClassHashAtResponse {
+    fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
+        write_stark_felt(vm, ptr, self.class_hash.0)?;
+        Ok(())
+    }
+}
+
+/// Returns the class hash of the contract deployed at the given address; zero if no contract is
+/// deployed there.
+pub fn get_class_hash_at(
+    request: GetClassHashAtRequest,
+    _vm: &mut VirtualMachine,
+    syscall_handler: &mut SyscallHintProcessor<'_>,
+    _remaining_gas: &mut u64,
+) -> SyscallResult<GetClassHashAtResponse> {
+    let class_hash = syscall_handler.state.get_class_hash_at(request.contract_address)?;
+    Ok(GetClassHashAtResponse { class_hash })
+}
+
 // GetExecutionInfo syscall.
 
 type GetExecutionInfoRequest = EmptyRequest;
@@ -559,11 +621,16 @@ pub fn send_message_to_l1(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<SendMessageToL1Response> {
+    let from_address = syscall_handler.storage_address();
     let execution_context = &mut syscall_handler.context;
     let ordered_message_to_l1 = OrderedL2ToL1Message {
         order: execution_context.n_sent_messages_to_l1,
         message: request.message,
     };
+    execution_context
+        .emitted_effects
+        .l2_to_l1_messages
+        .push(SentL2ToL1Message { from_address, message: ordered_message_to_l1.clone() });
     syscall_handler.l2_to_l1_messages.push(ordered_message_to_l1);
     execution_context.n_sent_messages_to_l1 += 1;
 
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::fmt;
+
 use ark_ec::short_weierstrass;
 use ark_ec::short_weierstrass::SWCurveConfig;
 use ark_ff::{BigInteger, PrimeField};
@@ -20,11 +23,41 @@ use crate::execution::syscalls::{
     WriteResponseResult,
 };
 
+#[cfg(test)]
+#[path = "secp_test.rs"]
+mod test;
+
 #[derive(Debug, Default, Eq, PartialEq)]
 pub struct SecpHintProcessor<Curve: SWCurveConfig> {
     points: Vec<short_weierstrass::Affine<Curve>>,
 }
 
+/// The points constructed from coordinates by the secp syscalls of a curve (or [None], for
+/// invalid coordinates), shared by the calls of an execution. Signature-heavy accounts construct
+/// the same points repeatedly, and validating them (and, given only the x coordinate, computing
+/// the y coordinate) is costly. Point IDs remain allocated per call.
+#[derive(Default)]
+pub struct SecpPointCache<Curve: SWCurveConfig> {
+    points_by_coordinates: HashMap<(BigUint, BigUint), Option<short_weierstrass::Affine<Curve>>>,
+    points_by_x: HashMap<(BigUint, bool), Option<short_weierstrass::Affine<Curve>>>,
+}
+
+// The curve configurations do not implement `Debug`; only the cache sizes are shown.
+impl<Curve: SWCurveConfig> fmt::Debug for SecpPointCache<Curve> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecpPointCache")
+            .field("n_points_by_coordinates", &self.points_by_coordinates.len())
+            .field("n_points_by_x", &self.points_by_x.len())
+            .finish()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SecpPointCaches {
+    pub secp256k1: SecpPointCache<ark_secp256k1::Config>,
+    pub secp256r1: SecpPointCache<ark_secp256r1::Config>,
+}
+
 impl<Curve: SWCurveConfig> SecpHintProcessor<Curve>
 where
     Curve::BaseField: PrimeField,
@@ -47,6 +80,7 @@ where
     pub fn secp_get_point_from_x(
         &mut self,
         request: SecpGetPointFromXRequest,
+        cache: &mut SecpPointCache<Curve>,
     ) -> SyscallResult<SecpGetPointFromXResponse> {
         let modulos = Curve::BaseField::MODULUS.into();
 
@@ -58,14 +92,10 @@ where
             });
         }
 
-        let x = request.x.into();
-        let maybe_ec_point = short_weierstrass::Affine::<Curve>::get_ys_from_x_unchecked(x)
-            .map(|(smaller, greater)| {
-                // Return the correct y coordinate based on the parity.
-                if smaller.into_bigint().is_odd() == request.y_parity { smaller } else { greater }
-            })
-            .map(|y| short_weierstrass::Affine::<Curve>::new_unchecked(x, y))
-            .filter(|p| p.is_in_correct_subgroup_assuming_on_curve());
+        let maybe_ec_point = *cache
+            .points_by_x
+            .entry((request.x, request.y_parity))
+            .or_insert_with_key(|(x, y_parity)| point_from_x(x.clone(), *y_parity));
 
         Ok(SecpGetPointFromXResponse {
             optional_ec_point_id: maybe_ec_point.map(|ec_point| self.allocate_point(ec_point)),
@@ -78,7 +108,11 @@ where
         Ok(SecpGetXyResponse { x: ec_point.x.into(), y: ec_point.y.into() })
     }
 
-    pub fn secp_new(&mut self, request: SecpNewRequest) -> SyscallResult<SecpNewResponse> {
+    pub fn secp_new(
+        &mut self,
+        request: SecpNewRequest,
+        cache: &mut SecpPointCache<Curve>,
+    ) -> SyscallResult<SecpNewResponse> {
         let modulos = Curve::BaseField::MODULUS.into();
         let (x, y) = (request.x, request.y);
         if x >= modulos || y >= modulos {
@@ -88,18 +122,14 @@ where
                 ],
             });
         }
-        let ec_point = if x.is_zero() && y.is_zero() {
-            short_weierstrass::Affine::<Curve>::identity()
-        } else {
-            short_weierstrass::Affine::<Curve>::new_unchecked(x.into(), y.into())
-        };
-        let optional_ec_point_id =
-            if ec_point.is_on_curve() && ec_point.is_in_correct_subgroup_assuming_on_curve() {
-                Some(self.allocate_point(ec_point))
-            } else {
-                None
-            };
-        Ok(SecpNewResponse { optional_ec_point_id })
+        let maybe_ec_point = *cache
+            .points_by_coordinates
+            .entry((x, y))
+            .or_insert_with_key(|(x, y)| new_point(x.clone(), y.clone()));
+
+        Ok(SecpNewResponse {
+            optional_ec_point_id: maybe_ec_point.map(|ec_point| self.allocate_point(ec_point)),
+        })
     }
 
     fn allocate_point(&mut self, ec_point: short_weierstrass::Affine<Curve>) -> usize {
@@ -122,6 +152,45 @@ where
     }
 }
 
+/// Returns the point of the subgroup with the given x coordinate and y parity, if it exists.
+fn point_from_x<Curve: SWCurveConfig>(
+    x: BigUint,
+    y_parity: bool,
+) -> Option<short_weierstrass::Affine<Curve>>
+where
+    Curve::BaseField: PrimeField,
+{
+    let x = x.into();
+    short_weierstrass::Affine::<Curve>::get_ys_from_x_unchecked(x)
+        .map(|(smaller, greater)| {
+            // Return the correct y coordinate based on the parity.
+            if smaller.into_bigint().is_odd() == y_parity {
+                smaller
+            } else {
+                greater
+            }
+        })
+        .map(|y| short_weierstrass::Affine::<Curve>::new_unchecked(x, y))
+        .filter(|p| p.is_in_correct_subgroup_assuming_on_curve())
+}
+
+/// Returns the point of the subgroup with the given coordinates, if it is one; (0, 0) stands for
+/// the point at infinity.
+fn new_point<Curve: SWCurveConfig>(
+    x: BigUint,
+    y: BigUint,
+) -> Option<short_weierstrass::Affine<Curve>>
+where
+    Curve::BaseField: PrimeField,
+{
+    let ec_point = if x.is_zero() && y.is_zero() {
+        short_weierstrass::Affine::<Curve>::identity()
+    } else {
+        short_weierstrass::Affine::<Curve>::new_unchecked(x.into(), y.into())
+    };
+    Some(ec_point).filter(|p| p.is_on_curve() && p.is_in_correct_subgroup_assuming_on_curve())
+}
+
 // The x and y coordinates of an elliptic curve point.
 #[derive(Debug, Eq, PartialEq)]
 pub struct EcPointCoordinates {
@@ -229,7 +298,9 @@ pub fn secp256k1_get_point_from_x(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<SecpGetPointFromXResponse> {
-    syscall_handler.secp256k1_hint_processor.secp_get_point_from_x(request)
+    syscall_handler
+        .secp256k1_hint_processor
+        .secp_get_point_from_x(request, &mut syscall_handler.context.secp_point_caches.secp256k1)
 }
 
 pub fn secp256r1_get_point_from_x(
@@ -238,7 +309,9 @@ pub fn secp256r1_get_point_from_x(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<SecpGetPointFromXResponse> {
-    syscall_handler.secp256r1_hint_processor.secp_get_point_from_x(request)
+    syscall_handler
+        .secp256r1_hint_processor
+        .secp_get_point_from_x(request, &mut syscall_handler.context.secp_point_caches.secp256r1)
 }
 
 // SecpGetXy syscall.
@@ -338,7 +411,9 @@ pub fn secp256k1_new(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<SecpNewResponse> {
-    syscall_handler.secp256k1_hint_processor.secp_new(request)
+    syscall_handler
+        .secp256k1_hint_processor
+        .secp_new(request, &mut syscall_handler.context.secp_point_caches.secp256k1)
 }
 
 type Secp256r1NewRequest = EcPointCoordinates;
@@ -350,5 +425,7 @@ pub fn secp256r1_new(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<Secp256r1NewResponse> {
-    syscall_handler.secp256r1_hint_processor.secp_new(request)
+    syscall_handler
+        .secp256r1_hint_processor
+        .secp_new(request, &mut syscall_handler.context.secp_point_caches.secp256r1)
 }
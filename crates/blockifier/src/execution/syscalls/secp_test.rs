@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use ark_ec::short_weierstrass::SWCurveConfig;
+use num_bigint::BigUint;
+use pretty_assertions::assert_eq;
+
+use crate::execution::syscalls::secp::{
+    EcPointCoordinates, SecpGetPointFromXRequest, SecpHintProcessor, SecpPointCache,
+};
+
+#[test]
+fn test_secp_point_cache() {
+    let generator = ark_secp256k1::Config::GENERATOR;
+    let (x, y): (BigUint, BigUint) = (generator.x.into(), generator.y.into());
+    let mut cache = SecpPointCache::<ark_secp256k1::Config>::default();
+
+    // Each call allocates its own point IDs, while the points are shared through the cache.
+    for _ in 0..2 {
+        let mut hint_processor = SecpHintProcessor::<ark_secp256k1::Config>::default();
+        let request = EcPointCoordinates { x: x.clone(), y: y.clone() };
+        let response = hint_processor.secp_new(request, &mut cache).unwrap();
+        assert_eq!(response.optional_ec_point_id, Some(0));
+
+        let request = SecpGetPointFromXRequest { x: x.clone(), y_parity: y.bit(0) };
+        let response = hint_processor.secp_get_point_from_x(request, &mut cache).unwrap();
+        assert_eq!(response.optional_ec_point_id, Some(1));
+        assert_eq!(hint_processor.points, vec![generator, generator]);
+    }
+    assert_eq!(
+        cache.points_by_coordinates,
+        HashMap::from([((x.clone(), y.clone()), Some(generator))])
+    );
+    assert_eq!(cache.points_by_x, HashMap::from([((x.clone(), y.bit(0)), Some(generator))]));
+
+    // Coordinates of points off the curve are cached as well.
+    let mut hint_processor = SecpHintProcessor::<ark_secp256k1::Config>::default();
+    let request = EcPointCoordinates { x: x.clone(), y: &y + 1_u8 };
+    let response = hint_processor.secp_new(request, &mut cache).unwrap();
+    assert_eq!(response.optional_ec_point_id, None);
+    assert_eq!(cache.points_by_coordinates.get(&(x, y + 1_u8)), Some(&None));
+}
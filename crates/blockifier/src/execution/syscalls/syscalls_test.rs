@@ -1,15 +1,19 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
 
 use assert_matches::assert_matches;
 use cairo_felt::Felt252;
+use cairo_lang_casm::hints::StarknetHint;
+use cairo_lang_casm::operand::{CellRef, Register, ResOperand};
 use cairo_lang_utils::byte_array::BYTE_ARRAY_MAGIC;
 use cairo_vm::vm::runners::builtin_runner::RANGE_CHECK_BUILTIN_NAME;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use cairo_vm::vm::vm_core::VirtualMachine;
 use num_traits::Pow;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
 use starknet_api::core::{
-    calculate_contract_address, ChainId, ContractAddress, EthAddress, PatriciaKey,
+    calculate_contract_address, ChainId, ClassHash, ContractAddress, EthAddress, PatriciaKey,
 };
 use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::hash::{StarkFelt, StarkHash};
@@ -24,18 +28,25 @@ use test_case::test_case;
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants;
-use crate::context::ChainInfo;
+use crate::context::{BlockContext, ChainInfo, GasMeteringMode, TransactionContext};
 use crate::execution::call_info::{
     CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
 };
 use crate::execution::common_hints::ExecutionMode;
-use crate::execution::entry_point::{CallEntryPoint, CallType};
-use crate::execution::errors::EntryPointExecutionError;
-use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+use crate::execution::entry_point::{
+    CallEntryPoint, CallType, EntryPointExecutionContext, ExecutionInfoOverrides,
+};
+use crate::execution::errors::{EntryPointExecutionError, ExhaustedResource};
+use crate::execution::execution_utils::{
+    calculate_deployed_contract_address, felt_to_stark_felt, stark_felt_from_ptr,
+    stark_felt_to_felt, write_felt, ReadOnlySegments,
+};
 use crate::execution::syscalls::hint_processor::{
-    EmitEventError, BLOCK_NUMBER_OUT_OF_RANGE_ERROR, L1_GAS, L2_GAS, OUT_OF_GAS_ERROR,
+    EmitEventError, SyscallExecutionError, SyscallHintProcessor, BLOCK_NUMBER_OUT_OF_RANGE_ERROR,
+    L1_GAS, L2_GAS, OUT_OF_GAS_ERROR,
 };
 use crate::execution::syscalls::SyscallSelector;
+use crate::fee::fee_utils::calculate_sierra_gas_by_vm_usage;
 use crate::state::state_api::{State, StateReader};
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
@@ -84,6 +95,85 @@ fn test_storage_read_write() {
     assert_eq!(value_from_state, value);
 }
 
+#[test]
+fn test_syscall_gas_cost_overrides() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+
+    let mut block_context = BlockContext::create_for_testing();
+    let storage_read_gas_cost =
+        block_context.versioned_constants().os_constants.gas_costs.storage_read_gas_cost;
+    let additional_gas = 100;
+    block_context
+        .set_gas_cost_overrides(&HashMap::from([(
+            "storage_read_gas_cost".to_string(),
+            storage_read_gas_cost + additional_gas,
+        )]))
+        .unwrap();
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+
+    // The test reads the storage once.
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let call_info = entry_point_call
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap();
+    assert_eq!(
+        call_info.execution.gas_consumed,
+        REQUIRED_GAS_STORAGE_READ_WRITE_TEST + additional_gas
+    );
+}
+
+#[rstest]
+fn test_syscall_gas_audit(#[values(false, true)] syscall_gas_audit: bool) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.execution_policy_mut().syscall_gas_audit = syscall_gas_audit;
+    let gas_costs = block_context.versioned_constants().os_constants.gas_costs.clone();
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+
+    // The test writes to the storage, then reads it.
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let call_info = entry_point_call
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap();
+
+    if !syscall_gas_audit {
+        assert!(call_info.syscall_gas_records.is_empty());
+        return;
+    }
+    let records = &call_info.syscall_gas_records;
+    assert_eq!(
+        records.iter().map(|record| (record.selector, record.gas_charged())).collect::<Vec<_>>(),
+        vec![
+            (SyscallSelector::StorageWrite, gas_costs.storage_write_gas_cost),
+            (SyscallSelector::StorageRead, gas_costs.storage_read_gas_cost),
+        ]
+    );
+    // The base costs of both syscalls are withdrawn ahead of the first one; hence, only the gas
+    // counter of the second request, exclusive of its base cost, follows the first syscall.
+    assert!(records[0].gas_after >= records[1].gas_before - gas_costs.syscall_base_gas_cost);
+}
+
 #[test]
 fn test_call_contract() {
     let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
@@ -114,6 +204,79 @@ fn test_call_contract() {
     );
 }
 
+#[test]
+fn test_call_contract_gas_charge() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let cairo0_test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let chain_info = &ChainInfo::create_for_testing();
+    let versioned_constants = VersionedConstants::create_for_testing();
+
+    let run_with_metering_mode = |gas_metering_mode: GasMeteringMode, initial_gas: u64| {
+        let mut state =
+            test_state(chain_info, BALANCE, &[(test_contract, 1), (cairo0_test_contract, 1)]);
+        let mut block_context = BlockContext::create_for_testing();
+        block_context.fee_policy_mut().gas_metering_mode = gas_metering_mode;
+        let tx_context = TransactionContext {
+            block_context,
+            tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+        };
+        let mut context =
+            EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+
+        let calldata = create_calldata(
+            cairo0_test_contract.get_instance_address(0),
+            "test_storage_read_write",
+            &[
+                stark_felt!(405_u16), // Calldata: address.
+                stark_felt!(48_u8),   // Calldata: value.
+            ],
+        );
+        let entry_point_call = CallEntryPoint {
+            entry_point_selector: selector_from_name("test_call_contract"),
+            calldata,
+            initial_gas,
+            ..trivial_external_entry_point_new(test_contract)
+        };
+        entry_point_call.execute(&mut state, &mut ExecutionResources::default(), &mut context)
+    };
+    let initial_gas = trivial_external_entry_point_new(test_contract).initial_gas;
+
+    // The Cairo 0 callee does not consume the gas forwarded to it.
+    let call_info = run_with_metering_mode(GasMeteringMode::VmResources, initial_gas).unwrap();
+    assert_eq!(call_info.inner_calls[0].execution.gas_consumed, 0);
+    assert_eq!(call_info.inner_calls[0].caller_gas_charge, None);
+
+    // In Sierra gas metering mode, the caller is charged for the VM resources of the callee; the
+    // charge is recorded apart from the gas the callee consumed.
+    let sierra_gas_call_info =
+        run_with_metering_mode(GasMeteringMode::SierraGas, initial_gas).unwrap();
+    let inner_call = &sierra_gas_call_info.inner_calls[0];
+    let inner_call_charge =
+        calculate_sierra_gas_by_vm_usage(&versioned_constants, &inner_call.exclusive_resources());
+    assert!(inner_call_charge > 0);
+    assert_eq!(inner_call.execution.gas_consumed, 0);
+    assert_eq!(inner_call.caller_gas_charge, Some(inner_call_charge));
+    assert_eq!(inner_call.charged_gas(), inner_call_charge);
+    assert_eq!(
+        sierra_gas_call_info.execution.gas_consumed,
+        call_info.execution.gas_consumed + inner_call_charge
+    );
+    // The gas of the callee is not charged for twice.
+    assert_eq!(
+        sierra_gas_call_info.sierra_gas_consumed(&versioned_constants),
+        sierra_gas_call_info.execution.gas_consumed
+    );
+
+    // A callee whose charge exceeds the gas forwarded to it fails with an out-of-gas error, rather
+    // than being charged the forwarded gas only.
+    let forwarded_gas = inner_call.call.initial_gas;
+    let insufficient_initial_gas = initial_gas - (forwarded_gas - inner_call_charge) - 1;
+    let error =
+        run_with_metering_mode(GasMeteringMode::SierraGas, insufficient_initial_gas).unwrap_err();
+    assert_matches!(error, EntryPointExecutionError::ExecutionFailed { error_data }
+        if error_data == vec![stark_felt!(OUT_OF_GAS_ERROR)]);
+}
+
 #[test]
 fn test_emit_event() {
     let versioned_constants = VersionedConstants::create_for_testing();
@@ -227,12 +390,28 @@ fn test_get_block_hash() {
     );
 
     // Negative flow. Execution mode is Validate.
-    let error = entry_point_call.execute_directly_in_validate_mode(&mut state).unwrap_err();
+    let error = entry_point_call.clone().execute_directly_in_validate_mode(&mut state).unwrap_err();
     check_entry_point_execution_error_for_custom_hint!(
         &error,
         "Unauthorized syscall get_block_hash in execution mode Validate.",
     );
 
+    // Positive flow in validate mode, when the syscall is not forbidden.
+    let mut versioned_constants = VersionedConstants::create_for_testing();
+    versioned_constants.validate_forbidden_syscalls = HashSet::new();
+    assert_eq!(
+        entry_point_call
+            .clone()
+            .execute_directly_in_validate_mode_given_versioned_constants(
+                &mut state,
+                versioned_constants
+            )
+            .unwrap()
+            .execution
+            .retdata,
+        retdata![block_hash]
+    );
+
     // Negative flow: Block number out of range.
     let requested_block_number = CURRENT_BLOCK_NUMBER - constants::STORED_BLOCK_HASH_BUFFER + 1;
     let block_number = stark_felt!(requested_block_number);
@@ -247,6 +426,92 @@ fn test_get_block_hash() {
         if error_data == vec![stark_felt!(BLOCK_NUMBER_OUT_OF_RANGE_ERROR)]);
 }
 
+/// Invokes `get_class_hash_at` through the syscall dispatch, as a contract does: the request is
+/// written to the syscall segment, whose pointer is passed to the `SystemCall` hint.
+#[rstest]
+fn test_get_class_hash_at(#[values(true, false)] supported: bool) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let mut block_context = BlockContext::create_for_testing();
+    if !supported {
+        // The syscall is not priced by the constants of the versions preceding it.
+        block_context.versioned_constants = serde_json::from_str(include_str!(
+            "../../../resources/versioned_constants_13_1_1.json"
+        ))
+        .unwrap();
+    }
+    let syscall_gas_cost = block_context.versioned_constants.os_constants.gas_costs.clone();
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+    let mut resources = ExecutionResources::default();
+    let hints = HashMap::new();
+    let mut vm = VirtualMachine::new(false);
+    // The program and execution segments, followed by the syscall segment.
+    vm.add_memory_segment();
+    vm.add_memory_segment();
+    let syscall_ptr = vm.add_memory_segment();
+    let mut syscall_handler = SyscallHintProcessor::new(
+        &mut state,
+        &mut resources,
+        &mut context,
+        syscall_ptr,
+        trivial_external_entry_point_new(test_contract),
+        &hints,
+        ReadOnlySegments::default(),
+    );
+
+    // The class hash of an undeployed contract is zero.
+    let gas_counter = 1_000_000_u64;
+    for (ap_offset, (contract_address, class_hash)) in [
+        (test_contract.get_instance_address(0), test_contract.get_class_hash()),
+        (test_contract.get_instance_address(1), ClassHash::default()),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut request_ptr = syscall_handler.syscall_ptr;
+        vm.insert_value((vm.get_ap() + ap_offset).unwrap(), request_ptr).unwrap();
+        for felt in [
+            Felt252::from_bytes_be(b"GetClassHashAt"),
+            Felt252::from(gas_counter),
+            stark_felt_to_felt(*contract_address.0.key()),
+        ] {
+            write_felt(&mut vm, &mut request_ptr, felt).unwrap();
+        }
+        let hint = StarknetHint::SystemCall {
+            system: ResOperand::Deref(CellRef {
+                register: Register::AP,
+                offset: i16::try_from(ap_offset).unwrap(),
+            }),
+        };
+        let result = syscall_handler.execute_next_syscall(&mut vm, &hint);
+
+        let Some(get_class_hash_at_gas_cost) = syscall_gas_cost.get_class_hash_at_gas_cost else {
+            assert!(result.unwrap_err().to_string().contains(
+                &SyscallExecutionError::UnsupportedSyscall {
+                    selector: SyscallSelector::GetClassHashAt
+                }
+                .to_string()
+            ));
+            return;
+        };
+        result.unwrap();
+        // The response follows the request: the remaining gas, the failure flag, and the class
+        // hash.
+        let mut response_ptr = request_ptr;
+        let response: Vec<StarkFelt> =
+            (0..3).map(|_| stark_felt_from_ptr(&vm, &mut response_ptr).unwrap()).collect();
+        let remaining_gas =
+            gas_counter - (get_class_hash_at_gas_cost - syscall_gas_cost.syscall_base_gas_cost);
+        assert_eq!(response, vec![StarkFelt::from(remaining_gas), StarkFelt::ZERO, class_hash.0]);
+        assert_eq!(syscall_handler.syscall_ptr, response_ptr);
+    }
+    assert_eq!(syscall_handler.syscall_counter[&SyscallSelector::GetClassHashAt], 2);
+}
+
 #[test]
 fn test_keccak() {
     let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
@@ -495,6 +760,78 @@ fn test_get_execution_info(
     assert!(!result.unwrap().execution.failed);
 }
 
+#[test_case(TransactionVersion::ONE, TransactionVersion::THREE; "V1 transaction seen as V3.")]
+#[test_case(TransactionVersion::THREE, TransactionVersion::ONE; "V3 transaction seen as V1.")]
+fn test_get_execution_info_with_overridden_version(
+    version: TransactionVersion,
+    overridden_version: TransactionVersion,
+) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let test_contract_address = test_contract.get_instance_address(0);
+    let common_fields = CommonAccountFields {
+        version,
+        sender_address: test_contract_address,
+        ..Default::default()
+    };
+    let tx_info = if version == TransactionVersion::ONE {
+        TransactionInfo::Deprecated(DeprecatedTransactionInfo { common_fields, max_fee: Fee(42) })
+    } else {
+        TransactionInfo::Current(CurrentTransactionInfo {
+            common_fields,
+            resource_bounds: ResourceBoundsMapping(BTreeMap::from([(
+                Resource::L1Gas,
+                ResourceBounds { max_amount: 13, max_price_per_unit: 61 },
+            )])),
+            tip: Tip::default(),
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+            paymaster_data: PaymasterData::default(),
+            account_deployment_data: AccountDeploymentData::default(),
+        })
+    };
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.execution_policy_mut().execution_info_overrides =
+        ExecutionInfoOverrides { tx_version: Some(overridden_version), ..Default::default() };
+    let tx_context = TransactionContext { block_context, tx_info };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), false).unwrap();
+
+    // Either way, the transaction info has neither a max fee nor resource bounds; the layout
+    // follows the overridden version, not the one of the transaction.
+    let entry_point_selector = selector_from_name("test_get_execution_info");
+    let expected_execution_info = vec![
+        stark_felt!(CURRENT_BLOCK_NUMBER),    // Block number.
+        stark_felt!(CURRENT_BLOCK_TIMESTAMP), // Block timestamp.
+        StarkFelt::try_from(TEST_SEQUENCER_ADDRESS).unwrap(), // Sequencer address.
+        overridden_version.0,                 // Transaction version.
+        *test_contract_address.0.key(),       // Account address.
+        StarkFelt::ZERO,                      // Max fee.
+        StarkFelt::ZERO,                      // Signature.
+        StarkFelt::ZERO,                      // Transaction hash.
+        stark_felt!(&*ChainId(CHAIN_ID_NAME.to_string()).as_hex()), // Chain ID.
+        StarkFelt::ZERO,                      // Nonce.
+        StarkFelt::ZERO,                      // Length of resource bounds.
+        StarkFelt::ZERO,                      // Tip.
+        StarkFelt::ZERO,                      // Paymaster data.
+        StarkFelt::ZERO,                      // Nonce DA.
+        StarkFelt::ZERO,                      // Fee DA.
+        StarkFelt::ZERO,                      // Account data.
+        StarkFelt::ZERO,                      // Caller address.
+        *test_contract_address.0.key(),       // Storage address.
+        stark_felt!(entry_point_selector.0),  // Entry point selector.
+    ];
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector,
+        calldata: Calldata(expected_execution_info.into()),
+        ..trivial_external_entry_point_with_address(test_contract_address)
+    };
+
+    let call_info = entry_point_call
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap();
+    assert!(!call_info.execution.failed);
+}
+
 #[test]
 fn test_library_call() {
     let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
@@ -622,6 +959,12 @@ fn test_nested_library_call() {
         resources: storage_entry_point_resources.clone(),
         storage_read_values: vec![stark_felt!(value + 1)],
         accessed_storage_keys: HashSet::from([storage_key!(key + 1)]),
+        syscall_counter: HashMap::from([
+            (SyscallSelector::StorageWrite, 1),
+            (SyscallSelector::StorageRead, 1),
+        ]),
+        call_index: 2,
+        parent_call_index: Some(1),
         ..Default::default()
     };
     let library_call_resources = &get_syscall_resources(SyscallSelector::LibraryCall)
@@ -639,6 +982,9 @@ fn test_nested_library_call() {
         },
         resources: library_call_resources,
         inner_calls: vec![nested_storage_call_info],
+        syscall_counter: HashMap::from([(SyscallSelector::LibraryCall, 1)]),
+        call_index: 1,
+        parent_call_index: Some(0),
         ..Default::default()
     };
     let storage_call_info = CallInfo {
@@ -651,6 +997,12 @@ fn test_nested_library_call() {
         resources: storage_entry_point_resources,
         storage_read_values: vec![stark_felt!(value)],
         accessed_storage_keys: HashSet::from([storage_key!(key)]),
+        syscall_counter: HashMap::from([
+            (SyscallSelector::StorageWrite, 1),
+            (SyscallSelector::StorageRead, 1),
+        ]),
+        call_index: 3,
+        parent_call_index: Some(0),
         ..Default::default()
     };
 
@@ -668,7 +1020,9 @@ fn test_nested_library_call() {
             ..CallExecution::default()
         },
         resources: main_call_resources,
+        vm_memory_holes: 2,
         inner_calls: vec![library_call_info, storage_call_info],
+        syscall_counter: HashMap::from([(SyscallSelector::LibraryCall, 2)]),
         ..Default::default()
     };
 
@@ -892,6 +1246,67 @@ fn test_deploy(
     assert_eq!(state.get_class_hash_at(contract_address).unwrap(), class_hash);
 }
 
+#[rstest]
+fn test_deploy_from_zero(#[values(false, true)] allow_deploy_from_zero: bool) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let empty_contract = FeatureContract::Empty(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, 0, &[(empty_contract, 0), (test_contract, 1)]);
+
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.chain_info.allow_deploy_from_zero = allow_deploy_from_zero;
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+
+    let class_hash = empty_contract.get_class_hash();
+    let calldata = calldata![
+        class_hash.0,
+        ContractAddressSalt::default().0,
+        stark_felt!(0_u8), // Constructor calldata length.
+        stark_felt!(1_u8)  // Deploy from zero.
+    ];
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_deploy"),
+        calldata,
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let result =
+        entry_point_call.execute(&mut state, &mut ExecutionResources::default(), &mut context);
+
+    if !allow_deploy_from_zero {
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Deploying from zero is not allowed on this chain."));
+        return;
+    }
+
+    // The deployed address does not depend on the deployer.
+    let contract_address = calculate_deployed_contract_address(
+        ContractAddressSalt::default(),
+        class_hash,
+        &calldata![],
+        test_contract.get_instance_address(0),
+        true,
+    )
+    .unwrap();
+    assert_eq!(
+        contract_address,
+        calculate_contract_address(
+            ContractAddressSalt::default(),
+            class_hash,
+            &calldata![],
+            ContractAddress::default()
+        )
+        .unwrap()
+    );
+    assert_eq!(result.unwrap().inner_calls[0].call.storage_address, contract_address);
+    assert_eq!(state.get_class_hash_at(contract_address).unwrap(), class_hash);
+}
+
 #[test]
 fn test_out_of_gas() {
     let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
@@ -908,8 +1323,22 @@ fn test_out_of_gas() {
         ..trivial_external_entry_point_new(test_contract)
     };
     let error = entry_point_call.execute_directly(&mut state).unwrap_err();
-    assert_matches!(error, EntryPointExecutionError::ExecutionFailed{ error_data }
+    let EntryPointExecutionError::OutOfResources { error, exhausted_resource } = error else {
+        panic!("Expected an out of resources error, got: {error:?}.");
+    };
+    assert_matches!(*error, EntryPointExecutionError::ExecutionFailed{ error_data }
         if error_data == vec![stark_felt!(OUT_OF_GAS_ERROR)]);
+
+    // The storage read, which is the last syscall, misses a single unit of gas.
+    let gas_costs = &VersionedConstants::create_for_testing().os_constants.gas_costs;
+    assert_eq!(
+        exhausted_resource,
+        ExhaustedResource::Gas {
+            selector: SyscallSelector::StorageRead,
+            remaining_gas: gas_costs.storage_read_gas_cost - gas_costs.syscall_base_gas_cost - 1,
+            required_gas: gas_costs.storage_read_gas_cost - gas_costs.syscall_base_gas_cost,
+        }
+    );
 }
 
 #[test]
@@ -0,0 +1,83 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+use starknet_api::core::ClassHash;
+
+#[cfg(test)]
+#[path = "visited_pcs_test.rs"]
+pub mod test;
+
+/// Receives the PCs visited by each Cairo 1 call instead of the state retaining them; e.g., for
+/// metrics counting or estimating (see [`VisitedPcsSketch`]) the distinct PCs visited in a class.
+pub trait VisitedPcsObserver: std::fmt::Debug + Send + Sync {
+    /// Invoked once per executed call, with the distinct bytecode PCs it visited.
+    fn on_visited_pcs(&self, class_hash: ClassHash, visited_pcs: &HashSet<usize>);
+}
+
+/// The number of hash bits that select the register of a visited PC in a sketch.
+const SKETCH_INDEX_BITS: u32 = 10;
+const SKETCH_N_REGISTERS: usize = 1 << SKETCH_INDEX_BITS;
+
+/// Estimates the number of distinct PCs visited in each class by a HyperLogLog sketch, in memory
+/// bounded by the number of classes rather than by the number of visited PCs: 1KB per class. The
+/// standard error of the estimates is about 3%.
+#[derive(Debug, Default)]
+pub struct VisitedPcsSketch {
+    registers_by_class: Mutex<HashMap<ClassHash, Box<[u8; SKETCH_N_REGISTERS]>>>,
+}
+
+impl VisitedPcsSketch {
+    /// Returns the estimated number of distinct PCs visited in the given class; [None] if none of
+    /// its calls were observed.
+    pub fn estimated_n_visited_pcs(&self, class_hash: ClassHash) -> Option<usize> {
+        let registers_by_class =
+            self.registers_by_class.lock().expect("Failed to lock the visited PCs sketch.");
+        registers_by_class.get(&class_hash).map(|registers| estimate_cardinality(registers))
+    }
+}
+
+impl VisitedPcsObserver for VisitedPcsSketch {
+    fn on_visited_pcs(&self, class_hash: ClassHash, visited_pcs: &HashSet<usize>) {
+        let mut registers_by_class =
+            self.registers_by_class.lock().expect("Failed to lock the visited PCs sketch.");
+        let registers = registers_by_class
+            .entry(class_hash)
+            .or_insert_with(|| Box::new([0; SKETCH_N_REGISTERS]));
+        for pc in visited_pcs {
+            let mut hasher = DefaultHasher::new();
+            pc.hash(&mut hasher);
+            let hash = hasher.finish();
+            let index = usize::try_from(hash >> (u64::BITS - SKETCH_INDEX_BITS))
+                .expect("Failed to convert u64 to usize.");
+            // The position of the first set bit among the rest of the hash bits.
+            let rank =
+                (hash << SKETCH_INDEX_BITS).leading_zeros().min(u64::BITS - SKETCH_INDEX_BITS) + 1;
+            let rank = u8::try_from(rank).expect("Failed to convert u32 to u8.");
+            registers[index] = registers[index].max(rank);
+        }
+    }
+}
+
+/// Returns the HyperLogLog estimate of the number of distinct values added to the given registers.
+fn estimate_cardinality(registers: &[u8; SKETCH_N_REGISTERS]) -> usize {
+    let n_registers = f64::from(u32::try_from(SKETCH_N_REGISTERS).expect("Failed to convert."));
+    let alpha = 0.7213 / (1.0 + 1.079 / n_registers);
+    let harmonic_sum: f64 = registers.iter().map(|&rank| 2_f64.powi(-i32::from(rank))).sum();
+    let raw_estimate = alpha * n_registers * n_registers / harmonic_sum;
+
+    // Small cardinalities are estimated by the number of empty registers (linear counting).
+    let n_empty_registers = registers.iter().filter(|&&rank| rank == 0).count();
+    let estimate = if raw_estimate <= 2.5 * n_registers && n_empty_registers > 0 {
+        let n_empty_registers =
+            f64::from(u32::try_from(n_empty_registers).expect("Failed to convert."));
+        n_registers * (n_registers / n_empty_registers).ln()
+    } else {
+        raw_estimate
+    };
+    // The estimate is non-negative, and far below `usize::MAX`.
+    #[allow(clippy::as_conversions)]
+    let estimate = estimate.round() as usize;
+    estimate
+}
@@ -0,0 +1,89 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use assert_matches::assert_matches;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use starknet_api::core::ClassHash;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::{BlockContext, ChainInfo, VisitedPcsTracking};
+use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
+use crate::execution::visited_pcs::{VisitedPcsObserver, VisitedPcsSketch};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
+
+/// Collects the distinct PCs visited in each class.
+#[derive(Debug, Default)]
+struct CollectingObserver {
+    visited_pcs: Mutex<HashMap<ClassHash, HashSet<usize>>>,
+}
+
+impl VisitedPcsObserver for CollectingObserver {
+    fn on_visited_pcs(&self, class_hash: ClassHash, visited_pcs: &HashSet<usize>) {
+        self.visited_pcs.lock().unwrap().entry(class_hash).or_default().extend(visited_pcs);
+    }
+}
+
+/// Executes a Cairo 1 entry point under the given tracking, selected for the execution over the
+/// default of the block, and returns the PCs visited in its class according to the state.
+fn state_visited_pcs(visited_pcs_tracking: VisitedPcsTracking) -> HashSet<usize> {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.execution_policy_mut().visited_pcs_tracking = VisitedPcsTracking::Disabled;
+    let tx_info = TransactionInfo::Deprecated(DeprecatedTransactionInfo::default());
+    let mut context = EntryPointExecutionContext::create_for_testing(block_context, tx_info);
+    // The execution context is initialized with the default of the block.
+    assert_matches!(context.visited_pcs_tracking, VisitedPcsTracking::Disabled);
+    context.visited_pcs_tracking = visited_pcs_tracking;
+
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("segment_arena_builtin"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    entry_point_call.execute(&mut state, &mut ExecutionResources::default(), &mut context).unwrap();
+
+    state.visited_pcs.remove(&test_contract.get_class_hash()).unwrap_or_default()
+}
+
+#[test]
+fn test_visited_pcs_tracking() {
+    let class_hash = FeatureContract::TestContract(CairoVersion::Cairo1).get_class_hash();
+
+    let full_visited_pcs = state_visited_pcs(VisitedPcsTracking::Full);
+    assert!(!full_visited_pcs.is_empty());
+
+    // The observer receives the same PCs, which the state does not retain.
+    let observer = Arc::new(CollectingObserver::default());
+    assert!(state_visited_pcs(VisitedPcsTracking::Observe(observer.clone())).is_empty());
+    assert_eq!(observer.visited_pcs.lock().unwrap().get(&class_hash), Some(&full_visited_pcs));
+
+    // The sketch estimates the number of PCs closely.
+    let sketch = Arc::new(VisitedPcsSketch::default());
+    assert!(state_visited_pcs(VisitedPcsTracking::Observe(sketch.clone())).is_empty());
+    let estimated_n_visited_pcs = sketch.estimated_n_visited_pcs(class_hash).unwrap();
+    assert!(
+        estimated_n_visited_pcs.abs_diff(full_visited_pcs.len()) * 20 <= full_visited_pcs.len()
+    );
+
+    assert!(state_visited_pcs(VisitedPcsTracking::Disabled).is_empty());
+}
+
+#[test]
+fn test_visited_pcs_sketch() {
+    let class_hash = FeatureContract::TestContract(CairoVersion::Cairo1).get_class_hash();
+    let sketch = VisitedPcsSketch::default();
+    assert_eq!(sketch.estimated_n_visited_pcs(class_hash), None);
+
+    // Overlapping calls, visiting 100000 distinct PCs in all; the estimate is within 10% of it.
+    let n_visited_pcs = 100000;
+    for first_pc in (0..n_visited_pcs).step_by(1000) {
+        let call_visited_pcs = (first_pc..(first_pc + 2000).min(n_visited_pcs)).collect();
+        sketch.on_visited_pcs(class_hash, &call_visited_pcs);
+    }
+    let estimated_n_visited_pcs = sketch.estimated_n_visited_pcs(class_hash).unwrap();
+    assert!(estimated_n_visited_pcs.abs_diff(n_visited_pcs) * 10 <= n_visited_pcs);
+}
@@ -0,0 +1,66 @@
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use cairo_vm::vm::vm_core::VirtualMachine;
+
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
+};
+
+#[cfg(test)]
+#[path = "vm_tracer_test.rs"]
+pub mod test;
+
+/// A step of the Cairo VM in the execution of an entry point.
+/// The registers are relocated, with the program segment at address 1 and the execution segment
+/// right after it; i.e., the first instruction of the program is at pc 1.
+#[derive(Debug)]
+pub struct VmStep<'a> {
+    pub call: &'a CallEntryPoint,
+    /// The index of the step in the run of the call; the steps of inner calls are not counted.
+    pub step_index: usize,
+    pub pc: usize,
+    pub ap: usize,
+    pub fp: usize,
+    /// The resources consumed by the transaction before the run of the call.
+    pub resources: &'a ExecutionResources,
+}
+
+/// Observes the steps of the Cairo VM, e.g., for debuggers and coverage tools.
+/// The steps of a call are reported once its run completes (including Cairo 1 runs that panicked);
+/// hence, the steps of inner calls are reported before the ones of the calls that invoked them.
+pub trait VmTracer: std::fmt::Debug + Send + Sync {
+    /// The tracer is invoked on every `step_interval`-th step of a run, starting from the first.
+    fn step_interval(&self) -> usize {
+        1
+    }
+
+    fn on_step(&self, step: &VmStep<'_>);
+}
+
+/// Reports the steps of a completed run of the given call to the tracer of the block, if set.
+/// The trace of the VM must already be relocated (see [`VmStep`]).
+pub fn trace_vm_steps(
+    vm: &VirtualMachine,
+    call: &CallEntryPoint,
+    resources: &ExecutionResources,
+    context: &EntryPointExecutionContext,
+) -> EntryPointExecutionResult<()> {
+    let Some(vm_tracer) = &context.tx_context.block_context.hooks.vm_tracer else {
+        return Ok(());
+    };
+
+    let step_interval = vm_tracer.step_interval().max(1);
+    for (step_index, trace_entry) in
+        vm.get_relocated_trace()?.iter().enumerate().step_by(step_interval)
+    {
+        vm_tracer.on_step(&VmStep {
+            call,
+            step_index,
+            pc: trace_entry.pc,
+            ap: trace_entry.ap,
+            fp: trace_entry.fp,
+            resources,
+        });
+    }
+
+    Ok(())
+}
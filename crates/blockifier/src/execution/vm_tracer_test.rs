@@ -0,0 +1,66 @@
+use std::sync::{Arc, Mutex};
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::{BlockContext, ChainInfo};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::vm_tracer::{VmStep, VmTracer};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::versioned_constants::VersionedConstants;
+
+/// Records the index and pc of the traced steps.
+#[derive(Debug, Default)]
+struct RecordingTracer {
+    step_interval: usize,
+    steps: Mutex<Vec<(usize, usize)>>,
+}
+
+impl VmTracer for RecordingTracer {
+    fn step_interval(&self) -> usize {
+        self.step_interval
+    }
+
+    fn on_step(&self, step: &VmStep<'_>) {
+        self.steps.lock().unwrap().push((step.step_index, step.pc));
+    }
+}
+
+#[rstest]
+fn test_vm_tracer(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+    #[values(1, 3)] step_interval: usize,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let tracer = Arc::new(RecordingTracer { step_interval, ..Default::default() });
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.hooks_mut().vm_tracer = Some(tracer.clone());
+
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let call_info =
+        entry_point_call.execute_directly_given_block_context(&mut state, block_context).unwrap();
+
+    // The resources of the call are the steps of its run and the ones of its syscalls.
+    let syscall_resources = VersionedConstants::create_for_testing()
+        .get_additional_os_syscall_resources(&call_info.syscall_counter)
+        .unwrap();
+    let n_steps = call_info.resources.n_steps - syscall_resources.n_steps;
+    let steps = tracer.steps.lock().unwrap();
+    let expected_step_indices: Vec<usize> = (0..n_steps).step_by(step_interval).collect();
+    assert_eq!(
+        steps.iter().map(|(step_index, _)| *step_index).collect::<Vec<_>>(),
+        expected_step_indices
+    );
+    assert!(steps.iter().all(|(_, pc)| *pc >= 1));
+}
@@ -2,12 +2,13 @@ use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use starknet_api::core::ContractAddress;
 use starknet_api::transaction::Fee;
 
-use crate::context::TransactionContext;
+use crate::context::{GasMeteringMode, TransactionContext};
 use crate::execution::call_info::CallInfo;
+use crate::fee::fee_utils::calculate_tip;
 use crate::state::cached_state::StateChanges;
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::objects::{
-    GasVector, HasRelatedFeeType, StarknetResources, TransactionExecutionResult,
+    GasVector, HasRelatedFeeType, SierraGasMetering, StarknetResources, TransactionExecutionResult,
     TransactionResources,
 };
 use crate::transaction::transaction_types::TransactionType;
@@ -35,7 +36,10 @@ struct TransactionReceiptParameters<'a, T: Iterator<Item = &'a CallInfo> + Clone
 // get passed around together.
 #[derive(Default)]
 pub struct TransactionReceipt {
+    /// The fee to charge, including the tip.
     pub fee: Fee,
+    /// The part of the fee paid as a tip to the sequencer.
+    pub tip: Fee,
     pub gas: GasVector,
     pub da_gas: GasVector,
     pub resources: TransactionResources,
@@ -63,11 +67,29 @@ impl TransactionReceipt {
             calldata_length,
             signature_length,
             code_size,
-            state_changes.count_for_fee_charge(sender_address, tx_context.fee_token_address()),
+            state_changes.count_for_fee_charge(
+                sender_address.map(|_| tx_context.fee_payer()),
+                tx_context.fee_token_address(),
+            ),
             l1_handler_payload_size,
-            call_infos,
+            call_infos.clone(),
         );
 
+        // In Sierra gas metering mode, and for transactions charged for L2 gas, the calls are
+        // charged by the Sierra gas they are metered by rather than by their VM resources; as L2
+        // gas if the transaction is charged for it. Thus, the VM resources of the calls are never
+        // charged on top of their L2 gas.
+        let metered_by_sierra_gas = tx_context.tx_info.l2_gas_limit().is_some()
+            || tx_context.block_context.fee_policy.gas_metering_mode == GasMeteringMode::SierraGas;
+        let (sierra_gas_metering, l2_gas) = if metered_by_sierra_gas {
+            let sierra_gas_metering =
+                SierraGasMetering::new(call_infos, &tx_context.block_context.versioned_constants);
+            let l2_gas = tx_context.charged_l2_gas(sierra_gas_metering.sierra_gas);
+            (sierra_gas_metering, l2_gas)
+        } else {
+            (SierraGasMetering::default(), 0)
+        };
+
         let cairo_resources = (execution_resources
             + &tx_context.block_context.versioned_constants.get_additional_os_tx_resources(
                 tx_type,
@@ -80,23 +102,33 @@ impl TransactionReceipt {
             starknet_resources,
             vm_resources: cairo_resources,
             n_reverted_steps: reverted_steps,
+            l2_gas,
+            sierra_gas_metering,
         };
 
-        // L1 handler transactions are not charged an L2 fee but it is compared to the L1 fee.
-        let fee = if tx_context.tx_info.enforce_fee()? || tx_type == TransactionType::L1Handler {
-            tx_context.tx_info.calculate_tx_fee(&tx_resources, &tx_context.block_context)?
-        } else {
-            Fee(0)
-        };
         let da_gas = tx_resources
             .starknet_resources
             .get_state_changes_cost(tx_context.block_context.block_info.use_kzg_da);
 
-        let gas = tx_resources.to_gas_vector(
+        let gas = tx_resources.to_gas_vector_with_rounding(
             &tx_context.block_context.versioned_constants,
             tx_context.block_context.block_info.use_kzg_da,
+            tx_context.block_context.fee_policy.fee_rounding,
         )?;
-        Ok(Self { resources: tx_resources, gas, da_gas, fee })
+
+        // L1 handler transactions are not charged an L2 fee but it is compared to the L1 fee.
+        let (fee, tip) = if tx_context.tx_info.enforce_fee()?
+            || tx_type == TransactionType::L1Handler
+            || tx_context.block_context.fee_policy.relax_max_fee_check
+        {
+            let base_fee =
+                tx_context.tx_info.calculate_tx_fee(&tx_resources, &tx_context.block_context)?;
+            let tip = calculate_tip(tx_context, &gas);
+            (Fee(base_fee.0.saturating_add(tip.0)), tip)
+        } else {
+            (Fee(0), Fee(0))
+        };
+        Ok(Self { resources: tx_resources, gas, da_gas, fee, tip })
     }
 
     /// Computes actual cost of an L1 handler transaction.
@@ -1,9 +1,11 @@
 use rstest::{fixture, rstest};
 use starknet_api::hash::StarkFelt;
 use starknet_api::stark_felt;
-use starknet_api::transaction::{Fee, L2ToL1Payload, TransactionVersion};
+use starknet_api::transaction::{
+    Fee, L2ToL1Payload, Resource, ResourceBounds, ResourceBoundsMapping, TransactionVersion,
+};
 
-use crate::context::BlockContext;
+use crate::context::{BlockContext, GasMeteringMode};
 use crate::execution::call_info::{CallExecution, CallInfo, MessageToL1, OrderedL2ToL1Message};
 use crate::fee::eth_gas_constants;
 use crate::fee::gas_usage::{
@@ -13,9 +15,14 @@ use crate::fee::gas_usage::{
 use crate::state::cached_state::StateChangesCount;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
-use crate::test_utils::{create_calldata, create_trivial_calldata, CairoVersion, BALANCE, MAX_FEE};
+use crate::test_utils::{
+    create_calldata, create_trivial_calldata, CairoVersion, BALANCE, DEFAULT_STRK_L2_GAS_PRICE,
+    MAX_FEE, MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE,
+};
 use crate::transaction::constants;
-use crate::transaction::objects::{GasVector, HasRelatedFeeType, StarknetResources};
+use crate::transaction::objects::{
+    GasVector, HasRelatedFeeType, SierraGasMetering, StarknetResources,
+};
 use crate::transaction::test_utils::{account_invoke_tx, calculate_class_info_for_testing};
 use crate::transaction::transactions::ExecutableTransaction;
 use crate::utils::{u128_from_usize, usize_from_u128};
@@ -197,6 +204,7 @@ fn test_calculate_tx_gas_usage_basic<'a>(#[values(false, true)] use_kzg_da: bool
     let manual_gas_computation = GasVector {
         l1_gas: u128_from_usize(manual_starknet_gas_usage + manual_sharp_gas_usage),
         l1_data_gas: manual_sharp_blob_gas_usage,
+        ..Default::default()
     };
 
     assert_eq!(l2_to_l1_messages_gas_usage_vector, manual_gas_computation);
@@ -268,6 +276,7 @@ fn test_calculate_tx_gas_usage_basic<'a>(#[values(false, true)] use_kzg_da: bool
         l1_data_gas: combined_cases_starknet_resources
             .get_state_changes_cost(use_kzg_da)
             .l1_data_gas,
+        ..Default::default()
     };
 
     assert_eq!(expected_gas_vector, gas_usage_vector);
@@ -376,3 +385,128 @@ fn test_calculate_tx_gas_usage(#[values(false, true)] use_kzg_da: bool) {
             .to_gas_vector(versioned_constants, use_kzg_da)
     );
 }
+
+#[rstest]
+fn test_sierra_gas_metering(
+    #[values(GasMeteringMode::VmResources, GasMeteringMode::SierraGas, GasMeteringMode::Dual)]
+    gas_metering_mode: GasMeteringMode,
+) {
+    let mut block_context = BlockContext::create_for_account_testing();
+    block_context.fee_policy_mut().gas_metering_mode = gas_metering_mode;
+    let account_contract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let state = &mut test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(account_contract, 1), (test_contract, 1)],
+    );
+
+    let account_tx = account_invoke_tx(invoke_tx_args! {
+        sender_address: account_contract.get_instance_address(0),
+        calldata: create_calldata(
+            test_contract.get_instance_address(0),
+            "test_storage_read_write",
+            &[
+                stark_felt!(15_u8), // Calldata: address.
+                stark_felt!(7_u8),  // Calldata: value.
+            ],
+        ),
+        max_fee: Fee(MAX_FEE),
+        version: TransactionVersion::ONE,
+    });
+    let tx_execution_info = account_tx.execute(state, &block_context, true, true).unwrap();
+    let tx_resources = &tx_execution_info.actual_resources;
+    let metering = &tx_resources.sierra_gas_metering;
+    let test_contract_call = &tx_execution_info.execute_call_info.as_ref().unwrap().inner_calls[0];
+    assert_eq!(test_contract_call.tracked_resource, gas_metering_mode.cairo1_tracked_resource());
+
+    match gas_metering_mode {
+        GasMeteringMode::VmResources | GasMeteringMode::Dual => {
+            assert_eq!(*metering, SierraGasMetering::default());
+            assert_eq!(tx_resources.charged_vm_resources(), tx_resources.vm_resources);
+        }
+        GasMeteringMode::SierraGas => {
+            // The Sierra gas consumed by the Cairo 1 call is metered as is.
+            assert!(metering.sierra_gas > test_contract_call.execution.gas_consumed);
+            assert!(metering.n_steps > 0);
+            // The VM resources of the metered calls are not charged for, yet still reported; only
+            // the additional OS resources remain charged.
+            assert!(metering.vm_resources.n_steps > 0);
+            assert_eq!(
+                tx_resources.charged_vm_resources().n_steps + metering.vm_resources.n_steps,
+                tx_resources.vm_resources.n_steps
+            );
+        }
+    }
+    assert_eq!(
+        tx_resources.to_gas_vector(&block_context.versioned_constants, false).unwrap(),
+        tx_execution_info.gas
+    );
+
+    // Only the Cairo 1 call is metered by both resources, and only in dual metering mode.
+    let dual_gas_metering = test_contract_call.dual_gas_metering;
+    if gas_metering_mode == GasMeteringMode::Dual {
+        let dual_gas_metering = dual_gas_metering.unwrap();
+        assert_eq!(dual_gas_metering.sierra_gas, test_contract_call.exclusive_gas_consumed());
+        assert!(dual_gas_metering.vm_resources_gas > 0);
+    } else {
+        assert_eq!(dual_gas_metering, None);
+    }
+    assert!(tx_execution_info.execute_call_info.as_ref().unwrap().dual_gas_metering.is_none());
+    assert_eq!(tx_execution_info.gas_metering_divergences(100).count(), 0);
+}
+
+#[test]
+fn test_l2_gas_charged_execution() {
+    let block_context = BlockContext::create_for_account_testing();
+    assert_eq!(block_context.fee_policy().gas_metering_mode, GasMeteringMode::VmResources);
+    let account_contract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let state = &mut test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(account_contract, 1), (test_contract, 1)],
+    );
+
+    let resource_bounds = ResourceBoundsMapping::try_from(vec![
+        (
+            Resource::L1Gas,
+            ResourceBounds { max_amount: MAX_L1_GAS_AMOUNT, max_price_per_unit: MAX_L1_GAS_PRICE },
+        ),
+        (
+            Resource::L2Gas,
+            ResourceBounds {
+                max_amount: u64::pow(10, 8),
+                max_price_per_unit: DEFAULT_STRK_L2_GAS_PRICE,
+            },
+        ),
+    ])
+    .unwrap();
+    let account_tx = account_invoke_tx(invoke_tx_args! {
+        sender_address: account_contract.get_instance_address(0),
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        resource_bounds,
+        version: TransactionVersion::THREE,
+    });
+    let tx_execution_info = account_tx.execute(state, &block_context, true, true).unwrap();
+    let tx_resources = &tx_execution_info.actual_resources;
+    let metering = &tx_resources.sierra_gas_metering;
+
+    // The execution is charged as L2 gas only; its VM resources are not charged as L1 gas.
+    let call_resources_n_steps: usize = tx_execution_info
+        .validate_call_info
+        .iter()
+        .chain(tx_execution_info.execute_call_info.iter())
+        .map(|call_info| call_info.resources.n_steps)
+        .sum();
+    assert!(call_resources_n_steps > 0);
+    assert_eq!(metering.vm_resources.n_steps, call_resources_n_steps);
+    assert_eq!(
+        tx_resources.charged_vm_resources().n_steps,
+        tx_resources.vm_resources.n_steps - call_resources_n_steps
+    );
+    assert!(metering.sierra_gas > 0);
+    assert_eq!(tx_resources.l2_gas, metering.sierra_gas);
+    assert_eq!(tx_resources.l1_charged_sierra_gas(), 0);
+    assert_eq!(tx_execution_info.gas.l2_gas, u128::from(metering.sierra_gas));
+}
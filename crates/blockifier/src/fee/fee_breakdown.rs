@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use starknet_api::transaction::Fee;
+
+use crate::blockifier::block::BlockInfo;
+use crate::context::TransactionContext;
+use crate::fee::fee_utils::{
+    calculate_l1_gas_by_sierra_gas, calculate_l1_gas_by_vm_resource, calculate_tip,
+    get_fee_by_gas_vector,
+};
+use crate::transaction::objects::{
+    FeeType, GasVector, HasRelatedFeeType, TransactionFeeResult, TransactionResources,
+};
+
+#[cfg(test)]
+#[path = "fee_breakdown_test.rs"]
+pub mod test;
+
+/// The gas prices a fee is computed by, in the units of the transaction's fee token.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct FeeGasPrices {
+    pub l1_gas_price: u128,
+    pub l1_data_gas_price: u128,
+    pub l2_gas_price: u128,
+}
+
+impl FeeGasPrices {
+    pub fn new(block_info: &BlockInfo, fee_type: &FeeType) -> Self {
+        let gas_prices = &block_info.gas_prices;
+        Self {
+            l1_gas_price: gas_prices.get_gas_price_by_fee_type(fee_type).into(),
+            l1_data_gas_price: gas_prices.get_data_gas_price_by_fee_type(fee_type).into(),
+            l2_gas_price: gas_prices.get_l2_gas_price_by_fee_type(fee_type).into(),
+        }
+    }
+}
+
+/// The gas consumed by a single fee component, and its cost.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct FeeComponent {
+    pub gas: GasVector,
+    pub fee: Fee,
+}
+
+impl FeeComponent {
+    fn new(gas: GasVector, gas_prices: &FeeGasPrices) -> Self {
+        let fee = gas.saturated_cost(
+            gas_prices.l1_gas_price,
+            gas_prices.l1_data_gas_price,
+            gas_prices.l2_gas_price,
+        );
+        Self { gas, fee }
+    }
+}
+
+/// Explains the fee of a transaction by the contribution of each resource it consumed.
+/// Note that Cairo resources do not add up: only the heaviest one (in terms of L1 gas) is charged,
+/// as the size of a proof is determined by the largest segment.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct FeeBreakdown {
+    pub gas_prices: FeeGasPrices,
+    /// The components of the Cairo resources (steps and builtins), keyed by the resource name.
+    pub vm_resources: BTreeMap<String, FeeComponent>,
+    /// The Cairo resource charged for; unset if no Cairo resource was consumed.
+    pub charged_vm_resource: Option<String>,
+    pub calldata_and_signature: FeeComponent,
+    pub code: FeeComponent,
+    pub state_diff: FeeComponent,
+    pub messages: FeeComponent,
+    pub events: FeeComponent,
+    /// The Sierra gas; only charged for transactions with all resource bounds.
+    pub l2_gas: FeeComponent,
+    /// The Sierra gas charged as L1 gas; in Sierra gas metering mode, the computation of
+    /// transactions not charged for L2 gas.
+    pub sierra_gas: FeeComponent,
+    /// The fee of all charged components.
+    pub base_fee: Fee,
+    pub tip: Fee,
+    /// The fee charged instead of the base fee and tip, if it differs from them; e.g., the
+    /// resource bounds, charged when the transaction exceeds them, or the max fee, which the fee
+    /// is clamped to when max fee checks are relaxed.
+    pub enforced_fee: Option<Fee>,
+}
+
+impl FeeBreakdown {
+    pub fn new(
+        tx_resources: &TransactionResources,
+        tx_context: &TransactionContext,
+    ) -> TransactionFeeResult<Self> {
+        let block_context = &tx_context.block_context;
+        let versioned_constants = &block_context.versioned_constants;
+        let use_kzg_da = block_context.block_info.use_kzg_da;
+        let rounding = block_context.fee_policy.fee_rounding;
+        let fee_type = tx_context.tx_info.fee_type();
+        let gas_prices = FeeGasPrices::new(&block_context.block_info, &fee_type);
+        let component = |gas| FeeComponent::new(gas, &gas_prices);
+
+        let vm_resources_l1_gas = calculate_l1_gas_by_vm_resource(
+            versioned_constants,
+            &tx_resources.charged_vm_resources(),
+            tx_resources.n_reverted_steps,
+            rounding,
+        )?;
+        let charged_vm_resource = vm_resources_l1_gas
+            .iter()
+            .filter(|(_, l1_gas)| **l1_gas > 0)
+            .max_by_key(|(_, l1_gas)| **l1_gas)
+            .map(|(name, _)| name.clone());
+        let vm_resources = vm_resources_l1_gas
+            .into_iter()
+            .map(|(name, l1_gas)| (name, component(GasVector::from_l1_gas(l1_gas))))
+            .collect();
+
+        let starknet_resources = &tx_resources.starknet_resources;
+        let gas =
+            tx_resources.to_gas_vector_with_rounding(versioned_constants, use_kzg_da, rounding)?;
+        Ok(Self {
+            gas_prices,
+            vm_resources,
+            charged_vm_resource,
+            calldata_and_signature: component(
+                starknet_resources.get_calldata_and_signature_cost(versioned_constants),
+            ),
+            code: component(starknet_resources.get_code_cost(versioned_constants)),
+            state_diff: component(starknet_resources.get_state_changes_cost(use_kzg_da)),
+            messages: component(starknet_resources.get_messages_cost()),
+            events: component(starknet_resources.get_events_cost(versioned_constants)),
+            l2_gas: component(GasVector::from_l2_gas(tx_resources.l2_gas.into())),
+            sierra_gas: component(GasVector::from_l1_gas(calculate_l1_gas_by_sierra_gas(
+                versioned_constants,
+                tx_resources.l1_charged_sierra_gas(),
+                rounding,
+            ))),
+            base_fee: get_fee_by_gas_vector(&block_context.block_info, gas, &fee_type),
+            tip: calculate_tip(tx_context, &gas),
+            enforced_fee: None,
+        })
+    }
+
+    /// Records the fee actually charged, if it differs from the computed one.
+    pub fn with_charged_fee(self, charged_fee: Fee) -> Self {
+        let enforced_fee = (charged_fee != self.computed_fee()).then_some(charged_fee);
+        Self { enforced_fee, ..self }
+    }
+
+    /// The base fee and tip.
+    pub fn computed_fee(&self) -> Fee {
+        Fee(self.base_fee.0.saturating_add(self.tip.0))
+    }
+
+    /// The fee charged.
+    pub fn total_fee(&self) -> Fee {
+        self.enforced_fee.unwrap_or_else(|| self.computed_fee())
+    }
+
+    /// All components, keyed by name; Cairo resources are keyed by the resource name.
+    pub fn components(&self) -> BTreeMap<String, FeeComponent> {
+        let named_components = [
+            ("calldata_and_signature", self.calldata_and_signature),
+            ("code", self.code),
+            ("state_diff", self.state_diff),
+            ("messages", self.messages),
+            ("events", self.events),
+            ("l2_gas", self.l2_gas),
+            ("sierra_gas", self.sierra_gas),
+        ];
+        self.vm_resources
+            .clone()
+            .into_iter()
+            .chain(named_components.map(|(name, component)| (name.to_string(), component)))
+            .collect()
+    }
+}
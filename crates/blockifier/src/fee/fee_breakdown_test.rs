@@ -0,0 +1,57 @@
+use rstest::rstest;
+use starknet_api::transaction::{Fee, TransactionVersion};
+
+use crate::context::BlockContext;
+use crate::invoke_tx_args;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{
+    create_trivial_calldata, CairoVersion, BALANCE, MAX_FEE, MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE,
+};
+use crate::transaction::objects::GasVector;
+use crate::transaction::test_utils::{account_invoke_tx, l1_resource_bounds, run_invoke_tx};
+
+#[rstest]
+fn test_fee_breakdown(
+    #[values(TransactionVersion::ONE, TransactionVersion::THREE)] version: TransactionVersion,
+) {
+    let block_context = BlockContext::create_for_account_testing();
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state =
+        test_state(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let invoke_args = invoke_tx_args! {
+        sender_address: account.get_instance_address(0),
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        max_fee: Fee(MAX_FEE),
+        resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE),
+        version,
+    };
+    let tx_context = block_context.to_tx_context(&account_invoke_tx(invoke_args.clone()));
+    let tx_execution_info = run_invoke_tx(&mut state, &block_context, invoke_args).unwrap();
+
+    let breakdown = tx_execution_info.fee_breakdown(&tx_context).unwrap();
+    assert_eq!(breakdown.enforced_fee, None);
+    assert_eq!(breakdown.total_fee(), tx_execution_info.actual_fee);
+
+    // Only the heaviest Cairo resource is charged.
+    let charged_vm_resource =
+        &breakdown.vm_resources[breakdown.charged_vm_resource.as_ref().unwrap()];
+    let max_vm_l1_gas = breakdown.vm_resources.values().map(|c| c.gas.l1_gas).max().unwrap();
+    assert_eq!(charged_vm_resource.gas.l1_gas, max_vm_l1_gas);
+
+    let charged_gas = [
+        breakdown.calldata_and_signature,
+        breakdown.code,
+        breakdown.state_diff,
+        breakdown.messages,
+        breakdown.events,
+        breakdown.l2_gas,
+        breakdown.sierra_gas,
+        *charged_vm_resource,
+    ]
+    .iter()
+    .map(|component| component.gas)
+    .sum::<GasVector>();
+    assert_eq!(charged_gas, tx_execution_info.gas);
+}
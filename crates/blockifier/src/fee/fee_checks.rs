@@ -4,7 +4,7 @@ use thiserror::Error;
 
 use crate::context::TransactionContext;
 use crate::fee::actual_cost::TransactionReceipt;
-use crate::fee::fee_utils::{get_balance_and_if_covers_fee, get_fee_by_gas_vector};
+use crate::fee::fee_utils::{calculate_tip, get_balance_and_if_covers_fee, get_fee_by_gas_vector};
 use crate::fee::gas_usage::compute_discounted_gas_from_gas_vector;
 use crate::state::state_api::StateReader;
 use crate::transaction::errors::TransactionExecutionError;
@@ -16,6 +16,8 @@ use crate::transaction::objects::{
 pub enum FeeCheckError {
     #[error("Insufficient max L1 gas: max amount: {max_amount}, actual used: {actual_amount}.")]
     MaxL1GasAmountExceeded { max_amount: u128, actual_amount: u128 },
+    #[error("Insufficient max L2 gas: max amount: {max_amount}, actual used: {actual_amount}.")]
+    MaxL2GasAmountExceeded { max_amount: u128, actual_amount: u128 },
     #[error("Insufficient max fee: max fee: {max_fee:?}, actual fee: {actual_fee:?}")]
     MaxFeeExceeded { max_fee: Fee, actual_fee: Fee },
     #[error(
@@ -25,6 +27,43 @@ pub enum FeeCheckError {
     InsufficientFeeTokenBalance { fee: Fee, balance_low: StarkFelt, balance_high: StarkFelt },
 }
 
+/// The policy applied when the fee of a revertible transaction cannot be fully charged after its
+/// execution; e.g., due to insufficient max fee or fee token balance.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FeeChargeFailurePolicy {
+    /// Revert the execution, and charge as much of the fee as possible.
+    #[default]
+    Revert,
+    /// Reject the transaction; no fee is charged.
+    Reject,
+}
+
+/// Determines the fee charged for a reverted transaction.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RevertedTxFeePolicy {
+    /// Charge for the resources consumed until the revert.
+    #[default]
+    ConsumedResources,
+    /// Charge the max fee of the transaction; see [`TransactionInfo::max_fee_bound`].
+    MaxFee,
+    /// Charge for the consumed resources, but at least the given fee, within the max fee.
+    ConsumedResourcesWithFloor(Fee),
+}
+
+impl RevertedTxFeePolicy {
+    /// Returns the fee to charge a reverted transaction, given the fee of the resources it
+    /// consumed and its max fee.
+    pub fn fee_to_charge(&self, consumed_resources_fee: Fee, max_fee: Fee) -> Fee {
+        match self {
+            Self::ConsumedResources => consumed_resources_fee,
+            Self::MaxFee => max_fee,
+            Self::ConsumedResourcesWithFloor(floor) => {
+                std::cmp::max(consumed_resources_fee, std::cmp::min(*floor, max_fee))
+            }
+        }
+    }
+}
+
 /// This struct holds the result of fee checks: recommended fee to charge (useful in post-execution
 /// revert flow) and an error if the check failed.
 struct FeeCheckReport {
@@ -68,16 +107,25 @@ impl FeeCheckReport {
             // If the error is resource overdraft, the recommended fee is the resource bounds.
             // If the transaction passed pre-validation checks (i.e. balance initially covered the
             // resource bounds), the sender should be able to pay this fee.
-            FeeCheckError::MaxFeeExceeded { .. } | FeeCheckError::MaxL1GasAmountExceeded { .. } => {
-                match &tx_context.tx_info {
-                    TransactionInfo::Current(info) => get_fee_by_gas_vector(
+            FeeCheckError::MaxFeeExceeded { .. }
+            | FeeCheckError::MaxL1GasAmountExceeded { .. }
+            | FeeCheckError::MaxL2GasAmountExceeded { .. } => match &tx_context.tx_info {
+                TransactionInfo::Current(info) => {
+                    let max_gas_vector = GasVector {
+                        l1_gas: info.l1_resource_bounds()?.max_amount.into(),
+                        l2_gas: info.l2_resource_bounds().max_amount.into(),
+                        ..Default::default()
+                    };
+                    let base_fee = get_fee_by_gas_vector(
                         &tx_context.block_context.block_info,
-                        GasVector::from_l1_gas(info.l1_resource_bounds()?.max_amount.into()),
+                        max_gas_vector,
                         &FeeType::Strk,
-                    ),
-                    TransactionInfo::Deprecated(context) => context.max_fee,
+                    );
+                    let tip = calculate_tip(tx_context, &max_gas_vector);
+                    Fee(base_fee.0.saturating_add(tip.0))
                 }
-            }
+                TransactionInfo::Deprecated(context) => context.max_fee,
+            },
         };
         Ok(Self { recommended_fee, error: Some(error) })
     }
@@ -111,11 +159,22 @@ impl FeeCheckReport {
                         actual_amount: total_discounted_gas_used,
                     })?;
                 }
+
+                // Check L2 gas limit; only enforced if set by the sender.
+                if let Some(max_l2_gas) = tx_info.l2_gas_limit() {
+                    let max_l2_gas = u128::from(max_l2_gas);
+                    if gas.l2_gas > max_l2_gas {
+                        return Err(FeeCheckError::MaxL2GasAmountExceeded {
+                            max_amount: max_l2_gas,
+                            actual_amount: gas.l2_gas,
+                        })?;
+                    }
+                }
             }
             TransactionInfo::Deprecated(context) => {
                 // Check max fee.
                 let max_fee = context.max_fee;
-                if fee > &max_fee {
+                if fee > &max_fee && !tx_context.block_context.fee_policy.relax_max_fee_check {
                     return Err(FeeCheckError::MaxFeeExceeded { max_fee, actual_fee: *fee })?;
                 }
             }
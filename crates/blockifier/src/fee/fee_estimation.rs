@@ -0,0 +1,96 @@
+use num_rational::Ratio;
+use num_traits::CheckedMul;
+use serde::Serialize;
+use starknet_api::transaction::Fee;
+
+use crate::context::{BlockContext, TransactionContext};
+use crate::fee::fee_utils::{calculate_tip, get_fee_by_gas_vector};
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::StateReader;
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::objects::{GasVector, HasRelatedFeeType, TransactionExecutionResult};
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[cfg(test)]
+#[path = "fee_estimation_test.rs"]
+pub mod test;
+
+/// A safety margin for fee estimates: an inflation factor per gas resource.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FeeEstimateMargin {
+    pub l1_gas: Ratio<u128>,
+    pub l1_data_gas: Ratio<u128>,
+    pub l2_gas: Ratio<u128>,
+}
+
+impl Default for FeeEstimateMargin {
+    fn default() -> Self {
+        Self::uniform(Ratio::from_integer(1))
+    }
+}
+
+impl FeeEstimateMargin {
+    /// A margin inflating all resources by the given factor.
+    pub fn uniform(factor: Ratio<u128>) -> Self {
+        Self { l1_gas: factor, l1_data_gas: factor, l2_gas: factor }
+    }
+
+    /// Inflates the given gas by the margin, rounding up; saturates on overflow.
+    pub fn apply(&self, gas: GasVector) -> GasVector {
+        let inflate = |factor: Ratio<u128>, gas: u128| {
+            factor
+                .checked_mul(&Ratio::from_integer(gas))
+                .map_or(u128::MAX, |inflated_gas| inflated_gas.ceil().to_integer())
+        };
+        GasVector {
+            l1_gas: inflate(self.l1_gas, gas.l1_gas),
+            l1_data_gas: inflate(self.l1_data_gas, gas.l1_data_gas),
+            l2_gas: inflate(self.l2_gas, gas.l2_gas),
+        }
+    }
+}
+
+/// A fee estimate, as is and with the safety margin of the block.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct FeeEstimate {
+    pub gas: GasVector,
+    pub fee: Fee,
+    pub gas_with_margin: GasVector,
+    pub fee_with_margin: Fee,
+}
+
+/// Returns the block context of estimation mode: as in fee estimation flows, the fee is not
+/// limited by the fee bounds of the transaction, and gas prices are taken from the block's gas
+/// price oracle, if set.
+pub fn estimation_block_context(block_context: &BlockContext) -> BlockContext {
+    let mut estimation_block_context = block_context.clone();
+    estimation_block_context.fee_policy.relax_max_fee_check = true;
+    estimation_block_context.block_info.gas_prices = block_context.estimation_gas_prices();
+    estimation_block_context
+}
+
+/// Estimates the fee of the given transaction, by running it in estimation mode, without
+/// validation and fee charge. The given state is not modified.
+pub fn estimate_fee<S: StateReader>(
+    tx: &AccountTransaction,
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+) -> TransactionExecutionResult<FeeEstimate> {
+    let estimation_block_context = estimation_block_context(block_context);
+    let gas = tx.simulate(state, &estimation_block_context, false, false)?.tx_execution_info.gas;
+    let gas_with_margin = block_context.fee_policy.fee_estimate_margin.apply(gas);
+
+    let tx_context = estimation_block_context.to_tx_context(tx);
+    Ok(FeeEstimate {
+        gas,
+        fee: calculate_fee_with_tip(&tx_context, gas),
+        gas_with_margin,
+        fee_with_margin: calculate_fee_with_tip(&tx_context, gas_with_margin),
+    })
+}
+
+fn calculate_fee_with_tip(tx_context: &TransactionContext, gas: GasVector) -> Fee {
+    let block_info = &tx_context.block_context.block_info;
+    let base_fee = get_fee_by_gas_vector(block_info, gas, &tx_context.tx_info.fee_type());
+    Fee(base_fee.0.saturating_add(calculate_tip(tx_context, &gas).0))
+}
@@ -0,0 +1,59 @@
+use num_rational::Ratio;
+use starknet_api::transaction::{Fee, TransactionVersion};
+
+use crate::context::BlockContext;
+use crate::fee::fee_estimation::{estimate_fee, FeeEstimateMargin};
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_trivial_calldata, CairoVersion, BALANCE, MAX_FEE};
+use crate::transaction::objects::GasVector;
+use crate::transaction::test_utils::account_invoke_tx;
+use crate::{invoke_tx_args, nonce};
+
+#[test]
+fn test_fee_estimate_margin() {
+    let gas = GasVector { l1_gas: 10, l1_data_gas: 3, l2_gas: 0 };
+    assert_eq!(FeeEstimateMargin::default().apply(gas), gas);
+    assert_eq!(
+        FeeEstimateMargin::uniform(Ratio::new(3, 2)).apply(gas),
+        GasVector { l1_gas: 15, l1_data_gas: 5, l2_gas: 0 }
+    );
+    let margin = FeeEstimateMargin { l1_gas: Ratio::new(11, 10), ..Default::default() };
+    assert_eq!(margin.apply(gas), GasVector { l1_gas: 11, ..gas });
+    assert_eq!(margin.apply(GasVector::from_l1_gas(u128::MAX)).l1_gas, u128::MAX);
+}
+
+#[test]
+fn test_estimate_fee() {
+    let mut block_context = BlockContext::create_for_account_testing();
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state =
+        test_state(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let sender_address = account.get_instance_address(0);
+    let tx = account_invoke_tx(invoke_tx_args! {
+        sender_address,
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        max_fee: Fee(MAX_FEE),
+        version: TransactionVersion::ONE,
+    });
+
+    // No margin by default.
+    let estimate = estimate_fee(&tx, &mut state, &block_context).unwrap();
+    assert!(estimate.fee > Fee(0));
+    assert_eq!(estimate.gas_with_margin, estimate.gas);
+    assert_eq!(estimate.fee_with_margin, estimate.fee);
+
+    // The margin is reported separately from the raw estimate.
+    let margin = FeeEstimateMargin::uniform(Ratio::from_integer(2));
+    block_context.fee_policy_mut().fee_estimate_margin = margin;
+    let estimate_with_margin = estimate_fee(&tx, &mut state, &block_context).unwrap();
+    assert_eq!(estimate_with_margin.gas, estimate.gas);
+    assert_eq!(estimate_with_margin.fee, estimate.fee);
+    assert_eq!(estimate_with_margin.gas_with_margin, margin.apply(estimate.gas));
+    assert_eq!(estimate_with_margin.fee_with_margin, Fee(estimate.fee.0 * 2));
+
+    // The state is not modified.
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), nonce!(0_u8));
+}
@@ -0,0 +1,86 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::context::BlockContext;
+use crate::fee::fee_breakdown::{FeeBreakdown, FeeComponent};
+use crate::fee::fee_estimation::estimation_block_context;
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::StateReader;
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::objects::TransactionExecutionResult;
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[cfg(test)]
+#[path = "fee_reconciliation_test.rs"]
+pub mod test;
+
+/// The estimated and actual values of a fee component that diverged.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct ComponentDivergence {
+    pub estimated: FeeComponent,
+    pub actual: FeeComponent,
+}
+
+/// Reconciles the fee estimation of a transaction with its actual fee, per resource.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct FeeReconciliationReport {
+    pub estimated: FeeBreakdown,
+    pub actual: FeeBreakdown,
+    /// The components whose gas or fee diverged, keyed by the component name (see
+    /// [`FeeBreakdown::components`]).
+    pub divergences: BTreeMap<String, ComponentDivergence>,
+}
+
+impl FeeReconciliationReport {
+    pub fn new(estimated: FeeBreakdown, actual: FeeBreakdown) -> Self {
+        let mut estimated_components = estimated.components();
+        let mut actual_components = actual.components();
+        let names: BTreeSet<String> =
+            estimated_components.keys().chain(actual_components.keys()).cloned().collect();
+        let divergences = names
+            .into_iter()
+            .filter_map(|name| {
+                let divergence = ComponentDivergence {
+                    estimated: estimated_components.remove(&name).unwrap_or_default(),
+                    actual: actual_components.remove(&name).unwrap_or_default(),
+                };
+                (divergence.estimated != divergence.actual).then_some((name, divergence))
+            })
+            .collect();
+
+        Self { estimated, actual, divergences }
+    }
+
+    /// The actual fee minus the estimated one; positive if the fee was underestimated.
+    pub fn fee_error(&self) -> i128 {
+        let to_signed = |fee: u128| i128::try_from(fee).unwrap_or(i128::MAX);
+        to_signed(self.actual.total_fee().0).saturating_sub(to_signed(self.estimated.total_fee().0))
+    }
+
+    pub fn is_exact(&self) -> bool {
+        self.divergences.is_empty() && self.fee_error() == 0
+    }
+}
+
+/// Runs the given transaction in estimation mode and in execution mode, and reports where the
+/// estimated fee diverged from the actual one. The given state is not modified.
+/// In estimation mode (see [`estimation_block_context`]), the transaction is not validated nor
+/// charged.
+pub fn reconcile_fee_estimation<S: StateReader>(
+    tx: &AccountTransaction,
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+) -> TransactionExecutionResult<FeeReconciliationReport> {
+    let estimation_block_context = estimation_block_context(block_context);
+    let estimation_info = tx.simulate(state, &estimation_block_context, false, false)?;
+    let estimated = estimation_info
+        .tx_execution_info
+        .fee_breakdown(&estimation_block_context.to_tx_context(tx))?;
+
+    let execution_info = tx.simulate(state, block_context, true, true)?;
+    let actual =
+        execution_info.tx_execution_info.fee_breakdown(&block_context.to_tx_context(tx))?;
+
+    Ok(FeeReconciliationReport::new(estimated, actual))
+}
@@ -0,0 +1,39 @@
+use starknet_api::transaction::{Fee, TransactionVersion};
+
+use crate::context::BlockContext;
+use crate::fee::fee_reconciliation::reconcile_fee_estimation;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_trivial_calldata, CairoVersion, BALANCE, MAX_FEE};
+use crate::transaction::test_utils::account_invoke_tx;
+use crate::{invoke_tx_args, nonce};
+
+#[test]
+fn test_reconcile_fee_estimation() {
+    let block_context = BlockContext::create_for_account_testing();
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state =
+        test_state(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let sender_address = account.get_instance_address(0);
+    let tx = account_invoke_tx(invoke_tx_args! {
+        sender_address,
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        max_fee: Fee(MAX_FEE),
+        version: TransactionVersion::ONE,
+    });
+
+    let report = reconcile_fee_estimation(&tx, &mut state, &block_context).unwrap();
+
+    // The estimation does not cover validation.
+    assert!(report.fee_error() > 0);
+    assert!(!report.is_exact());
+    let n_steps_divergence = &report.divergences["n_steps"];
+    assert!(n_steps_divergence.estimated.gas.l1_gas < n_steps_divergence.actual.gas.l1_gas);
+    assert_eq!(report.actual.gas_prices, report.estimated.gas_prices);
+    assert!(!report.divergences.contains_key("calldata_and_signature"));
+
+    // The state is not modified.
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), nonce!(0_u8));
+}
@@ -8,18 +8,25 @@ use cairo_vm::vm::runners::builtin_runner::{
 };
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use rstest::rstest;
-use starknet_api::transaction::{Fee, TransactionVersion};
+use starknet_api::transaction::{Fee, Tip, TransactionVersion};
 
 use crate::abi::constants::N_STEPS_RESOURCE;
+use crate::blockifier::block::{BlockInfo, GasPrices};
 use crate::context::BlockContext;
 use crate::fee::actual_cost::TransactionReceipt;
 use crate::fee::fee_checks::{FeeCheckError, FeeCheckReportFields, PostExecutionReport};
-use crate::fee::fee_utils::calculate_l1_gas_by_vm_usage;
+use crate::fee::fee_utils::{
+    calculate_l1_gas_by_vm_usage, calculate_l1_gas_by_vm_usage_with_rounding, calculate_tip,
+    checked_add_fee, checked_mul_price, convert_fee, saturating_mul_price, OverflowBehavior,
+    Rounding,
+};
+use crate::fee::gas_usage::compute_discounted_gas_from_gas_vector;
 use crate::invoke_tx_args;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{CairoVersion, BALANCE};
-use crate::transaction::objects::GasVector;
+use crate::transaction::errors::FeeOverflowError;
+use crate::transaction::objects::{FeeType, GasVector};
 use crate::transaction::test_utils::{account_invoke_tx, l1_resource_bounds};
 use crate::utils::u128_from_usize;
 use crate::versioned_constants::VersionedConstants;
@@ -156,6 +163,7 @@ fn test_discounted_gas_overdraft(
         gas: GasVector {
             l1_gas: u128_from_usize(l1_gas_used),
             l1_data_gas: u128_from_usize(l1_data_gas_used),
+            ..Default::default()
         },
         ..Default::default()
     };
@@ -180,3 +188,140 @@ fn test_discounted_gas_overdraft(
         assert_matches!(report.error(), None);
     }
 }
+
+#[rstest]
+#[case::no_tip(TransactionVersion::THREE, 0, 0)]
+#[case::tip(TransactionVersion::THREE, 3, 300)]
+#[case::deprecated_tx(TransactionVersion::ONE, 3, 0)]
+fn test_calculate_tip(
+    #[case] version: TransactionVersion,
+    #[case] tip: u64,
+    #[case] expected_tip: u128,
+) {
+    let block_context = BlockContext::create_for_account_testing();
+    let tx = account_invoke_tx(invoke_tx_args! {
+        resource_bounds: l1_resource_bounds(1000, 1000),
+        tip: Tip(tip),
+        version,
+    });
+
+    let gas_vector = GasVector::from_l1_gas(100);
+    assert_eq!(calculate_tip(&block_context.to_tx_context(&tx), &gas_vector), Fee(expected_tip));
+}
+
+#[rstest]
+#[case::down(Rounding::Down, [2, 2], [0, 0])]
+#[case::up(Rounding::Up, [3, 3], [1, 1])]
+#[case::nearest(Rounding::Nearest, [2, 3], [0, 1])]
+fn test_fee_rounding(
+    #[case] rounding: Rounding,
+    #[case] expected_vm_l1_gas: [u128; 2],
+    #[case] expected_discounted_gas: [u128; 2],
+) {
+    // A step costs 0.0025 L1 gas; i.e., 900 and 1100 steps cost 2.25 and 2.75 L1 gas.
+    let versioned_constants = VersionedConstants::create_float_for_testing();
+    let vm_l1_gas = [900, 1100].map(|n_steps| {
+        let vm_resource_usage = ExecutionResources { n_steps, ..Default::default() };
+        calculate_l1_gas_by_vm_usage_with_rounding(
+            &versioned_constants,
+            &vm_resource_usage,
+            0,
+            rounding,
+        )
+        .unwrap()
+        .l1_gas
+    });
+    assert_eq!(vm_l1_gas, expected_vm_l1_gas);
+
+    // Data gas costs a quarter of L1 gas; i.e., 1 and 3 data gas units cost 0.25 and 0.75 L1 gas.
+    let mut block_context = BlockContext::create_for_account_testing();
+    block_context.fee_policy_mut().fee_rounding = rounding;
+    block_context.block_info.gas_prices.strk_l1_gas_price = 4_u128.try_into().unwrap();
+    block_context.block_info.gas_prices.strk_l1_data_gas_price = 1_u128.try_into().unwrap();
+    let tx = account_invoke_tx(invoke_tx_args! { version: TransactionVersion::THREE });
+    let tx_context = block_context.to_tx_context(&tx);
+    let discounted_gas = [1, 3].map(|l1_data_gas| {
+        let gas_vector = GasVector { l1_data_gas, ..Default::default() };
+        compute_discounted_gas_from_gas_vector(&gas_vector, &tx_context)
+    });
+    assert_eq!(discounted_gas, expected_discounted_gas);
+}
+
+#[test]
+fn test_checked_fee_arithmetic() {
+    assert_eq!(checked_mul_price(3, 4), Ok(Fee(12)));
+    assert_eq!(
+        checked_mul_price(u128::MAX, 2),
+        Err(FeeOverflowError::GasCost { gas_amount: u128::MAX, gas_price: 2 })
+    );
+    assert_eq!(saturating_mul_price(u128::MAX, 2), Fee(u128::MAX));
+
+    assert_eq!(checked_add_fee(Fee(1), Fee(2)), Ok(Fee(3)));
+    assert_eq!(
+        checked_add_fee(Fee(u128::MAX), Fee(1)),
+        Err(FeeOverflowError::FeeAddition { lhs: Fee(u128::MAX), rhs: Fee(1) })
+    );
+
+    let gas_vector = GasVector { l1_gas: 1, l1_data_gas: 2, l2_gas: 3 };
+    assert_eq!(
+        gas_vector.checked_add(gas_vector),
+        Ok(GasVector { l1_gas: 2, l1_data_gas: 4, l2_gas: 6 })
+    );
+    let max_gas_vector = GasVector::from_l1_data_gas(u128::MAX);
+    assert_eq!(
+        gas_vector.checked_add(max_gas_vector),
+        Err(FeeOverflowError::GasVectorAddition { lhs: gas_vector, rhs: max_gas_vector })
+    );
+
+    assert_eq!(gas_vector.checked_cost(1, 10, 100), Ok(Fee(321)));
+    assert_matches!(
+        max_gas_vector.checked_cost(1, 2, 1),
+        Err(FeeOverflowError::GasCost { gas_amount: u128::MAX, gas_price: 2 })
+    );
+    assert_eq!(max_gas_vector.saturated_cost(1, 2, 1), Fee(u128::MAX));
+    assert_matches!(
+        GasVector { l1_gas: u128::MAX, ..gas_vector }.checked_cost(1, 1, 1),
+        Err(FeeOverflowError::FeeAddition { .. })
+    );
+}
+
+#[rstest]
+#[case::wei_to_fri_down(FeeType::Eth, FeeType::Strk, Rounding::Down, 5, 7)]
+#[case::wei_to_fri_up(FeeType::Eth, FeeType::Strk, Rounding::Up, 5, 8)]
+#[case::wei_to_fri_nearest(FeeType::Eth, FeeType::Strk, Rounding::Nearest, 5, 8)]
+#[case::fri_to_wei_down(FeeType::Strk, FeeType::Eth, Rounding::Down, 5, 3)]
+#[case::fri_to_wei_up(FeeType::Strk, FeeType::Eth, Rounding::Up, 5, 4)]
+#[case::fri_to_wei_nearest(FeeType::Strk, FeeType::Eth, Rounding::Nearest, 5, 3)]
+#[case::exact(FeeType::Strk, FeeType::Eth, Rounding::Up, 6, 4)]
+#[case::same_unit(FeeType::Strk, FeeType::Strk, Rounding::Up, 5, 5)]
+fn test_convert_fee(
+    #[case] from: FeeType,
+    #[case] to: FeeType,
+    #[case] rounding: Rounding,
+    #[case] fee: u128,
+    #[case] expected_fee: u128,
+) {
+    // A conversion rate of 3 Fri per 2 Wei.
+    let gas_prices = GasPrices {
+        eth_l1_gas_price: 2_u128.try_into().unwrap(),
+        strk_l1_gas_price: 3_u128.try_into().unwrap(),
+        ..BlockInfo::create_for_testing().gas_prices
+    };
+    let overflow_behavior = OverflowBehavior::Fail;
+    assert_eq!(
+        convert_fee(Fee(fee), &from, &to, &gas_prices, rounding, overflow_behavior),
+        Ok(Fee(expected_fee))
+    );
+
+    // Overflow.
+    let max_fee = Fee(u128::MAX);
+    let (from, to) = (FeeType::Eth, FeeType::Strk);
+    assert_eq!(
+        convert_fee(max_fee, &from, &to, &gas_prices, rounding, OverflowBehavior::Fail),
+        Err(FeeOverflowError::FeeConversion { fee: max_fee, from_price: 2, to_price: 3 })
+    );
+    assert_eq!(
+        convert_fee(max_fee, &from, &to, &gas_prices, rounding, OverflowBehavior::Saturate),
+        Ok(max_fee)
+    );
+}
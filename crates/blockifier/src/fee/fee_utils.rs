@@ -1,6 +1,11 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_rational::Ratio;
+use num_traits::ToPrimitive;
+use starknet_api::core::ContractAddress;
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::Fee;
@@ -8,16 +13,17 @@ use starknet_api::transaction::Fee;
 use crate::abi::abi_utils::get_fee_token_var_address;
 use crate::abi::constants;
 use crate::abi::sierra_types::next_storage_key;
-use crate::blockifier::block::BlockInfo;
+use crate::blockifier::block::{BlockInfo, GasPrices};
 use crate::context::{BlockContext, TransactionContext};
+use crate::fee::gas_usage::compute_discounted_gas_from_gas_vector;
 use crate::state::state_api::StateReader;
-use crate::transaction::errors::TransactionFeeError;
+use crate::transaction::errors::{FeeOverflowError, TransactionFeeError};
 use crate::transaction::objects::{
     ExecutionResourcesTraits, FeeType, GasVector, TransactionFeeResult, TransactionInfo,
     TransactionResources,
 };
 use crate::utils::u128_from_usize;
-use crate::versioned_constants::VersionedConstants;
+use crate::versioned_constants::{ResourceCost, VersionedConstants};
 
 #[cfg(test)]
 #[path = "fee_test.rs"]
@@ -31,6 +37,41 @@ pub fn calculate_l1_gas_by_vm_usage(
     vm_resource_usage: &ExecutionResources,
     n_reverted_steps: usize,
 ) -> TransactionFeeResult<GasVector> {
+    calculate_l1_gas_by_vm_usage_with_rounding(
+        versioned_constants,
+        vm_resource_usage,
+        n_reverted_steps,
+        Rounding::Up,
+    )
+}
+
+/// As [`calculate_l1_gas_by_vm_usage`], with the given rounding of the L1 gas of each resource.
+pub fn calculate_l1_gas_by_vm_usage_with_rounding(
+    versioned_constants: &VersionedConstants,
+    vm_resource_usage: &ExecutionResources,
+    n_reverted_steps: usize,
+    rounding: Rounding,
+) -> TransactionFeeResult<GasVector> {
+    let vm_l1_gas_usage = calculate_l1_gas_by_vm_resource(
+        versioned_constants,
+        vm_resource_usage,
+        n_reverted_steps,
+        rounding,
+    )?
+    .into_values()
+    .fold(0, u128::max);
+
+    Ok(GasVector::from_l1_gas(vm_l1_gas_usage))
+}
+
+/// Returns the weight (in terms of L1 gas) of each Cairo resource with a fee cost, keyed by the
+/// resource name; fractional weights are rounded by the given rounding.
+pub fn calculate_l1_gas_by_vm_resource(
+    versioned_constants: &VersionedConstants,
+    vm_resource_usage: &ExecutionResources,
+    n_reverted_steps: usize,
+    rounding: Rounding,
+) -> TransactionFeeResult<BTreeMap<String, u128>> {
     // TODO(Yoni, 1/7/2024): rename vm -> cairo.
     let vm_resource_fee_costs = versioned_constants.vm_resource_fee_cost();
     let mut vm_resource_usage_for_fee = vm_resource_usage.prover_builtins();
@@ -50,17 +91,57 @@ pub fn calculate_l1_gas_by_vm_usage(
     );
 
     // Convert Cairo usage to L1 gas usage.
-    let vm_l1_gas_usage = vm_resource_fee_costs
+    Ok(vm_resource_fee_costs
         .iter()
         .map(|(key, resource_val)| {
-            ((*resource_val)
-                * u128_from_usize(vm_resource_usage_for_fee.get(key).cloned().unwrap_or_default()))
-            .ceil()
-            .to_integer()
+            let usage = vm_resource_usage_for_fee.get(key).cloned().unwrap_or_default();
+            let l1_gas = rounding.round(*resource_val * u128_from_usize(usage));
+            (key.clone(), l1_gas)
         })
-        .fold(0, u128::max);
+        .collect())
+}
 
-    Ok(GasVector::from_l1_gas(vm_l1_gas_usage))
+/// Converts Cairo resources to Sierra gas, for metering Cairo 0 calls by Sierra gas: each resource
+/// costs its weight (in terms of L1 gas) relative to a step, which costs the step gas cost.
+pub fn calculate_sierra_gas_by_vm_usage(
+    versioned_constants: &VersionedConstants,
+    vm_resource_usage: &ExecutionResources,
+) -> u64 {
+    let vm_resource_fee_costs = versioned_constants.vm_resource_fee_cost();
+    let step_gas_cost = versioned_constants.os_constants.gas_costs.step_gas_cost;
+    let l1_gas_per_step =
+        vm_resource_fee_costs.get(constants::N_STEPS_RESOURCE).copied().unwrap_or_default();
+    let n_steps = u64::try_from(vm_resource_usage.total_n_steps()).unwrap_or(u64::MAX);
+    let steps_gas = n_steps.saturating_mul(step_gas_cost);
+    if l1_gas_per_step == ResourceCost::from_integer(0) {
+        return steps_gas;
+    }
+
+    let builtins_gas: ResourceCost = vm_resource_usage
+        .prover_builtins()
+        .iter()
+        .map(|(name, count)| {
+            let l1_gas_cost = vm_resource_fee_costs.get(name).copied().unwrap_or_default();
+            l1_gas_cost * u128_from_usize(*count) / l1_gas_per_step * u128::from(step_gas_cost)
+        })
+        .sum();
+    steps_gas.saturating_add(u64::try_from(builtins_gas.ceil().to_integer()).unwrap_or(u64::MAX))
+}
+
+/// Converts Sierra gas to L1 gas, by the L1 gas cost of the equivalent steps; for charging Sierra
+/// gas of transactions not charged for L2 gas; fractional L1 gas is rounded by the given rounding.
+pub fn calculate_l1_gas_by_sierra_gas(
+    versioned_constants: &VersionedConstants,
+    sierra_gas: u64,
+    rounding: Rounding,
+) -> u128 {
+    let l1_gas_per_step = versioned_constants
+        .vm_resource_fee_cost()
+        .get(constants::N_STEPS_RESOURCE)
+        .copied()
+        .unwrap_or_default();
+    let step_gas_cost = versioned_constants.os_constants.gas_costs.step_gas_cost.max(1);
+    rounding.round(l1_gas_per_step * u128::from(sierra_gas) / u128::from(step_gas_cost))
 }
 
 /// Converts the gas vector to a fee.
@@ -72,6 +153,7 @@ pub fn get_fee_by_gas_vector(
     gas_vector.saturated_cost(
         u128::from(block_info.gas_prices.get_gas_price_by_fee_type(fee_type)),
         u128::from(block_info.gas_prices.get_data_gas_price_by_fee_type(fee_type)),
+        u128::from(block_info.gas_prices.get_l2_gas_price_by_fee_type(fee_type)),
     )
 }
 
@@ -81,20 +163,113 @@ pub fn calculate_tx_fee(
     block_context: &BlockContext,
     fee_type: &FeeType,
 ) -> TransactionFeeResult<Fee> {
-    let gas_vector = tx_resources
-        .to_gas_vector(&block_context.versioned_constants, block_context.block_info.use_kzg_da)?;
+    let gas_vector = tx_resources.to_gas_vector_with_rounding(
+        &block_context.versioned_constants,
+        block_context.block_info.use_kzg_da,
+        block_context.fee_policy.fee_rounding,
+    )?;
     Ok(get_fee_by_gas_vector(&block_context.block_info, gas_vector, fee_type))
 }
 
-/// Returns the current fee balance and a boolean indicating whether the balance covers the fee.
+/// Returns the cost of the given gas amount at the given price, failing on overflow.
+pub fn checked_mul_price(gas_amount: u128, gas_price: u128) -> Result<Fee, FeeOverflowError> {
+    gas_amount
+        .checked_mul(gas_price)
+        .map(Fee)
+        .ok_or(FeeOverflowError::GasCost { gas_amount, gas_price })
+}
+
+/// Returns the cost of the given gas amount at the given price, saturating on overflow.
+pub fn saturating_mul_price(gas_amount: u128, gas_price: u128) -> Fee {
+    checked_mul_price(gas_amount, gas_price).unwrap_or_else(|error| {
+        log::warn!("{error} Saturating to the maximal fee.");
+        Fee(u128::MAX)
+    })
+}
+
+/// Returns the sum of the given fees, failing on overflow.
+pub fn checked_add_fee(lhs: Fee, rhs: Fee) -> Result<Fee, FeeOverflowError> {
+    lhs.0.checked_add(rhs.0).map(Fee).ok_or(FeeOverflowError::FeeAddition { lhs, rhs })
+}
+
+/// The rounding of fee unit conversions, and of fractional gas amounts.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Rounding {
+    Down,
+    #[default]
+    Up,
+    /// Rounds half up.
+    Nearest,
+}
+
+impl Rounding {
+    pub fn round(self, value: Ratio<u128>) -> u128 {
+        match self {
+            Self::Down => value.floor(),
+            Self::Up => value.ceil(),
+            Self::Nearest => value.round(),
+        }
+        .to_integer()
+    }
+}
+
+/// The behavior of fee unit conversions whose result exceeds the fee range.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OverflowBehavior {
+    #[default]
+    Fail,
+    Saturate,
+}
+
+/// Converts a fee between fee units (Wei and Fri), by the conversion rate of the given gas prices;
+/// i.e., the ratio of their L1 gas prices.
+pub fn convert_fee(
+    fee: Fee,
+    from: &FeeType,
+    to: &FeeType,
+    gas_prices: &GasPrices,
+    rounding: Rounding,
+    overflow_behavior: OverflowBehavior,
+) -> Result<Fee, FeeOverflowError> {
+    let from_price = u128::from(gas_prices.get_gas_price_by_fee_type(from));
+    let to_price = u128::from(gas_prices.get_gas_price_by_fee_type(to));
+    let denominator = BigUint::from(from_price);
+    let (quotient, remainder) = (BigUint::from(fee.0) * to_price).div_rem(&denominator);
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => remainder != BigUint::from(0_u8),
+        Rounding::Nearest => remainder * 2_u8 >= denominator,
+    };
+    let converted_fee = if round_up { quotient + 1_u8 } else { quotient };
+
+    match (converted_fee.to_u128(), overflow_behavior) {
+        (Some(converted_fee), _) => Ok(Fee(converted_fee)),
+        (None, OverflowBehavior::Saturate) => Ok(Fee(u128::MAX)),
+        (None, OverflowBehavior::Fail) => {
+            Err(FeeOverflowError::FeeConversion { fee, from_price, to_price })
+        }
+    }
+}
+
+/// Returns the tip paid to the sequencer on top of the base fee. The tip is paid per unit of
+/// (discounted) L1 gas; transactions prior to V3 have no tip.
+pub fn calculate_tip(tx_context: &TransactionContext, gas_vector: &GasVector) -> Fee {
+    let tip = match &tx_context.tx_info {
+        TransactionInfo::Current(context) => u128::from(context.tip.0),
+        TransactionInfo::Deprecated(_) => return Fee(0),
+    };
+    saturating_mul_price(compute_discounted_gas_from_gas_vector(gas_vector, tx_context), tip)
+}
+
+/// Returns the current fee balance of the fee payer (see [`TransactionContext::fee_payer`]) and a
+/// boolean indicating whether the balance covers the fee.
 pub fn get_balance_and_if_covers_fee(
     state: &mut dyn StateReader,
     tx_context: &TransactionContext,
     fee: Fee,
 ) -> TransactionFeeResult<(StarkFelt, StarkFelt, bool)> {
-    let tx_info = &tx_context.tx_info;
     let (balance_low, balance_high) =
-        state.get_fee_token_balance(tx_info.sender_address(), tx_context.fee_token_address())?;
+        state.get_fee_token_balance(tx_context.fee_payer(), tx_context.fee_token_address())?;
     Ok((
         balance_low,
         balance_high,
@@ -112,21 +287,22 @@ pub fn verify_can_pay_committed_bounds(
 ) -> TransactionFeeResult<()> {
     let tx_info = &tx_context.tx_info;
     let committed_fee = match tx_info {
-        TransactionInfo::Current(context) => {
-            let l1_bounds = context.l1_resource_bounds()?;
-            let max_amount: u128 = l1_bounds.max_amount.into();
-            // Sender will not be charged by `max_price_per_unit`, but this check should not depend
-            // on the current gas price.
-            Fee(max_amount * l1_bounds.max_price_per_unit)
-        }
+        // Sender will not be charged by `max_price_per_unit`, but this check should not depend
+        // on the current gas price.
+        TransactionInfo::Current(context) => context.committed_fee()?,
         TransactionInfo::Deprecated(context) => context.max_fee,
     };
     let (balance_low, balance_high, can_pay) =
         get_balance_and_if_covers_fee(state, tx_context, committed_fee)?;
-    if can_pay {
-        Ok(())
-    } else {
-        Err(match tx_info {
+    if !can_pay {
+        return Err(match tx_info {
+            TransactionInfo::Current(_) if tx_info.l2_gas_limit().is_some() => {
+                TransactionFeeError::ResourceBoundsExceedBalance {
+                    committed_fee,
+                    balance_low,
+                    balance_high,
+                }
+            }
             TransactionInfo::Current(context) => {
                 let l1_bounds = context.l1_resource_bounds()?;
                 TransactionFeeError::L1GasBoundsExceedBalance {
@@ -141,12 +317,40 @@ pub fn verify_can_pay_committed_bounds(
                 balance_low,
                 balance_high,
             },
+        });
+    }
+
+    verify_sponsor_allowance(state, tx_context, committed_fee)
+}
+
+/// Verifies that, given the current state, the allowance the sponsor of the sender (if any)
+/// granted the fee recipient covers the given fee.
+pub fn verify_sponsor_allowance(
+    state: &mut dyn StateReader,
+    tx_context: &TransactionContext,
+    fee: Fee,
+) -> TransactionFeeResult<()> {
+    let Some(sponsor) = tx_context.fee_sponsor() else {
+        return Ok(());
+    };
+    let (allowance_low, allowance_high) = state.get_fee_token_allowance(
+        sponsor,
+        tx_context.fee_recipient(),
+        tx_context.fee_token_address(),
+    )?;
+    if allowance_high > StarkFelt::ZERO || allowance_low >= StarkFelt::from(fee.0) {
+        Ok(())
+    } else {
+        Err(TransactionFeeError::FeeExceedsAllowance {
+            fee,
+            sponsor,
+            allowance_low,
+            allowance_high,
         })
     }
 }
 
-pub fn get_sequencer_balance_keys(block_context: &BlockContext) -> (StorageKey, StorageKey) {
-    let sequencer_address = block_context.block_info.sequencer_address;
+pub fn get_sequencer_balance_keys(sequencer_address: ContractAddress) -> (StorageKey, StorageKey) {
     let sequencer_balance_key_low = get_fee_token_var_address(sequencer_address);
     let sequencer_balance_key_high = next_storage_key(&sequencer_balance_key_low)
         .expect("Cannot get sequencer balance high key.");
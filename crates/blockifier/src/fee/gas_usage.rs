@@ -1,13 +1,21 @@
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use num_rational::Ratio;
+use serde::Serialize;
+use starknet_api::core::ContractAddress;
+use starknet_api::transaction::Fee;
 
-use super::fee_utils::calculate_l1_gas_by_vm_usage;
+use super::fee_utils::{calculate_l1_gas_by_vm_usage_with_rounding, get_fee_by_gas_vector};
 use crate::abi::constants;
+use crate::blockifier::block::{BlockInfo, GasPrices};
 use crate::context::{BlockContext, TransactionContext};
+use crate::execution::call_info::{MessageL1CostInfo, SentL2ToL1Message};
 use crate::fee::eth_gas_constants;
-use crate::state::cached_state::StateChangesCount;
+use crate::state::cached_state::{StateChanges, StateChangesCount};
 use crate::transaction::account_transaction::AccountTransaction;
-use crate::transaction::objects::{GasVector, HasRelatedFeeType, TransactionPreValidationResult};
-use crate::utils::{u128_div_ceil, u128_from_usize};
+use crate::transaction::objects::{
+    FeeType, GasVector, HasRelatedFeeType, TransactionPreValidationResult,
+};
+use crate::utils::u128_from_usize;
 
 #[cfg(test)]
 #[path = "gas_usage_test.rs"]
@@ -72,7 +80,126 @@ pub fn get_da_gas_cost(state_changes_count: &StateChangesCount, use_kzg_da: bool
         (u128_from_usize(gas), 0)
     };
 
-    GasVector { l1_gas, l1_data_gas: blob_gas }
+    GasVector { l1_gas, l1_data_gas: blob_gas, ..Default::default() }
+}
+
+/// The way state diffs are published on L1.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum L1DaMode {
+    /// As calldata, paid for with L1 gas.
+    Calldata,
+    /// As blobs (EIP-4844), paid for with L1 data gas.
+    Blob,
+}
+
+impl L1DaMode {
+    pub fn from_use_kzg_da(use_kzg_da: bool) -> Self {
+        if use_kzg_da {
+            Self::Blob
+        } else {
+            Self::Calldata
+        }
+    }
+
+    pub fn use_kzg_da(&self) -> bool {
+        matches!(self, Self::Blob)
+    }
+}
+
+/// Returns the DA gas a transaction with the given state changes is charged for in the given mode,
+/// as computed by the sequencer (see [`StateChanges::count_for_fee_charge`]): the update of the
+/// sender's balance by the fee transfer is counted, while the modification of the fee token
+/// contract and the update of the sequencer's balance are not, as they are amortized across the
+/// block.
+pub fn compute_da_gas_for_fee_charge(
+    state_changes: &StateChanges,
+    sender_address: Option<ContractAddress>,
+    fee_token_address: ContractAddress,
+    da_mode: L1DaMode,
+) -> GasVector {
+    get_da_gas_cost(
+        &state_changes.count_for_fee_charge(sender_address, fee_token_address),
+        da_mode.use_kzg_da(),
+    )
+}
+
+/// Returns the cost, in Wei, of publishing the given state changes on L1 in the given mode.
+pub fn get_da_cost(
+    state_changes_count: &StateChangesCount,
+    da_mode: L1DaMode,
+    gas_prices: &GasPrices,
+) -> Fee {
+    get_da_gas_cost(state_changes_count, da_mode.use_kzg_da()).saturated_cost(
+        gas_prices.eth_l1_gas_price.into(),
+        gas_prices.eth_l1_data_gas_price.into(),
+        0,
+    )
+}
+
+/// Returns the mode in which publishing the given state changes on L1 is cheaper at the given gas
+/// prices; blob mode is preferred on a tie. Transactions are priced in the mode of their block, so
+/// the mode must be selected before the block is executed; see [`BlockInfo::select_da_mode`].
+pub fn select_da_mode(state_changes_count: &StateChangesCount, gas_prices: &GasPrices) -> L1DaMode {
+    let calldata_cost = get_da_cost(state_changes_count, L1DaMode::Calldata, gas_prices);
+    let blob_cost = get_da_cost(state_changes_count, L1DaMode::Blob, gas_prices);
+    if blob_cost <= calldata_cost {
+        L1DaMode::Blob
+    } else {
+        L1DaMode::Calldata
+    }
+}
+
+/// The size of a hypothetical state diff, by its number of changes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct StateDiffSize {
+    /// The contracts whose storage, class or nonce changed.
+    pub n_modified_contracts: usize,
+    pub n_storage_updates: usize,
+    /// Deployed contracts and class replacements.
+    pub n_class_hash_updates: usize,
+    pub n_declared_classes: usize,
+    /// A nonce is published along with its contract, so nonce updates only count as contract
+    /// modifications.
+    pub n_nonce_updates: usize,
+}
+
+impl From<StateDiffSize> for StateChangesCount {
+    fn from(state_diff_size: StateDiffSize) -> Self {
+        Self {
+            n_storage_updates: state_diff_size.n_storage_updates,
+            n_class_hash_updates: state_diff_size.n_class_hash_updates,
+            n_compiled_class_hash_updates: state_diff_size.n_declared_classes,
+            n_modified_contracts: state_diff_size
+                .n_modified_contracts
+                .max(state_diff_size.n_nonce_updates),
+        }
+    }
+}
+
+/// The gas cost of data availability on L1 in each mode.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct DaGasCosts {
+    pub calldata: GasVector,
+    pub blob: GasVector,
+}
+
+impl DaGasCosts {
+    pub fn get(&self, da_mode: L1DaMode) -> GasVector {
+        match da_mode {
+            L1DaMode::Calldata => self.calldata,
+            L1DaMode::Blob => self.blob,
+        }
+    }
+}
+
+/// Returns the gas cost of publishing a state diff of the given size on L1, in each mode, without
+/// executing anything; e.g., for tuning the bouncer.
+pub fn calculate_da_gas_costs(state_diff_size: &StateDiffSize) -> DaGasCosts {
+    let state_changes_count = StateChangesCount::from(*state_diff_size);
+    DaGasCosts {
+        calldata: get_da_gas_cost(&state_changes_count, false),
+        blob: get_da_gas_cost(&state_changes_count, true),
+    }
 }
 
 /// Returns the number of felts added to the output messages segment as a result of adding
@@ -132,6 +259,90 @@ pub fn get_log_message_to_l1_emissions_cost(l2_to_l1_payload_lengths: &[usize])
         .sum()
 }
 
+/// Returns an estimation of the gas usage for processing L1<>L2 messages on L1. Accounts for
+/// Starknet contract only.
+pub(crate) fn get_starknet_messages_gas_usage(
+    message_cost_info: &MessageL1CostInfo,
+    l1_handler_payload_size: Option<usize>,
+) -> GasVector {
+    let n_l2_to_l1_messages = message_cost_info.l2_to_l1_payload_lengths.len();
+    let n_l1_to_l2_messages = usize::from(l1_handler_payload_size.is_some());
+
+    GasVector::from_l1_gas(
+        // Starknet's updateState gets the message segment as an argument.
+        u128_from_usize(
+            message_cost_info.message_segment_length * eth_gas_constants::GAS_PER_MEMORY_WORD
+            // Starknet's updateState increases a (storage) counter for each L2-to-L1 message.
+            + n_l2_to_l1_messages * eth_gas_constants::GAS_PER_ZERO_TO_NONZERO_STORAGE_SET
+            // Starknet's updateState decreases a (storage) counter for each L1-to-L2 consumed
+            // message (note that we will probably get a refund of 15,000 gas for each consumed
+            // message but we ignore it since refunded gas cannot be used for the current
+            // transaction execution).
+            + n_l1_to_l2_messages * eth_gas_constants::GAS_PER_COUNTER_DECREASE,
+        ),
+    ) + get_consumed_message_to_l2_emissions_cost(l1_handler_payload_size)
+        + get_log_message_to_l1_emissions_cost(&message_cost_info.l2_to_l1_payload_lengths)
+}
+
+/// Returns an estimation of the gas usage for processing L1<>L2 messages on L1. Accounts for
+/// both Starknet and SHARP contracts.
+pub fn get_messages_gas_cost(
+    message_cost_info: &MessageL1CostInfo,
+    l1_handler_payload_size: Option<usize>,
+) -> GasVector {
+    let starknet_gas_usage =
+        get_starknet_messages_gas_usage(message_cost_info, l1_handler_payload_size);
+    let sharp_gas_usage = GasVector::from_l1_gas(u128_from_usize(
+        message_cost_info.message_segment_length * eth_gas_constants::SHARP_GAS_PER_MEMORY_WORD,
+    ));
+
+    starknet_gas_usage + sharp_gas_usage
+}
+
+/// Returns the fee of processing the given messages on L1, independently of transaction
+/// execution; i.e., of L2-to-L1 messages with the given payload lengths, and, for L1 handlers,
+/// of consuming the L1-to-L2 message with the given payload size. Intended for quoting message
+/// costs.
+pub fn get_messages_fee(
+    l2_to_l1_payload_lengths: &[usize],
+    l1_handler_payload_size: Option<usize>,
+    block_info: &BlockInfo,
+    fee_type: &FeeType,
+) -> Fee {
+    let message_cost_info =
+        MessageL1CostInfo::new(l2_to_l1_payload_lengths.to_vec(), l1_handler_payload_size);
+    let gas_vector = get_messages_gas_cost(&message_cost_info, l1_handler_payload_size);
+    get_fee_by_gas_vector(block_info, gas_vector, fee_type)
+}
+
+/// The L1 cost attributable to a single L2-to-L1 message.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct L2ToL1MessageCost {
+    pub payload_length: usize,
+    /// The number of felts the message adds to the output messages segment.
+    pub segment_length: usize,
+    /// The gas consumed by processing the message on L1, by both Starknet and SHARP contracts.
+    pub gas: GasVector,
+}
+
+impl L2ToL1MessageCost {
+    pub fn new(payload_length: usize) -> Self {
+        let message_cost_info = MessageL1CostInfo::new(vec![payload_length], None);
+        let gas = get_messages_gas_cost(&message_cost_info, None);
+
+        Self { payload_length, segment_length: message_cost_info.message_segment_length, gas }
+    }
+}
+
+/// Returns the L1 cost attributable to each of the given L2-to-L1 messages, in order.
+/// The costs sum up to the L2-to-L1 messages part of the transaction's messages cost.
+pub fn get_l2_to_l1_messages_costs(messages: &[SentL2ToL1Message]) -> Vec<L2ToL1MessageCost> {
+    messages
+        .iter()
+        .map(|message| L2ToL1MessageCost::new(message.message.message.payload.0.len()))
+        .collect()
+}
+
 fn get_event_emission_cost(n_topics: usize, data_length: usize) -> GasVector {
     GasVector::from_l1_gas(u128_from_usize(
         eth_gas_constants::GAS_PER_LOG
@@ -180,7 +391,12 @@ pub fn estimate_minimal_gas_vector(
 
     let resources = ExecutionResources { n_steps: os_steps_for_type, ..Default::default() };
     Ok(get_da_gas_cost(&state_changes_by_account_transaction, block_info.use_kzg_da)
-        + calculate_l1_gas_by_vm_usage(versioned_constants, &resources, 0)?)
+        + calculate_l1_gas_by_vm_usage_with_rounding(
+            versioned_constants,
+            &resources,
+            0,
+            block_context.fee_policy.fee_rounding,
+        )?)
 }
 
 /// Compute l1_gas estimation from gas_vector using the following formula:
@@ -189,15 +405,17 @@ pub fn estimate_minimal_gas_vector(
 /// would be DGP / (16 * GP).
 /// X non-data-related gas consumption and Y bytes of data, in non-blob mode, would
 /// cost (X + 16*Y) units of gas. Applying the discount ratio to the data-related
-/// summand, we get total_gas = (X + Y * DGP / GP).
+/// summand, we get total_gas = (X + Y * DGP / GP); rounded by the fee rounding of the block.
 pub fn compute_discounted_gas_from_gas_vector(
     gas_usage_vector: &GasVector,
     tx_context: &TransactionContext,
 ) -> u128 {
     let gas_prices = &tx_context.block_context.block_info.gas_prices;
-    let GasVector { l1_gas: gas_usage, l1_data_gas: blob_gas_usage } = gas_usage_vector;
+    let GasVector { l1_gas: gas_usage, l1_data_gas: blob_gas_usage, .. } = gas_usage_vector;
     let fee_type = tx_context.tx_info.fee_type();
     let gas_price = gas_prices.get_gas_price_by_fee_type(&fee_type);
     let data_gas_price = gas_prices.get_data_gas_price_by_fee_type(&fee_type);
-    gas_usage + u128_div_ceil(blob_gas_usage * u128::from(data_gas_price), gas_price)
+    let data_gas_in_l1_gas =
+        Ratio::new(blob_gas_usage * u128::from(data_gas_price), u128::from(gas_price));
+    gas_usage + tx_context.block_context.fee_policy.fee_rounding.round(data_gas_in_l1_gas)
 }
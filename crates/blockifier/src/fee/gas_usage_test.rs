@@ -1,20 +1,28 @@
+use std::collections::HashMap;
 use std::num::NonZeroU128;
 
 use pretty_assertions::assert_eq;
 use rstest::{fixture, rstest};
+use starknet_api::core::ContractAddress;
 use starknet_api::hash::StarkFelt;
-use starknet_api::transaction::{EventContent, EventData, EventKey, Fee};
+use starknet_api::transaction::{EventContent, EventData, EventKey, Fee, L2ToL1Payload};
 
+use crate::abi::abi_utils::get_fee_token_var_address;
 use crate::abi::constants;
+use crate::blockifier::block::BlockInfo;
 use crate::context::BlockContext;
-use crate::execution::call_info::{CallExecution, CallInfo, OrderedEvent};
+use crate::execution::call_info::{
+    CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message,
+};
 use crate::fee::eth_gas_constants;
 use crate::fee::fee_utils::get_fee_by_gas_vector;
 use crate::fee::gas_usage::{
-    compute_discounted_gas_from_gas_vector, get_da_gas_cost, get_message_segment_length,
+    calculate_da_gas_costs, compute_da_gas_for_fee_charge, compute_discounted_gas_from_gas_vector,
+    get_da_cost, get_da_gas_cost, get_l2_to_l1_messages_costs, get_message_segment_length,
+    get_messages_fee, select_da_mode, L1DaMode, StateDiffSize,
 };
 use crate::invoke_tx_args;
-use crate::state::cached_state::StateChangesCount;
+use crate::state::cached_state::{StateChanges, StateChangesCount};
 use crate::test_utils::{DEFAULT_ETH_L1_DATA_GAS_PRICE, DEFAULT_ETH_L1_GAS_PRICE};
 use crate::transaction::objects::{FeeType, GasVector, StarknetResources};
 use crate::transaction::test_utils::account_invoke_tx;
@@ -178,6 +186,103 @@ fn test_onchain_data_discount() {
     assert!(cost_ratio >= ResourceCost::new(88, 100));
 }
 
+#[test]
+fn test_select_da_mode() {
+    let state_changes_count = StateChangesCount {
+        n_storage_updates: 10,
+        n_class_hash_updates: 1,
+        n_compiled_class_hash_updates: 1,
+        n_modified_contracts: 3,
+    };
+    let mut gas_prices = BlockInfo::create_for_testing().gas_prices;
+
+    for da_mode in [L1DaMode::Calldata, L1DaMode::Blob] {
+        let gas_vector = get_da_gas_cost(&state_changes_count, da_mode.use_kzg_da());
+        assert_eq!(
+            get_da_cost(&state_changes_count, da_mode, &gas_prices),
+            Fee(gas_vector.l1_gas * DEFAULT_ETH_L1_GAS_PRICE
+                + gas_vector.l1_data_gas * DEFAULT_ETH_L1_DATA_GAS_PRICE)
+        );
+    }
+    assert_eq!(select_da_mode(&state_changes_count, &gas_prices), L1DaMode::Blob);
+
+    // Calldata is cheaper once blob space is in high demand.
+    gas_prices.eth_l1_data_gas_price = NonZeroU128::new(100 * DEFAULT_ETH_L1_GAS_PRICE).unwrap();
+    assert_eq!(select_da_mode(&state_changes_count, &gas_prices), L1DaMode::Calldata);
+
+    // Nothing to publish; blob mode is preferred.
+    assert_eq!(select_da_mode(&StateChangesCount::default(), &gas_prices), L1DaMode::Blob);
+}
+
+#[rstest]
+fn test_compute_da_gas_for_fee_charge(
+    #[values(L1DaMode::Calldata, L1DaMode::Blob)] da_mode: L1DaMode,
+) {
+    let sender_address = ContractAddress::from(1_u64);
+    let fee_token_address = ContractAddress::from(2_u64);
+    let other_address = ContractAddress::from(3_u64);
+    let mut state_changes = StateChanges {
+        storage_updates: HashMap::from([
+            ((other_address, get_fee_token_var_address(other_address)), StarkFelt::ONE),
+            ((fee_token_address, get_fee_token_var_address(other_address)), StarkFelt::ONE),
+        ]),
+        ..Default::default()
+    };
+    // The sender balance update is counted, and the fee token contract is not.
+    let expected_da_gas = get_da_gas_cost(
+        &StateChangesCount {
+            n_storage_updates: 3,
+            n_class_hash_updates: 0,
+            n_compiled_class_hash_updates: 0,
+            n_modified_contracts: 1,
+        },
+        da_mode.use_kzg_da(),
+    );
+    let da_gas_for_fee_charge = |state_changes: &StateChanges| {
+        compute_da_gas_for_fee_charge(
+            state_changes,
+            Some(sender_address),
+            fee_token_address,
+            da_mode,
+        )
+    };
+    assert_eq!(da_gas_for_fee_charge(&state_changes), expected_da_gas);
+
+    // The sender balance update is counted once.
+    state_changes
+        .storage_updates
+        .insert((fee_token_address, get_fee_token_var_address(sender_address)), StarkFelt::ONE);
+    assert_eq!(da_gas_for_fee_charge(&state_changes), expected_da_gas);
+}
+
+#[test]
+fn test_calculate_da_gas_costs() {
+    let state_diff_size = StateDiffSize {
+        n_modified_contracts: 2,
+        n_storage_updates: 10,
+        n_class_hash_updates: 1,
+        n_declared_classes: 1,
+        n_nonce_updates: 3,
+    };
+    // Each contract whose nonce was updated is modified.
+    let state_changes_count = StateChangesCount {
+        n_storage_updates: 10,
+        n_class_hash_updates: 1,
+        n_compiled_class_hash_updates: 1,
+        n_modified_contracts: 3,
+    };
+
+    let da_gas_costs = calculate_da_gas_costs(&state_diff_size);
+    for da_mode in [L1DaMode::Calldata, L1DaMode::Blob] {
+        assert_eq!(
+            da_gas_costs.get(da_mode),
+            get_da_gas_cost(&state_changes_count, da_mode.use_kzg_da())
+        );
+    }
+    assert_eq!(da_gas_costs.calldata.l1_data_gas, 0);
+    assert_eq!(da_gas_costs.blob.l1_gas, 0);
+}
+
 #[rstest]
 #[case(vec![10, 20, 30], Some(50))]
 #[case(vec![10, 20, 30], None)]
@@ -201,11 +306,84 @@ fn test_get_message_segment_length(
     assert_eq!(result, expected_result);
 }
 
+#[rstest]
+fn test_l2_to_l1_messages_costs() {
+    let message = |order: usize, payload_length: usize| OrderedL2ToL1Message {
+        order,
+        message: MessageToL1 {
+            payload: L2ToL1Payload(vec![StarkFelt::ONE; payload_length]),
+            ..Default::default()
+        },
+    };
+    let call_info = CallInfo {
+        execution: CallExecution {
+            l2_to_l1_messages: vec![message(0, 0), message(1, 3)],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let messages = call_info.emitted_effects().l2_to_l1_messages;
+
+    let costs = get_l2_to_l1_messages_costs(&messages);
+    assert_eq!(
+        costs.iter().map(|cost| cost.segment_length).collect::<Vec<_>>(),
+        vec![constants::L2_TO_L1_MSG_HEADER_SIZE, constants::L2_TO_L1_MSG_HEADER_SIZE + 3]
+    );
+    assert!(costs[0].gas.l1_gas < costs[1].gas.l1_gas);
+
+    // The per-message costs add up to the messages cost the transaction is charged for.
+    let starknet_resources =
+        StarknetResources::new(0, 0, 0, StateChangesCount::default(), None, [call_info].iter());
+    assert_eq!(
+        costs.iter().map(|cost| cost.gas).sum::<GasVector>(),
+        starknet_resources.get_messages_cost()
+    );
+}
+
+#[rstest]
+fn test_get_messages_fee(
+    #[values(None, Some(5))] l1_handler_payload_size: Option<usize>,
+    #[values(FeeType::Eth, FeeType::Strk)] fee_type: FeeType,
+) {
+    let message = |order: usize, payload_length: usize| OrderedL2ToL1Message {
+        order,
+        message: MessageToL1 {
+            payload: L2ToL1Payload(vec![StarkFelt::ONE; payload_length]),
+            ..Default::default()
+        },
+    };
+    let call_info = CallInfo {
+        execution: CallExecution {
+            l2_to_l1_messages: vec![message(0, 2), message(1, 3)],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let block_info = BlockInfo::create_for_testing();
+
+    // The quote matches the messages cost of a transaction sending the same messages.
+    let starknet_resources = StarknetResources::new(
+        0,
+        0,
+        0,
+        StateChangesCount::default(),
+        l1_handler_payload_size,
+        [call_info].iter(),
+    );
+    let expected_fee =
+        get_fee_by_gas_vector(&block_info, starknet_resources.get_messages_cost(), &fee_type);
+    assert_eq!(
+        get_messages_fee(&[2, 3], l1_handler_payload_size, &block_info, &fee_type),
+        expected_fee
+    );
+    assert!(expected_fee > Fee(0));
+}
+
 #[rstest]
 fn test_compute_discounted_gas_from_gas_vector() {
     let tx_context =
         BlockContext::create_for_testing().to_tx_context(&account_invoke_tx(invoke_tx_args! {}));
-    let gas_usage = GasVector { l1_gas: 100, l1_data_gas: 2 };
+    let gas_usage = GasVector { l1_gas: 100, l1_data_gas: 2, ..Default::default() };
     let actual_result = compute_discounted_gas_from_gas_vector(&gas_usage, &tx_context);
 
     let result_div_ceil = gas_usage.l1_gas
@@ -346,7 +346,7 @@ impl From<StorageView> for IndexMap<ContractAddress, IndexMap<StorageKey, StarkF
     }
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct StateMaps {
     pub(crate) nonces: HashMap<ContractAddress, Nonce>,
     pub(crate) class_hashes: HashMap<ContractAddress, ClassHash>,
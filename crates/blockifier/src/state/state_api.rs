@@ -4,7 +4,7 @@ use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
 
-use crate::abi::abi_utils::get_fee_token_var_address;
+use crate::abi::abi_utils::{get_fee_token_allowance_var_address, get_fee_token_var_address};
 use crate::abi::sierra_types::next_storage_key;
 use crate::execution::contract_class::ContractClass;
 use crate::state::errors::StateError;
@@ -62,6 +62,50 @@ pub trait StateReader {
 
         Ok((low, high))
     }
+
+    /// Returns the storage value representing the allowance (in fee token) the owner granted the
+    /// spender.
+    fn get_fee_token_allowance(
+        &mut self,
+        owner: ContractAddress,
+        spender: ContractAddress,
+        fee_token_address: ContractAddress,
+    ) -> Result<(StarkFelt, StarkFelt), StateError> {
+        let low_key = get_fee_token_allowance_var_address(owner, spender);
+        let high_key = next_storage_key(&low_key)?;
+        let low = self.get_storage_at(fee_token_address, low_key)?;
+        let high = self.get_storage_at(fee_token_address, high_key)?;
+
+        Ok((low, high))
+    }
+}
+
+/// Allows sharing a reader among several states; e.g., among cached states executing on top of
+/// the same snapshot.
+impl<S: StateReader + ?Sized> StateReader for &S {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        (**self).get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        (**self).get_nonce_at(contract_address)
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        (**self).get_class_hash_at(contract_address)
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        (**self).get_compiled_contract_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        (**self).get_compiled_class_hash(class_hash)
+    }
 }
 
 /// A class defining the API for writing to Starknet global state.
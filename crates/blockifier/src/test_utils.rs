@@ -48,6 +48,8 @@ pub const TEST_ERC20_CONTRACT_CLASS_HASH: &str = "0x1010";
 // Paths.
 pub const ERC20_CONTRACT_PATH: &str =
     "./ERC20_without_some_syscalls/ERC20/erc20_contract_without_some_syscalls_compiled.json";
+pub const MINIMAL_CONTRACT_SIERRA_PATH: &str =
+    "./feature_contracts/cairo1/compiled/minimal_contract.sierra.json";
 
 #[derive(Clone, Copy, Debug)]
 pub enum CairoVersion {
@@ -94,6 +96,8 @@ pub const DEFAULT_ETH_L1_GAS_PRICE: u128 = 100 * u128::pow(10, 9); // Given in u
 pub const DEFAULT_STRK_L1_GAS_PRICE: u128 = 100 * u128::pow(10, 9); // Given in units of STRK.
 pub const DEFAULT_ETH_L1_DATA_GAS_PRICE: u128 = u128::pow(10, 6); // Given in units of Wei.
 pub const DEFAULT_STRK_L1_DATA_GAS_PRICE: u128 = u128::pow(10, 9); // Given in units of STRK.
+pub const DEFAULT_ETH_L2_GAS_PRICE: u128 = u128::pow(10, 6); // Given in units of Wei.
+pub const DEFAULT_STRK_L2_GAS_PRICE: u128 = u128::pow(10, 6); // Given in units of STRK.
 
 // The block number of the BlockContext being used for testing.
 pub const CURRENT_BLOCK_NUMBER: u64 = 2001;
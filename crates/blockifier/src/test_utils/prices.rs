@@ -9,7 +9,9 @@ use starknet_api::{calldata, stark_felt};
 
 use crate::abi::abi_utils::{get_fee_token_var_address, selector_from_name};
 use crate::context::BlockContext;
+use crate::execution::call_info::CallInfo;
 use crate::execution::common_hints::ExecutionMode;
+use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
 use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
 use crate::state::state_api::State;
 use crate::test_utils::initial_test_state::test_state;
@@ -42,6 +44,19 @@ fn fee_transfer_resources(
     account_contract_address: ContractAddress,
     fee_type: FeeType,
 ) -> ExecutionResources {
+    execute_fee_transfer(account_contract_address, fee_type).resources
+}
+
+/// Returns the expected syscall invocations of a fee transfer call from the given address.
+#[cached]
+pub fn fee_transfer_syscall_counter(
+    account_contract_address: ContractAddress,
+    fee_type: FeeType,
+) -> SyscallCounter {
+    execute_fee_transfer(account_contract_address, fee_type).syscall_counter
+}
+
+fn execute_fee_transfer(account_contract_address: ContractAddress, fee_type: FeeType) -> CallInfo {
     let block_context = &BlockContext::create_for_account_testing();
     let chain_info = &block_context.chain_info;
     let state = &mut test_state(chain_info, BALANCE, &[]);
@@ -80,5 +95,4 @@ fn fee_transfer_resources(
             .unwrap(),
         )
         .unwrap()
-        .resources
 }
@@ -10,7 +10,10 @@ use starknet_api::{contract_address, patricia_key};
 use super::update_json_value;
 use crate::blockifier::block::{BlockInfo, GasPrices};
 use crate::bouncer::{BouncerConfig, BouncerWeights, BuiltinCount};
-use crate::context::{BlockContext, ChainInfo, FeeTokenAddresses, TransactionContext};
+use crate::context::{
+    BlockContext, ChainInfo, ExecutionPolicy, ExtensionHooks, FeePolicy, FeeTokenAddresses,
+    TransactionContext,
+};
 use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
 use crate::execution::contract_class::{ContractClassV0, ContractClassV1};
 use crate::execution::entry_point::{
@@ -19,9 +22,9 @@ use crate::execution::entry_point::{
 use crate::state::state_api::State;
 use crate::test_utils::{
     get_raw_contract_class, CHAIN_ID_NAME, CURRENT_BLOCK_NUMBER, CURRENT_BLOCK_TIMESTAMP,
-    DEFAULT_ETH_L1_DATA_GAS_PRICE, DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_STRK_L1_DATA_GAS_PRICE,
-    DEFAULT_STRK_L1_GAS_PRICE, TEST_ERC20_CONTRACT_ADDRESS, TEST_ERC20_CONTRACT_ADDRESS2,
-    TEST_SEQUENCER_ADDRESS,
+    DEFAULT_ETH_L1_DATA_GAS_PRICE, DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_ETH_L2_GAS_PRICE,
+    DEFAULT_STRK_L1_DATA_GAS_PRICE, DEFAULT_STRK_L1_GAS_PRICE, DEFAULT_STRK_L2_GAS_PRICE,
+    TEST_ERC20_CONTRACT_ADDRESS, TEST_ERC20_CONTRACT_ADDRESS2, TEST_SEQUENCER_ADDRESS,
 };
 use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
 use crate::versioned_constants::{
@@ -53,6 +56,20 @@ impl CallEntryPoint {
         self.execute(state, &mut ExecutionResources::default(), &mut context)
     }
 
+    /// Executes the call directly, without account context, in the given block context. Limits
+    /// the number of steps by resource bounds.
+    pub fn execute_directly_given_block_context(
+        self,
+        state: &mut dyn State,
+        block_context: BlockContext,
+    ) -> EntryPointExecutionResult<CallInfo> {
+        let mut context = EntryPointExecutionContext::create_for_testing(
+            block_context,
+            TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+        );
+        self.execute(state, &mut ExecutionResources::default(), &mut context)
+    }
+
     /// Executes the call directly in validate mode, without account context. Limits the number of
     /// steps by resource bounds.
     pub fn execute_directly_in_validate_mode(
@@ -81,6 +98,34 @@ impl CallEntryPoint {
         .unwrap();
         self.execute(state, &mut ExecutionResources::default(), &mut context)
     }
+
+    /// Executes the call directly in validate mode, without account context, under the given
+    /// versioned constants.
+    pub fn execute_directly_in_validate_mode_given_versioned_constants(
+        self,
+        state: &mut dyn State,
+        versioned_constants: VersionedConstants,
+    ) -> EntryPointExecutionResult<CallInfo> {
+        let tx_context = TransactionContext {
+            block_context: BlockContext {
+                versioned_constants,
+                ..BlockContext::create_for_testing()
+            },
+            tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+        };
+        let mut context =
+            EntryPointExecutionContext::new_validate(Arc::new(tx_context), true).unwrap();
+        self.execute(state, &mut ExecutionResources::default(), &mut context)
+    }
+}
+
+impl EntryPointExecutionContext {
+    /// An invoke context for executing calls directly, without account context, in the given block
+    /// context. Limits the number of steps by resource bounds.
+    pub fn create_for_testing(block_context: BlockContext, tx_info: TransactionInfo) -> Self {
+        let tx_context = TransactionContext { block_context, tx_info };
+        Self::new_invoke(Arc::new(tx_context), true).unwrap()
+    }
 }
 
 impl VersionedConstants {
@@ -111,6 +156,8 @@ impl ChainInfo {
                 eth_fee_token_address: contract_address!(TEST_ERC20_CONTRACT_ADDRESS),
                 strk_fee_token_address: contract_address!(TEST_ERC20_CONTRACT_ADDRESS2),
             },
+            allowed_custom_hints: Default::default(),
+            allow_deploy_from_zero: true,
         }
     }
 }
@@ -126,6 +173,8 @@ impl BlockInfo {
                 strk_l1_gas_price: DEFAULT_STRK_L1_GAS_PRICE.try_into().unwrap(),
                 eth_l1_data_gas_price: DEFAULT_ETH_L1_DATA_GAS_PRICE.try_into().unwrap(),
                 strk_l1_data_gas_price: DEFAULT_STRK_L1_DATA_GAS_PRICE.try_into().unwrap(),
+                eth_l2_gas_price: DEFAULT_ETH_L2_GAS_PRICE.try_into().unwrap(),
+                strk_l2_gas_price: DEFAULT_STRK_L2_GAS_PRICE.try_into().unwrap(),
             },
             use_kzg_da: false,
         }
@@ -143,6 +192,9 @@ impl BlockContext {
             chain_info: ChainInfo::create_for_testing(),
             versioned_constants: VersionedConstants::create_for_testing(),
             concurrency_mode: false,
+            fee_policy: FeePolicy::default(),
+            execution_policy: ExecutionPolicy::default(),
+            hooks: ExtensionHooks::default(),
         }
     }
 
@@ -152,6 +204,9 @@ impl BlockContext {
             chain_info: ChainInfo::create_for_testing(),
             versioned_constants: VersionedConstants::create_for_account_testing(),
             concurrency_mode: false,
+            fee_policy: FeePolicy::default(),
+            execution_policy: ExecutionPolicy::default(),
+            hooks: ExtensionHooks::default(),
         }
     }
 
@@ -194,6 +249,7 @@ impl BouncerConfig {
         Self {
             block_max_capacity_with_keccak: BouncerWeights::create_for_testing(true),
             block_max_capacity: BouncerWeights::create_for_testing(false),
+            min_tx_weights: BouncerWeights::default(),
         }
     }
 }
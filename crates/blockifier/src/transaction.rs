@@ -1,9 +1,19 @@
 pub mod account_transaction;
 pub mod constants;
 pub mod errors;
+pub mod execution_info_diff;
+pub mod execution_record;
+pub mod multicall;
 pub mod objects;
+pub mod ordered_effects;
+pub mod os_execution_helper;
+pub mod outside_execution;
+pub mod receipt;
+pub mod signature_verification;
+pub mod stateless_validation;
 #[cfg(any(feature = "testing", test))]
 pub mod test_utils;
+pub mod trace;
 pub mod transaction_execution;
 pub mod transaction_types;
 pub mod transaction_utils;
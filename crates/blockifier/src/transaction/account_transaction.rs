@@ -8,12 +8,14 @@ use starknet_api::hash::StarkFelt;
 use starknet_api::transaction::{Calldata, Fee, ResourceBounds, TransactionVersion};
 
 use crate::abi::abi_utils::selector_from_name;
-use crate::context::{BlockContext, TransactionContext};
-use crate::execution::call_info::{CallInfo, Retdata};
+use crate::blockifier::hooks::{NoopHooks, TransactionExecutorHooks};
+use crate::context::{BlockContext, SizeLimits, TransactionContext, ValidateLimit};
+use crate::execution::call_info::{CallInfo, EmittedEffects, Retdata};
 use crate::execution::contract_class::ContractClass;
 use crate::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
+use crate::execution::errors::gen_revert_reason;
 use crate::fee::actual_cost::TransactionReceipt;
-use crate::fee::fee_checks::{FeeCheckReportFields, PostExecutionReport};
+use crate::fee::fee_checks::{FeeChargeFailurePolicy, FeeCheckReportFields, PostExecutionReport};
 use crate::fee::fee_utils::{
     get_fee_by_gas_vector, get_sequencer_balance_keys, verify_can_pay_committed_bounds,
 };
@@ -26,12 +28,14 @@ use crate::transaction::errors::{
     TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
 };
 use crate::transaction::objects::{
-    DeprecatedTransactionInfo, HasRelatedFeeType, TransactionExecutionInfo,
-    TransactionExecutionResult, TransactionInfo, TransactionInfoCreator,
+    DeprecatedTransactionInfo, HasRelatedFeeType, RevertReason, TransactionExecutionInfo,
+    TransactionExecutionResult, TransactionFeeResult, TransactionInfo, TransactionInfoCreator,
     TransactionPreValidationResult,
 };
 use crate::transaction::transaction_types::TransactionType;
-use crate::transaction::transaction_utils::update_remaining_gas;
+use crate::transaction::transaction_utils::{
+    counterfactual_sender_class_hash, update_remaining_gas,
+};
 use crate::transaction::transactions::{
     DeclareTransaction, DeployAccountTransaction, Executable, ExecutableTransaction,
     InvokeTransaction, ValidatableTransaction,
@@ -85,6 +89,15 @@ impl AccountTransaction {
         }
     }
 
+    /// Directs the transaction's fee to the given address instead of the block's sequencer.
+    pub fn with_fee_recipient(self, fee_recipient: ContractAddress) -> Self {
+        match self {
+            Self::Declare(tx) => Self::Declare(tx.with_fee_recipient(fee_recipient)),
+            Self::DeployAccount(tx) => Self::DeployAccount(tx.with_fee_recipient(fee_recipient)),
+            Self::Invoke(tx) => Self::Invoke(tx.with_fee_recipient(fee_recipient)),
+        }
+    }
+
     fn validate_entry_point_selector(&self) -> EntryPointSelector {
         let validate_entry_point_name = match self {
             Self::Declare(_) => constants::VALIDATE_DECLARE_ENTRY_POINT_NAME,
@@ -98,7 +111,7 @@ impl AccountTransaction {
     // `et_tx_info()`.
     fn validate_entrypoint_calldata(&self) -> Calldata {
         match self {
-            Self::Declare(tx) => calldata![tx.class_hash().0],
+            Self::Declare(tx) => tx.validate_calldata(),
             Self::DeployAccount(tx) => Calldata(
                 [
                     vec![tx.class_hash().0, tx.contract_address_salt().0],
@@ -167,6 +180,7 @@ impl AccountTransaction {
         strict_nonce_check: bool,
     ) -> TransactionPreValidationResult<()> {
         let tx_info = &tx_context.tx_info;
+        self.check_size_limits(tx_context.block_context.execution_policy.size_limits)?;
         Self::handle_nonce(state, tx_info, strict_nonce_check)?;
 
         if charge_fee && tx_info.enforce_fee()? {
@@ -178,6 +192,13 @@ impl AccountTransaction {
         Ok(())
     }
 
+    fn check_size_limits(&self, size_limits: SizeLimits) -> TransactionPreValidationResult<()> {
+        size_limits.check_calldata_length(self.calldata_length())?;
+        size_limits.check_signature_length(self.signature_length())?;
+
+        Ok(())
+    }
+
     fn check_fee_bounds(
         &self,
         tx_context: &TransactionContext,
@@ -217,6 +238,43 @@ impl AccountTransaction {
                         actual_l1_gas_price: actual_l1_gas_price.into(),
                     })?;
                 }
+
+                // The tip is paid per gas unit on top of the gas price, within the price bound.
+                let tip = context.tip;
+                if max_l1_gas_price - u128::from(actual_l1_gas_price) < u128::from(tip.0) {
+                    return Err(TransactionFeeError::MaxL1GasPriceTooLowForTip {
+                        max_l1_gas_price,
+                        actual_l1_gas_price: actual_l1_gas_price.into(),
+                        tip,
+                    })?;
+                }
+
+                if tx_info.l2_gas_limit().is_some() {
+                    let max_l2_gas_price = context.l2_resource_bounds().max_price_per_unit;
+                    let actual_l2_gas_price =
+                        block_info.gas_prices.get_l2_gas_price_by_fee_type(fee_type);
+                    if max_l2_gas_price < actual_l2_gas_price.into() {
+                        return Err(TransactionFeeError::MaxL2GasPriceTooLow {
+                            max_l2_gas_price,
+                            actual_l2_gas_price: actual_l2_gas_price.into(),
+                        })?;
+                    }
+                }
+
+                let fee_floor = block_context.fee_policy.fee_floor;
+                if max_l1_gas_price < fee_floor.min_l1_gas_price {
+                    return Err(TransactionFeeError::L1GasPriceBelowFloor {
+                        min_l1_gas_price: fee_floor.min_l1_gas_price,
+                        max_l1_gas_price,
+                    })?;
+                }
+                let committed_fee = context.committed_fee()?;
+                if committed_fee < fee_floor.min_fee {
+                    return Err(TransactionFeeError::FeeBelowFloor {
+                        min_fee: fee_floor.min_fee,
+                        offered_fee: committed_fee,
+                    })?;
+                }
             }
             TransactionInfo::Deprecated(context) => {
                 let max_fee = context.max_fee;
@@ -225,6 +283,13 @@ impl AccountTransaction {
                 if max_fee < min_fee {
                     return Err(TransactionFeeError::MaxFeeTooLow { min_fee, max_fee })?;
                 }
+                let min_fee = block_context.fee_policy.fee_floor.min_fee;
+                if max_fee < min_fee {
+                    return Err(TransactionFeeError::FeeBelowFloor {
+                        min_fee,
+                        offered_fee: max_fee,
+                    })?;
+                }
             }
         };
         Ok(())
@@ -283,7 +348,7 @@ impl AccountTransaction {
                     max_amount: max_l1_gas_amount,
                     max_price_per_unit: max_l1_gas_price,
                 } = context.l1_resource_bounds()?;
-                if actual_fee > Fee(u128::from(max_l1_gas_amount) * max_l1_gas_price) {
+                if actual_fee > context.committed_fee()? {
                     panic!(
                         "Actual fee {:#?} exceeded bounds; max amount is {:#?}, max price is
                          {:#?}.",
@@ -303,6 +368,31 @@ impl AccountTransaction {
         Ok(())
     }
 
+    /// Returns the fee to charge; the fee of reverted transactions is determined by the reverted
+    /// transaction fee policy, and under a relaxed max fee check, the actual fee of deprecated
+    /// transactions is clamped to their max fee.
+    fn fee_to_charge(
+        tx_context: &TransactionContext,
+        actual_fee: Fee,
+        is_reverted: bool,
+    ) -> TransactionFeeResult<Fee> {
+        let block_context = &tx_context.block_context;
+        let fee = if is_reverted {
+            let max_fee = tx_context.tx_info.max_fee_bound()?;
+            block_context.fee_policy.reverted_tx_fee_policy.fee_to_charge(actual_fee, max_fee)
+        } else {
+            actual_fee
+        };
+        Ok(match &tx_context.tx_info {
+            TransactionInfo::Deprecated(DeprecatedTransactionInfo { max_fee, .. })
+                if block_context.fee_policy.relax_max_fee_check =>
+            {
+                std::cmp::min(fee, *max_fee)
+            }
+            _ => fee,
+        })
+    }
+
     fn handle_fee<S: StateReader>(
         &self,
         state: &mut TransactionalState<'_, S>,
@@ -318,10 +408,7 @@ impl AccountTransaction {
         // TODO(Amos, 8/04/2024): Add test for this assert.
         Self::assert_actual_fee_in_bounds(&tx_context, actual_fee)?;
 
-        let fee_transfer_call_info = if tx_context.block_context.concurrency_mode
-            && tx_context.block_context.block_info.sequencer_address
-                != tx_context.tx_info.sender_address()
-        {
+        let fee_transfer_call_info = if tx_context.is_concurrent_fee_transfer() {
             Self::concurrency_execute_fee_transfer(state, tx_context, actual_fee)?
         } else {
             Self::execute_fee_transfer(state, tx_context, actual_fee)?
@@ -340,20 +427,40 @@ impl AccountTransaction {
         // The most significant 128 bits of the amount transferred.
         let msb_amount = StarkFelt::from(0_u8);
 
+        let storage_address = tx_context.fee_token_address();
+        let fee_recipient = tx_context.fee_recipient();
         let TransactionContext { block_context, tx_info } = tx_context.as_ref();
-        let storage_address = block_context.chain_info.fee_token_address(&tx_info.fee_type());
+        // The fee of a sponsored sender is transferred from its sponsor by the fee recipient,
+        // against the allowance the sponsor granted it.
+        let (entry_point_name, calldata, caller_address) = match tx_context.fee_sponsor() {
+            Some(sponsor) => (
+                constants::TRANSFER_FROM_ENTRY_POINT_NAME,
+                calldata![
+                    *sponsor.0.key(),       // Sender.
+                    *fee_recipient.0.key(), // Recipient.
+                    lsb_amount,
+                    msb_amount
+                ],
+                fee_recipient,
+            ),
+            None => (
+                constants::TRANSFER_ENTRY_POINT_NAME,
+                calldata![
+                    *fee_recipient.0.key(), // Recipient.
+                    lsb_amount,
+                    msb_amount
+                ],
+                tx_info.sender_address(),
+            ),
+        };
         let fee_transfer_call = CallEntryPoint {
             class_hash: None,
             code_address: None,
             entry_point_type: EntryPointType::External,
-            entry_point_selector: selector_from_name(constants::TRANSFER_ENTRY_POINT_NAME),
-            calldata: calldata![
-                *block_context.block_info.sequencer_address.0.key(), // Recipient.
-                lsb_amount,
-                msb_amount
-            ],
+            entry_point_selector: selector_from_name(entry_point_name),
+            calldata,
             storage_address,
-            caller_address: tx_info.sender_address(),
+            caller_address,
             call_type: CallType::Call,
             // The fee-token contract is a Cairo 0 contract, hence the initial gas is irrelevant.
             initial_gas: block_context.versioned_constants.os_constants.gas_costs.initial_gas_cost,
@@ -377,10 +484,9 @@ impl AccountTransaction {
         tx_context: Arc<TransactionContext>,
         actual_fee: Fee,
     ) -> TransactionExecutionResult<CallInfo> {
-        let TransactionContext { block_context, tx_info } = tx_context.as_ref();
-        let fee_address = block_context.chain_info.fee_token_address(&tx_info.fee_type());
+        let fee_address = tx_context.fee_token_address();
         let (sequencer_balance_key_low, sequencer_balance_key_high) =
-            get_sequencer_balance_keys(block_context);
+            get_sequencer_balance_keys(tx_context.fee_recipient());
         let mut transfer_state = CachedState::create_transactional(state);
 
         // Set the initial sequencer balance to avoid tarnishing the read-set of the transaction.
@@ -554,6 +660,13 @@ impl AccountTransaction {
                     charge_fee,
                 )?;
                 match post_execution_report.error() {
+                    Some(post_execution_error)
+                        if tx_context.block_context.fee_policy.fee_charge_failure_policy
+                            == FeeChargeFailurePolicy::Reject =>
+                    {
+                        execution_state.abort();
+                        Err(post_execution_error.into())
+                    }
                     Some(post_execution_error) => {
                         // Post-execution check failed. Revert the execution, compute the final fee
                         // to charge and recompute resources used (to be consistent with other
@@ -562,7 +675,8 @@ impl AccountTransaction {
                         execution_state.abort();
                         Ok(ValidateExecuteCallInfo::new_reverted(
                             validate_call_info,
-                            post_execution_error.to_string(),
+                            post_execution_error.into(),
+                            std::mem::take(&mut execution_context.emitted_effects),
                             TransactionReceipt {
                                 fee: post_execution_report.recommended_fee(),
                                 ..revert_cost
@@ -585,9 +699,20 @@ impl AccountTransaction {
                 execution_state.abort();
                 let post_execution_report =
                     PostExecutionReport::new(state, &tx_context, &revert_cost, charge_fee)?;
+                if let Some(post_execution_error) = post_execution_report.error() {
+                    if tx_context.block_context.fee_policy.fee_charge_failure_policy
+                        == FeeChargeFailurePolicy::Reject
+                    {
+                        return Err(post_execution_error.into());
+                    }
+                }
                 Ok(ValidateExecuteCallInfo::new_reverted(
                     validate_call_info,
-                    execution_error.to_string(),
+                    gen_revert_reason(
+                        &execution_error,
+                        &tx_context.block_context.execution_policy.trace_truncation,
+                    ),
+                    std::mem::take(&mut execution_context.emitted_effects),
                     TransactionReceipt {
                         fee: post_execution_report.recommended_fee(),
                         ..revert_cost
@@ -597,10 +722,13 @@ impl AccountTransaction {
         }
     }
 
-    /// Returns 0 on non-declare transactions; for declare transactions, returns the class code
-    /// size.
+    /// Returns 0 on non-declare transactions; for declare transactions, returns the code size of
+    /// the declared classes.
     pub(crate) fn declare_code_size(&self) -> usize {
-        if let Self::Declare(tx) = self { tx.class_info.code_size() } else { 0 }
+        match self {
+            Self::Declare(tx) => tx.code_size(),
+            Self::DeployAccount(_) | Self::Invoke(_) => 0,
+        }
     }
 
     fn is_non_revertible(&self, tx_info: &TransactionInfo) -> bool {
@@ -631,32 +759,41 @@ impl AccountTransaction {
 
         self.run_revertible(state, tx_context, remaining_gas, validate, charge_fee)
     }
-}
 
-impl<S: StateReader> ExecutableTransaction<S> for AccountTransaction {
-    fn execute_raw(
+    /// Same as `execute_raw`, invoking the given hooks along the execution path.
+    pub fn execute_raw_with_hooks<S: StateReader>(
         &self,
         state: &mut TransactionalState<'_, S>,
         block_context: &BlockContext,
         charge_fee: bool,
         validate: bool,
+        hooks: &dyn TransactionExecutorHooks,
     ) -> TransactionExecutionResult<TransactionExecutionInfo> {
         let tx_context = Arc::new(block_context.to_tx_context(self));
         self.verify_tx_version(tx_context.tx_info.version())?;
+        // Fee-exempt transactions are run as if their fee is not charged.
+        let fee_exempt = block_context
+            .fee_policy
+            .fee_exemptions
+            .is_exempt_address(tx_context.tx_info.sender_address());
+        let charge_fee = charge_fee && !fee_exempt;
 
         // Nonce and fee check should be done before running user code.
         let strict_nonce_check = true;
         self.perform_pre_validation_stage(state, &tx_context, charge_fee, strict_nonce_check)?;
+        hooks.before_validate(&tx_context, state)?;
 
         // Run validation and execution.
-        let mut remaining_gas = block_context.versioned_constants.tx_initial_gas();
+        let mut remaining_gas = tx_context.initial_gas();
         let ValidateExecuteCallInfo {
             validate_call_info,
             execute_call_info,
             revert_error,
+            reverted_effects,
             final_cost:
                 TransactionReceipt {
                     fee: final_fee,
+                    gas: final_gas,
                     da_gas: final_da_gas,
                     resources: final_resources,
                     ..
@@ -668,6 +805,16 @@ impl<S: StateReader> ExecutableTransaction<S> for AccountTransaction {
             validate,
             charge_fee,
         )?;
+        hooks.after_execute(&tx_context, state, execute_call_info.as_ref());
+        let final_fee = if fee_exempt {
+            Fee::default()
+        } else if charge_fee {
+            Self::fee_to_charge(&tx_context, final_fee, revert_error.is_some())?
+        } else {
+            final_fee
+        };
+
+        hooks.before_fee_transfer(&tx_context, state, final_fee);
         let fee_transfer_call_info = self.handle_fee(state, tx_context, final_fee, charge_fee)?;
 
         let tx_execution_info = TransactionExecutionInfo {
@@ -675,14 +822,30 @@ impl<S: StateReader> ExecutableTransaction<S> for AccountTransaction {
             execute_call_info,
             fee_transfer_call_info,
             actual_fee: final_fee,
+            gas: final_gas,
             da_gas: final_da_gas,
             actual_resources: final_resources,
             revert_error,
+            reverted_effects,
+            l1_handler_fee_info: None,
+            metadata: None,
         };
         Ok(tx_execution_info)
     }
 }
 
+impl<S: StateReader> ExecutableTransaction<S> for AccountTransaction {
+    fn execute_raw(
+        &self,
+        state: &mut TransactionalState<'_, S>,
+        block_context: &BlockContext,
+        charge_fee: bool,
+        validate: bool,
+    ) -> TransactionExecutionResult<TransactionExecutionInfo> {
+        self.execute_raw_with_hooks(state, block_context, charge_fee, validate, &NoopHooks)
+    }
+}
+
 impl TransactionInfoCreator for AccountTransaction {
     fn create_tx_info(&self) -> TransactionInfo {
         match self {
@@ -697,7 +860,8 @@ impl TransactionInfoCreator for AccountTransaction {
 struct ValidateExecuteCallInfo {
     validate_call_info: Option<CallInfo>,
     execute_call_info: Option<CallInfo>,
-    revert_error: Option<String>,
+    revert_error: Option<RevertReason>,
+    reverted_effects: Option<EmittedEffects>,
     final_cost: TransactionReceipt,
 }
 
@@ -707,18 +871,26 @@ impl ValidateExecuteCallInfo {
         execute_call_info: Option<CallInfo>,
         final_cost: TransactionReceipt,
     ) -> Self {
-        Self { validate_call_info, execute_call_info, revert_error: None, final_cost }
+        Self {
+            validate_call_info,
+            execute_call_info,
+            revert_error: None,
+            reverted_effects: None,
+            final_cost,
+        }
     }
 
     pub fn new_reverted(
         validate_call_info: Option<CallInfo>,
-        revert_error: String,
+        revert_error: RevertReason,
+        reverted_effects: EmittedEffects,
         final_cost: TransactionReceipt,
     ) -> Self {
         Self {
             validate_call_info,
             execute_call_info: None,
             revert_error: Some(revert_error),
+            reverted_effects: Some(reverted_effects),
             final_cost,
         }
     }
@@ -742,17 +914,27 @@ impl ValidatableTransaction for AccountTransaction {
 
         let storage_address = tx_info.sender_address();
         let class_hash = state.get_class_hash_at(storage_address)?;
+        let counterfactual_class_hash = match self {
+            Self::Invoke(_) => counterfactual_sender_class_hash(class_hash, &context.tx_context),
+            Self::Declare(_) | Self::DeployAccount(_) => None,
+        };
+        let class_hash = counterfactual_class_hash.unwrap_or(class_hash);
         let validate_selector = self.validate_entry_point_selector();
+        let initial_gas =
+            match context.tx_context.block_context.execution_policy.validation_limits.max_gas {
+                ValidateLimit::Bounded(max_gas) => max_gas.min(*remaining_gas),
+                ValidateLimit::Default | ValidateLimit::Unlimited => *remaining_gas,
+            };
         let validate_call = CallEntryPoint {
             entry_point_type: EntryPointType::External,
             entry_point_selector: validate_selector,
             calldata: self.validate_entrypoint_calldata(),
-            class_hash: None,
+            class_hash: counterfactual_class_hash,
             code_address: None,
             storage_address,
             caller_address: ContractAddress::default(),
             call_type: CallType::Call,
-            initial_gas: *remaining_gas,
+            initial_gas,
         };
 
         let validate_call_info =
@@ -1,27 +1,36 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use assert_matches::assert_matches;
 use cairo_felt::Felt252;
 use cairo_vm::vm::runners::cairo_runner::ResourceTracker;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
-use starknet_api::core::{calculate_contract_address, ClassHash, ContractAddress, PatriciaKey};
+use starknet_api::core::{
+    calculate_contract_address, ClassHash, ContractAddress, Nonce, PatriciaKey,
+};
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{
-    Calldata, ContractAddressSalt, DeclareTransactionV2, Fee, ResourceBoundsMapping,
-    TransactionHash, TransactionVersion,
+    Calldata, ContractAddressSalt, DeclareTransactionV2, Fee, Resource, ResourceBounds,
+    ResourceBoundsMapping, TransactionHash, TransactionSignature, TransactionVersion,
 };
 use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
 
 use crate::abi::abi_utils::{
     get_fee_token_var_address, get_storage_var_address, selector_from_name,
 };
-use crate::context::BlockContext;
+use crate::context::{
+    BlockContext, FeeTokenAddresses, SizeLimits, StepLimitOverride, UndeployedSenderPolicy,
+    ValidateLimit, ValidationLimits,
+};
 use crate::execution::contract_class::{ContractClass, ContractClassV1};
 use crate::execution::entry_point::EntryPointExecutionContext;
+use crate::execution::errors::{EntryPointExecutionError, PreExecutionError};
 use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+use crate::execution::syscalls::hint_processor::EmitEventError;
 use crate::execution::syscalls::SyscallSelector;
+use crate::fee::fee_checks::{FeeChargeFailurePolicy, FeeCheckError, RevertedTxFeePolicy};
 use crate::fee::fee_utils::{get_fee_by_gas_vector, get_sequencer_balance_keys};
 use crate::fee::gas_usage::estimate_minimal_gas_vector;
 use crate::state::cached_state::{CachedState, StateChangesCount};
@@ -33,11 +42,15 @@ use crate::test_utils::initial_test_state::{fund_account, test_state};
 use crate::test_utils::invoke::InvokeTxArgs;
 use crate::test_utils::{
     create_calldata, create_trivial_calldata, get_syscall_resources, get_tx_resources,
-    u64_from_usize, CairoVersion, NonceManager, BALANCE, DEFAULT_STRK_L1_GAS_PRICE, MAX_FEE,
-    MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE,
+    u64_from_usize, CairoVersion, NonceManager, BALANCE, DEFAULT_STRK_L1_GAS_PRICE,
+    DEFAULT_STRK_L2_GAS_PRICE, MAX_FEE, MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE,
+    TEST_ERC20_CONTRACT_ADDRESS,
 };
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::constants::TRANSFER_ENTRY_POINT_NAME;
+use crate::transaction::errors::{
+    SizeLimitError, TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
+};
 use crate::transaction::objects::{FeeType, HasRelatedFeeType, TransactionInfoCreator};
 use crate::transaction::test_utils::{
     account_invoke_tx, block_context, calculate_class_info_for_testing,
@@ -47,6 +60,7 @@ use crate::transaction::test_utils::{
 };
 use crate::transaction::transaction_types::TransactionType;
 use crate::transaction::transactions::{DeclareTransaction, ExecutableTransaction};
+use crate::versioned_constants::EventLimits;
 use crate::{
     check_transaction_execution_error_for_invalid_scenario, declare_tx_args,
     deploy_account_tx_args, invoke_tx_args, nonce, storage_key,
@@ -164,7 +178,7 @@ fn test_invoke_tx_from_non_deployed_account(
     match tx_result {
         Ok(info) => {
             //  Make sure the error is because the account wasn't deployed.
-            assert!(info.revert_error.is_some_and(|err_str| err_str.contains(expected_error)));
+            assert!(info.revert_error.is_some_and(|err| err.to_string().contains(expected_error)));
         }
         Err(err) => {
             //  Make sure the error is because the account wasn't deployed.
@@ -225,6 +239,7 @@ fn test_infinite_recursion(
             tx_execution_info
                 .revert_error
                 .unwrap()
+                .to_string()
                 .contains("RunResources has no remaining steps.")
         );
     }
@@ -404,7 +419,53 @@ fn test_recursion_depth_exceeded(
     };
     let tx_execution_info = run_invoke_tx(&mut state, &block_context, invoke_args);
 
-    assert!(tx_execution_info.unwrap().revert_error.unwrap().contains("recursion depth exceeded"));
+    assert!(
+        tx_execution_info
+            .unwrap()
+            .revert_error
+            .unwrap()
+            .to_string()
+            .contains("recursion depth exceeded")
+    );
+}
+
+#[rstest]
+fn test_configured_recursion_depth(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+    mut block_context: BlockContext,
+    max_fee: Fee,
+) {
+    let max_recursion_depth = 5;
+    block_context.set_max_recursion_depth(max_recursion_depth);
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, cairo_version);
+
+    // A recursion depth allowed by the default limit, but exceeding the configured one.
+    let recursive_syscall_entry_point_name = "recursive_syscall";
+    let calldata = create_calldata(
+        contract_address,
+        recursive_syscall_entry_point_name,
+        &[
+            *contract_address.0.key(),
+            selector_from_name(recursive_syscall_entry_point_name).0,
+            stark_felt!(u8::try_from(max_recursion_depth).unwrap()),
+        ],
+    );
+    let invoke_args = invoke_tx_args! {
+        max_fee,
+        sender_address: account_address,
+        calldata,
+        nonce: nonce_manager.next(account_address),
+    };
+    let tx_execution_info = run_invoke_tx(&mut state, &block_context, invoke_args).unwrap();
+
+    assert!(
+        tx_execution_info
+            .revert_error
+            .unwrap()
+            .to_string()
+            .contains(&format!("recursion depth exceeded (max depth: {max_recursion_depth})"))
+    );
 }
 
 #[rstest]
@@ -473,6 +534,382 @@ fn test_revert_invoke(
     );
 }
 
+#[rstest]
+fn test_reverted_tx_fee_policy(mut block_context: BlockContext, max_fee: Fee) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let chain_info = block_context.chain_info.clone();
+    let run_reverted_tx = |block_context: &BlockContext| {
+        let state = &mut test_state(&chain_info, BALANCE, &[(test_contract, 1), (account, 1)]);
+        let tx_execution_info = run_invoke_tx(
+            state,
+            block_context,
+            invoke_tx_args! {
+                max_fee,
+                sender_address: account.get_instance_address(0),
+                calldata: create_calldata(
+                    test_contract.get_instance_address(0),
+                    "write_and_revert",
+                    &[stark_felt!(9_u8), stark_felt!(99_u8)]
+                ),
+                version: TransactionVersion::ONE,
+            },
+        )
+        .unwrap();
+        assert!(tx_execution_info.is_reverted());
+        tx_execution_info.actual_fee
+    };
+
+    let consumed_resources_fee = run_reverted_tx(&block_context);
+    let floor = Fee(consumed_resources_fee.0 + 1);
+    for (policy, expected_fee) in [
+        (RevertedTxFeePolicy::MaxFee, max_fee),
+        (RevertedTxFeePolicy::ConsumedResourcesWithFloor(floor), floor),
+        (RevertedTxFeePolicy::ConsumedResourcesWithFloor(Fee(1)), consumed_resources_fee),
+        // The floor is capped by the max fee.
+        (RevertedTxFeePolicy::ConsumedResourcesWithFloor(Fee(max_fee.0 + 1)), max_fee),
+    ] {
+        block_context.fee_policy_mut().reverted_tx_fee_policy = policy;
+        assert_eq!(run_reverted_tx(&block_context), expected_fee);
+    }
+}
+
+#[rstest]
+/// Tests that on a single-token chain, fees of both types are charged from the same token.
+fn test_single_fee_token_chain(
+    mut block_context: BlockContext,
+    max_fee: Fee,
+    max_resource_bounds: ResourceBoundsMapping,
+) {
+    let fee_token_address = contract_address!(TEST_ERC20_CONTRACT_ADDRESS);
+    block_context.chain_info.fee_token_addresses =
+        FeeTokenAddresses::single_token(fee_token_address);
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state =
+        &mut test_state(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let account_address = account.get_instance_address(0);
+    let mut nonce_manager = NonceManager::default();
+
+    let mut total_fee = 0;
+    for version in [TransactionVersion::ONE, TransactionVersion::THREE] {
+        let tx_execution_info = run_invoke_tx(
+            state,
+            &block_context,
+            invoke_tx_args! {
+                max_fee,
+                resource_bounds: max_resource_bounds.clone(),
+                sender_address: account_address,
+                calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+                version,
+                nonce: nonce_manager.next(account_address),
+            },
+        )
+        .unwrap();
+        let fee_transfer_call_info = tx_execution_info.fee_transfer_call_info.unwrap();
+        assert_eq!(fee_transfer_call_info.call.storage_address, fee_token_address);
+        total_fee += tx_execution_info.actual_fee.0;
+    }
+
+    assert_eq!(
+        state.get_fee_token_balance(account_address, fee_token_address).unwrap(),
+        (stark_felt!(BALANCE - total_fee), stark_felt!(0_u8))
+    );
+}
+
+#[rstest]
+fn test_fee_recipient_override(
+    max_resource_bounds: ResourceBoundsMapping,
+    #[values(true, false)] concurrency_mode: bool,
+) {
+    let block_context =
+        &BlockContext::create_for_account_testing_with_concurrency_mode(concurrency_mode);
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state =
+        &mut test_state(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let fee_recipient = contract_address!("0x1234");
+    let fee_token_address = block_context.chain_info.fee_token_address(&FeeType::Strk);
+
+    let account_tx = account_invoke_tx(invoke_tx_args! {
+        resource_bounds: max_resource_bounds,
+        sender_address: account.get_instance_address(0),
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        version: TransactionVersion::THREE,
+    })
+    .with_fee_recipient(fee_recipient);
+    let tx_execution_info = account_tx.execute(state, block_context, true, true).unwrap();
+
+    let sequencer_address = block_context.block_info.sequencer_address;
+    assert_eq!(
+        state.get_fee_token_balance(sequencer_address, fee_token_address).unwrap(),
+        (stark_felt!(0_u8), stark_felt!(0_u8))
+    );
+    // In concurrency mode, the recipient's balance is updated at the commit stage.
+    let expected_recipient_balance =
+        if concurrency_mode { 0 } else { tx_execution_info.actual_fee.0 };
+    assert_eq!(
+        state.get_fee_token_balance(fee_recipient, fee_token_address).unwrap(),
+        (stark_felt!(expected_recipient_balance), stark_felt!(0_u8))
+    );
+    let fee_transfer_calldata = tx_execution_info.fee_transfer_call_info.unwrap().call.calldata;
+    assert_eq!(fee_transfer_calldata.0[0], *fee_recipient.0.key());
+}
+
+#[rstest]
+fn test_undeployed_sender_policy(
+    mut block_context: BlockContext,
+    max_resource_bounds: ResourceBoundsMapping,
+    #[values(true, false)] counterfactual: bool,
+) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    // The account class is declared, but not deployed.
+    let state =
+        &mut test_state(&block_context.chain_info, BALANCE, &[(account, 0), (test_contract, 1)]);
+    let sender_address = account.get_instance_address(0);
+    fund_account(&block_context.chain_info, sender_address, BALANCE, &mut state.state);
+    if counterfactual {
+        block_context.execution_policy_mut().undeployed_sender_policy =
+            UndeployedSenderPolicy::Counterfactual(account.get_class_hash());
+    }
+
+    let result = run_invoke_tx(
+        state,
+        &block_context,
+        invoke_tx_args! {
+            resource_bounds: max_resource_bounds,
+            sender_address,
+            calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+            version: TransactionVersion::THREE,
+        },
+    );
+
+    if counterfactual {
+        let tx_execution_info = result.unwrap();
+        assert!(!tx_execution_info.is_reverted());
+        let execute_call_info = tx_execution_info.execute_call_info.unwrap();
+        assert_eq!(execute_call_info.call.class_hash, Some(account.get_class_hash()));
+        // The sender remains undeployed.
+        assert_eq!(state.get_class_hash_at(sender_address).unwrap(), ClassHash::default());
+    } else {
+        assert_matches!(
+            result.unwrap_err(),
+            TransactionExecutionError::ValidateTransactionError {
+                error: EntryPointExecutionError::PreExecutionError(
+                    PreExecutionError::UninitializedStorageAddress(address)
+                ),
+                ..
+            } if address == sender_address
+        );
+    }
+}
+
+#[rstest]
+fn test_size_limits(mut block_context: BlockContext, max_resource_bounds: ResourceBoundsMapping) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let state =
+        &mut test_state(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let sender_address = account.get_instance_address(0);
+    // Emits a single event with two keys and five data words.
+    let calldata = create_calldata(
+        test_contract.get_instance_address(0),
+        "test_emit_events",
+        &[1_u8, 2, 3, 4, 5, 5, 6, 7, 8, 9].map(StarkFelt::from),
+    );
+    let calldata_length = calldata.0.len();
+    let signature = TransactionSignature(vec![stark_felt!(1_u8), stark_felt!(2_u8)]);
+    let event_limits =
+        EventLimits { max_keys_length: 1, ..block_context.versioned_constants.tx_event_limits };
+    // The event payload consists of seven felts; the limit still admits the event of the fee
+    // transfer, which consists of five.
+    let payload_event_limits = EventLimits {
+        max_total_payload_size: 223,
+        ..block_context.versioned_constants.tx_event_limits
+    };
+    let mut run_with_limits = |size_limits: SizeLimits, nonce: Nonce| {
+        block_context.execution_policy_mut().size_limits = size_limits;
+        run_invoke_tx(
+            state,
+            &block_context,
+            invoke_tx_args! {
+                resource_bounds: max_resource_bounds.clone(),
+                sender_address,
+                calldata: calldata.clone(),
+                signature: signature.clone(),
+                version: TransactionVersion::THREE,
+                nonce,
+            },
+        )
+    };
+
+    let result = run_with_limits(
+        SizeLimits { max_calldata_length: Some(calldata_length - 1), ..Default::default() },
+        nonce!(0_u8),
+    );
+    assert_matches!(
+        result.unwrap_err(),
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::SizeLimitError(SizeLimitError::CalldataTooLong {
+                calldata_length: length,
+                ..
+            })
+        ) if length == calldata_length
+    );
+
+    let result = run_with_limits(
+        SizeLimits { max_signature_length: Some(1), ..Default::default() },
+        nonce!(0_u8),
+    );
+    assert_matches!(
+        result.unwrap_err(),
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::SizeLimitError(SizeLimitError::SignatureTooLong {
+                signature_length: 2,
+                max_signature_length: 1
+            })
+        )
+    );
+
+    // Limits are inclusive; the event limits apply to the execution, which is reverted.
+    let tx_execution_info = run_with_limits(
+        SizeLimits {
+            max_calldata_length: Some(calldata_length),
+            max_signature_length: Some(2),
+            event_limits: Some(event_limits),
+        },
+        nonce!(0_u8),
+    )
+    .unwrap();
+    let expected_error =
+        EmitEventError::ExceedsMaxKeysLength { keys_length: 2, max_keys_length: 1 };
+    let revert_error = tx_execution_info.revert_error.unwrap().to_string();
+    assert!(revert_error.contains(&expected_error.to_string()));
+
+    let tx_execution_info = run_with_limits(
+        SizeLimits { event_limits: Some(payload_event_limits), ..Default::default() },
+        nonce!(1_u8),
+    )
+    .unwrap();
+    let expected_error = EmitEventError::ExceedsMaxTotalPayloadSize {
+        total_payload_size: 224,
+        max_total_payload_size: 223,
+    };
+    let revert_error = tx_execution_info.revert_error.unwrap().to_string();
+    assert!(revert_error.contains(&expected_error.to_string()));
+}
+
+#[rstest]
+fn test_l2_gas_bounds(block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state =
+        &mut test_state(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let sender_address = account.get_instance_address(0);
+    let calldata = create_trivial_calldata(test_contract.get_instance_address(0));
+    let mut run_with_l2_bounds = |max_amount: u64, max_price_per_unit: u128| {
+        let resource_bounds = ResourceBoundsMapping::try_from(vec![
+            (
+                Resource::L1Gas,
+                ResourceBounds {
+                    max_amount: MAX_L1_GAS_AMOUNT,
+                    max_price_per_unit: MAX_L1_GAS_PRICE,
+                },
+            ),
+            (Resource::L2Gas, ResourceBounds { max_amount, max_price_per_unit }),
+        ])
+        .unwrap();
+        // Failed transactions do not advance the nonce.
+        let nonce = state.get_nonce_at(sender_address).unwrap();
+        run_invoke_tx(
+            state,
+            &block_context,
+            invoke_tx_args! {
+                resource_bounds,
+                sender_address,
+                calldata: calldata.clone(),
+                version: TransactionVersion::THREE,
+                nonce,
+            },
+        )
+    };
+
+    // The L2 gas price bound must cover the actual L2 gas price.
+    assert_matches!(
+        run_with_l2_bounds(u64::pow(10, 8), DEFAULT_STRK_L2_GAS_PRICE - 1).unwrap_err(),
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::MaxL2GasPriceTooLow { .. }
+            )
+        )
+    );
+
+    // The execution is charged as L2 gas, within the L2 gas bound.
+    let max_l2_gas_amount = u64::pow(10, 8);
+    let tx_execution_info =
+        run_with_l2_bounds(max_l2_gas_amount, DEFAULT_STRK_L2_GAS_PRICE).unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    let l2_gas = tx_execution_info.gas.l2_gas;
+    assert!(l2_gas > 0 && l2_gas <= u128::from(max_l2_gas_amount));
+    assert!(tx_execution_info.actual_fee.0 >= l2_gas * DEFAULT_STRK_L2_GAS_PRICE);
+
+    // Running out of L2 gas fails the transaction.
+    assert_matches!(
+        run_with_l2_bounds(1, DEFAULT_STRK_L2_GAS_PRICE).unwrap_err(),
+        TransactionExecutionError::ValidateTransactionError { .. }
+    );
+}
+
+#[rstest]
+fn test_validation_limits(
+    mut block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let faulty_account = FeatureContract::FaultyAccount(cairo_version);
+    let state = &mut test_state(&block_context.chain_info, BALANCE, &[(faulty_account, 1)]);
+    let sender_address = faulty_account.get_instance_address(0);
+    let mut nonce_manager = NonceManager::default();
+    let mut validate_with_limits = |validation_limits: ValidationLimits| {
+        block_context.execution_policy_mut().validation_limits = validation_limits;
+        let tx = create_account_tx_for_validate_test(
+            &mut nonce_manager,
+            FaultyAccountTxCreatorArgs {
+                sender_address,
+                class_hash: faulty_account.get_class_hash(),
+                ..Default::default()
+            },
+        );
+        let result = tx.execute(state, &block_context, false, true);
+        if result.is_err() {
+            // Rejected transactions do not bump the nonce.
+            nonce_manager.rollback(sender_address);
+        }
+        result
+    };
+
+    // Too few steps.
+    let step_limits =
+        ValidationLimits { max_n_steps: ValidateLimit::Bounded(1), ..Default::default() };
+    assert_matches!(
+        validate_with_limits(step_limits).unwrap_err(),
+        TransactionExecutionError::ValidateTransactionError { .. }
+    );
+
+    // Too little gas; only Cairo 1 validation consumes gas.
+    let gas_limits = ValidationLimits { max_gas: ValidateLimit::Bounded(1), ..Default::default() };
+    let result = validate_with_limits(gas_limits);
+    match cairo_version {
+        CairoVersion::Cairo0 => assert!(result.is_ok()),
+        CairoVersion::Cairo1 => assert_matches!(
+            result.unwrap_err(),
+            TransactionExecutionError::ValidateTransactionError { .. }
+        ),
+    }
+
+    assert!(validate_with_limits(ValidationLimits::unlimited()).is_ok());
+}
+
 #[rstest]
 /// Tests that hitting an execution error in an account contract constructor outputs the correct
 /// traceback (including correct class hash, contract address and constructor entry point selector).
@@ -753,6 +1190,52 @@ fn test_reverted_reach_steps_limit(
     assert!(actual_fee_fail == actual_fee_fail_next);
 }
 
+#[rstest]
+/// Tests that the step limit can be overridden for a single execution, without modifying the
+/// shared versioned constants.
+fn test_max_steps_override(
+    max_fee: Fee,
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, cairo_version);
+    let mut recurse = |block_context: &BlockContext, depth: u32| {
+        run_invoke_tx(
+            &mut state,
+            block_context,
+            invoke_tx_args! {
+                max_fee,
+                sender_address: account_address,
+                nonce: nonce_manager.next(account_address),
+                calldata: recursive_function_calldata(&contract_address, depth, false),
+            },
+        )
+        .unwrap()
+    };
+
+    // Tighten the limit for a single execution.
+    let depth = 1000;
+    let mut bounded_block_context = block_context.clone();
+    bounded_block_context.execution_policy_mut().max_steps_override =
+        Some(StepLimitOverride::Bounded(5000));
+    let result = recurse(&bounded_block_context, depth);
+    let revert_error = result.revert_error.unwrap().to_string();
+    assert!(revert_error.contains("RunResources has no remaining steps."));
+    assert!(revert_error.contains("steps were available to the call."));
+
+    // Lift the limit for a single execution.
+    let mut unlimited_block_context = block_context.clone();
+    unlimited_block_context.execution_policy_mut().max_steps_override =
+        Some(StepLimitOverride::Unlimited);
+    let result = recurse(&unlimited_block_context, depth);
+    assert!(!result.is_reverted());
+    assert!(result.actual_resources.total_charged_steps() > 5000);
+
+    // The shared block context is not affected.
+    assert_eq!(block_context.execution_policy().max_steps_override, None);
+}
+
 #[rstest]
 /// Tests that n_steps and actual_fees of reverted transactions invocations are consistent.
 /// In this test reverted transactions are recursive function invocations where the innermost call
@@ -979,7 +1462,13 @@ fn test_insufficient_max_fee_reverts(
     .unwrap();
     assert!(tx_execution_info2.is_reverted());
     assert!(tx_execution_info2.actual_fee == actual_fee_depth1);
-    assert!(tx_execution_info2.revert_error.unwrap().starts_with("Insufficient max fee"));
+    assert!(
+        tx_execution_info2
+            .revert_error
+            .unwrap()
+            .to_string()
+            .starts_with("Insufficient max fee")
+    );
 
     // Invoke the `recurse` function with depth of 824 and the actual fee of depth 1 as max_fee.
     // This call should fail due to no remaining steps (execution steps based on max_fee are bounded
@@ -998,8 +1487,112 @@ fn test_insufficient_max_fee_reverts(
     assert!(tx_execution_info3.is_reverted());
     assert!(tx_execution_info3.actual_fee == actual_fee_depth1);
     assert!(
-        tx_execution_info3.revert_error.unwrap().contains("RunResources has no remaining steps.")
+        tx_execution_info3
+            .revert_error
+            .unwrap()
+            .to_string()
+            .contains("RunResources has no remaining steps.")
+    );
+}
+
+#[rstest]
+/// Tests that under the `Reject` fee charge failure policy, transactions with insufficient max_fee
+/// are rejected rather than reverted.
+fn test_insufficient_max_fee_rejects(
+    mut block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    block_context.fee_policy_mut().fee_charge_failure_policy = FeeChargeFailurePolicy::Reject;
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, cairo_version);
+    let recursion_base_args = invoke_tx_args! {
+        sender_address: account_address,
+        version: TransactionVersion::ONE,
+    };
+
+    // Invoke the `recurse` function with depth 1 and MAX_FEE. This call should succeed.
+    let tx_execution_info = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            max_fee: Fee(MAX_FEE),
+            nonce: nonce_manager.next(account_address),
+            calldata: recursive_function_calldata(&contract_address, 1, false),
+            ..recursion_base_args.clone()
+        },
+    )
+    .unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    let actual_fee_depth1 = tx_execution_info.actual_fee;
+
+    // Invoke the `recurse` function with depth of 2 and the actual fee of depth 1 as max_fee.
+    // The post-execution fee check fails, and the transaction is rejected.
+    let error = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            max_fee: actual_fee_depth1,
+            nonce: nonce_manager.next(account_address),
+            calldata: recursive_function_calldata(&contract_address, 2, false),
+            ..recursion_base_args
+        },
+    )
+    .unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::FeeCheckError(FeeCheckError::MaxFeeExceeded { max_fee, .. })
+        if max_fee == actual_fee_depth1
     );
+    // Rejected transactions leave no trace in the state.
+    assert_eq!(state.get_nonce_at(account_address).unwrap(), nonce!(1_u8));
+}
+
+#[rstest]
+/// Tests that under a relaxed max fee check, the actual fee is computed even if max_fee is zero,
+/// and that an insufficient max_fee is charged instead of reverting the transaction.
+fn test_relaxed_max_fee_check(
+    mut block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    block_context.fee_policy_mut().relax_max_fee_check = true;
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, cairo_version);
+    let recursion_base_args = invoke_tx_args! {
+        sender_address: account_address,
+        version: TransactionVersion::ONE,
+        calldata: recursive_function_calldata(&contract_address, 2, false),
+    };
+
+    // Zero max_fee; the actual fee is reported.
+    let charge_fee = false;
+    let tx_execution_info = account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(0),
+        nonce: nonce_manager.next(account_address),
+        ..recursion_base_args.clone()
+    })
+    .execute(&mut state, &block_context, charge_fee, true)
+    .unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    let actual_fee = tx_execution_info.actual_fee;
+    assert!(actual_fee > Fee(0));
+
+    // Insufficient max_fee, as the fee transfer adds to the actual fee, yet above the minimal fee
+    // checked before the validation; the transaction succeeds, and the charged fee is clamped to
+    // max_fee.
+    let max_fee = Fee(actual_fee.0 - 1);
+    let tx_execution_info = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            max_fee,
+            nonce: nonce_manager.next(account_address),
+            ..recursion_base_args
+        },
+    )
+    .unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    assert_eq!(tx_execution_info.actual_fee, max_fee);
+    assert!(tx_execution_info.fee_transfer_call_info.is_some());
 }
 
 #[rstest]
@@ -1239,7 +1832,7 @@ fn test_concurrency_execute_fee_transfer(#[values(FeeType::Eth, FeeType::Strk)]
     let state = &mut test_state(chain_info, BALANCE, &[(account, 1)]);
 
     let (sequencer_balance_key_low, sequencer_balance_key_high) =
-        get_sequencer_balance_keys(&block_context);
+        get_sequencer_balance_keys(block_context.block_info.sequencer_address);
 
     // Case 1: The transaction did not read form/ write to the sequenser balance before executing
     // fee transfer.
@@ -1,9 +1,18 @@
 pub const EXECUTE_ENTRY_POINT_NAME: &str = "__execute__";
 pub const TRANSFER_ENTRY_POINT_NAME: &str = "transfer";
+pub const TRANSFER_FROM_ENTRY_POINT_NAME: &str = "transferFrom";
 pub const VALIDATE_ENTRY_POINT_NAME: &str = "__validate__";
 pub const VALIDATE_DECLARE_ENTRY_POINT_NAME: &str = "__validate_declare__";
 pub const VALIDATE_DEPLOY_ENTRY_POINT_NAME: &str = "__validate_deploy__";
 
+// Outside execution (SNIP-9) entry points.
+pub const EXECUTE_FROM_OUTSIDE_ENTRY_POINT_NAME: &str = "execute_from_outside";
+pub const EXECUTE_FROM_OUTSIDE_V2_ENTRY_POINT_NAME: &str = "execute_from_outside_v2";
+pub const IS_VALID_OUTSIDE_EXECUTION_NONCE_ENTRY_POINT_NAME: &str =
+    "is_valid_outside_execution_nonce";
+// The short string `ANY_CALLER`; allows any account to relay an outside execution.
+pub const OUTSIDE_EXECUTION_ANY_CALLER: &str = "0x414e595f43414c4c4552";
+
 pub const TRANSFER_EVENT_NAME: &str = "Transfer";
 
 // Cairo constants.
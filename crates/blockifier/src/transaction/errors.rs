@@ -1,16 +1,35 @@
-use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, Nonce};
+use cairo_lang_starknet_classes::casm_contract_class::StarknetSierraCompilationError;
+use starknet_api::block::BlockTimestamp;
+use starknet_api::core::{
+    ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, Nonce,
+};
+use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::hash::StarkFelt;
-use starknet_api::transaction::{Fee, TransactionVersion};
+use starknet_api::transaction::{Fee, ResourceBounds, Tip, TransactionVersion};
 use starknet_api::StarknetApiError;
+use starknet_crypto::VerifyError;
 use thiserror::Error;
 
 use crate::execution::call_info::Retdata;
 use crate::execution::errors::{
     gen_transaction_execution_error_trace, ConstructorEntryPointExecutionError,
-    EntryPointExecutionError,
+    EntryPointExecutionError, TraceTruncation,
 };
 use crate::fee::fee_checks::FeeCheckError;
 use crate::state::errors::StateError;
+use crate::transaction::objects::GasVector;
+
+#[derive(Debug, Eq, Error, PartialEq)]
+pub enum FeeOverflowError {
+    #[error("Fee addition overflowed: {lhs:?} + {rhs:?}.")]
+    FeeAddition { lhs: Fee, rhs: Fee },
+    #[error("Fee conversion overflowed: {fee:?} at a conversion rate of {to_price}/{from_price}.")]
+    FeeConversion { fee: Fee, from_price: u128, to_price: u128 },
+    #[error("Gas cost overflowed: {gas_amount} gas at a price of {gas_price} per unit.")]
+    GasCost { gas_amount: u128, gas_price: u128 },
+    #[error("Gas vector addition overflowed: {lhs:?} + {rhs:?}.")]
+    GasVectorAddition { lhs: GasVector, rhs: GasVector },
+}
 
 #[derive(Debug, Error)]
 pub enum TransactionFeeError {
@@ -18,10 +37,29 @@ pub enum TransactionFeeError {
     CairoResourcesNotContainedInFeeCosts,
     #[error(transparent)]
     ExecuteFeeTransferError(#[from] EntryPointExecutionError),
+    #[error(
+        "Fee ({fee:?}) exceeds the allowance of sponsor {sponsor:?} (Uint256({allowance_low:?}, \
+         {allowance_high:?}))."
+    )]
+    FeeExceedsAllowance {
+        fee: Fee,
+        sponsor: ContractAddress,
+        allowance_low: StarkFelt,
+        allowance_high: StarkFelt,
+    },
+    #[error("Offered fee ({offered_fee:?}) is below the fee floor: {min_fee:?}.")]
+    FeeBelowFloor { min_fee: Fee, offered_fee: Fee },
     #[error("Actual fee ({actual_fee:?}) exceeded max fee ({max_fee:?}).")]
     FeeTransferError { max_fee: Fee, actual_fee: Fee },
+    #[error(transparent)]
+    FeeOverflowError(#[from] FeeOverflowError),
     #[error("Actual fee ({actual_fee:?}) exceeded paid fee on L1 ({paid_fee:?}).")]
     InsufficientL1Fee { paid_fee: Fee, actual_fee: Fee },
+    #[error(
+        "Max L1 gas price ({max_l1_gas_price:?}) is below the gas price floor: \
+         {min_l1_gas_price:?}."
+    )]
+    L1GasPriceBelowFloor { min_l1_gas_price: u128, max_l1_gas_price: u128 },
     #[error(
         "L1 gas bounds (max amount: {max_amount:?}, max price: {max_price:?}) exceed balance \
          (Uint256({balance_low:?}, {balance_high:?}))."
@@ -41,32 +79,62 @@ pub enum TransactionFeeError {
          {actual_l1_gas_price:?}."
     )]
     MaxL1GasPriceTooLow { max_l1_gas_price: u128, actual_l1_gas_price: u128 },
+    #[error(
+        "Max L1 gas price ({max_l1_gas_price:?}) does not cover the actual gas price \
+         ({actual_l1_gas_price:?}) and the tip ({tip:?})."
+    )]
+    MaxL1GasPriceTooLowForTip { max_l1_gas_price: u128, actual_l1_gas_price: u128, tip: Tip },
     #[error(
         "Max L1 gas amount ({max_l1_gas_amount:?}) is lower than the minimal gas amount: \
          {minimal_l1_gas_amount:?}."
     )]
     MaxL1GasAmountTooLow { max_l1_gas_amount: u64, minimal_l1_gas_amount: u64 },
+    #[error(
+        "Max L2 gas price ({max_l2_gas_price:?}) is lower than the actual L2 gas price: \
+         {actual_l2_gas_price:?}."
+    )]
+    MaxL2GasPriceTooLow { max_l2_gas_price: u128, actual_l2_gas_price: u128 },
     #[error("Missing L1 gas bounds in resource bounds.")]
     MissingL1GasBounds,
+    #[error(
+        "Resource bounds (committed fee: {committed_fee:?}) exceed balance \
+         (Uint256({balance_low:?}, {balance_high:?}))."
+    )]
+    ResourceBoundsExceedBalance {
+        committed_fee: Fee,
+        balance_low: StarkFelt,
+        balance_high: StarkFelt,
+    },
     #[error(transparent)]
     StateError(#[from] StateError),
 }
 
 #[derive(Debug, Error)]
 pub enum TransactionExecutionError {
+    #[error(transparent)]
+    CallEncodingError(#[from] CallEncodingError),
+    #[error(
+        "Compiled class hash mismatch for class {class_hash:?}: declared \
+         {declared_compiled_class_hash:?}, recompiled {recompiled_compiled_class_hash:?}."
+    )]
+    CompiledClassHashMismatch {
+        class_hash: ClassHash,
+        declared_compiled_class_hash: CompiledClassHash,
+        recompiled_compiled_class_hash: CompiledClassHash,
+    },
     #[error(
         "Declare transaction version {declare_version:?} must have a contract class of Cairo \
          version {cairo_version:?}."
     )]
     ContractClassVersionMismatch { declare_version: TransactionVersion, cairo_version: u64 },
-    #[error(
-        "Contract constructor execution has failed:\n{}",
-        gen_transaction_execution_error_trace(self)
-    )]
+    #[error("{}", self.message_with_trace_truncation(&TraceTruncation::default()))]
     ContractConstructorExecutionFailed(#[from] ConstructorEntryPointExecutionError),
     #[error("Class with hash {class_hash:?} is already declared.")]
     DeclareTransactionError { class_hash: ClassHash },
-    #[error("Transaction execution has failed:\n{}", gen_transaction_execution_error_trace(self))]
+    #[cfg(feature = "batch_declare")]
+    #[error("Class with hash {class_hash:?} is declared more than once in the transaction.")]
+    DuplicateDeclaredClass { class_hash: ClassHash },
+    #[error("{}", self.message_with_trace_truncation(&TraceTruncation::default()))]
     ExecutionError {
         error: EntryPointExecutionError,
         class_hash: ClassHash,
@@ -82,6 +150,14 @@ pub enum TransactionExecutionError {
          {allowed_versions:?}."
     )]
     InvalidVersion { version: TransactionVersion, allowed_versions: Vec<TransactionVersion> },
+    #[error("The Sierra class of the declared class {class_hash:?} is missing.")]
+    MissingSierraContractClass { class_hash: ClassHash },
+    #[error("Failed to compile the Sierra class {class_hash:?}: {error}")]
+    SierraCompilationError {
+        class_hash: ClassHash,
+        #[source]
+        error: StarknetSierraCompilationError,
+    },
     #[error(transparent)]
     StarknetApiError(#[from] StarknetApiError),
     #[error(transparent)]
@@ -92,9 +168,15 @@ pub enum TransactionExecutionError {
     TransactionPreValidationError(#[from] TransactionPreValidationError),
     #[error(transparent)]
     TryFromIntError(#[from] std::num::TryFromIntError),
+    #[error("System transaction sent by {sender_address:?}, which is not the operator.")]
+    UnauthorizedSystemTransaction { sender_address: ContractAddress },
+    #[error("Transaction type {tx_type} is not supported.")]
+    UnsupportedTransactionType { tx_type: String },
     #[error("Transaction size exceeds the maximum block capacity.")]
     TransactionTooLarge,
-    #[error("Transaction validation has failed:\n{}", gen_transaction_execution_error_trace(self))]
+    #[error("Transaction was rejected by the executor hooks: {reason}")]
+    TransactionRejectedByHooks { reason: String },
+    #[error("{}", self.message_with_trace_truncation(&TraceTruncation::default()))]
     ValidateTransactionError {
         error: EntryPointExecutionError,
         class_hash: ClassHash,
@@ -106,6 +188,27 @@ pub enum TransactionExecutionError {
          not."
     )]
     InvalidSegmentStructure(usize, usize),
+    #[error(
+        "Invalid bytecode segment lengths: the segments are of total length {segments_length}, \
+         but the bytecode is of length {bytecode_length}."
+    )]
+    InvalidBytecodeSegmentLengths { segments_length: usize, bytecode_length: usize },
+}
+
+impl TransactionExecutionError {
+    /// Returns the error message, with the error trace of failed calls truncated as given; the
+    /// error is displayed with the default truncation.
+    pub fn message_with_trace_truncation(&self, truncation: &TraceTruncation) -> String {
+        let heading = match self {
+            Self::ContractConstructorExecutionFailed(_) => {
+                "Contract constructor execution has failed:"
+            }
+            Self::ExecutionError { .. } => "Transaction execution has failed:",
+            Self::ValidateTransactionError { .. } => "Transaction validation has failed:",
+            _ => return self.to_string(),
+        };
+        format!("{heading}\n{}", gen_transaction_execution_error_trace(self, truncation))
+    }
 }
 
 #[derive(Debug, Error)]
@@ -116,11 +219,94 @@ pub enum TransactionPreValidationError {
     )]
     InvalidNonce { address: ContractAddress, account_nonce: Nonce, incoming_tx_nonce: Nonce },
     #[error(transparent)]
+    SizeLimitError(#[from] SizeLimitError),
+    #[error(transparent)]
     StateError(#[from] StateError),
     #[error(transparent)]
     TransactionFeeError(#[from] TransactionFeeError),
 }
 
+#[derive(Debug, Error)]
+pub enum OutsideExecutionError {
+    #[error(transparent)]
+    EntryPointExecutionError(#[from] EntryPointExecutionError),
+    #[error(
+        "Outside execution of {signer_address:?} may only be relayed by {expected_caller:?}; got: \
+         {actual_caller:?}."
+    )]
+    InvalidCaller {
+        signer_address: ContractAddress,
+        expected_caller: ContractAddress,
+        actual_caller: ContractAddress,
+    },
+    #[error(
+        "Outside execution of {signer_address:?} is valid strictly between {execute_after:?} and \
+         {execute_before:?}; block timestamp: {block_timestamp:?}."
+    )]
+    OutOfTimeBounds {
+        signer_address: ContractAddress,
+        execute_after: StarkFelt,
+        execute_before: StarkFelt,
+        block_timestamp: BlockTimestamp,
+    },
+    #[error(
+        "Outside execution nonce {nonce:?} of contract at address {signer_address:?} is invalid."
+    )]
+    InvalidNonce { signer_address: ContractAddress, nonce: StarkFelt },
+    #[error(transparent)]
+    StateError(#[from] StateError),
+    #[error(transparent)]
+    TransactionExecutionError(#[from] TransactionExecutionError),
+    #[error("Outside execution signer at address {0:?} is not deployed.")]
+    UndeployedSigner(ContractAddress),
+}
+
+#[derive(Debug, Error)]
+pub enum SignatureVerificationError {
+    #[error("Signature of length {actual} is invalid; expected length: {expected}.")]
+    InvalidSignatureLength { expected: usize, actual: usize },
+    #[error("Signature is invalid for public key {public_key:?}.")]
+    InvalidSignature { public_key: StarkFelt },
+    #[error("Malformed signature or public key: {0:?}.")]
+    MalformedSignature(VerifyError),
+    #[error("Signature of length {actual} is too short; expected at least: {min_length}.")]
+    SignatureTooShort { min_length: usize, actual: usize },
+}
+
+#[derive(Debug, Error)]
+pub enum CallEncodingError {
+    #[error("Single-call accounts support exactly one call; got: {n_calls}.")]
+    InvalidSingleCallCount { n_calls: usize },
+}
+
+#[derive(Debug, Error)]
+pub enum SizeLimitError {
+    #[error("Calldata of length {calldata_length} exceeds the maximum: {max_calldata_length}.")]
+    CalldataTooLong { calldata_length: usize, max_calldata_length: usize },
+    #[error("Signature of length {signature_length} exceeds the maximum: {max_signature_length}.")]
+    SignatureTooLong { signature_length: usize, max_signature_length: usize },
+}
+
+#[derive(Debug, Error)]
+pub enum StatelessValidationError {
+    #[error("Sender address {0:?} is reserved or out of range.")]
+    InvalidSenderAddress(ContractAddress),
+    #[error("L2 gas bounds are not supported; got: {0:?}.")]
+    NonZeroL2GasBounds(ResourceBounds),
+    #[error(transparent)]
+    SizeLimitError(#[from] SizeLimitError),
+    #[error(transparent)]
+    TransactionFeeError(#[from] TransactionFeeError),
+    #[error("Account deployment data is not supported.")]
+    UnsupportedAccountDeploymentData,
+    #[error("Data availability mode {0:?} is not supported.")]
+    UnsupportedDataAvailabilityMode(DataAvailabilityMode),
+    #[error("Paymaster data is not supported.")]
+    UnsupportedPaymasterData,
+    #[error("Transaction fee bounds must be positive.")]
+    ZeroFeeBounds,
+}
+
 #[derive(Debug, Error)]
 pub enum ParseError {
     #[error("Unsupported transaction type: {0}")]
@@ -0,0 +1,175 @@
+use std::collections::HashSet;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::Fee;
+
+use crate::execution::call_info::{
+    CallInfo, EmittedEffects, OrderedEvent, OrderedL2ToL1Message, Retdata,
+};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::state::cached_state::StateChangesCount;
+use crate::transaction::objects::{
+    GasVector, L1HandlerFeeInfo, RevertReason, TransactionExecutionInfo, TransactionResources,
+};
+
+#[cfg(test)]
+#[path = "execution_info_diff_test.rs"]
+mod test;
+
+/// A value that differs between the compared execution infos.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldDiff<T> {
+    pub left: T,
+    pub right: T,
+}
+
+fn diff_field<T: Clone + PartialEq>(left: &T, right: &T) -> Option<FieldDiff<T>> {
+    (left != right).then(|| FieldDiff { left: left.clone(), right: right.clone() })
+}
+
+/// The top-level call of a transaction that a call belongs to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CallRole {
+    Validate,
+    Execute,
+    FeeTransfer,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CallDiffKind {
+    /// The call appears only in the left execution info; its inner calls are not compared.
+    OnlyInLeft,
+    /// The call appears only in the right execution info; its inner calls are not compared.
+    OnlyInRight,
+    EntryPoint(Box<FieldDiff<CallEntryPoint>>),
+    Retdata(FieldDiff<Retdata>),
+    Failed(FieldDiff<bool>),
+    GasConsumed(FieldDiff<u64>),
+    Resources(FieldDiff<ExecutionResources>),
+    Events(FieldDiff<Vec<OrderedEvent>>),
+    L2ToL1Messages(FieldDiff<Vec<OrderedL2ToL1Message>>),
+    StorageReadValues(FieldDiff<Vec<StarkFelt>>),
+    AccessedStorageKeys(FieldDiff<HashSet<StorageKey>>),
+}
+
+/// A difference between the calls at the same position of the compared call trees.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CallDiff {
+    pub role: CallRole,
+    /// The indices of the inner calls leading to the call from the top-level call of its role;
+    /// empty for the top-level call.
+    pub path: Vec<usize>,
+    pub kind: CallDiffKind,
+}
+
+/// A structured diff of two transaction execution infos; e.g., for differential testing of
+/// executor versions or execution modes. The transaction metadata is not compared, as it contains
+/// the (nondeterministic) execution duration.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExecutionInfoDiff {
+    /// The differing calls, in pre-order of the call trees.
+    pub calls: Vec<CallDiff>,
+    pub actual_fee: Option<FieldDiff<Fee>>,
+    pub gas: Option<FieldDiff<GasVector>>,
+    pub da_gas: Option<FieldDiff<GasVector>>,
+    pub resources: Option<FieldDiff<TransactionResources>>,
+    /// The state writes the transaction is charged for; also covered by `resources`.
+    pub state_changes: Option<FieldDiff<StateChangesCount>>,
+    pub revert_error: Option<FieldDiff<Option<RevertReason>>>,
+    pub reverted_effects: Option<FieldDiff<Option<EmittedEffects>>>,
+    pub l1_handler_fee_info: Option<FieldDiff<Option<L1HandlerFeeInfo>>>,
+}
+
+impl ExecutionInfoDiff {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Compares the given execution infos; see [`ExecutionInfoDiff`].
+pub fn diff_execution_infos(
+    left: &TransactionExecutionInfo,
+    right: &TransactionExecutionInfo,
+) -> ExecutionInfoDiff {
+    let mut calls = Vec::new();
+    for (role, left_call, right_call) in [
+        (CallRole::Validate, &left.validate_call_info, &right.validate_call_info),
+        (CallRole::Execute, &left.execute_call_info, &right.execute_call_info),
+        (CallRole::FeeTransfer, &left.fee_transfer_call_info, &right.fee_transfer_call_info),
+    ] {
+        diff_calls(role, &mut Vec::new(), left_call.as_ref(), right_call.as_ref(), &mut calls);
+    }
+
+    ExecutionInfoDiff {
+        calls,
+        actual_fee: diff_field(&left.actual_fee, &right.actual_fee),
+        gas: diff_field(&left.gas, &right.gas),
+        da_gas: diff_field(&left.da_gas, &right.da_gas),
+        resources: diff_field(&left.actual_resources, &right.actual_resources),
+        state_changes: diff_field(
+            &left.actual_resources.starknet_resources.state_changes_for_fee,
+            &right.actual_resources.starknet_resources.state_changes_for_fee,
+        ),
+        revert_error: diff_field(&left.revert_error, &right.revert_error),
+        reverted_effects: diff_field(&left.reverted_effects, &right.reverted_effects),
+        l1_handler_fee_info: diff_field(&left.l1_handler_fee_info, &right.l1_handler_fee_info),
+    }
+}
+
+fn diff_calls(
+    role: CallRole,
+    path: &mut Vec<usize>,
+    left: Option<&CallInfo>,
+    right: Option<&CallInfo>,
+    diffs: &mut Vec<CallDiff>,
+) {
+    let (left, right) = match (left, right) {
+        (None, None) => return,
+        (Some(_), None) => {
+            diffs.push(CallDiff { role, path: path.clone(), kind: CallDiffKind::OnlyInLeft });
+            return;
+        }
+        (None, Some(_)) => {
+            diffs.push(CallDiff { role, path: path.clone(), kind: CallDiffKind::OnlyInRight });
+            return;
+        }
+        (Some(left), Some(right)) => (left, right),
+    };
+
+    let (left_execution, right_execution) = (&left.execution, &right.execution);
+    let kinds = [
+        diff_field(&left.call, &right.call).map(|diff| CallDiffKind::EntryPoint(Box::new(diff))),
+        diff_field(&left_execution.retdata, &right_execution.retdata).map(CallDiffKind::Retdata),
+        diff_field(&left_execution.failed, &right_execution.failed).map(CallDiffKind::Failed),
+        diff_field(&left_execution.gas_consumed, &right_execution.gas_consumed)
+            .map(CallDiffKind::GasConsumed),
+        diff_field(&left.resources, &right.resources).map(CallDiffKind::Resources),
+        diff_field(&left_execution.events, &right_execution.events).map(CallDiffKind::Events),
+        diff_field(&left_execution.l2_to_l1_messages, &right_execution.l2_to_l1_messages)
+            .map(CallDiffKind::L2ToL1Messages),
+        diff_field(&left.storage_read_values, &right.storage_read_values)
+            .map(CallDiffKind::StorageReadValues),
+        diff_field(&left.accessed_storage_keys, &right.accessed_storage_keys)
+            .map(CallDiffKind::AccessedStorageKeys),
+    ];
+    diffs.extend(kinds.into_iter().flatten().map(|kind| CallDiff {
+        role,
+        path: path.clone(),
+        kind,
+    }));
+
+    let n_inner_calls = left.inner_calls.len().max(right.inner_calls.len());
+    for inner_call_index in 0..n_inner_calls {
+        path.push(inner_call_index);
+        diff_calls(
+            role,
+            path,
+            left.inner_calls.get(inner_call_index),
+            right.inner_calls.get(inner_call_index),
+            diffs,
+        );
+        path.pop();
+    }
+}
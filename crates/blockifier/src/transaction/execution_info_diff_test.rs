@@ -0,0 +1,63 @@
+use pretty_assertions::assert_eq;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::Fee;
+
+use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
+use crate::retdata;
+use crate::transaction::execution_info_diff::{
+    diff_execution_infos, CallDiff, CallDiffKind, CallRole, ExecutionInfoDiff, FieldDiff,
+};
+use crate::transaction::objects::TransactionExecutionInfo;
+
+fn call_info(retdata: Retdata, inner_calls: Vec<CallInfo>) -> CallInfo {
+    CallInfo {
+        execution: CallExecution { retdata, ..Default::default() },
+        inner_calls,
+        ..Default::default()
+    }
+}
+
+fn execution_info(
+    inner_retdata: Retdata,
+    n_inner_calls: usize,
+    fee: u128,
+) -> TransactionExecutionInfo {
+    let inner_calls =
+        (0..n_inner_calls).map(|_| call_info(inner_retdata.clone(), vec![])).collect();
+    TransactionExecutionInfo {
+        validate_call_info: Some(call_info(retdata![], vec![])),
+        execute_call_info: Some(call_info(retdata![stark_felt!(1_u8)], inner_calls)),
+        actual_fee: Fee(fee),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_identical_execution_infos() {
+    let execution_info = execution_info(retdata![stark_felt!(2_u8)], 2, 1);
+    assert!(diff_execution_infos(&execution_info, &execution_info).is_empty());
+}
+
+#[test]
+fn test_execution_info_diff() {
+    let left = execution_info(retdata![stark_felt!(2_u8)], 2, 1);
+    let right = execution_info(retdata![stark_felt!(3_u8)], 1, 2);
+
+    let expected_diff = ExecutionInfoDiff {
+        calls: vec![
+            CallDiff {
+                role: CallRole::Execute,
+                path: vec![0],
+                kind: CallDiffKind::Retdata(FieldDiff {
+                    left: retdata![stark_felt!(2_u8)],
+                    right: retdata![stark_felt!(3_u8)],
+                }),
+            },
+            CallDiff { role: CallRole::Execute, path: vec![1], kind: CallDiffKind::OnlyInLeft },
+        ],
+        actual_fee: Some(FieldDiff { left: Fee(1), right: Fee(2) }),
+        ..Default::default()
+    };
+    assert_eq!(diff_execution_infos(&left, &right), expected_diff);
+}
@@ -0,0 +1,81 @@
+use cairo_vm::vm::runners::builtin_runner::{
+    BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
+    POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME, SEGMENT_ARENA_BUILTIN_NAME,
+    SIGNATURE_BUILTIN_NAME,
+};
+use serde::Serialize;
+use starknet_api::transaction::TransactionHash;
+
+use crate::transaction::objects::TransactionExecutionInfo;
+
+#[cfg(test)]
+#[path = "execution_record_test.rs"]
+pub mod test;
+
+/// A flat record of the gas, fee and resources of an executed transaction, for export to columnar
+/// formats. Unlike the execution info, every field is a scalar, and the layout is kept stable
+/// across releases; new fields are only appended.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct TransactionExecutionRecord {
+    /// Set for transactions executed by the transaction executor.
+    pub tx_hash: Option<TransactionHash>,
+    pub tx_index: Option<usize>,
+    pub is_reverted: bool,
+    pub revert_error: Option<String>,
+    /// The fee charged, in the units of the transaction's fee token.
+    pub fee: u128,
+    pub l1_gas: u128,
+    pub l1_data_gas: u128,
+    pub l2_gas: u128,
+    /// The part of the gas charged for data availability.
+    pub da_l1_gas: u128,
+    pub da_l1_data_gas: u128,
+    // The Cairo resources executed, including the ones of reverted calls.
+    pub n_steps: usize,
+    pub n_reverted_steps: usize,
+    pub n_memory_holes: usize,
+    pub pedersen_builtin: usize,
+    pub range_check_builtin: usize,
+    pub ecdsa_builtin: usize,
+    pub bitwise_builtin: usize,
+    pub ec_op_builtin: usize,
+    pub keccak_builtin: usize,
+    pub poseidon_builtin: usize,
+    pub segment_arena_builtin: usize,
+}
+
+impl From<&TransactionExecutionInfo> for TransactionExecutionRecord {
+    fn from(tx_execution_info: &TransactionExecutionInfo) -> Self {
+        let TransactionExecutionInfo {
+            actual_fee, gas, da_gas, actual_resources, metadata, ..
+        } = tx_execution_info;
+        let vm_resources = &actual_resources.vm_resources;
+        let builtin_count = |name: &str| {
+            vm_resources.builtin_instance_counter.get(name).copied().unwrap_or_default()
+        };
+
+        Self {
+            tx_hash: metadata.map(|metadata| metadata.tx_hash),
+            tx_index: metadata.map(|metadata| metadata.tx_index),
+            is_reverted: tx_execution_info.is_reverted(),
+            revert_error: tx_execution_info.revert_error.as_ref().map(ToString::to_string),
+            fee: actual_fee.0,
+            l1_gas: gas.l1_gas,
+            l1_data_gas: gas.l1_data_gas,
+            l2_gas: gas.l2_gas,
+            da_l1_gas: da_gas.l1_gas,
+            da_l1_data_gas: da_gas.l1_data_gas,
+            n_steps: vm_resources.n_steps,
+            n_reverted_steps: actual_resources.n_reverted_steps,
+            n_memory_holes: vm_resources.n_memory_holes,
+            pedersen_builtin: builtin_count(HASH_BUILTIN_NAME),
+            range_check_builtin: builtin_count(RANGE_CHECK_BUILTIN_NAME),
+            ecdsa_builtin: builtin_count(SIGNATURE_BUILTIN_NAME),
+            bitwise_builtin: builtin_count(BITWISE_BUILTIN_NAME),
+            ec_op_builtin: builtin_count(EC_OP_BUILTIN_NAME),
+            keccak_builtin: builtin_count(KECCAK_BUILTIN_NAME),
+            poseidon_builtin: builtin_count(POSEIDON_BUILTIN_NAME),
+            segment_arena_builtin: builtin_count(SEGMENT_ARENA_BUILTIN_NAME),
+        }
+    }
+}
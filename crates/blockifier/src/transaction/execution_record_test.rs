@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use cairo_vm::vm::runners::builtin_runner::{POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::{Fee, TransactionHash};
+
+use crate::transaction::execution_record::TransactionExecutionRecord;
+use crate::transaction::objects::{
+    GasVector, RevertReason, TransactionExecutionInfo, TransactionMetadata, TransactionResources,
+};
+
+#[test]
+fn test_execution_record() {
+    let tx_execution_info = TransactionExecutionInfo {
+        actual_fee: Fee(1000),
+        gas: GasVector { l1_gas: 10, l1_data_gas: 20, l2_gas: 30 },
+        da_gas: GasVector { l1_gas: 1, l1_data_gas: 2, l2_gas: 0 },
+        actual_resources: TransactionResources {
+            vm_resources: ExecutionResources {
+                n_steps: 100,
+                n_memory_holes: 5,
+                builtin_instance_counter: HashMap::from([
+                    (RANGE_CHECK_BUILTIN_NAME.to_string(), 7),
+                    (POSEIDON_BUILTIN_NAME.to_string(), 3),
+                ]),
+            },
+            n_reverted_steps: 50,
+            ..Default::default()
+        },
+        revert_error: Some(RevertReason::new("Out of gas.".to_string(), vec![], vec![])),
+        metadata: Some(TransactionMetadata {
+            tx_hash: TransactionHash(StarkFelt::ONE),
+            tx_index: 2,
+            execution_duration: Duration::from_millis(1),
+        }),
+        ..Default::default()
+    };
+
+    let record = tx_execution_info.to_record();
+    assert_eq!(
+        record,
+        TransactionExecutionRecord {
+            tx_hash: Some(TransactionHash(StarkFelt::ONE)),
+            tx_index: Some(2),
+            is_reverted: true,
+            revert_error: Some("Out of gas.".to_string()),
+            fee: 1000,
+            l1_gas: 10,
+            l1_data_gas: 20,
+            l2_gas: 30,
+            da_l1_gas: 1,
+            da_l1_data_gas: 2,
+            n_steps: 100,
+            n_reverted_steps: 50,
+            n_memory_holes: 5,
+            range_check_builtin: 7,
+            poseidon_builtin: 3,
+            ..Default::default()
+        }
+    );
+
+    // The record serializes into a flat object.
+    let serialized_record = serde_json::to_value(&record).unwrap();
+    let serialized_fields = serialized_record.as_object().unwrap();
+    assert!(serialized_fields.values().all(|value| !value.is_object() && !value.is_array()));
+}
@@ -0,0 +1,126 @@
+use starknet_api::core::{ContractAddress, EntryPointSelector};
+use starknet_api::transaction::{
+    Calldata, Fee, InvokeTransaction as StarknetApiInvokeTransaction, InvokeTransactionV1,
+    TransactionHash, TransactionSignature,
+};
+
+use crate::context::BlockContext;
+use crate::execution::call_info::{CallInfo, Retdata};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::StateReader;
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::errors::CallEncodingError;
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
+use crate::transaction::transactions::{ExecutableTransaction, InvokeTransaction};
+use crate::utils::usize_to_felt;
+
+#[cfg(test)]
+#[path = "multicall_test.rs"]
+mod test;
+
+/// A call to a contract, made by an account's `__execute__`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Call {
+    pub to: ContractAddress,
+    pub selector: EntryPointSelector,
+    pub calldata: Calldata,
+}
+
+/// The `__execute__` calldata layout expected by the sender account.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum AccountCallEncoding {
+    /// `[n_calls, (to, selector, calldata_len, calldata)...]`, as in SNIP-6 accounts.
+    #[default]
+    Multicall,
+    /// `[to, selector, calldata_len, calldata]`; supports a single call.
+    SingleCall,
+}
+
+impl AccountCallEncoding {
+    pub fn encode(&self, calls: &[Call]) -> Result<Calldata, CallEncodingError> {
+        let mut calldata = vec![];
+        match self {
+            Self::Multicall => calldata.push(usize_to_felt(calls.len())),
+            Self::SingleCall if calls.len() != 1 => {
+                return Err(CallEncodingError::InvalidSingleCallCount { n_calls: calls.len() });
+            }
+            Self::SingleCall => {}
+        }
+        for Call { to, selector, calldata: call_calldata } in calls {
+            calldata.extend([*to.0.key(), selector.0, usize_to_felt(call_calldata.0.len())]);
+            calldata.extend(call_calldata.0.iter().copied());
+        }
+
+        Ok(Calldata(calldata.into()))
+    }
+}
+
+/// A call made during the execution of a transaction, in a flattened trace.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlatCall {
+    /// The depth of the call in the call tree; the account's `__execute__` is at depth 0.
+    pub depth: usize,
+    pub call: CallEntryPoint,
+    pub retdata: Retdata,
+    pub failed: bool,
+}
+
+#[derive(Debug)]
+pub struct MulticallSimulationInfo {
+    /// The return data of each of the given calls, in order; empty if the transaction reverted.
+    pub call_retdata: Vec<Retdata>,
+    /// The calls made by `__execute__`, in execution order (depth first).
+    pub trace: Vec<FlatCall>,
+    pub tx_execution_info: TransactionExecutionInfo,
+}
+
+/// Simulates the given calls as a query-only invoke transaction of the sender account, without
+/// validation and fee charge. The state is not modified.
+pub fn simulate_multicall<S: StateReader>(
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+    sender_address: ContractAddress,
+    calls: &[Call],
+    encoding: AccountCallEncoding,
+) -> TransactionExecutionResult<MulticallSimulationInfo> {
+    let invoke_tx = StarknetApiInvokeTransaction::V1(InvokeTransactionV1 {
+        max_fee: Fee(0),
+        signature: TransactionSignature::default(),
+        nonce: state.get_nonce_at(sender_address)?,
+        sender_address,
+        calldata: encoding.encode(calls)?,
+    });
+    let tx = AccountTransaction::Invoke(InvokeTransaction::new_for_query(
+        invoke_tx,
+        TransactionHash::default(),
+    ));
+    let (charge_fee, validate) = (false, false);
+    let tx_execution_info =
+        tx.simulate(state, block_context, charge_fee, validate)?.tx_execution_info;
+
+    let mut trace = vec![];
+    let mut call_retdata = vec![];
+    if let Some(execute_call_info) = &tx_execution_info.execute_call_info {
+        flatten_call_info(execute_call_info, 0, &mut trace);
+        call_retdata = execute_call_info
+            .inner_calls
+            .iter()
+            .map(|call_info| call_info.execution.retdata.clone())
+            .collect();
+    }
+
+    Ok(MulticallSimulationInfo { call_retdata, trace, tx_execution_info })
+}
+
+fn flatten_call_info(call_info: &CallInfo, depth: usize, trace: &mut Vec<FlatCall>) {
+    trace.push(FlatCall {
+        depth,
+        call: call_info.call.clone(),
+        retdata: call_info.execution.retdata.clone(),
+        failed: call_info.execution.failed,
+    });
+    for inner_call in &call_info.inner_calls {
+        flatten_call_info(inner_call, depth + 1, trace);
+    }
+}
@@ -0,0 +1,107 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::{ContractAddress, EntryPointSelector, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, contract_address, patricia_key, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::BlockContext;
+use crate::execution::call_info::Retdata;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{CairoVersion, BALANCE};
+use crate::transaction::constants::EXECUTE_ENTRY_POINT_NAME;
+use crate::transaction::errors::CallEncodingError;
+use crate::transaction::multicall::{simulate_multicall, AccountCallEncoding, Call};
+use crate::transaction::test_utils::block_context;
+use crate::{nonce, retdata};
+
+#[test]
+fn test_multicall_encoding() {
+    let calls = [
+        Call {
+            to: contract_address!("0x10"),
+            selector: EntryPointSelector(stark_felt!(1_u8)),
+            calldata: calldata![stark_felt!(2_u8), stark_felt!(3_u8)],
+        },
+        Call {
+            to: contract_address!("0x20"),
+            selector: EntryPointSelector(stark_felt!(4_u8)),
+            calldata: calldata![],
+        },
+    ];
+
+    assert_eq!(
+        AccountCallEncoding::Multicall.encode(&calls).unwrap(),
+        calldata![
+            stark_felt!(2_u8),
+            stark_felt!(0x10_u8),
+            stark_felt!(1_u8),
+            stark_felt!(2_u8),
+            stark_felt!(2_u8),
+            stark_felt!(3_u8),
+            stark_felt!(0x20_u8),
+            stark_felt!(4_u8),
+            stark_felt!(0_u8)
+        ]
+    );
+    assert_eq!(
+        AccountCallEncoding::SingleCall.encode(&calls[1..]).unwrap(),
+        calldata![stark_felt!(0x20_u8), stark_felt!(4_u8), stark_felt!(0_u8)]
+    );
+    assert_matches!(
+        AccountCallEncoding::SingleCall.encode(&calls),
+        Err(CallEncodingError::InvalidSingleCallCount { n_calls: 2 })
+    );
+}
+
+#[rstest]
+fn test_simulate_multicall(
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let account = FeatureContract::AccountWithoutValidations(cairo_version);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state =
+        &mut test_state(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let sender_address = account.get_instance_address(0);
+    let test_contract_address = test_contract.get_instance_address(0);
+    let call = Call {
+        to: test_contract_address,
+        selector: selector_from_name("return_result"),
+        calldata: calldata![stark_felt!(7_u8)],
+    };
+
+    let simulation_info = simulate_multicall(
+        state,
+        &block_context,
+        sender_address,
+        &[call],
+        AccountCallEncoding::SingleCall,
+    )
+    .unwrap();
+
+    assert!(simulation_info.tx_execution_info.revert_error.is_none());
+    assert_eq!(simulation_info.call_retdata, vec![retdata![stark_felt!(7_u8)]]);
+    let trace: Vec<_> = simulation_info
+        .trace
+        .iter()
+        .map(|flat_call| {
+            (flat_call.depth, flat_call.call.entry_point_selector, flat_call.retdata.clone())
+        })
+        .collect();
+    assert_eq!(
+        trace,
+        vec![
+            // The account returns the return data of its single call as is.
+            (0, selector_from_name(EXECUTE_ENTRY_POINT_NAME), retdata![stark_felt!(7_u8)]),
+            (1, selector_from_name("return_result"), retdata![stark_felt!(7_u8)]),
+        ]
+    );
+
+    // The state is not modified.
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), nonce!(0_u8));
+}
@@ -1,12 +1,13 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
 
-use cairo_felt::Felt252;
 use cairo_vm::vm::runners::builtin_runner::SEGMENT_ARENA_BUILTIN_NAME;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
-use num_traits::Pow;
 use serde::Serialize;
-use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, Nonce};
 use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::hash::StarkFelt;
 use starknet_api::transaction::{
     AccountDeploymentData, Fee, PaymasterData, Resource, ResourceBounds, ResourceBoundsMapping,
     Tip, TransactionHash, TransactionSignature, TransactionVersion,
@@ -14,20 +15,26 @@ use starknet_api::transaction::{
 use strum_macros::EnumIter;
 
 use crate::abi::constants as abi_constants;
-use crate::context::BlockContext;
-use crate::execution::call_info::{CallInfo, ExecutionSummary, MessageL1CostInfo, OrderedEvent};
-use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
-use crate::fee::eth_gas_constants;
-use crate::fee::fee_utils::{calculate_l1_gas_by_vm_usage, calculate_tx_fee};
+use crate::context::{BlockContext, TransactionContext};
+use crate::execution::call_info::{
+    CallInfo, EmittedEffects, ExecutionSummary, MessageL1CostInfo, OrderedEvent,
+};
+use crate::fee::fee_breakdown::FeeBreakdown;
+use crate::fee::fee_checks::FeeCheckError;
+use crate::fee::fee_utils::{
+    calculate_l1_gas_by_sierra_gas, calculate_l1_gas_by_vm_usage_with_rounding, calculate_tx_fee,
+    checked_add_fee, checked_mul_price, saturating_mul_price, Rounding,
+};
 use crate::fee::gas_usage::{
-    get_consumed_message_to_l2_emissions_cost, get_da_gas_cost,
-    get_log_message_to_l1_emissions_cost, get_onchain_data_segment_length,
+    get_da_gas_cost, get_messages_gas_cost, get_onchain_data_segment_length,
+    get_starknet_messages_gas_usage,
 };
-use crate::state::cached_state::StateChangesCount;
-use crate::transaction::constants;
+use crate::state::cached_state::{CommitmentStateDiff, StateChangesCount};
 use crate::transaction::errors::{
-    TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
+    FeeOverflowError, TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
 };
+use crate::transaction::execution_record::TransactionExecutionRecord;
+use crate::transaction::transaction_utils::to_query_version;
 use crate::utils::{u128_from_usize, usize_from_u128};
 use crate::versioned_constants::VersionedConstants;
 
@@ -51,6 +58,8 @@ macro_rules! implement_getters {
 }
 
 /// Contains the account information of the transaction (outermost call).
+// Both variants hold the common account fields, and the current one is the larger.
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum TransactionInfo {
     Current(CurrentTransactionInfo),
@@ -63,7 +72,8 @@ impl TransactionInfo {
         (version, TransactionVersion),
         (nonce, Nonce),
         (sender_address, ContractAddress),
-        (only_query, bool)
+        (only_query, bool),
+        (fee_recipient, Option<ContractAddress>)
     );
 
     pub fn signature(&self) -> TransactionSignature {
@@ -83,21 +93,43 @@ impl TransactionInfo {
             return version;
         }
 
-        let query_version_base = Pow::pow(Felt252::from(2_u8), constants::QUERY_VERSION_BASE_BIT);
-        let query_version = query_version_base + stark_felt_to_felt(version.0);
-        TransactionVersion(felt_to_stark_felt(&query_version))
+        to_query_version(version)
     }
 
     pub fn enforce_fee(&self) -> TransactionFeeResult<bool> {
         match self {
             TransactionInfo::Current(context) => {
                 let l1_bounds = context.l1_resource_bounds()?;
-                let max_amount: u128 = l1_bounds.max_amount.into();
-                Ok(max_amount * l1_bounds.max_price_per_unit > 0)
+                let l2_bounds = context.l2_resource_bounds();
+                Ok([l1_bounds, l2_bounds].into_iter().any(|bounds| {
+                    saturating_mul_price(bounds.max_amount.into(), bounds.max_price_per_unit)
+                        > Fee(0)
+                }))
             }
             TransactionInfo::Deprecated(context) => Ok(context.max_fee != Fee(0)),
         }
     }
+
+    /// Returns the maximal fee the transaction may be charged: its max fee, or the fee committed to
+    /// by its resource bounds.
+    pub fn max_fee_bound(&self) -> TransactionFeeResult<Fee> {
+        match self {
+            TransactionInfo::Current(context) => context.committed_fee(),
+            TransactionInfo::Deprecated(context) => Ok(context.max_fee),
+        }
+    }
+
+    /// Returns the L2 gas limit of transactions with all resource bounds, i.e., with a non-zero L2
+    /// gas amount bound; [None] if L2 gas is not bounded, as in transactions prior to V3.
+    pub fn l2_gas_limit(&self) -> Option<u64> {
+        match self {
+            TransactionInfo::Current(context) => {
+                let max_amount = context.l2_resource_bounds().max_amount;
+                (max_amount > 0).then_some(max_amount)
+            }
+            TransactionInfo::Deprecated(_) => None,
+        }
+    }
 }
 
 impl HasRelatedFeeType for TransactionInfo {
@@ -129,6 +161,20 @@ impl CurrentTransactionInfo {
             None => Err(TransactionFeeError::MissingL1GasBounds),
         }
     }
+
+    /// Fetch the L2 resource bounds; zero bounds if they do not exist.
+    pub fn l2_resource_bounds(&self) -> ResourceBounds {
+        self.resource_bounds.0.get(&Resource::L2Gas).copied().unwrap_or_default()
+    }
+
+    /// The maximal fee the sender commits to by the resource bounds; fails on overflow.
+    pub fn committed_fee(&self) -> TransactionFeeResult<Fee> {
+        let l1_bounds = self.l1_resource_bounds()?;
+        let l2_bounds = self.l2_resource_bounds();
+        let l1_fee = checked_mul_price(l1_bounds.max_amount.into(), l1_bounds.max_price_per_unit)?;
+        let l2_fee = checked_mul_price(l2_bounds.max_amount.into(), l2_bounds.max_price_per_unit)?;
+        Ok(checked_add_fee(l1_fee, l2_fee)?)
+    }
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -143,44 +189,59 @@ pub struct DeprecatedTransactionInfo {
 pub struct GasVector {
     pub l1_gas: u128,
     pub l1_data_gas: u128,
+    /// Sierra gas; only charged for transactions with all resource bounds. Omitted from the
+    /// serialization if zero.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub l2_gas: u128,
+}
+
+fn is_zero(value: &u128) -> bool {
+    *value == 0
 }
 
 impl GasVector {
     pub fn from_l1_gas(l1_gas: u128) -> Self {
-        Self { l1_gas, l1_data_gas: 0 }
+        Self { l1_gas, ..Default::default() }
     }
 
     pub fn from_l1_data_gas(l1_data_gas: u128) -> Self {
-        Self { l1_gas: 0, l1_data_gas }
+        Self { l1_data_gas, ..Default::default() }
+    }
+
+    pub fn from_l2_gas(l2_gas: u128) -> Self {
+        Self { l2_gas, ..Default::default() }
+    }
+
+    /// Adds the given gas vector; fails if any kind of gas overflows.
+    pub fn checked_add(self, rhs: Self) -> Result<Self, FeeOverflowError> {
+        let overflow = || FeeOverflowError::GasVectorAddition { lhs: self, rhs };
+        Ok(Self {
+            l1_gas: self.l1_gas.checked_add(rhs.l1_gas).ok_or_else(overflow)?,
+            l1_data_gas: self.l1_data_gas.checked_add(rhs.l1_data_gas).ok_or_else(overflow)?,
+            l2_gas: self.l2_gas.checked_add(rhs.l2_gas).ok_or_else(overflow)?,
+        })
+    }
+
+    /// Computes the cost (in fee token units) of the gas vector; fails on overflow.
+    pub fn checked_cost(
+        &self,
+        gas_price: u128,
+        blob_gas_price: u128,
+        l2_gas_price: u128,
+    ) -> Result<Fee, FeeOverflowError> {
+        [(self.l1_gas, gas_price), (self.l1_data_gas, blob_gas_price), (self.l2_gas, l2_gas_price)]
+            .into_iter()
+            .try_fold(Fee(0), |total, (gas_amount, gas_price)| {
+                checked_add_fee(total, checked_mul_price(gas_amount, gas_price)?)
+            })
     }
 
     /// Computes the cost (in fee token units) of the gas vector (saturating on overflow).
-    pub fn saturated_cost(&self, gas_price: u128, blob_gas_price: u128) -> Fee {
-        let l1_gas_cost = self.l1_gas.checked_mul(gas_price).unwrap_or_else(|| {
-            log::warn!(
-                "L1 gas cost overflowed: multiplication of {} by {} resulted in overflow.",
-                self.l1_gas,
-                gas_price
-            );
-            u128::MAX
-        });
-        let l1_data_gas_cost = self.l1_data_gas.checked_mul(blob_gas_price).unwrap_or_else(|| {
-            log::warn!(
-                "L1 blob gas cost overflowed: multiplication of {} by {} resulted in overflow.",
-                self.l1_data_gas,
-                blob_gas_price
-            );
-            u128::MAX
-        });
-        let total = l1_gas_cost.checked_add(l1_data_gas_cost).unwrap_or_else(|| {
-            log::warn!(
-                "Total gas cost overflowed: addition of {} and {} resulted in overflow.",
-                l1_gas_cost,
-                l1_data_gas_cost
-            );
-            u128::MAX
-        });
-        Fee(total)
+    pub fn saturated_cost(&self, gas_price: u128, blob_gas_price: u128, l2_gas_price: u128) -> Fee {
+        self.checked_cost(gas_price, blob_gas_price, l2_gas_price).unwrap_or_else(|error| {
+            log::warn!("{error} Saturating to the maximal fee.");
+            Fee(u128::MAX)
+        })
     }
 }
 
@@ -192,6 +253,64 @@ pub struct CommonAccountFields {
     pub nonce: Nonce,
     pub sender_address: ContractAddress,
     pub only_query: bool,
+    /// Overrides the recipient of the transaction's fee; if unset, the fee is transferred to the
+    /// block's sequencer.
+    pub fee_recipient: Option<ContractAddress>,
+}
+
+/// A call frame on the path to the failure of a reverted transaction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RevertFrame {
+    pub contract_address: ContractAddress,
+    pub class_hash: ClassHash,
+    /// [None] if the selector of the call is unknown.
+    pub selector: Option<EntryPointSelector>,
+}
+
+/// The reason a transaction was reverted.
+/// Its display is the error trace, as reported before the structured fields were introduced.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RevertReason {
+    /// The call frames leading to the failure, from the outermost call inwards.
+    pub frames: Vec<RevertFrame>,
+    /// The panic data of the failing call; empty if the failure was not a panic.
+    pub panic_data: Vec<StarkFelt>,
+    trace: String,
+}
+
+impl RevertReason {
+    pub fn new(trace: String, frames: Vec<RevertFrame>, panic_data: Vec<StarkFelt>) -> Self {
+        Self { frames, panic_data, trace }
+    }
+}
+
+impl From<FeeCheckError> for RevertReason {
+    fn from(error: FeeCheckError) -> Self {
+        Self::new(error.to_string(), vec![], vec![])
+    }
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.trace)
+    }
+}
+
+/// Fee accounting of an L1 handler transaction, whose fee is paid on L1 when the message is sent.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct L1HandlerFeeInfo {
+    pub paid_fee_on_l1: Fee,
+    /// The fee the transaction actually consumed.
+    pub actual_fee: Fee,
+    /// The part of the paid fee that exceeds the actual fee, to be refunded on L1.
+    pub refundable_fee: Fee,
+}
+
+impl L1HandlerFeeInfo {
+    pub fn new(paid_fee_on_l1: Fee, actual_fee: Fee) -> Self {
+        let refundable_fee = Fee(paid_fee_on_l1.0.saturating_sub(actual_fee.0));
+        Self { paid_fee_on_l1, actual_fee, refundable_fee }
+    }
 }
 
 /// Contains the information gathered by the execution of a transaction.
@@ -205,15 +324,54 @@ pub struct TransactionExecutionInfo {
     pub fee_transfer_call_info: Option<CallInfo>,
     /// The actual fee that was charged (in Wei).
     pub actual_fee: Fee,
+    /// Total gas consumption the transaction is charged for, split into L1 gas, L1 data gas and L2
+    /// gas.
+    pub gas: GasVector,
     /// Actual gas consumption the transaction is charged for data availability.
     pub da_gas: GasVector,
     /// Actual execution resources the transaction is charged for,
     /// including L1 gas and additional OS resources estimation.
     pub actual_resources: TransactionResources,
-    /// Error string for reverted transactions; [None] if transaction execution was successful.
+    /// Revert reason for reverted transactions; [None] if transaction execution was successful.
     // TODO(Dori, 1/8/2023): If the `Eq` and `PartialEq` traits are removed, or implemented on all
     //   internal structs in this enum, this field should be `Option<TransactionExecutionError>`.
-    pub revert_error: Option<String>,
+    pub revert_error: Option<RevertReason>,
+    /// Events and L2-to-L1 messages emitted by the execution before it was reverted; [None] if
+    /// transaction execution was successful. Note that these effects did not take place.
+    pub reverted_effects: Option<EmittedEffects>,
+    /// Fee accounting of the fee paid on L1; [None] for account transactions.
+    pub l1_handler_fee_info: Option<L1HandlerFeeInfo>,
+    /// Metadata of the transaction within its block; set by the transaction executor, [None]
+    /// otherwise.
+    pub metadata: Option<TransactionMetadata>,
+}
+
+/// Metadata of a transaction executed as part of a block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TransactionMetadata {
+    pub tx_hash: TransactionHash,
+    /// The position of the transaction in the block.
+    pub tx_index: usize,
+    /// Wall-clock duration of the execution, including validation and fee transfer.
+    pub execution_duration: Duration,
+}
+
+/// The result of a transaction dry run: the execution info, and the state diff the transaction
+/// would have applied.
+#[derive(Debug, PartialEq)]
+pub struct TransactionSimulationInfo {
+    pub tx_execution_info: TransactionExecutionInfo,
+    pub state_diff: CommitmentStateDiff,
+}
+
+/// The fee estimation of a deploy account transaction of a counterfactual account.
+#[derive(Debug, PartialEq)]
+pub struct DeployAccountEstimation {
+    /// The derived address of the account.
+    pub contract_address: ContractAddress,
+    pub simulation_info: TransactionSimulationInfo,
+    /// Whether the current balance of the account covers the estimated fee.
+    pub is_funded: bool,
 }
 
 impl TransactionExecutionInfo {
@@ -228,11 +386,35 @@ impl TransactionExecutionInfo {
         self.revert_error.is_some()
     }
 
+    /// Returns the calls of the transaction whose metering by Sierra gas diverges from their
+    /// metering by VM resources by more than the given percentage; recorded only under
+    /// [`crate::context::GasMeteringMode::Dual`].
+    pub fn gas_metering_divergences(
+        &self,
+        tolerance_percent: u64,
+    ) -> impl Iterator<Item = &CallInfo> {
+        self.non_optional_call_infos()
+            .flat_map(move |call_info| call_info.gas_metering_divergences(tolerance_percent))
+    }
+
     /// Returns a summary of transaction execution, including executed class hashes, visited storage
     /// entries, L2-to-L1_payload_lengths, and the number of emitted events.
     pub fn summarize(&self) -> ExecutionSummary {
         self.non_optional_call_infos().map(|call_info| call_info.summarize()).sum()
     }
+
+    /// Returns a breakdown of the actual fee, given the context the transaction was executed in.
+    pub fn fee_breakdown(
+        &self,
+        tx_context: &TransactionContext,
+    ) -> TransactionFeeResult<FeeBreakdown> {
+        Ok(FeeBreakdown::new(&self.actual_resources, tx_context)?.with_charged_fee(self.actual_fee))
+    }
+
+    /// Returns a flat record of the gas, fee and resources of the transaction, for export.
+    pub fn to_record(&self) -> TransactionExecutionRecord {
+        TransactionExecutionRecord::from(self)
+    }
 }
 
 /// A mapping from a transaction execution resource to its actual usage.
@@ -326,35 +508,13 @@ impl StarknetResources {
     /// Returns an estimation of the gas usage for processing L1<>L2 messages on L1. Accounts for
     /// Starknet contract only.
     fn get_messages_gas_usage(&self) -> GasVector {
-        let n_l2_to_l1_messages = self.message_cost_info.l2_to_l1_payload_lengths.len();
-        let n_l1_to_l2_messages = usize::from(self.l1_handler_payload_size.is_some());
-
-        GasVector::from_l1_gas(
-            // Starknet's updateState gets the message segment as an argument.
-            u128_from_usize(
-                self.message_cost_info.message_segment_length * eth_gas_constants::GAS_PER_MEMORY_WORD
-                // Starknet's updateState increases a (storage) counter for each L2-to-L1 message.
-                + n_l2_to_l1_messages * eth_gas_constants::GAS_PER_ZERO_TO_NONZERO_STORAGE_SET
-                // Starknet's updateState decreases a (storage) counter for each L1-to-L2 consumed
-                // message (note that we will probably get a refund of 15,000 gas for each consumed
-                // message but we ignore it since refunded gas cannot be used for the current
-                // transaction execution).
-                + n_l1_to_l2_messages * eth_gas_constants::GAS_PER_COUNTER_DECREASE,
-            ),
-        ) + get_consumed_message_to_l2_emissions_cost(self.l1_handler_payload_size)
-            + get_log_message_to_l1_emissions_cost(&self.message_cost_info.l2_to_l1_payload_lengths)
+        get_starknet_messages_gas_usage(&self.message_cost_info, self.l1_handler_payload_size)
     }
 
     /// Returns an estimation of the gas usage for processing L1<>L2 messages on L1. Accounts for
     /// both Starknet and SHARP contracts.
     pub fn get_messages_cost(&self) -> GasVector {
-        let starknet_gas_usage = self.get_messages_gas_usage();
-        let sharp_gas_usage = GasVector::from_l1_gas(u128_from_usize(
-            self.message_cost_info.message_segment_length
-                * eth_gas_constants::SHARP_GAS_PER_MEMORY_WORD,
-        ));
-
-        starknet_gas_usage + sharp_gas_usage
+        get_messages_gas_cost(&self.message_cost_info, self.l1_handler_payload_size)
     }
 
     /// Calculates the L1 resources used by L1<>L2 messages.
@@ -423,28 +583,118 @@ impl StarknetResources {
     }
 }
 
+/// The computation metered by Sierra gas, in Sierra gas metering mode or for transactions charged
+/// for L2 gas.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct SierraGasMetering {
+    /// The Sierra gas consumed by Cairo 1 calls, and the VM resources of Cairo 0 calls converted
+    /// to Sierra gas.
+    pub sierra_gas: u64,
+    /// The VM resources of the metered calls; these are not charged for as VM resources.
+    pub vm_resources: ExecutionResources,
+    /// The Sierra gas in terms of Cairo steps; counted by the bouncer in place of the steps of the
+    /// metered calls.
+    pub n_steps: usize,
+}
+
+impl SierraGasMetering {
+    /// Meters the given calls, including their inner calls, by Sierra gas.
+    pub fn new<'a>(
+        call_infos: impl Iterator<Item = &'a CallInfo>,
+        versioned_constants: &VersionedConstants,
+    ) -> Self {
+        let mut sierra_gas: u64 = 0;
+        let mut vm_resources = ExecutionResources::default();
+        for call_info in call_infos {
+            vm_resources += &call_info.resources;
+            sierra_gas =
+                sierra_gas.saturating_add(call_info.sierra_gas_consumed(versioned_constants));
+        }
+        let step_gas_cost = versioned_constants.os_constants.gas_costs.step_gas_cost.max(1);
+        let n_steps = usize::try_from(sierra_gas.div_ceil(step_gas_cost)).unwrap_or(usize::MAX);
+
+        Self { sierra_gas, vm_resources: vm_resources.filter_unused_builtins(), n_steps }
+    }
+}
+
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct TransactionResources {
     pub starknet_resources: StarknetResources,
     pub vm_resources: ExecutionResources,
     pub n_reverted_steps: usize,
+    /// The Sierra gas charged as L2 gas; zero for transactions without all resource bounds.
+    pub l2_gas: u64,
+    /// The computation metered by Sierra gas; empty unless in Sierra gas metering mode or charged
+    /// for L2 gas.
+    pub sierra_gas_metering: SierraGasMetering,
 }
 
 impl TransactionResources {
-    /// Computes and returns the total L1 gas consumption.
+    /// Computes and returns the total gas consumption.
     /// We add the l1_gas_usage (which may include, for example, the direct cost of L2-to-L1
-    /// messages) to the gas consumed by Cairo VM resource.
+    /// messages) to the gas consumed by Cairo VM resource, and the charged L2 gas.
     pub fn to_gas_vector(
         &self,
         versioned_constants: &VersionedConstants,
         use_kzg_da: bool,
     ) -> TransactionFeeResult<GasVector> {
-        Ok(self.starknet_resources.to_gas_vector(versioned_constants, use_kzg_da)
-            + calculate_l1_gas_by_vm_usage(
-                versioned_constants,
-                &self.vm_resources,
-                self.n_reverted_steps,
-            )?)
+        self.to_gas_vector_with_rounding(versioned_constants, use_kzg_da, Rounding::Up)
+    }
+
+    /// As [`Self::to_gas_vector`], with the given rounding of the L1 gas the Cairo resources and
+    /// the Sierra gas are converted to.
+    pub fn to_gas_vector_with_rounding(
+        &self,
+        versioned_constants: &VersionedConstants,
+        use_kzg_da: bool,
+        rounding: Rounding,
+    ) -> TransactionFeeResult<GasVector> {
+        let vm_gas = calculate_l1_gas_by_vm_usage_with_rounding(
+            versioned_constants,
+            &self.charged_vm_resources(),
+            self.n_reverted_steps,
+            rounding,
+        )?;
+        let sierra_gas = GasVector::from_l1_gas(calculate_l1_gas_by_sierra_gas(
+            versioned_constants,
+            self.l1_charged_sierra_gas(),
+            rounding,
+        ));
+        Ok(self
+            .starknet_resources
+            .to_gas_vector(versioned_constants, use_kzg_da)
+            .checked_add(vm_gas)?
+            .checked_add(sierra_gas)?
+            .checked_add(GasVector::from_l2_gas(self.l2_gas.into()))?)
+    }
+
+    /// Returns the VM resources charged for; i.e., excluding the ones of calls metered by Sierra
+    /// gas.
+    pub fn charged_vm_resources(&self) -> ExecutionResources {
+        let metered_resources = &self.sierra_gas_metering.vm_resources;
+        ExecutionResources {
+            n_steps: self.vm_resources.n_steps.saturating_sub(metered_resources.n_steps),
+            n_memory_holes: self
+                .vm_resources
+                .n_memory_holes
+                .saturating_sub(metered_resources.n_memory_holes),
+            builtin_instance_counter: self
+                .vm_resources
+                .builtin_instance_counter
+                .iter()
+                .map(|(name, count)| {
+                    let metered_count =
+                        metered_resources.builtin_instance_counter.get(name).copied();
+                    (name.clone(), count.saturating_sub(metered_count.unwrap_or_default()))
+                })
+                .collect(),
+        }
+        .filter_unused_builtins()
+    }
+
+    /// Returns the metered Sierra gas not charged as L2 gas, which is charged as L1 gas instead.
+    pub fn l1_charged_sierra_gas(&self) -> u64 {
+        self.sierra_gas_metering.sierra_gas.saturating_sub(self.l2_gas)
     }
 
     pub fn to_resources_mapping(
@@ -453,7 +703,7 @@ impl TransactionResources {
         use_kzg_da: bool,
         with_reverted_steps: bool,
     ) -> ResourcesMapping {
-        let GasVector { l1_gas, l1_data_gas } =
+        let GasVector { l1_gas, l1_data_gas, .. } =
             self.starknet_resources.to_gas_vector(versioned_constants, use_kzg_da);
         let mut resources = self.vm_resources.to_resources_mapping();
         resources.0.extend(HashMap::from([
@@ -481,6 +731,7 @@ impl TransactionResources {
 
 pub trait ExecutionResourcesTraits {
     fn total_n_steps(&self) -> usize;
+    fn segment_arena_n_steps(&self) -> usize;
     fn to_resources_mapping(&self) -> ResourcesMapping;
     fn prover_builtins(&self) -> HashMap<String, usize>;
 }
@@ -491,21 +742,25 @@ impl ExecutionResourcesTraits for ExecutionResources {
             // Memory holes are slightly cheaper than actual steps, but we count them as such
             // for simplicity.
             + self.n_memory_holes
-            // The "segment arena" builtin is not part of the prover (not in any proof layout);
-            // It is transformed into regular steps by the OS program - each instance requires
-            // approximately 10 steps.
-            + abi_constants::N_STEPS_PER_SEGMENT_ARENA_BUILTIN
-                * self
-                    .builtin_instance_counter
-                    .get(SEGMENT_ARENA_BUILTIN_NAME)
-                    .cloned()
-                    .unwrap_or_default()
+            + self.segment_arena_n_steps()
+    }
+
+    fn segment_arena_n_steps(&self) -> usize {
+        // The "segment arena" builtin is not part of the prover (not in any proof layout);
+        // It is transformed into regular steps by the OS program - each instance requires
+        // approximately 10 steps.
+        abi_constants::N_STEPS_PER_SEGMENT_ARENA_BUILTIN
+            * self
+                .builtin_instance_counter
+                .get(SEGMENT_ARENA_BUILTIN_NAME)
+                .cloned()
+                .unwrap_or_default()
     }
 
     fn prover_builtins(&self) -> HashMap<String, usize> {
         let mut builtins = self.builtin_instance_counter.clone();
 
-        // See "total_n_steps" documentation.
+        // See "segment_arena_n_steps" documentation.
         builtins.remove(SEGMENT_ARENA_BUILTIN_NAME);
         builtins
     }
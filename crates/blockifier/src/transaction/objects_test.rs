@@ -1,13 +1,15 @@
 use rstest::rstest;
-use starknet_api::class_hash;
 use starknet_api::core::ClassHash;
-use starknet_api::hash::StarkHash;
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::TransactionVersion;
+use starknet_api::{class_hash, stark_felt};
 
 use crate::execution::call_info::{
     CallExecution, CallInfo, ExecutionSummary, OrderedEvent, TestExecutionSummary,
 };
 use crate::execution::entry_point::CallEntryPoint;
 use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::transaction_utils::{split_query_version, to_query_version};
 
 fn shared_call_info() -> CallInfo {
     CallInfo {
@@ -166,3 +168,14 @@ fn test_summarize(
     assert_eq!(actual_summary.n_events, expected_summary.n_events);
     assert_eq!(actual_summary.l2_to_l1_payload_lengths, expected_summary.l2_to_l1_payload_lengths);
 }
+
+#[rstest]
+#[case::v0(TransactionVersion::ZERO, "0x100000000000000000000000000000000")]
+#[case::v1(TransactionVersion::ONE, "0x100000000000000000000000000000001")]
+#[case::v3(TransactionVersion::THREE, "0x100000000000000000000000000000003")]
+fn test_query_version(#[case] version: TransactionVersion, #[case] expected_query_version: &str) {
+    let query_version = to_query_version(version);
+    assert_eq!(query_version, TransactionVersion(stark_felt!(expected_query_version)));
+    assert_eq!(split_query_version(query_version), (version, true));
+    assert_eq!(split_query_version(version), (version, false));
+}
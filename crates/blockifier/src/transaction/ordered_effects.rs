@@ -0,0 +1,125 @@
+use starknet_api::core::ContractAddress;
+
+use crate::execution::call_info::{CallExecution, CallInfo, OrderedEvent, OrderedL2ToL1Message};
+use crate::transaction::execution_info_diff::CallRole;
+use crate::transaction::objects::TransactionExecutionInfo;
+
+#[cfg(test)]
+#[path = "ordered_effects_test.rs"]
+mod test;
+
+/// An event or an L2-to-L1 message, along with the call that emitted it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LocatedEffect<T> {
+    /// The position of the emitting transaction in the block.
+    pub tx_index: usize,
+    pub role: CallRole,
+    /// The indices of the inner calls leading to the emitting call from the top-level call of its
+    /// role; empty for the top-level call.
+    pub call_path: Vec<usize>,
+    pub from_address: ContractAddress,
+    pub effect: T,
+}
+
+pub type LocatedEvent = LocatedEffect<OrderedEvent>;
+pub type LocatedL2ToL1Message = LocatedEffect<OrderedL2ToL1Message>;
+
+/// Returns the events emitted by the given transaction, in protocol order: the events of the
+/// validation, execution and fee transfer calls, in that order, each ordered by emission.
+pub fn tx_ordered_events(
+    tx_index: usize,
+    tx_execution_info: &TransactionExecutionInfo,
+) -> impl Iterator<Item = LocatedEvent> {
+    located_effects(tx_index, tx_execution_info, |execution| &execution.events, |event| event.order)
+        .into_iter()
+}
+
+/// Returns the L2-to-L1 messages sent by the given transaction, in protocol order; see
+/// [`tx_ordered_events`].
+pub fn tx_ordered_messages(
+    tx_index: usize,
+    tx_execution_info: &TransactionExecutionInfo,
+) -> impl Iterator<Item = LocatedL2ToL1Message> {
+    located_effects(
+        tx_index,
+        tx_execution_info,
+        |execution| &execution.l2_to_l1_messages,
+        |message| message.order,
+    )
+    .into_iter()
+}
+
+/// Returns the events emitted by the given transactions of a block, in protocol order; the
+/// transactions are indexed by their position in the given slice.
+pub fn block_ordered_events(
+    tx_execution_infos: &[TransactionExecutionInfo],
+) -> impl Iterator<Item = LocatedEvent> + '_ {
+    tx_execution_infos
+        .iter()
+        .enumerate()
+        .flat_map(|(tx_index, tx_execution_info)| tx_ordered_events(tx_index, tx_execution_info))
+}
+
+/// Returns the L2-to-L1 messages sent by the given transactions of a block, in protocol order; see
+/// [`block_ordered_events`].
+pub fn block_ordered_messages(
+    tx_execution_infos: &[TransactionExecutionInfo],
+) -> impl Iterator<Item = LocatedL2ToL1Message> + '_ {
+    tx_execution_infos
+        .iter()
+        .enumerate()
+        .flat_map(|(tx_index, tx_execution_info)| tx_ordered_messages(tx_index, tx_execution_info))
+}
+
+fn located_effects<T: Clone>(
+    tx_index: usize,
+    tx_execution_info: &TransactionExecutionInfo,
+    effects_of: impl Fn(&CallExecution) -> &Vec<T>,
+    order_of: impl Fn(&T) -> usize,
+) -> Vec<LocatedEffect<T>> {
+    let mut located_effects = Vec::new();
+    for (role, call_info) in [
+        (CallRole::Validate, &tx_execution_info.validate_call_info),
+        (CallRole::Execute, &tx_execution_info.execute_call_info),
+        (CallRole::FeeTransfer, &tx_execution_info.fee_transfer_call_info),
+    ] {
+        let Some(call_info) = call_info else {
+            continue;
+        };
+
+        // The order of an effect is unique within the call tree of its role.
+        let mut role_effects = Vec::new();
+        collect_call_effects(call_info, &mut Vec::new(), &effects_of, &mut role_effects);
+        role_effects.sort_by_key(|(_, _, effect)| order_of(effect));
+        located_effects.extend(role_effects.into_iter().map(
+            |(call_path, from_address, effect)| LocatedEffect {
+                tx_index,
+                role,
+                call_path,
+                from_address,
+                effect,
+            },
+        ));
+    }
+
+    located_effects
+}
+
+fn collect_call_effects<T: Clone>(
+    call_info: &CallInfo,
+    call_path: &mut Vec<usize>,
+    effects_of: &impl Fn(&CallExecution) -> &Vec<T>,
+    effects: &mut Vec<(Vec<usize>, ContractAddress, T)>,
+) {
+    let from_address = call_info.call.storage_address;
+    effects.extend(
+        effects_of(&call_info.execution)
+            .iter()
+            .map(|effect| (call_path.clone(), from_address, effect.clone())),
+    );
+    for (index, inner_call) in call_info.inner_calls.iter().enumerate() {
+        call_path.push(index);
+        collect_call_effects(inner_call, call_path, effects_of, effects);
+        call_path.pop();
+    }
+}
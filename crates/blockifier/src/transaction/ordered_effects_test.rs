@@ -0,0 +1,107 @@
+use pretty_assertions::assert_eq;
+use starknet_api::core::{ContractAddress, EthAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{EventContent, EventKey, L2ToL1Payload};
+use starknet_api::{contract_address, patricia_key, stark_felt};
+
+use crate::execution::call_info::{
+    CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message,
+};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::transaction::execution_info_diff::CallRole;
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::ordered_effects::{
+    block_ordered_events, tx_ordered_messages, LocatedEffect,
+};
+
+fn ordered_event(order: u8) -> OrderedEvent {
+    OrderedEvent {
+        order: usize::from(order),
+        event: EventContent { keys: vec![EventKey(stark_felt!(order))], ..Default::default() },
+    }
+}
+
+fn ordered_message(order: u8) -> OrderedL2ToL1Message {
+    OrderedL2ToL1Message {
+        order: usize::from(order),
+        message: MessageToL1 {
+            to_address: EthAddress::default(),
+            payload: L2ToL1Payload(vec![stark_felt!(order)]),
+        },
+    }
+}
+
+fn call_info(
+    storage_address: ContractAddress,
+    execution: CallExecution,
+    inner_calls: Vec<CallInfo>,
+) -> CallInfo {
+    CallInfo {
+        call: CallEntryPoint { storage_address, ..Default::default() },
+        execution,
+        inner_calls,
+        ..Default::default()
+    }
+}
+
+/// Returns the execution info of a transaction whose execution call emits an event after its
+/// inner calls do.
+fn execution_info() -> TransactionExecutionInfo {
+    let (validate_address, outer_address, inner_address) =
+        (contract_address!("0x30"), contract_address!("0x10"), contract_address!("0x20"));
+    let execution = |orders: &[u8]| CallExecution {
+        events: orders.iter().copied().map(ordered_event).collect(),
+        l2_to_l1_messages: orders.iter().copied().map(ordered_message).collect(),
+        ..Default::default()
+    };
+    let inner_calls = vec![
+        call_info(inner_address, execution(&[]), vec![]),
+        call_info(inner_address, execution(&[0, 1]), vec![]),
+    ];
+    TransactionExecutionInfo {
+        validate_call_info: Some(call_info(validate_address, execution(&[0]), vec![])),
+        execute_call_info: Some(call_info(outer_address, execution(&[2]), inner_calls)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_ordered_effects() {
+    let expected_locations = [
+        (CallRole::Validate, vec![], contract_address!("0x30"), 0),
+        (CallRole::Execute, vec![1], contract_address!("0x20"), 0),
+        (CallRole::Execute, vec![1], contract_address!("0x20"), 1),
+        (CallRole::Execute, vec![], contract_address!("0x10"), 2),
+    ];
+
+    let tx_execution_infos = [execution_info(), execution_info()];
+    let expected_events: Vec<_> = (0..2)
+        .flat_map(|tx_index| {
+            expected_locations.iter().cloned().map(move |(role, call_path, from_address, order)| {
+                LocatedEffect {
+                    tx_index,
+                    role,
+                    call_path,
+                    from_address,
+                    effect: ordered_event(order),
+                }
+            })
+        })
+        .collect();
+    assert_eq!(block_ordered_events(&tx_execution_infos).collect::<Vec<_>>(), expected_events);
+
+    let expected_messages: Vec<_> = expected_locations
+        .into_iter()
+        .map(|(role, call_path, from_address, order)| LocatedEffect {
+            tx_index: 7,
+            role,
+            call_path,
+            from_address,
+            effect: ordered_message(order),
+        })
+        .collect();
+    assert_eq!(
+        tx_ordered_messages(7, &tx_execution_infos[0]).collect::<Vec<_>>(),
+        expected_messages
+    );
+}
@@ -0,0 +1,87 @@
+use std::collections::{HashMap, HashSet};
+
+use starknet_api::core::ClassHash;
+use starknet_api::hash::StarkFelt;
+
+use crate::execution::call_info::CallInfo;
+use crate::execution::contract_class::{ContractClass, ContractClassV0};
+use crate::state::state_api::{StateReader, StateResult};
+use crate::transaction::objects::TransactionExecutionInfo;
+
+#[cfg(test)]
+#[path = "os_execution_helper_test.rs"]
+mod test;
+
+/// The execution data of a block consumed by the Starknet OS when re-executing its transactions
+/// for proving: the calls of each transaction, the values read from storage by each call, and the
+/// deprecated (Cairo 0) classes the OS runs.
+#[derive(Clone, Copy, Debug)]
+pub struct OsExecutionHelper<'a> {
+    tx_execution_infos: &'a [TransactionExecutionInfo],
+}
+
+impl<'a> OsExecutionHelper<'a> {
+    /// The transactions are indexed by their position in the given slice.
+    pub fn new(tx_execution_infos: &'a [TransactionExecutionInfo]) -> Self {
+        Self { tx_execution_infos }
+    }
+
+    pub fn n_txs(&self) -> usize {
+        self.tx_execution_infos.len()
+    }
+
+    /// Returns the execution info of the given transaction; [None] if the block has no such
+    /// transaction.
+    pub fn tx_execution_info(&self, tx_index: usize) -> Option<&'a TransactionExecutionInfo> {
+        self.tx_execution_infos.get(tx_index)
+    }
+
+    /// Returns the calls of the given transaction, in the order the OS iterates them: the
+    /// validation, execution and fee transfer calls, each followed by its inner calls in
+    /// pre-order; [None] if the block has no such transaction.
+    pub fn tx_call_iterator(&self, tx_index: usize) -> Option<impl Iterator<Item = &'a CallInfo>> {
+        let tx_execution_info = self.tx_execution_info(tx_index)?;
+        Some(tx_execution_info.non_optional_call_infos().flat_map(CallInfo::iter))
+    }
+
+    /// Returns the calls of the given transaction as in [`Self::tx_call_iterator`], each paired
+    /// with an iterator over the values it read from storage, in the order of reading; the OS
+    /// consumes the reads of a call while executing it.
+    pub fn tx_call_storage_read_iterators(
+        &self,
+        tx_index: usize,
+    ) -> Option<impl Iterator<Item = (&'a CallInfo, impl Iterator<Item = StarkFelt> + 'a)>> {
+        let call_iterator = self.tx_call_iterator(tx_index)?;
+        Some(call_iterator.map(|call_info| (call_info, call_storage_read_iterator(call_info))))
+    }
+
+    /// Returns the hashes of the classes executed by the transactions of the block; calls that
+    /// were not executed (e.g., mocked calls) are skipped.
+    pub fn executed_class_hashes(&self) -> HashSet<ClassHash> {
+        (0..self.n_txs())
+            .flat_map(|tx_index| self.tx_call_iterator(tx_index).into_iter().flatten())
+            .filter_map(|call_info| call_info.call.class_hash)
+            .collect()
+    }
+
+    /// Returns the deprecated classes executed by the transactions of the block, by class hash,
+    /// as read from the given state.
+    pub fn deprecated_compiled_classes(
+        &self,
+        state_reader: &impl StateReader,
+    ) -> StateResult<HashMap<ClassHash, ContractClassV0>> {
+        let mut deprecated_classes = HashMap::new();
+        for class_hash in self.executed_class_hashes() {
+            let class = state_reader.get_compiled_contract_class(class_hash)?;
+            if let ContractClass::V0(class) = class {
+                deprecated_classes.insert(class_hash, class);
+            }
+        }
+
+        Ok(deprecated_classes)
+    }
+}
+
+fn call_storage_read_iterator(call_info: &CallInfo) -> impl Iterator<Item = StarkFelt> + '_ {
+    call_info.storage_read_values.iter().copied()
+}
@@ -0,0 +1,109 @@
+use std::collections::{HashMap, HashSet};
+
+use pretty_assertions::assert_eq;
+use starknet_api::core::ClassHash;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+
+use crate::execution::call_info::CallInfo;
+use crate::execution::contract_class::ContractClass;
+use crate::execution::entry_point::CallEntryPoint;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::CairoVersion;
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::os_execution_helper::OsExecutionHelper;
+
+fn call_info(
+    class_hash: Option<ClassHash>,
+    storage_read_values: &[u8],
+    inner_calls: Vec<CallInfo>,
+) -> CallInfo {
+    CallInfo {
+        call: CallEntryPoint { class_hash, ..Default::default() },
+        inner_calls,
+        storage_read_values: storage_read_values.iter().map(|value| stark_felt!(*value)).collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_os_execution_helper() {
+    let cairo0_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let cairo1_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let (cairo0_class_hash, cairo1_class_hash) =
+        (cairo0_contract.get_class_hash(), cairo1_contract.get_class_hash());
+
+    let execute_call_info = call_info(
+        Some(cairo1_class_hash),
+        &[3],
+        vec![call_info(Some(cairo0_class_hash), &[4, 5], vec![]), call_info(None, &[6], vec![])],
+    );
+    let tx_execution_infos = [
+        TransactionExecutionInfo {
+            validate_call_info: Some(call_info(Some(cairo1_class_hash), &[1, 2], vec![])),
+            execute_call_info: Some(execute_call_info),
+            fee_transfer_call_info: Some(call_info(Some(cairo1_class_hash), &[7], vec![])),
+            ..Default::default()
+        },
+        TransactionExecutionInfo::default(),
+    ];
+    let helper = OsExecutionHelper::new(&tx_execution_infos);
+
+    assert_eq!(helper.n_txs(), 2);
+    assert_eq!(
+        helper
+            .tx_call_iterator(0)
+            .unwrap()
+            .map(|call_info| call_info.call.class_hash)
+            .collect::<Vec<_>>(),
+        vec![
+            Some(cairo1_class_hash),
+            Some(cairo1_class_hash),
+            Some(cairo0_class_hash),
+            None,
+            Some(cairo1_class_hash)
+        ]
+    );
+    // The reads are grouped by the calls that made them.
+    let reads: Vec<(Option<ClassHash>, Vec<StarkFelt>)> = helper
+        .tx_call_storage_read_iterators(0)
+        .unwrap()
+        .map(|(call_info, reads)| (call_info.call.class_hash, reads.collect()))
+        .collect();
+    let felts = |values: &[u8]| -> Vec<StarkFelt> {
+        values.iter().map(|value| stark_felt!(*value)).collect()
+    };
+    assert_eq!(
+        reads,
+        vec![
+            (Some(cairo1_class_hash), felts(&[1, 2])),
+            (Some(cairo1_class_hash), felts(&[3])),
+            (Some(cairo0_class_hash), felts(&[4, 5])),
+            (None, felts(&[6])),
+            (Some(cairo1_class_hash), felts(&[7])),
+        ]
+    );
+    assert_eq!(helper.tx_call_iterator(1).unwrap().count(), 0);
+    assert!(helper.tx_execution_info(2).is_none());
+    assert!(helper.tx_call_iterator(2).is_none());
+    assert_eq!(
+        helper.executed_class_hashes(),
+        HashSet::from([cairo0_class_hash, cairo1_class_hash])
+    );
+
+    let state_reader = DictStateReader {
+        class_hash_to_class: HashMap::from([
+            (cairo0_class_hash, cairo0_contract.get_class()),
+            (cairo1_class_hash, cairo1_contract.get_class()),
+        ]),
+        ..Default::default()
+    };
+    let ContractClass::V0(cairo0_class) = cairo0_contract.get_class() else {
+        panic!("Expected a deprecated class.");
+    };
+    assert_eq!(
+        helper.deprecated_compiled_classes(&state_reader).unwrap(),
+        HashMap::from([(cairo0_class_hash, cairo0_class)])
+    );
+}
@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use starknet_api::block::BlockTimestamp;
+use starknet_api::calldata;
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Calldata;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::TransactionContext;
+use crate::execution::call_info::Retdata;
+use crate::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
+use crate::execution::errors::{EntryPointExecutionError, PreExecutionError};
+use crate::retdata;
+use crate::state::state_api::State;
+use crate::transaction::constants;
+use crate::transaction::errors::OutsideExecutionError;
+
+#[cfg(test)]
+#[path = "outside_execution_test.rs"]
+mod test;
+
+pub type OutsideExecutionResult<T> = Result<T, OutsideExecutionError>;
+
+/// An outside execution (SNIP-9) request, relayed through a call to `execute_from_outside` on the
+/// signer account. The signer is not the transaction sender, and its outside execution nonce is
+/// independent of its protocol nonce.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OutsideExecution {
+    pub signer_address: ContractAddress,
+    pub caller: ContractAddress,
+    pub nonce: StarkFelt,
+    pub execute_after: StarkFelt,
+    pub execute_before: StarkFelt,
+}
+
+impl OutsideExecution {
+    /// Extracts the outside execution requests from the calldata of a Cairo 1 account multicall,
+    /// i.e., `[n_calls, (to, selector, calldata_len, calldata)...]`.
+    /// Malformed calldata yields no requests; its validity is left for the account to decide.
+    pub fn extract_from_calldata(calldata: &Calldata) -> Vec<Self> {
+        Self::try_extract_from_calldata(calldata).unwrap_or_default()
+    }
+
+    fn try_extract_from_calldata(calldata: &Calldata) -> Option<Vec<Self>> {
+        let outside_execution_selectors = [
+            selector_from_name(constants::EXECUTE_FROM_OUTSIDE_ENTRY_POINT_NAME),
+            selector_from_name(constants::EXECUTE_FROM_OUTSIDE_V2_ENTRY_POINT_NAME),
+        ];
+        let (n_calls, mut remaining_calldata) = calldata.0.split_first()?;
+
+        let mut outside_executions = vec![];
+        for _ in 0..usize::try_from(*n_calls).ok()? {
+            let [to, selector, call_calldata_len, rest @ ..] = remaining_calldata else {
+                return None;
+            };
+            let call_calldata_len = usize::try_from(*call_calldata_len).ok()?;
+            if rest.len() < call_calldata_len {
+                return None;
+            }
+            let (call_calldata, rest) = rest.split_at(call_calldata_len);
+            remaining_calldata = rest;
+
+            if !outside_execution_selectors.contains(&EntryPointSelector(*selector)) {
+                continue;
+            }
+            // The serialized `OutsideExecution` struct starts with:
+            // `caller`, `nonce`, `execute_after`, `execute_before`.
+            let [caller, nonce, execute_after, execute_before, ..] = call_calldata else {
+                return None;
+            };
+            outside_executions.push(Self {
+                signer_address: ContractAddress::try_from(*to).ok()?,
+                caller: ContractAddress::try_from(*caller).ok()?,
+                nonce: *nonce,
+                execute_after: *execute_after,
+                execute_before: *execute_before,
+            });
+        }
+
+        Some(outside_executions)
+    }
+
+    /// Performs the checks that do not require running the signer account: the relayer must be
+    /// the designated caller (unless `ANY_CALLER` is used), and the block timestamp must be inside
+    /// the execution window.
+    pub fn verify_context(
+        &self,
+        relayer_address: ContractAddress,
+        block_timestamp: BlockTimestamp,
+    ) -> OutsideExecutionResult<()> {
+        let any_caller = StarkFelt::try_from(constants::OUTSIDE_EXECUTION_ANY_CALLER)
+            .expect("ANY_CALLER must be a valid felt.");
+        if *self.caller.0.key() != any_caller && self.caller != relayer_address {
+            return Err(OutsideExecutionError::InvalidCaller {
+                signer_address: self.signer_address,
+                expected_caller: self.caller,
+                actual_caller: relayer_address,
+            });
+        }
+
+        let timestamp = StarkFelt::from(block_timestamp.0);
+        if !(self.execute_after < timestamp && timestamp < self.execute_before) {
+            return Err(OutsideExecutionError::OutOfTimeBounds {
+                signer_address: self.signer_address,
+                execute_after: self.execute_after,
+                execute_before: self.execute_before,
+                block_timestamp,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the outside execution nonce against the signer account's own nonce channel.
+    /// Accounts that do not expose `is_valid_outside_execution_nonce` are trusted to check the
+    /// nonce upon execution.
+    pub fn verify_nonce(
+        &self,
+        state: &mut dyn State,
+        tx_context: Arc<TransactionContext>,
+    ) -> OutsideExecutionResult<()> {
+        if state.get_class_hash_at(self.signer_address)? == ClassHash::default() {
+            return Err(OutsideExecutionError::UndeployedSigner(self.signer_address));
+        }
+
+        let selector =
+            selector_from_name(constants::IS_VALID_OUTSIDE_EXECUTION_NONCE_ENTRY_POINT_NAME);
+        let nonce_check_call = CallEntryPoint {
+            class_hash: None,
+            code_address: None,
+            entry_point_type: EntryPointType::External,
+            entry_point_selector: selector,
+            calldata: calldata![self.nonce],
+            storage_address: self.signer_address,
+            caller_address: ContractAddress::default(),
+            call_type: CallType::Call,
+            initial_gas: tx_context.block_context.versioned_constants.tx_initial_gas(),
+        };
+        let limit_steps_by_resources = false;
+        let mut context =
+            EntryPointExecutionContext::new_invoke(tx_context, limit_steps_by_resources)?;
+
+        let call_info =
+            match nonce_check_call.execute(state, &mut ExecutionResources::default(), &mut context)
+            {
+                Ok(call_info) => call_info,
+                Err(EntryPointExecutionError::PreExecutionError(
+                    PreExecutionError::EntryPointNotFound(_),
+                )) => return Ok(()),
+                Err(error) => return Err(error.into()),
+            };
+
+        if call_info.execution.retdata != retdata![StarkFelt::ONE] {
+            return Err(OutsideExecutionError::InvalidNonce {
+                signer_address: self.signer_address,
+                nonce: self.nonce,
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use starknet_api::block::BlockTimestamp;
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, contract_address, patricia_key, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::transaction::constants;
+use crate::transaction::outside_execution::OutsideExecution;
+
+fn outside_execution_calldata(caller: StarkFelt) -> Calldata {
+    let outside_execution_selector =
+        selector_from_name(constants::EXECUTE_FROM_OUTSIDE_V2_ENTRY_POINT_NAME).0;
+    let transfer_selector = selector_from_name(constants::TRANSFER_ENTRY_POINT_NAME).0;
+    calldata![
+        stark_felt!(2_u8),          // Number of calls.
+        stark_felt!("0x1234"),      // Call 0: to.
+        transfer_selector,          // Call 0: selector.
+        stark_felt!(1_u8),          // Call 0: calldata length.
+        stark_felt!(7_u8),          // Call 0: calldata.
+        stark_felt!("0x5678"),      // Call 1: to (the signer).
+        outside_execution_selector, // Call 1: selector.
+        stark_felt!(6_u8),          // Call 1: calldata length.
+        caller,                     // Call 1: outside execution caller.
+        stark_felt!(3_u8),          // Call 1: outside execution nonce.
+        stark_felt!(10_u8),         // Call 1: execute after.
+        stark_felt!(20_u8),         // Call 1: execute before.
+        stark_felt!(0_u8),          // Call 1: number of inner calls.
+        stark_felt!(0_u8)           // Call 1: signature length.
+    ]
+}
+
+#[test]
+fn test_extract_outside_executions() {
+    let caller = stark_felt!(constants::OUTSIDE_EXECUTION_ANY_CALLER);
+    let outside_executions =
+        OutsideExecution::extract_from_calldata(&outside_execution_calldata(caller));
+    assert_eq!(
+        outside_executions,
+        vec![OutsideExecution {
+            signer_address: contract_address!("0x5678"),
+            caller: contract_address!(constants::OUTSIDE_EXECUTION_ANY_CALLER),
+            nonce: stark_felt!(3_u8),
+            execute_after: stark_felt!(10_u8),
+            execute_before: stark_felt!(20_u8),
+        }]
+    );
+
+    // Malformed calldata yields no outside executions.
+    let truncated_calldata = Calldata(outside_execution_calldata(caller).0[..8].to_vec().into());
+    assert!(OutsideExecution::extract_from_calldata(&truncated_calldata).is_empty());
+}
+
+#[test]
+fn test_verify_outside_execution_context() {
+    let relayer_address = contract_address!("0x1");
+    let outside_execution = &OutsideExecution::extract_from_calldata(&outside_execution_calldata(
+        *relayer_address.0.key(),
+    ))[0];
+
+    assert!(outside_execution.verify_context(relayer_address, BlockTimestamp(15)).is_ok());
+    // Out of the execution window.
+    assert!(outside_execution.verify_context(relayer_address, BlockTimestamp(20)).is_err());
+    // Not the designated relayer.
+    assert!(outside_execution
+        .verify_context(contract_address!("0x2"), BlockTimestamp(15))
+        .is_err());
+}
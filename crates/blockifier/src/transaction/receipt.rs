@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+use cairo_vm::vm::runners::builtin_runner::{
+    BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
+    POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME, SEGMENT_ARENA_BUILTIN_NAME,
+    SIGNATURE_BUILTIN_NAME,
+};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use serde::Serialize;
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{Fee, TransactionHash};
+use starknet_crypto::{poseidon_hash_many, FieldElement};
+
+use crate::execution::call_info::{EmittedEvent, SentL2ToL1Message};
+use crate::execution::messages_to_l1::encode_messages_to_l1_segment;
+use crate::transaction::objects::{FeeType, GasVector, TransactionExecutionInfo};
+use crate::utils::usize_to_felt;
+
+#[cfg(test)]
+#[path = "receipt_test.rs"]
+pub mod test;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExecutionStatus {
+    Succeeded,
+    Reverted,
+}
+
+/// The unit of a fee amount.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum PriceUnit {
+    #[serde(rename = "WEI")]
+    Wei,
+    #[serde(rename = "FRI")]
+    Fri,
+}
+
+impl From<FeeType> for PriceUnit {
+    fn from(fee_type: FeeType) -> Self {
+        match fee_type {
+            FeeType::Eth => Self::Wei,
+            FeeType::Strk => Self::Fri,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct FeePayment {
+    pub amount: Fee,
+    pub unit: PriceUnit,
+}
+
+/// The resources consumed by a transaction, in the JSON-RPC `EXECUTION_RESOURCES` shape.
+/// Zero counters are omitted.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct RpcExecutionResources {
+    /// The number of Cairo steps, including reverted steps and excluding memory holes.
+    pub steps: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_holes: Option<usize>,
+    /// The non-zero builtin counters, keyed by their JSON-RPC names (see [`rpc_builtin_name`]).
+    #[serde(flatten)]
+    pub builtin_counters: BTreeMap<&'static str, usize>,
+    pub data_availability: GasVector,
+}
+
+impl RpcExecutionResources {
+    pub fn new(
+        vm_resources: &ExecutionResources,
+        n_reverted_steps: usize,
+        data_availability: GasVector,
+    ) -> Self {
+        let non_zero = |count: usize| Some(count).filter(|count| *count > 0);
+
+        Self {
+            steps: vm_resources.n_steps + n_reverted_steps,
+            memory_holes: non_zero(vm_resources.n_memory_holes),
+            builtin_counters: rpc_builtin_counters(vm_resources),
+            data_availability,
+        }
+    }
+}
+
+/// Returns the name of the counter of the given builtin in the JSON-RPC resources schema; [None]
+/// for builtins the schema does not count.
+pub fn rpc_builtin_name(builtin_name: &str) -> Option<&'static str> {
+    match builtin_name {
+        RANGE_CHECK_BUILTIN_NAME => Some("range_check_builtin_applications"),
+        HASH_BUILTIN_NAME => Some("pedersen_builtin_applications"),
+        POSEIDON_BUILTIN_NAME => Some("poseidon_builtin_applications"),
+        EC_OP_BUILTIN_NAME => Some("ec_op_builtin_applications"),
+        SIGNATURE_BUILTIN_NAME => Some("ecdsa_builtin_applications"),
+        BITWISE_BUILTIN_NAME => Some("bitwise_builtin_applications"),
+        KECCAK_BUILTIN_NAME => Some("keccak_builtin_applications"),
+        SEGMENT_ARENA_BUILTIN_NAME => Some("segment_arena_builtin"),
+        _ => None,
+    }
+}
+
+/// Returns the non-zero builtin counters of the given resources, keyed by their JSON-RPC names.
+pub fn rpc_builtin_counters(vm_resources: &ExecutionResources) -> BTreeMap<&'static str, usize> {
+    vm_resources
+        .builtin_instance_counter
+        .iter()
+        .filter(|(_, count)| **count > 0)
+        .filter_map(|(builtin_name, count)| Some((rpc_builtin_name(builtin_name)?, *count)))
+        .collect()
+}
+
+/// The receipt of an executed transaction, following the Starknet RPC schema.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct RpcTransactionReceipt {
+    pub transaction_hash: TransactionHash,
+    pub execution_status: ExecutionStatus,
+    /// The revert reason; [None] if the transaction execution was successful.
+    pub revert_reason: Option<String>,
+    pub actual_fee: FeePayment,
+    /// The gas the transaction is charged for, split into L1 gas, L1 data gas and L2 gas.
+    pub gas_consumed: GasVector,
+    /// Events emitted by the validation, execution and fee transfer calls, in that order.
+    pub events: Vec<EmittedEvent>,
+    /// L2-to-L1 messages sent by the validation, execution and fee transfer calls, in that order.
+    pub messages_sent: Vec<SentL2ToL1Message>,
+    pub execution_resources: RpcExecutionResources,
+}
+
+impl RpcTransactionReceipt {
+    pub fn new(
+        transaction_hash: TransactionHash,
+        fee_type: FeeType,
+        tx_execution_info: &TransactionExecutionInfo,
+    ) -> Self {
+        let (execution_status, revert_reason) = match &tx_execution_info.revert_error {
+            Some(revert_error) => (ExecutionStatus::Reverted, Some(revert_error.to_string())),
+            None => (ExecutionStatus::Succeeded, None),
+        };
+
+        let mut events = Vec::new();
+        let mut messages_sent = Vec::new();
+        for call_info in tx_execution_info.non_optional_call_infos() {
+            let effects = call_info.emitted_effects();
+            events.extend(effects.events);
+            messages_sent.extend(effects.l2_to_l1_messages);
+        }
+
+        let actual_resources = &tx_execution_info.actual_resources;
+        let execution_resources = RpcExecutionResources::new(
+            &actual_resources.vm_resources,
+            actual_resources.n_reverted_steps,
+            tx_execution_info.da_gas,
+        );
+
+        Self {
+            transaction_hash,
+            execution_status,
+            revert_reason,
+            actual_fee: FeePayment {
+                amount: tx_execution_info.actual_fee,
+                unit: PriceUnit::from(fee_type),
+            },
+            gas_consumed: tx_execution_info.gas,
+            events,
+            messages_sent,
+            execution_resources,
+        }
+    }
+
+    /// Returns the hashes of the emitted events, as committed to in the block's event commitment:
+    /// `poseidon(from_address, tx_hash, n_keys, keys, n_data, data)`.
+    pub fn event_hashes(&self) -> Vec<StarkHash> {
+        self.events
+            .iter()
+            .map(|EmittedEvent { from_address, event }| {
+                let keys = &event.event.keys;
+                let data = &event.event.data.0;
+                let mut felts = vec![*from_address.0.key(), self.transaction_hash.0];
+                felts.push(usize_to_felt(keys.len()));
+                felts.extend(keys.iter().map(|key| key.0));
+                felts.push(usize_to_felt(data.len()));
+                felts.extend(data.iter().copied());
+                poseidon_hash_array(&felts)
+            })
+            .collect()
+    }
+
+    /// Returns the hash of the sent L2-to-L1 messages, as committed to in the block's receipt
+    /// commitment: `poseidon(n_messages, (from_address, to_address, payload_length, payload)*)`.
+    pub fn messages_sent_hash(&self) -> StarkHash {
+        let mut felts = vec![usize_to_felt(self.messages_sent.len())];
+        felts.extend(encode_messages_to_l1_segment(&self.messages_sent));
+
+        poseidon_hash_array(&felts)
+    }
+}
+
+fn poseidon_hash_array(felts: &[StarkFelt]) -> StarkHash {
+    let felts: Vec<FieldElement> = felts.iter().map(|felt| FieldElement::from(*felt)).collect();
+    StarkHash::from(poseidon_hash_many(&felts))
+}
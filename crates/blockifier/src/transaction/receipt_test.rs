@@ -0,0 +1,173 @@
+use std::collections::{BTreeMap, HashMap};
+
+use cairo_vm::vm::runners::builtin_runner::{
+    HASH_BUILTIN_NAME, POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME, SEGMENT_ARENA_BUILTIN_NAME,
+};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use serde_json::json;
+use starknet_api::core::{ContractAddress, EthAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{
+    EventContent, EventData, EventKey, Fee, L2ToL1Payload, TransactionHash,
+};
+use starknet_api::{contract_address, patricia_key, stark_felt};
+use starknet_crypto::{poseidon_hash_many, FieldElement};
+
+use crate::execution::call_info::{
+    CallExecution, CallInfo, EmittedEvent, MessageToL1, OrderedEvent, OrderedL2ToL1Message,
+};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::transaction::objects::{FeeType, GasVector, RevertReason, TransactionExecutionInfo};
+use crate::transaction::receipt::{
+    ExecutionStatus, PriceUnit, RpcExecutionResources, RpcTransactionReceipt,
+};
+
+fn ordered_event(order: usize, key: u8) -> OrderedEvent {
+    OrderedEvent {
+        order,
+        event: EventContent {
+            keys: vec![EventKey(stark_felt!(key))],
+            data: EventData(vec![stark_felt!(key), stark_felt!(key)]),
+        },
+    }
+}
+
+fn call_info(
+    storage_address: ContractAddress,
+    events: Vec<OrderedEvent>,
+    l2_to_l1_messages: Vec<OrderedL2ToL1Message>,
+    inner_calls: Vec<CallInfo>,
+) -> CallInfo {
+    CallInfo {
+        call: CallEntryPoint { storage_address, ..Default::default() },
+        execution: CallExecution { events, l2_to_l1_messages, ..Default::default() },
+        inner_calls,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_receipt_effects_order() {
+    let (validate_address, outer_address, inner_address) =
+        (contract_address!("0x30"), contract_address!("0x10"), contract_address!("0x20"));
+    let message = OrderedL2ToL1Message {
+        order: 0,
+        message: MessageToL1 {
+            to_address: EthAddress::try_from(stark_felt!(5_u8)).unwrap(),
+            payload: L2ToL1Payload(vec![stark_felt!(6_u8)]),
+        },
+    };
+    let inner_call = call_info(inner_address, vec![ordered_event(0, 2)], vec![message], vec![]);
+    let tx_execution_info = TransactionExecutionInfo {
+        validate_call_info: Some(call_info(
+            validate_address,
+            vec![ordered_event(0, 1)],
+            vec![],
+            vec![],
+        )),
+        execute_call_info: Some(call_info(
+            outer_address,
+            vec![ordered_event(1, 3)],
+            vec![],
+            vec![inner_call],
+        )),
+        actual_fee: Fee(7),
+        gas: GasVector { l1_gas: 9, l1_data_gas: 10, ..Default::default() },
+        ..Default::default()
+    };
+    let tx_hash = TransactionHash(stark_felt!(8_u8));
+
+    let receipt = RpcTransactionReceipt::new(tx_hash, FeeType::Strk, &tx_execution_info);
+
+    assert_eq!(receipt.execution_status, ExecutionStatus::Succeeded);
+    assert_eq!(receipt.revert_reason, None);
+    assert_eq!(receipt.actual_fee.amount, Fee(7));
+    assert_eq!(receipt.actual_fee.unit, PriceUnit::Fri);
+    assert_eq!(
+        receipt.gas_consumed,
+        GasVector { l1_gas: 9, l1_data_gas: 10, ..Default::default() }
+    );
+    assert_eq!(
+        receipt.events,
+        vec![
+            EmittedEvent { from_address: validate_address, event: ordered_event(0, 1) },
+            EmittedEvent { from_address: inner_address, event: ordered_event(0, 2) },
+            EmittedEvent { from_address: outer_address, event: ordered_event(1, 3) },
+        ]
+    );
+    assert_eq!(receipt.messages_sent.len(), 1);
+    assert_eq!(receipt.messages_sent[0].from_address, inner_address);
+
+    // Hashes.
+    let to_field_elements = |felts: &[u8]| -> Vec<FieldElement> {
+        felts.iter().map(|felt| FieldElement::from(*felt)).collect()
+    };
+    let expected_event_hash = poseidon_hash_many(&to_field_elements(&[0x30, 8, 1, 1, 2, 1, 1]));
+    assert_eq!(receipt.event_hashes()[0], StarkHash::from(expected_event_hash));
+    let expected_messages_hash = poseidon_hash_many(&to_field_elements(&[1, 0x20, 5, 1, 6]));
+    assert_eq!(receipt.messages_sent_hash(), StarkHash::from(expected_messages_hash));
+}
+
+#[rstest]
+fn test_reverted_receipt(#[values(FeeType::Eth, FeeType::Strk)] fee_type: FeeType) {
+    let tx_execution_info = TransactionExecutionInfo {
+        revert_error: Some(RevertReason::new("Reverted.".to_string(), vec![], vec![])),
+        ..Default::default()
+    };
+
+    let receipt =
+        RpcTransactionReceipt::new(TransactionHash::default(), fee_type, &tx_execution_info);
+
+    assert_eq!(receipt.execution_status, ExecutionStatus::Reverted);
+    assert_eq!(receipt.revert_reason, Some("Reverted.".to_string()));
+    assert_eq!(receipt.actual_fee.unit, PriceUnit::from(fee_type));
+    assert!(receipt.events.is_empty());
+    assert_eq!(
+        receipt.messages_sent_hash(),
+        StarkFelt::from(poseidon_hash_many(&[FieldElement::ZERO]))
+    );
+}
+
+#[test]
+fn test_rpc_execution_resources() {
+    let vm_resources = ExecutionResources {
+        n_steps: 100,
+        n_memory_holes: 0,
+        builtin_instance_counter: HashMap::from([
+            (HASH_BUILTIN_NAME.to_string(), 2),
+            (RANGE_CHECK_BUILTIN_NAME.to_string(), 3),
+            (SEGMENT_ARENA_BUILTIN_NAME.to_string(), 1),
+            (POSEIDON_BUILTIN_NAME.to_string(), 0),
+        ]),
+    };
+    let da_gas = GasVector { l1_gas: 4, l1_data_gas: 5, ..Default::default() };
+
+    let rpc_resources = RpcExecutionResources::new(&vm_resources, 10, da_gas);
+
+    assert_eq!(
+        rpc_resources,
+        RpcExecutionResources {
+            steps: 110,
+            builtin_counters: BTreeMap::from([
+                ("pedersen_builtin_applications", 2),
+                ("range_check_builtin_applications", 3),
+                ("segment_arena_builtin", 1),
+            ]),
+            data_availability: da_gas,
+            ..Default::default()
+        }
+    );
+    // Zero counters are omitted.
+    assert_eq!(
+        serde_json::to_value(&rpc_resources).unwrap(),
+        json!({
+            "steps": 110,
+            "pedersen_builtin_applications": 2,
+            "range_check_builtin_applications": 3,
+            "segment_arena_builtin": 1,
+            "data_availability": {"l1_gas": 4, "l1_data_gas": 5},
+        })
+    );
+}
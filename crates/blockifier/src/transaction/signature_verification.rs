@@ -0,0 +1,101 @@
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::{TransactionHash, TransactionSignature};
+use starknet_crypto::{verify, FieldElement};
+
+use crate::transaction::errors::SignatureVerificationError;
+
+#[cfg(test)]
+#[path = "signature_verification_test.rs"]
+mod test;
+
+pub type SignatureVerificationResult<T> = Result<T, SignatureVerificationError>;
+
+const STARK_SIGNATURE_LENGTH: usize = 2;
+
+/// A well-known account implementation, along with the public keys its `__validate__` checks the
+/// transaction signature against.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AccountSignatureScheme {
+    /// A plain Stark-curve account (e.g., OpenZeppelin); the signature is `[r, s]`.
+    StarkCurve { public_key: StarkFelt },
+    /// An Argent account; the signature is `[r, s]` of the owner, followed by `[r, s]` of the
+    /// guardian, if set.
+    Argent { owner: StarkFelt, guardian: Option<StarkFelt> },
+    /// A Braavos account with a Stark signer; the signature starts with `[r, s]` of the signer.
+    /// Trailing elements (e.g., of additional signers) are not checked.
+    Braavos { signer: StarkFelt },
+}
+
+impl AccountSignatureScheme {
+    /// Verifies the signature of the transaction with the given hash, without running the VM.
+    /// Intended as a cheap pre-filter: a valid signature does not imply a successful validation.
+    pub fn verify(
+        &self,
+        tx_hash: TransactionHash,
+        signature: &TransactionSignature,
+    ) -> SignatureVerificationResult<()> {
+        let signature = signature.0.as_slice();
+        match self {
+            Self::StarkCurve { public_key } => {
+                verify_signature_length(signature, STARK_SIGNATURE_LENGTH)?;
+                verify_stark_signature(*public_key, tx_hash, signature)
+            }
+            Self::Argent { owner, guardian } => {
+                let signers: Vec<StarkFelt> =
+                    [Some(*owner), *guardian].into_iter().flatten().collect();
+                verify_signature_length(signature, STARK_SIGNATURE_LENGTH * signers.len())?;
+                signers.iter().zip(signature.chunks_exact(STARK_SIGNATURE_LENGTH)).try_for_each(
+                    |(signer, signer_signature)| {
+                        verify_stark_signature(*signer, tx_hash, signer_signature)
+                    },
+                )
+            }
+            Self::Braavos { signer } => {
+                if signature.len() < STARK_SIGNATURE_LENGTH {
+                    return Err(SignatureVerificationError::SignatureTooShort {
+                        min_length: STARK_SIGNATURE_LENGTH,
+                        actual: signature.len(),
+                    });
+                }
+                verify_stark_signature(*signer, tx_hash, &signature[..STARK_SIGNATURE_LENGTH])
+            }
+        }
+    }
+}
+
+fn verify_signature_length(
+    signature: &[StarkFelt],
+    expected: usize,
+) -> SignatureVerificationResult<()> {
+    if signature.len() != expected {
+        return Err(SignatureVerificationError::InvalidSignatureLength {
+            expected,
+            actual: signature.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Verifies a Stark-curve `[r, s]` signature of the transaction hash.
+fn verify_stark_signature(
+    public_key: StarkFelt,
+    tx_hash: TransactionHash,
+    signature: &[StarkFelt],
+) -> SignatureVerificationResult<()> {
+    let [r, s] = signature else {
+        panic!("A Stark-curve signature must be of length {STARK_SIGNATURE_LENGTH}.");
+    };
+    let is_valid = verify(
+        &FieldElement::from(public_key),
+        &FieldElement::from(tx_hash.0),
+        &FieldElement::from(*r),
+        &FieldElement::from(*s),
+    )
+    .map_err(SignatureVerificationError::MalformedSignature)?;
+    if !is_valid {
+        return Err(SignatureVerificationError::InvalidSignature { public_key });
+    }
+
+    Ok(())
+}
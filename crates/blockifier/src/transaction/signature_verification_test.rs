@@ -0,0 +1,93 @@
+use assert_matches::assert_matches;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::{TransactionHash, TransactionSignature};
+use starknet_crypto::{get_public_key, sign, FieldElement};
+
+use crate::transaction::errors::SignatureVerificationError;
+use crate::transaction::signature_verification::AccountSignatureScheme;
+
+const OWNER_PRIVATE_KEY: u8 = 7;
+const GUARDIAN_PRIVATE_KEY: u8 = 11;
+
+fn tx_hash() -> TransactionHash {
+    TransactionHash(stark_felt!("0x1234"))
+}
+
+fn public_key(private_key: u8) -> StarkFelt {
+    StarkFelt::from(get_public_key(&FieldElement::from(private_key)))
+}
+
+/// Returns the `[r, s]` signature of the transaction hash by the given private key.
+fn stark_signature(private_key: u8) -> Vec<StarkFelt> {
+    let k = FieldElement::from(13_u8);
+    let signature =
+        sign(&FieldElement::from(private_key), &FieldElement::from(tx_hash().0), &k).unwrap();
+    vec![StarkFelt::from(signature.r), StarkFelt::from(signature.s)]
+}
+
+#[test]
+fn test_stark_curve_signature() {
+    let scheme = AccountSignatureScheme::StarkCurve { public_key: public_key(OWNER_PRIVATE_KEY) };
+    let signature = stark_signature(OWNER_PRIVATE_KEY);
+
+    scheme.verify(tx_hash(), &TransactionSignature(signature.clone())).unwrap();
+
+    // Signed by another key.
+    assert_matches!(
+        scheme.verify(tx_hash(), &TransactionSignature(stark_signature(GUARDIAN_PRIVATE_KEY))),
+        Err(SignatureVerificationError::InvalidSignature { .. })
+    );
+
+    // Trailing elements.
+    let long_signature = [signature, vec![stark_felt!(1_u8)]].concat();
+    assert_matches!(
+        scheme.verify(tx_hash(), &TransactionSignature(long_signature)),
+        Err(SignatureVerificationError::InvalidSignatureLength { expected: 2, actual: 3 })
+    );
+}
+
+#[test]
+fn test_argent_signature() {
+    let owner_signature = stark_signature(OWNER_PRIVATE_KEY);
+    let guardian_signature = stark_signature(GUARDIAN_PRIVATE_KEY);
+    let scheme = AccountSignatureScheme::Argent {
+        owner: public_key(OWNER_PRIVATE_KEY),
+        guardian: Some(public_key(GUARDIAN_PRIVATE_KEY)),
+    };
+
+    let full_signature = [owner_signature.clone(), guardian_signature.clone()].concat();
+    scheme.verify(tx_hash(), &TransactionSignature(full_signature)).unwrap();
+
+    // Missing guardian signature.
+    assert_matches!(
+        scheme.verify(tx_hash(), &TransactionSignature(owner_signature.clone())),
+        Err(SignatureVerificationError::InvalidSignatureLength { expected: 4, actual: 2 })
+    );
+
+    // Signatures in the wrong order.
+    let swapped_signature = [guardian_signature, owner_signature.clone()].concat();
+    assert_matches!(
+        scheme.verify(tx_hash(), &TransactionSignature(swapped_signature)),
+        Err(SignatureVerificationError::InvalidSignature { .. })
+    );
+
+    // No guardian.
+    let scheme =
+        AccountSignatureScheme::Argent { owner: public_key(OWNER_PRIVATE_KEY), guardian: None };
+    scheme.verify(tx_hash(), &TransactionSignature(owner_signature)).unwrap();
+}
+
+#[test]
+fn test_braavos_signature() {
+    let scheme = AccountSignatureScheme::Braavos { signer: public_key(OWNER_PRIVATE_KEY) };
+
+    // Trailing elements are not checked.
+    let signature = [stark_signature(OWNER_PRIVATE_KEY), vec![stark_felt!(1_u8)]].concat();
+    scheme.verify(tx_hash(), &TransactionSignature(signature)).unwrap();
+
+    assert_matches!(
+        scheme.verify(tx_hash(), &TransactionSignature(vec![stark_felt!(1_u8)])),
+        Err(SignatureVerificationError::SignatureTooShort { min_length: 2, actual: 1 })
+    );
+}
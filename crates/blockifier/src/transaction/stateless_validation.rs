@@ -0,0 +1,101 @@
+use cairo_felt::Felt252;
+use starknet_api::core::{ContractAddress, L2_ADDRESS_UPPER_BOUND};
+use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::transaction::Resource;
+
+use crate::context::SizeLimits;
+use crate::execution::execution_utils::stark_felt_to_felt;
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::errors::StatelessValidationError;
+use crate::transaction::objects::{TransactionInfo, TransactionInfoCreator};
+
+#[cfg(test)]
+#[path = "stateless_validation_test.rs"]
+mod test;
+
+pub type StatelessValidationResult<T> = Result<T, StatelessValidationError>;
+
+// Addresses below this one are reserved for system contracts.
+const MIN_SENDER_ADDRESS: u8 = 2;
+
+/// The limits enforced by the stateless validation. These are mempool policies, rather than
+/// protocol constants.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatelessValidationConfig {
+    /// The calldata and signature length limits; event limits apply to the execution only.
+    pub size_limits: SizeLimits,
+    /// Whether transactions may set (enforced) L2 gas bounds.
+    pub allow_l2_gas_bounds: bool,
+}
+
+impl Default for StatelessValidationConfig {
+    fn default() -> Self {
+        Self {
+            size_limits: SizeLimits {
+                max_calldata_length: Some(4000),
+                max_signature_length: Some(4000),
+                event_limits: None,
+            },
+            allow_l2_gas_bounds: false,
+        }
+    }
+}
+
+/// Performs the checks that require neither state nor execution, to reject malformed
+/// transactions before they reach the (stateful) pre-validation and validation stages.
+pub fn statically_validate(
+    tx: &AccountTransaction,
+    config: &StatelessValidationConfig,
+) -> StatelessValidationResult<()> {
+    let tx_info = tx.create_tx_info();
+    validate_sender_address(tx_info.sender_address())?;
+
+    config.size_limits.check_signature_length(tx.signature_length())?;
+    config.size_limits.check_calldata_length(tx.calldata_length())?;
+
+    validate_fee_fields(&tx_info, config)
+}
+
+fn validate_sender_address(sender_address: ContractAddress) -> StatelessValidationResult<()> {
+    let address = stark_felt_to_felt(*sender_address.0.key());
+    let upper_bound = Felt252::from_bytes_be(&L2_ADDRESS_UPPER_BOUND.to_bytes_be());
+    if address < Felt252::from(MIN_SENDER_ADDRESS) || address >= upper_bound {
+        return Err(StatelessValidationError::InvalidSenderAddress(sender_address));
+    }
+
+    Ok(())
+}
+
+fn validate_fee_fields(
+    tx_info: &TransactionInfo,
+    config: &StatelessValidationConfig,
+) -> StatelessValidationResult<()> {
+    if let TransactionInfo::Current(context) = tx_info {
+        for mode in [context.nonce_data_availability_mode, context.fee_data_availability_mode] {
+            if mode != DataAvailabilityMode::L1 {
+                return Err(StatelessValidationError::UnsupportedDataAvailabilityMode(mode));
+            }
+        }
+        if !context.paymaster_data.0.is_empty() {
+            return Err(StatelessValidationError::UnsupportedPaymasterData);
+        }
+        if !context.account_deployment_data.0.is_empty() {
+            return Err(StatelessValidationError::UnsupportedAccountDeploymentData);
+        }
+
+        context.l1_resource_bounds()?;
+        let l2_gas_bounds = context.resource_bounds.0.get(&Resource::L2Gas);
+        if let Some(l2_gas_bounds) = l2_gas_bounds.filter(|_| !config.allow_l2_gas_bounds) {
+            if l2_gas_bounds.max_amount != 0 || l2_gas_bounds.max_price_per_unit != 0 {
+                return Err(StatelessValidationError::NonZeroL2GasBounds(*l2_gas_bounds));
+            }
+        }
+    }
+
+    // Only query transactions may avoid paying fees.
+    if !tx_info.only_query() && !tx_info.enforce_fee()? {
+        return Err(StatelessValidationError::ZeroFeeBounds);
+    }
+
+    Ok(())
+}
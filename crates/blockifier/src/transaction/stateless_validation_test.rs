@@ -0,0 +1,133 @@
+use assert_matches::assert_matches;
+use rstest::rstest;
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{
+    Calldata, Fee, PaymasterData, Resource, ResourceBounds, ResourceBoundsMapping,
+    TransactionSignature, TransactionVersion,
+};
+use starknet_api::{calldata, contract_address, patricia_key, stark_felt};
+
+use crate::context::SizeLimits;
+use crate::invoke_tx_args;
+use crate::test_utils::invoke::InvokeTxArgs;
+use crate::transaction::errors::{SizeLimitError, StatelessValidationError};
+use crate::transaction::stateless_validation::{statically_validate, StatelessValidationConfig};
+use crate::transaction::test_utils::{account_invoke_tx, l1_resource_bounds};
+
+fn valid_invoke_args(version: TransactionVersion) -> InvokeTxArgs {
+    invoke_tx_args! {
+        sender_address: contract_address!("0x100"),
+        calldata: calldata![stark_felt!(1_u8), stark_felt!(2_u8)],
+        signature: TransactionSignature(vec![stark_felt!(3_u8)]),
+        max_fee: Fee(1),
+        resource_bounds: l1_resource_bounds(1, 1),
+        version,
+    }
+}
+
+fn validate(invoke_args: InvokeTxArgs) -> Result<(), StatelessValidationError> {
+    let config = StatelessValidationConfig {
+        size_limits: SizeLimits {
+            max_calldata_length: Some(2),
+            max_signature_length: Some(1),
+            event_limits: None,
+        },
+        ..Default::default()
+    };
+    statically_validate(&account_invoke_tx(invoke_args), &config)
+}
+
+#[rstest]
+fn test_stateless_validation(
+    #[values(TransactionVersion::ONE, TransactionVersion::THREE)] version: TransactionVersion,
+) {
+    validate(valid_invoke_args(version)).unwrap();
+
+    assert_matches!(
+        validate(InvokeTxArgs {
+            calldata: calldata![stark_felt!(1_u8), stark_felt!(2_u8), stark_felt!(3_u8)],
+            ..valid_invoke_args(version)
+        }),
+        Err(StatelessValidationError::SizeLimitError(SizeLimitError::CalldataTooLong {
+            calldata_length: 3,
+            max_calldata_length: 2
+        }))
+    );
+    assert_matches!(
+        validate(InvokeTxArgs {
+            signature: TransactionSignature(vec![stark_felt!(1_u8), stark_felt!(2_u8)]),
+            ..valid_invoke_args(version)
+        }),
+        Err(StatelessValidationError::SizeLimitError(SizeLimitError::SignatureTooLong {
+            signature_length: 2,
+            max_signature_length: 1
+        }))
+    );
+    // Reserved address.
+    assert_matches!(
+        validate(InvokeTxArgs {
+            sender_address: contract_address!("0x1"),
+            ..valid_invoke_args(version)
+        }),
+        Err(StatelessValidationError::InvalidSenderAddress(_))
+    );
+    // Address above the L2 address upper bound.
+    let out_of_range_address =
+        contract_address!("0x7ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+    assert_matches!(
+        validate(InvokeTxArgs {
+            sender_address: out_of_range_address,
+            ..valid_invoke_args(version)
+        }),
+        Err(StatelessValidationError::InvalidSenderAddress(_))
+    );
+
+    // Zero fee bounds are only allowed for query transactions.
+    let free_invoke_args = InvokeTxArgs {
+        max_fee: Fee(0),
+        resource_bounds: l1_resource_bounds(0, 0),
+        ..valid_invoke_args(version)
+    };
+    assert_matches!(
+        validate(free_invoke_args.clone()),
+        Err(StatelessValidationError::ZeroFeeBounds)
+    );
+    validate(InvokeTxArgs { only_query: true, ..free_invoke_args }).unwrap();
+}
+
+#[test]
+fn test_stateless_validation_of_current_fields() {
+    let valid_invoke_args = valid_invoke_args(TransactionVersion::THREE);
+
+    assert_matches!(
+        validate(InvokeTxArgs {
+            fee_data_availability_mode: DataAvailabilityMode::L2,
+            ..valid_invoke_args.clone()
+        }),
+        Err(StatelessValidationError::UnsupportedDataAvailabilityMode(DataAvailabilityMode::L2))
+    );
+    assert_matches!(
+        validate(InvokeTxArgs {
+            paymaster_data: PaymasterData(vec![stark_felt!(1_u8)]),
+            ..valid_invoke_args.clone()
+        }),
+        Err(StatelessValidationError::UnsupportedPaymasterData)
+    );
+    let l2_gas_bounds = ResourceBounds { max_amount: 1, max_price_per_unit: 1 };
+    let resource_bounds = ResourceBoundsMapping::try_from(vec![
+        (Resource::L1Gas, ResourceBounds { max_amount: 1, max_price_per_unit: 1 }),
+        (Resource::L2Gas, l2_gas_bounds),
+    ])
+    .unwrap();
+    let l2_gas_bounds_args = InvokeTxArgs { resource_bounds, ..valid_invoke_args };
+    assert_matches!(
+        validate(l2_gas_bounds_args.clone()),
+        Err(StatelessValidationError::NonZeroL2GasBounds(bounds)) if bounds == l2_gas_bounds
+    );
+
+    // L2 gas bounds are accepted once enabled.
+    let config = StatelessValidationConfig { allow_l2_gas_bounds: true, ..Default::default() };
+    statically_validate(&account_invoke_tx(l2_gas_bounds_args), &config).unwrap();
+}
@@ -0,0 +1,249 @@
+use std::collections::BTreeMap;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use serde::Serialize;
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, EthAddress};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::{Calldata, EventKey};
+
+use crate::execution::call_info::{CallInfo, SyscallGasRecord};
+use crate::execution::entry_point::CallType;
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::receipt::{rpc_builtin_counters, RpcExecutionResources};
+use crate::transaction::transaction_types::TransactionType;
+
+#[cfg(test)]
+#[path = "trace_test.rs"]
+pub mod test;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcEntryPointType {
+    External,
+    L1Handler,
+    Constructor,
+}
+
+impl From<EntryPointType> for RpcEntryPointType {
+    fn from(entry_point_type: EntryPointType) -> Self {
+        match entry_point_type {
+            EntryPointType::External => Self::External,
+            EntryPointType::L1Handler => Self::L1Handler,
+            EntryPointType::Constructor => Self::Constructor,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcCallType {
+    Call,
+    LibraryCall,
+}
+
+impl From<CallType> for RpcCallType {
+    fn from(call_type: CallType) -> Self {
+        match call_type {
+            CallType::Call => Self::Call,
+            // Both library calls and (deprecated) delegate calls execute in the caller's context.
+            CallType::Delegate => Self::LibraryCall,
+        }
+    }
+}
+
+/// The resources consumed by a single call, in the JSON-RPC `COMPUTATION_RESOURCES` shape.
+/// Zero counters are omitted.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct RpcComputationResources {
+    pub steps: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_holes: Option<usize>,
+    /// The non-zero builtin counters, keyed by their JSON-RPC names (see
+    /// [`rpc_builtin_counters`]).
+    #[serde(flatten)]
+    pub builtin_counters: BTreeMap<&'static str, usize>,
+}
+
+impl From<&ExecutionResources> for RpcComputationResources {
+    fn from(vm_resources: &ExecutionResources) -> Self {
+        let non_zero = |count: usize| Some(count).filter(|count| *count > 0);
+
+        Self {
+            steps: vm_resources.n_steps,
+            memory_holes: non_zero(vm_resources.n_memory_holes),
+            builtin_counters: rpc_builtin_counters(vm_resources),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct RpcOrderedEvent {
+    pub order: usize,
+    pub keys: Vec<EventKey>,
+    pub data: Vec<StarkFelt>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct RpcOrderedMessage {
+    pub order: usize,
+    pub from_address: ContractAddress,
+    pub to_address: EthAddress,
+    pub payload: Vec<StarkFelt>,
+}
+
+/// A call and its inner calls, in the JSON-RPC `FUNCTION_INVOCATION` shape.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct RpcFunctionInvocation {
+    pub contract_address: ContractAddress,
+    pub entry_point_selector: EntryPointSelector,
+    pub calldata: Calldata,
+    pub caller_address: ContractAddress,
+    /// The hash of the executed class; zero for calls that were not executed (e.g., mocked).
+    pub class_hash: ClassHash,
+    pub entry_point_type: RpcEntryPointType,
+    pub call_type: RpcCallType,
+    pub result: Vec<StarkFelt>,
+    pub calls: Vec<RpcFunctionInvocation>,
+    pub events: Vec<RpcOrderedEvent>,
+    pub messages: Vec<RpcOrderedMessage>,
+    /// The resources consumed by the call, including its inner calls.
+    pub execution_resources: RpcComputationResources,
+    /// The gas charged for each syscall invocation by the call; not part of the JSON-RPC shape,
+    /// and only recorded under syscall gas audit.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub syscall_gas: Vec<SyscallGasRecord>,
+}
+
+impl From<&CallInfo> for RpcFunctionInvocation {
+    fn from(call_info: &CallInfo) -> Self {
+        let CallInfo { call, execution, resources, inner_calls, syscall_gas_records, .. } =
+            call_info;
+        Self {
+            contract_address: call.storage_address,
+            entry_point_selector: call.entry_point_selector,
+            calldata: call.calldata.clone(),
+            caller_address: call.caller_address,
+            class_hash: call.class_hash.unwrap_or_default(),
+            entry_point_type: call.entry_point_type.into(),
+            call_type: call.call_type.into(),
+            result: execution.retdata.0.clone(),
+            calls: inner_calls.iter().map(Self::from).collect(),
+            events: execution
+                .events
+                .iter()
+                .map(|event| RpcOrderedEvent {
+                    order: event.order,
+                    keys: event.event.keys.clone(),
+                    data: event.event.data.0.clone(),
+                })
+                .collect(),
+            messages: execution
+                .l2_to_l1_messages
+                .iter()
+                .map(|message| RpcOrderedMessage {
+                    order: message.order,
+                    from_address: call.storage_address,
+                    to_address: message.message.to_address,
+                    payload: message.message.payload.0.clone(),
+                })
+                .collect(),
+            execution_resources: resources.into(),
+            syscall_gas: syscall_gas_records.clone(),
+        }
+    }
+}
+
+/// The execution of an invoke transaction or an L1 handler; either the invocation, or the
+/// reason it was reverted.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum RpcExecuteInvocation {
+    Invocation(RpcFunctionInvocation),
+    Reverted { revert_reason: String },
+}
+
+impl RpcExecuteInvocation {
+    fn new(tx_execution_info: &TransactionExecutionInfo) -> Self {
+        match (&tx_execution_info.revert_error, &tx_execution_info.execute_call_info) {
+            (None, Some(execute_call_info)) => Self::Invocation(execute_call_info.into()),
+            (revert_error, _) => Self::Reverted {
+                revert_reason: revert_error
+                    .as_ref()
+                    .map(|revert_error| revert_error.to_string())
+                    .unwrap_or_default(),
+            },
+        }
+    }
+}
+
+/// The trace of an executed transaction, in the JSON-RPC `TRANSACTION_TRACE` shape.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcTransactionTrace {
+    Invoke {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        validate_invocation: Option<RpcFunctionInvocation>,
+        execute_invocation: RpcExecuteInvocation,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fee_transfer_invocation: Option<RpcFunctionInvocation>,
+        execution_resources: RpcExecutionResources,
+    },
+    Declare {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        validate_invocation: Option<RpcFunctionInvocation>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fee_transfer_invocation: Option<RpcFunctionInvocation>,
+        execution_resources: RpcExecutionResources,
+    },
+    DeployAccount {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        validate_invocation: Option<RpcFunctionInvocation>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        constructor_invocation: Option<RpcFunctionInvocation>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fee_transfer_invocation: Option<RpcFunctionInvocation>,
+        execution_resources: RpcExecutionResources,
+    },
+    L1Handler {
+        function_invocation: RpcExecuteInvocation,
+        execution_resources: RpcExecutionResources,
+    },
+}
+
+impl RpcTransactionTrace {
+    pub fn new(tx_type: TransactionType, tx_execution_info: &TransactionExecutionInfo) -> Self {
+        let invocation =
+            |call_info: &Option<CallInfo>| call_info.as_ref().map(RpcFunctionInvocation::from);
+        let validate_invocation = invocation(&tx_execution_info.validate_call_info);
+        let fee_transfer_invocation = invocation(&tx_execution_info.fee_transfer_call_info);
+        let actual_resources = &tx_execution_info.actual_resources;
+        let execution_resources = RpcExecutionResources::new(
+            &actual_resources.vm_resources,
+            actual_resources.n_reverted_steps,
+            tx_execution_info.da_gas,
+        );
+
+        match tx_type {
+            TransactionType::InvokeFunction => Self::Invoke {
+                validate_invocation,
+                execute_invocation: RpcExecuteInvocation::new(tx_execution_info),
+                fee_transfer_invocation,
+                execution_resources,
+            },
+            TransactionType::Declare => {
+                Self::Declare { validate_invocation, fee_transfer_invocation, execution_resources }
+            }
+            TransactionType::DeployAccount => Self::DeployAccount {
+                validate_invocation,
+                constructor_invocation: invocation(&tx_execution_info.execute_call_info),
+                fee_transfer_invocation,
+                execution_resources,
+            },
+            TransactionType::L1Handler => Self::L1Handler {
+                function_invocation: RpcExecuteInvocation::new(tx_execution_info),
+                execution_resources,
+            },
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use cairo_vm::vm::runners::builtin_runner::RANGE_CHECK_BUILTIN_NAME;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, EthAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{Calldata, EventContent, EventData, EventKey, L2ToL1Payload};
+use starknet_api::{calldata, contract_address, patricia_key, stark_felt};
+
+use crate::execution::call_info::{
+    CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
+};
+use crate::execution::entry_point::{CallEntryPoint, CallType};
+use crate::retdata;
+use crate::transaction::objects::{RevertReason, TransactionExecutionInfo};
+use crate::transaction::trace::{
+    RpcCallType, RpcExecuteInvocation, RpcFunctionInvocation, RpcTransactionTrace,
+};
+use crate::transaction::transaction_types::TransactionType;
+
+fn call_info(storage_address: ContractAddress, inner_calls: Vec<CallInfo>) -> CallInfo {
+    CallInfo {
+        call: CallEntryPoint {
+            class_hash: Some(ClassHash(stark_felt!(1_u8))),
+            storage_address,
+            ..Default::default()
+        },
+        inner_calls,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_function_invocation() {
+    let address = contract_address!("0x10");
+    let to_address = EthAddress::try_from(stark_felt!(5_u8)).unwrap();
+    let inner_call = CallInfo {
+        call: CallEntryPoint {
+            call_type: CallType::Delegate,
+            caller_address: address,
+            ..call_info(address, vec![]).call
+        },
+        execution: CallExecution {
+            events: vec![OrderedEvent {
+                order: 0,
+                event: EventContent {
+                    keys: vec![EventKey(stark_felt!(2_u8))],
+                    data: EventData(vec![stark_felt!(3_u8)]),
+                },
+            }],
+            l2_to_l1_messages: vec![OrderedL2ToL1Message {
+                order: 0,
+                message: MessageToL1 {
+                    to_address,
+                    payload: L2ToL1Payload(vec![stark_felt!(6_u8)]),
+                },
+            }],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let outer_call = CallInfo {
+        call: CallEntryPoint {
+            entry_point_selector: EntryPointSelector(stark_felt!(4_u8)),
+            calldata: calldata![stark_felt!(7_u8)],
+            ..call_info(address, vec![]).call
+        },
+        execution: CallExecution { retdata: retdata![stark_felt!(8_u8)], ..Default::default() },
+        resources: ExecutionResources {
+            n_steps: 100,
+            n_memory_holes: 0,
+            builtin_instance_counter: HashMap::from([(RANGE_CHECK_BUILTIN_NAME.to_string(), 3)]),
+        },
+        inner_calls: vec![inner_call],
+        ..Default::default()
+    };
+
+    let invocation = RpcFunctionInvocation::from(&outer_call);
+
+    assert_eq!(invocation.calls.len(), 1);
+    assert_eq!(invocation.calls[0].call_type, RpcCallType::LibraryCall);
+    assert_eq!(
+        serde_json::to_value(&invocation).unwrap(),
+        json!({
+            "contract_address": address,
+            "entry_point_selector": stark_felt!(4_u8),
+            "calldata": [stark_felt!(7_u8)],
+            "caller_address": ContractAddress::default(),
+            "class_hash": stark_felt!(1_u8),
+            "entry_point_type": "EXTERNAL",
+            "call_type": "CALL",
+            "result": [stark_felt!(8_u8)],
+            "calls": [{
+                "contract_address": address,
+                "entry_point_selector": StarkFelt::ZERO,
+                "calldata": [],
+                "caller_address": address,
+                "class_hash": stark_felt!(1_u8),
+                "entry_point_type": "EXTERNAL",
+                "call_type": "LIBRARY_CALL",
+                "result": [],
+                "calls": [],
+                "events": [{"order": 0, "keys": [stark_felt!(2_u8)], "data": [stark_felt!(3_u8)]}],
+                "messages": [{
+                    "order": 0,
+                    "from_address": address,
+                    "to_address": to_address,
+                    "payload": [stark_felt!(6_u8)],
+                }],
+                "execution_resources": {"steps": 0},
+            }],
+            "events": [],
+            "messages": [],
+            "execution_resources": {"steps": 100, "range_check_builtin_applications": 3},
+        })
+    );
+}
+
+#[test]
+fn test_transaction_trace() {
+    let tx_execution_info = TransactionExecutionInfo {
+        validate_call_info: Some(call_info(contract_address!("0x30"), vec![])),
+        execute_call_info: Some(call_info(contract_address!("0x10"), vec![])),
+        fee_transfer_call_info: Some(call_info(contract_address!("0x20"), vec![])),
+        ..Default::default()
+    };
+
+    let trace = RpcTransactionTrace::new(TransactionType::InvokeFunction, &tx_execution_info);
+    let RpcTransactionTrace::Invoke {
+        validate_invocation,
+        execute_invocation,
+        fee_transfer_invocation,
+        ..
+    } = &trace
+    else {
+        panic!("Expected an invoke trace, got: {trace:?}.");
+    };
+    assert_eq!(validate_invocation.as_ref().unwrap().contract_address, contract_address!("0x30"));
+    assert_eq!(
+        *execute_invocation,
+        RpcExecuteInvocation::Invocation(RpcFunctionInvocation::from(
+            tx_execution_info.execute_call_info.as_ref().unwrap()
+        ))
+    );
+    assert_eq!(
+        fee_transfer_invocation.as_ref().unwrap().contract_address,
+        contract_address!("0x20")
+    );
+    assert_eq!(serde_json::to_value(&trace).unwrap()["type"], json!("INVOKE"));
+
+    // The execution of a reverted transaction is reported by its revert reason.
+    let reverted_tx_execution_info = TransactionExecutionInfo {
+        execute_call_info: None,
+        revert_error: Some(RevertReason::new("Reverted.".to_string(), vec![], vec![])),
+        ..tx_execution_info
+    };
+    let trace = RpcTransactionTrace::new(TransactionType::L1Handler, &reverted_tx_execution_info);
+    assert_eq!(
+        serde_json::to_value(&trace).unwrap()["function_invocation"],
+        json!({"revert_reason": "Reverted."})
+    );
+
+    let trace = RpcTransactionTrace::new(TransactionType::Declare, &reverted_tx_execution_info);
+    let trace_json = serde_json::to_value(&trace).unwrap();
+    assert_eq!(trace_json["type"], json!("DECLARE"));
+    assert!(trace_json.get("execute_invocation").is_none());
+}
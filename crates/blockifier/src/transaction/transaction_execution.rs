@@ -2,22 +2,27 @@ use std::sync::Arc;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use starknet_api::core::{calculate_contract_address, ContractAddress};
-use starknet_api::transaction::{Fee, Transaction as StarknetApiTransaction, TransactionHash};
+use starknet_api::transaction::{
+    Fee, Transaction as StarknetApiTransaction, TransactionHash, TransactionVersion,
+};
 
-use crate::context::BlockContext;
+use crate::blockifier::hooks::{NoopHooks, TransactionExecutorHooks};
+use crate::context::{BlockContext, SystemTransactionConfig};
 use crate::execution::contract_class::ClassInfo;
 use crate::execution::entry_point::EntryPointExecutionContext;
 use crate::fee::actual_cost::TransactionReceipt;
 use crate::state::cached_state::TransactionalState;
 use crate::state::state_api::StateReader;
 use crate::transaction::account_transaction::AccountTransaction;
-use crate::transaction::errors::TransactionFeeError;
+use crate::transaction::errors::{TransactionExecutionError, TransactionFeeError};
 use crate::transaction::objects::{
-    TransactionExecutionInfo, TransactionExecutionResult, TransactionInfo, TransactionInfoCreator,
+    L1HandlerFeeInfo, TransactionExecutionInfo, TransactionExecutionResult, TransactionInfo,
+    TransactionInfoCreator,
 };
+use crate::transaction::transaction_utils::{split_query_version, to_query_version};
 use crate::transaction::transactions::{
     DeclareTransaction, DeployAccountTransaction, Executable, ExecutableTransaction,
-    InvokeTransaction, L1HandlerTransaction,
+    InvokeTransaction, L1HandlerTransaction, SystemTransaction,
 };
 
 // TODO: Move into transaction.rs, makes more sense to be defined there.
@@ -25,6 +30,7 @@ use crate::transaction::transactions::{
 pub enum Transaction {
     AccountTransaction(AccountTransaction),
     L1HandlerTransaction(L1HandlerTransaction),
+    SystemTransaction(SystemTransaction),
 }
 
 impl Transaction {
@@ -88,6 +94,46 @@ impl Transaction {
             _ => unimplemented!(),
         }
     }
+
+    /// Same as `from_api`, deriving whether the transaction is query-only from the version it was
+    /// signed with, i.e., whether the query bit is set on it (see [`to_query_version`]); the
+    /// version must otherwise match the transaction's.
+    pub fn from_api_with_signed_version(
+        tx: StarknetApiTransaction,
+        tx_hash: TransactionHash,
+        class_info: Option<ClassInfo>,
+        paid_fee_on_l1: Option<Fee>,
+        deployed_contract_address: Option<ContractAddress>,
+        signed_version: TransactionVersion,
+    ) -> TransactionExecutionResult<Self> {
+        let tx_version = match &tx {
+            StarknetApiTransaction::L1Handler(l1_handler) => l1_handler.version,
+            StarknetApiTransaction::Declare(declare) => declare.version(),
+            StarknetApiTransaction::DeployAccount(deploy_account) => deploy_account.version(),
+            StarknetApiTransaction::Invoke(invoke) => invoke.version(),
+            StarknetApiTransaction::Deploy(_) => {
+                return Err(TransactionExecutionError::UnsupportedTransactionType {
+                    tx_type: "Deploy".to_string(),
+                });
+            }
+        };
+        let (version, only_query) = split_query_version(signed_version);
+        if version != tx_version {
+            return Err(TransactionExecutionError::InvalidVersion {
+                version: signed_version,
+                allowed_versions: vec![tx_version, to_query_version(tx_version)],
+            });
+        }
+
+        Self::from_api(
+            tx,
+            tx_hash,
+            class_info,
+            paid_fee_on_l1,
+            deployed_contract_address,
+            only_query,
+        )
+    }
 }
 
 impl TransactionInfoCreator for Transaction {
@@ -95,6 +141,7 @@ impl TransactionInfoCreator for Transaction {
         match self {
             Self::AccountTransaction(account_tx) => account_tx.create_tx_info(),
             Self::L1HandlerTransaction(l1_handler_tx) => l1_handler_tx.create_tx_info(),
+            Self::SystemTransaction(system_tx) => system_tx.create_tx_info(),
         }
     }
 }
@@ -116,19 +163,23 @@ impl<S: StateReader> ExecutableTransaction<S> for L1HandlerTransaction {
             self.run_execute(state, &mut execution_resources, &mut context, &mut remaining_gas)?;
         let l1_handler_payload_size = self.payload_size();
 
-        let TransactionReceipt { fee: actual_fee, da_gas, resources: actual_resources, .. } =
-            TransactionReceipt::from_l1_handler(
-                &tx_context,
-                l1_handler_payload_size,
-                execute_call_info.iter(),
-                &state.get_actual_state_changes()?,
-                &execution_resources,
-            )?;
+        let TransactionReceipt {
+            fee: actual_fee, gas, da_gas, resources: actual_resources, ..
+        } = TransactionReceipt::from_l1_handler(
+            &tx_context,
+            l1_handler_payload_size,
+            execute_call_info.iter(),
+            &state.get_actual_state_changes()?,
+            &execution_resources,
+        )?;
 
         let paid_fee = self.paid_fee_on_l1;
+        let fee_exemptions = &block_context.fee_policy.fee_exemptions;
+        let fee_exempt = fee_exemptions.l1_handlers
+            || fee_exemptions.is_exempt_address(self.tx.contract_address);
         // For now, assert only that any amount of fee was paid.
         // The error message still indicates the required fee.
-        if paid_fee == Fee(0) {
+        if paid_fee == Fee(0) && !fee_exempt {
             return Err(TransactionFeeError::InsufficientL1Fee { paid_fee, actual_fee })?;
         }
 
@@ -137,13 +188,91 @@ impl<S: StateReader> ExecutableTransaction<S> for L1HandlerTransaction {
             execute_call_info,
             fee_transfer_call_info: None,
             actual_fee: Fee::default(),
+            gas,
             da_gas,
             revert_error: None,
+            reverted_effects: None,
+            l1_handler_fee_info: Some(L1HandlerFeeInfo::new(paid_fee, actual_fee)),
+            metadata: None,
             actual_resources,
         })
     }
 }
 
+impl SystemTransaction {
+    /// Same as `execute_raw`, invoking the given hooks along the execution path.
+    pub fn execute_raw_with_hooks<S: StateReader>(
+        &self,
+        state: &mut TransactionalState<'_, S>,
+        block_context: &BlockContext,
+        validate: bool,
+        hooks: &dyn TransactionExecutorHooks,
+    ) -> TransactionExecutionResult<TransactionExecutionInfo> {
+        let SystemTransactionConfig { operator_address, validation_limits } =
+            block_context.execution_policy.system_tx_config;
+        let sender_address = self.tx.sender_address();
+        if operator_address != Some(sender_address) {
+            return Err(TransactionExecutionError::UnauthorizedSystemTransaction {
+                sender_address,
+            });
+        }
+
+        let mut block_context = block_context.clone();
+        if let Some(validation_limits) = validation_limits {
+            block_context.execution_policy.validation_limits = validation_limits;
+        }
+        // System transactions are never charged for.
+        let charge_fee = false;
+        let account_tx = AccountTransaction::Invoke(self.tx.clone());
+        let tx_execution_info = account_tx.execute_raw_with_hooks(
+            state,
+            &block_context,
+            charge_fee,
+            validate,
+            hooks,
+        )?;
+
+        Ok(TransactionExecutionInfo { actual_fee: Fee::default(), ..tx_execution_info })
+    }
+}
+
+impl<S: StateReader> ExecutableTransaction<S> for SystemTransaction {
+    fn execute_raw(
+        &self,
+        state: &mut TransactionalState<'_, S>,
+        block_context: &BlockContext,
+        _charge_fee: bool,
+        validate: bool,
+    ) -> TransactionExecutionResult<TransactionExecutionInfo> {
+        self.execute_raw_with_hooks(state, block_context, validate, &NoopHooks)
+    }
+}
+
+impl Transaction {
+    /// Same as `execute_raw`, invoking the given hooks along the execution path of account and
+    /// system transactions.
+    pub fn execute_raw_with_hooks<S: StateReader>(
+        &self,
+        state: &mut TransactionalState<'_, S>,
+        block_context: &BlockContext,
+        charge_fee: bool,
+        validate: bool,
+        hooks: &dyn TransactionExecutorHooks,
+    ) -> TransactionExecutionResult<TransactionExecutionInfo> {
+        match self {
+            Self::AccountTransaction(account_tx) => {
+                account_tx.execute_raw_with_hooks(state, block_context, charge_fee, validate, hooks)
+            }
+            Self::L1HandlerTransaction(tx) => {
+                tx.execute_raw(state, block_context, charge_fee, validate)
+            }
+            Self::SystemTransaction(tx) => {
+                tx.execute_raw_with_hooks(state, block_context, validate, hooks)
+            }
+        }
+    }
+}
+
 impl<S: StateReader> ExecutableTransaction<S> for Transaction {
     fn execute_raw(
         &self,
@@ -159,6 +288,9 @@ impl<S: StateReader> ExecutableTransaction<S> for Transaction {
             Self::L1HandlerTransaction(tx) => {
                 tx.execute_raw(state, block_context, charge_fee, validate)
             }
+            Self::SystemTransaction(tx) => {
+                tx.execute_raw(state, block_context, charge_fee, validate)
+            }
         }
     }
 }
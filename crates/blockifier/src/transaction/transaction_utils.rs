@@ -1,13 +1,36 @@
+use cairo_felt::Felt252;
+use num_traits::Pow;
+use starknet_api::core::ClassHash;
 use starknet_api::transaction::TransactionVersion;
 
+use crate::context::{TransactionContext, UndeployedSenderPolicy};
 use crate::execution::call_info::CallInfo;
 use crate::execution::contract_class::ContractClass;
+use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+use crate::transaction::constants;
 use crate::transaction::errors::TransactionExecutionError;
 
 pub fn update_remaining_gas(remaining_gas: &mut u64, call_info: &CallInfo) {
     *remaining_gas -= call_info.execution.gas_consumed;
 }
 
+/// Returns the class to run the account entry points of an undeployed sender with, given its
+/// (default) class hash, as set by the block's [`UndeployedSenderPolicy`]; [None] if the sender is
+/// deployed, or if undeployed senders are rejected.
+pub fn counterfactual_sender_class_hash(
+    sender_class_hash: ClassHash,
+    tx_context: &TransactionContext,
+) -> Option<ClassHash> {
+    if sender_class_hash != ClassHash::default() {
+        return None;
+    }
+
+    match tx_context.block_context.execution_policy.undeployed_sender_policy {
+        UndeployedSenderPolicy::Reject => None,
+        UndeployedSenderPolicy::Counterfactual(class_hash) => Some(class_hash),
+    }
+}
+
 pub fn verify_contract_class_version(
     contract_class: &ContractClass,
     declare_version: TransactionVersion,
@@ -33,3 +56,25 @@ pub fn verify_contract_class_version(
         }
     }
 }
+
+fn query_version_base() -> Felt252 {
+    Pow::pow(Felt252::from(2_u8), constants::QUERY_VERSION_BASE_BIT)
+}
+
+/// Returns the given version with the query bit set; query-only transactions are signed (and
+/// hashed) with this version.
+pub fn to_query_version(version: TransactionVersion) -> TransactionVersion {
+    let query_version = query_version_base() + stark_felt_to_felt(version.0);
+    TransactionVersion(felt_to_stark_felt(&query_version))
+}
+
+/// Splits a possibly query-marked version into its base version and whether the query bit is set.
+pub fn split_query_version(version: TransactionVersion) -> (TransactionVersion, bool) {
+    let version_felt = stark_felt_to_felt(version.0);
+    let query_version_base = query_version_base();
+    if version_felt < query_version_base {
+        return (version, false);
+    }
+
+    (TransactionVersion(felt_to_stark_felt(&(version_felt - query_version_base))), true)
+}
@@ -1,14 +1,22 @@
+#[cfg(feature = "batch_declare")]
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use starknet_api::calldata;
-use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::core::{
+    calculate_contract_address, ClassHash, CompiledClassHash, ContractAddress, Nonce,
+};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
 use starknet_api::transaction::{
     AccountDeploymentData, Calldata, ContractAddressSalt, DeclareTransactionV2,
     DeclareTransactionV3, Fee, TransactionHash, TransactionSignature, TransactionVersion,
 };
+#[cfg(feature = "batch_declare")]
+use starknet_crypto::{poseidon_hash_many, FieldElement};
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::context::{BlockContext, TransactionContext};
@@ -17,17 +25,23 @@ use crate::execution::contract_class::{ClassInfo, ContractClass};
 use crate::execution::entry_point::{
     CallEntryPoint, CallType, ConstructorContext, EntryPointExecutionContext,
 };
-use crate::execution::execution_utils::execute_deployment;
+use crate::execution::execution_utils::{execute_deployment, felt_to_stark_felt};
 use crate::state::cached_state::{CachedState, TransactionalState};
 use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateReader};
+use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::constants;
 use crate::transaction::errors::TransactionExecutionError;
 use crate::transaction::objects::{
-    CommonAccountFields, CurrentTransactionInfo, DeprecatedTransactionInfo, HasRelatedFeeType,
-    TransactionExecutionInfo, TransactionExecutionResult, TransactionInfo, TransactionInfoCreator,
+    CommonAccountFields, CurrentTransactionInfo, DeployAccountEstimation,
+    DeprecatedTransactionInfo, HasRelatedFeeType, TransactionExecutionInfo,
+    TransactionExecutionResult, TransactionInfo, TransactionInfoCreator, TransactionSimulationInfo,
+};
+use crate::transaction::transaction_utils::{
+    counterfactual_sender_class_hash, update_remaining_gas, verify_contract_class_version,
 };
-use crate::transaction::transaction_utils::{update_remaining_gas, verify_contract_class_version};
+#[cfg(feature = "batch_declare")]
+use crate::utils::usize_to_felt;
 
 #[cfg(test)]
 #[path = "transactions_test.rs"]
@@ -70,6 +84,28 @@ pub trait ExecutableTransaction<S: StateReader>: Sized {
         }
     }
 
+    /// Executes the transaction without modifying the given state (dry run), and returns the
+    /// execution info along with the state diff the transaction would have applied.
+    fn simulate(
+        &self,
+        state: &mut CachedState<S>,
+        block_context: &BlockContext,
+        charge_fee: bool,
+        validate: bool,
+    ) -> TransactionExecutionResult<TransactionSimulationInfo> {
+        let mut transactional_state = CachedState::create_transactional(state);
+        let execution_result =
+            self.execute_raw(&mut transactional_state, block_context, charge_fee, validate);
+        let simulation_result =
+            execution_result.map(|tx_execution_info| TransactionSimulationInfo {
+                tx_execution_info,
+                state_diff: transactional_state.to_state_diff(),
+            });
+        transactional_state.abort();
+
+        simulation_result
+    }
+
     /// Note: In case of execution failure, the state may become corrupted. This means that
     /// any changes made up to the point of failure will persist in the state. To revert these
     /// changes, you should call `state.abort()`. Alternatively, consider using `execute`
@@ -105,6 +141,31 @@ pub trait ValidatableTransaction {
     ) -> TransactionExecutionResult<Option<CallInfo>>;
 }
 
+/// A class declared by a batch declare transaction, in addition to its main class.
+#[cfg(feature = "batch_declare")]
+#[derive(Clone, Debug)]
+pub struct ClassDeclaration {
+    pub class_hash: ClassHash,
+    pub compiled_class_hash: CompiledClassHash,
+    pub class_info: ClassInfo,
+}
+
+/// Returns the hash signed by the account for a batch declare: the Poseidon hash of the declare
+/// transaction hash, the number of additional classes, and their class and compiled class hashes.
+#[cfg(feature = "batch_declare")]
+pub fn batch_declare_tx_hash(
+    declare_tx_hash: TransactionHash,
+    additional_classes: &[ClassDeclaration],
+) -> TransactionHash {
+    let mut elements =
+        vec![FieldElement::from(declare_tx_hash.0), FieldElement::from(additional_classes.len())];
+    for declaration in additional_classes {
+        elements.push(FieldElement::from(declaration.class_hash.0));
+        elements.push(FieldElement::from(declaration.compiled_class_hash.0));
+    }
+    TransactionHash(StarkFelt::from(poseidon_hash_many(&elements)))
+}
+
 #[derive(Debug)]
 pub struct DeclareTransaction {
     pub tx: starknet_api::transaction::DeclareTransaction,
@@ -112,6 +173,12 @@ pub struct DeclareTransaction {
     // Indicates the presence of the only_query bit in the version.
     only_query: bool,
     pub class_info: ClassInfo,
+    fee_recipient: Option<ContractAddress>,
+    // The Sierra class the declared class was compiled from, if the transaction carries it.
+    sierra_contract_class: Option<SierraContractClass>,
+    // Classes declared atomically along with the main one; validated and charged for as a whole.
+    #[cfg(feature = "batch_declare")]
+    additional_classes: Vec<ClassDeclaration>,
 }
 
 impl DeclareTransaction {
@@ -123,7 +190,16 @@ impl DeclareTransaction {
     ) -> TransactionExecutionResult<Self> {
         let declare_version = declare_tx.version();
         verify_contract_class_version(&class_info.contract_class(), declare_version)?;
-        Ok(Self { tx: declare_tx, tx_hash, class_info, only_query })
+        Ok(Self {
+            tx: declare_tx,
+            tx_hash,
+            class_info,
+            only_query,
+            fee_recipient: None,
+            sierra_contract_class: None,
+            #[cfg(feature = "batch_declare")]
+            additional_classes: vec![],
+        })
     }
 
     pub fn new(
@@ -159,6 +235,141 @@ impl DeclareTransaction {
     pub fn only_query(&self) -> bool {
         self.only_query
     }
+
+    /// Directs the transaction's fee to the given address instead of the block's sequencer.
+    pub fn with_fee_recipient(self, fee_recipient: ContractAddress) -> Self {
+        Self { fee_recipient: Some(fee_recipient), ..self }
+    }
+
+    /// Attaches the Sierra class the declared class was compiled from. If the block context
+    /// verifies Sierra compilation (see
+    /// [`crate::context::ExecutionPolicy::verify_sierra_compilation`]), it is recompiled on
+    /// execution and checked against the declared compiled class hash; additional classes of
+    /// batch declares are not checked.
+    pub fn with_sierra_contract_class(self, sierra_contract_class: SierraContractClass) -> Self {
+        Self { sierra_contract_class: Some(sierra_contract_class), ..self }
+    }
+
+    /// Turns the transaction into a batch declare, declaring the given classes along with the main
+    /// one; either all classes are declared, or none. Only supported for declare transactions of
+    /// version 2 and above.
+    /// The transaction hash is replaced by [`batch_declare_tx_hash`], so that the signature covers
+    /// the additional classes; the account's `__validate_declare__` receives their hashes as well
+    /// (see [`DeclareTransaction::validate_calldata`]).
+    #[cfg(feature = "batch_declare")]
+    pub fn with_additional_classes(
+        self,
+        additional_classes: Vec<ClassDeclaration>,
+    ) -> TransactionExecutionResult<Self> {
+        let declare_version = self.tx.version();
+        if let starknet_api::transaction::DeclareTransaction::V0(_)
+        | starknet_api::transaction::DeclareTransaction::V1(_) = self.tx
+        {
+            return Err(TransactionExecutionError::InvalidVersion {
+                version: declare_version,
+                allowed_versions: vec![TransactionVersion::TWO, TransactionVersion::THREE],
+            });
+        }
+
+        let mut class_hashes = HashSet::from([self.class_hash()]);
+        for declaration in &additional_classes {
+            verify_contract_class_version(
+                &declaration.class_info.contract_class(),
+                declare_version,
+            )?;
+            if !class_hashes.insert(declaration.class_hash) {
+                return Err(TransactionExecutionError::DuplicateDeclaredClass {
+                    class_hash: declaration.class_hash,
+                });
+            }
+        }
+
+        let tx_hash = batch_declare_tx_hash(self.tx_hash, &additional_classes);
+        Ok(Self { tx_hash, additional_classes, ..self })
+    }
+
+    #[cfg(feature = "batch_declare")]
+    pub fn additional_classes(&self) -> &[ClassDeclaration] {
+        &self.additional_classes
+    }
+
+    /// Returns the calldata passed to the account's `__validate_declare__`: the class hash,
+    /// followed, for batch declares, by the number of additional classes and their hashes.
+    pub fn validate_calldata(&self) -> Calldata {
+        #[cfg(feature = "batch_declare")]
+        if !self.additional_classes.is_empty() {
+            let mut calldata =
+                vec![self.class_hash().0, usize_to_felt(self.additional_classes.len())];
+            calldata
+                .extend(self.additional_classes.iter().map(|declaration| declaration.class_hash.0));
+            return Calldata(calldata.into());
+        }
+        calldata![self.class_hash().0]
+    }
+
+    /// Returns the total code size of the declared classes.
+    pub fn code_size(&self) -> usize {
+        #[cfg(feature = "batch_declare")]
+        return self.class_info.code_size()
+            + self
+                .additional_classes
+                .iter()
+                .map(|declaration| declaration.class_info.code_size())
+                .sum::<usize>();
+        #[cfg(not(feature = "batch_declare"))]
+        self.class_info.code_size()
+    }
+
+    /// Recompiles the given Sierra class with the compiler this crate is pinned to, and verifies
+    /// that the resulting compiled class hash matches the declared one.
+    /// This check is opt-in, for sequencers that accept raw Sierra classes (see
+    /// [`DeclareTransaction::with_sierra_contract_class`]); it only applies to declare
+    /// transactions of version 2 and above.
+    pub fn verify_sierra_compilation(
+        &self,
+        sierra_contract_class: SierraContractClass,
+    ) -> TransactionExecutionResult<()> {
+        let declared_compiled_class_hash = match &self.tx {
+            starknet_api::transaction::DeclareTransaction::V0(_)
+            | starknet_api::transaction::DeclareTransaction::V1(_) => {
+                return Err(TransactionExecutionError::InvalidVersion {
+                    version: self.tx.version(),
+                    allowed_versions: vec![TransactionVersion::TWO, TransactionVersion::THREE],
+                });
+            }
+            starknet_api::transaction::DeclareTransaction::V2(DeclareTransactionV2 {
+                compiled_class_hash,
+                ..
+            })
+            | starknet_api::transaction::DeclareTransaction::V3(DeclareTransactionV3 {
+                compiled_class_hash,
+                ..
+            }) => *compiled_class_hash,
+        };
+
+        let class_hash = self.class_hash();
+        let add_pythonic_hints = false;
+        // Bytecode size limits are enforced before the class reaches the execution layer.
+        let max_bytecode_size = usize::MAX;
+        let casm_contract_class = CasmContractClass::from_contract_class(
+            sierra_contract_class,
+            add_pythonic_hints,
+            max_bytecode_size,
+        )
+        .map_err(|error| TransactionExecutionError::SierraCompilationError { class_hash, error })?;
+
+        let recompiled_compiled_class_hash =
+            CompiledClassHash(felt_to_stark_felt(&casm_contract_class.compiled_class_hash()));
+        if recompiled_compiled_class_hash != declared_compiled_class_hash {
+            return Err(TransactionExecutionError::CompiledClassHashMismatch {
+                class_hash,
+                declared_compiled_class_hash,
+                recompiled_compiled_class_hash,
+            });
+        }
+
+        Ok(())
+    }
 }
 
 impl<S: State> Executable<S> for DeclareTransaction {
@@ -166,7 +377,7 @@ impl<S: State> Executable<S> for DeclareTransaction {
         &self,
         state: &mut S,
         _resources: &mut ExecutionResources,
-        _context: &mut EntryPointExecutionContext,
+        context: &mut EntryPointExecutionContext,
         _remaining_gas: &mut u64,
     ) -> TransactionExecutionResult<Option<CallInfo>> {
         let class_hash = self.class_hash();
@@ -186,25 +397,51 @@ impl<S: State> Executable<S> for DeclareTransaction {
                 compiled_class_hash,
                 ..
             }) => {
-                match state.get_compiled_contract_class(class_hash) {
-                    Err(StateError::UndeclaredClassHash(_)) => {
-                        // Class is undeclared; declare it.
-                        state.set_contract_class(class_hash, self.contract_class())?;
-                        state.set_compiled_class_hash(class_hash, *compiled_class_hash)?;
-                        Ok(None)
-                    }
-                    Err(error) => Err(error)?,
-                    Ok(_) => {
-                        // Class is already declared, cannot redeclare
-                        // (i.e., make sure the leaf is uninitialized).
-                        Err(TransactionExecutionError::DeclareTransactionError { class_hash })
-                    }
+                if context.tx_context.block_context.execution_policy.verify_sierra_compilation {
+                    let sierra_contract_class = self.sierra_contract_class.clone().ok_or(
+                        TransactionExecutionError::MissingSierraContractClass { class_hash },
+                    )?;
+                    self.verify_sierra_compilation(sierra_contract_class)?;
+                }
+                declare_class(state, class_hash, *compiled_class_hash, self.contract_class())?;
+                #[cfg(feature = "batch_declare")]
+                for declaration in &self.additional_classes {
+                    declare_class(
+                        state,
+                        declaration.class_hash,
+                        declaration.compiled_class_hash,
+                        declaration.class_info.contract_class(),
+                    )?;
                 }
+                Ok(None)
             }
         }
     }
 }
 
+/// Declares a Cairo 1 class, failing if it is already declared.
+fn declare_class<S: State>(
+    state: &mut S,
+    class_hash: ClassHash,
+    compiled_class_hash: CompiledClassHash,
+    contract_class: ContractClass,
+) -> TransactionExecutionResult<()> {
+    match state.get_compiled_contract_class(class_hash) {
+        Err(StateError::UndeclaredClassHash(_)) => {
+            // Class is undeclared; declare it.
+            state.set_contract_class(class_hash, contract_class)?;
+            state.set_compiled_class_hash(class_hash, compiled_class_hash)?;
+            Ok(())
+        }
+        Err(error) => Err(error)?,
+        Ok(_) => {
+            // Class is already declared, cannot redeclare
+            // (i.e., make sure the leaf is uninitialized).
+            Err(TransactionExecutionError::DeclareTransactionError { class_hash })
+        }
+    }
+}
+
 impl TransactionInfoCreator for DeclareTransaction {
     fn create_tx_info(&self) -> TransactionInfo {
         // TODO(Nir, 01/11/2023): Consider to move this (from all get_tx_info methods).
@@ -215,6 +452,7 @@ impl TransactionInfoCreator for DeclareTransaction {
             nonce: self.tx.nonce(),
             sender_address: self.tx.sender_address(),
             only_query: self.only_query,
+            fee_recipient: self.fee_recipient,
         };
 
         match &self.tx {
@@ -252,6 +490,7 @@ pub struct DeployAccountTransaction {
     pub contract_address: ContractAddress,
     // Indicates the presence of the only_query bit in the version.
     pub only_query: bool,
+    pub fee_recipient: Option<ContractAddress>,
 }
 
 impl DeployAccountTransaction {
@@ -260,7 +499,13 @@ impl DeployAccountTransaction {
         tx_hash: TransactionHash,
         contract_address: ContractAddress,
     ) -> Self {
-        Self { tx: deploy_account_tx, tx_hash, contract_address, only_query: false }
+        Self {
+            tx: deploy_account_tx,
+            tx_hash,
+            contract_address,
+            only_query: false,
+            fee_recipient: None,
+        }
     }
 
     pub fn new_for_query(
@@ -268,7 +513,61 @@ impl DeployAccountTransaction {
         tx_hash: TransactionHash,
         contract_address: ContractAddress,
     ) -> Self {
-        Self { tx: deploy_account_tx, tx_hash, contract_address, only_query: true }
+        Self {
+            tx: deploy_account_tx,
+            tx_hash,
+            contract_address,
+            only_query: true,
+            fee_recipient: None,
+        }
+    }
+
+    /// Directs the transaction's fee to the given address instead of the block's sequencer.
+    pub fn with_fee_recipient(self, fee_recipient: ContractAddress) -> Self {
+        Self { fee_recipient: Some(fee_recipient), ..self }
+    }
+
+    /// Creates a query-only transaction deploying a counterfactual account, i.e., one that is not
+    /// deployed yet, at the address derived from the transaction.
+    pub fn new_counterfactual(
+        deploy_account_tx: starknet_api::transaction::DeployAccountTransaction,
+        tx_hash: TransactionHash,
+    ) -> TransactionExecutionResult<Self> {
+        let contract_address = calculate_contract_address(
+            deploy_account_tx.contract_address_salt(),
+            deploy_account_tx.class_hash(),
+            &deploy_account_tx.constructor_calldata(),
+            ContractAddress::default(),
+        )?;
+        Ok(Self::new_for_query(deploy_account_tx, tx_hash, contract_address))
+    }
+
+    /// Estimates the fee of deploying the account, before it is funded and the transaction is
+    /// signed: the transaction is simulated without validation (hence, the estimation does not
+    /// cover it), and the fee is computed regardless of the transaction's fee bounds, but not
+    /// charged.
+    pub fn estimate<S: StateReader>(
+        &self,
+        state: &mut CachedState<S>,
+        block_context: &BlockContext,
+    ) -> TransactionExecutionResult<DeployAccountEstimation> {
+        let mut estimation_block_context = block_context.clone();
+        estimation_block_context.fee_policy.relax_max_fee_check = true;
+        let account_tx = AccountTransaction::DeployAccount(self.clone());
+        let simulation_info =
+            account_tx.simulate(state, &estimation_block_context, false, false)?;
+
+        let fee_token_address = block_context.to_tx_context(self).fee_token_address();
+        let (balance_low, balance_high) =
+            state.get_fee_token_balance(self.contract_address, fee_token_address)?;
+        let actual_fee = StarkFelt::from(simulation_info.tx_execution_info.actual_fee.0);
+        let is_funded = balance_high > StarkFelt::ZERO || balance_low >= actual_fee;
+
+        Ok(DeployAccountEstimation {
+            contract_address: self.contract_address,
+            simulation_info,
+            is_funded,
+        })
     }
 
     implement_inner_tx_getter_calls!(
@@ -322,6 +621,7 @@ impl TransactionInfoCreator for DeployAccountTransaction {
             nonce: self.tx.nonce(),
             sender_address: self.contract_address,
             only_query: self.only_query,
+            fee_recipient: self.fee_recipient,
         };
 
         match &self.tx {
@@ -352,6 +652,7 @@ pub struct InvokeTransaction {
     pub tx_hash: TransactionHash,
     // Indicates the presence of the only_query bit in the version.
     pub only_query: bool,
+    pub fee_recipient: Option<ContractAddress>,
 }
 
 impl InvokeTransaction {
@@ -359,14 +660,19 @@ impl InvokeTransaction {
         invoke_tx: starknet_api::transaction::InvokeTransaction,
         tx_hash: TransactionHash,
     ) -> Self {
-        Self { tx: invoke_tx, tx_hash, only_query: false }
+        Self { tx: invoke_tx, tx_hash, only_query: false, fee_recipient: None }
     }
 
     pub fn new_for_query(
         invoke_tx: starknet_api::transaction::InvokeTransaction,
         tx_hash: TransactionHash,
     ) -> Self {
-        Self { tx: invoke_tx, tx_hash, only_query: true }
+        Self { tx: invoke_tx, tx_hash, only_query: true, fee_recipient: None }
+    }
+
+    /// Directs the transaction's fee to the given address instead of the block's sequencer.
+    pub fn with_fee_recipient(self, fee_recipient: ContractAddress) -> Self {
+        Self { fee_recipient: Some(fee_recipient), ..self }
     }
 
     implement_inner_tx_getter_calls!(
@@ -393,11 +699,14 @@ impl<S: State> Executable<S> for InvokeTransaction {
         };
         let storage_address = context.tx_context.tx_info.sender_address();
         let class_hash = state.get_class_hash_at(storage_address)?;
+        let counterfactual_class_hash =
+            counterfactual_sender_class_hash(class_hash, &context.tx_context);
+        let class_hash = counterfactual_class_hash.unwrap_or(class_hash);
         let execute_call = CallEntryPoint {
             entry_point_type: EntryPointType::External,
             entry_point_selector,
             calldata: self.calldata(),
-            class_hash: None,
+            class_hash: counterfactual_class_hash,
             code_address: None,
             storage_address,
             caller_address: ContractAddress::default(),
@@ -428,6 +737,7 @@ impl TransactionInfoCreator for InvokeTransaction {
             nonce: self.tx.nonce(),
             sender_address: self.tx.sender_address(),
             only_query: self.only_query,
+            fee_recipient: self.fee_recipient,
         };
 
         match &self.tx {
@@ -544,8 +854,29 @@ impl TransactionInfoCreator for L1HandlerTransaction {
                 nonce: self.tx.nonce,
                 sender_address: self.tx.contract_address,
                 only_query: false,
+                fee_recipient: None,
             },
             max_fee: Fee::default(),
         })
     }
 }
+
+/// An invoke transaction of the chain operator, for maintenance calls. It is validated (i.e.,
+/// signed) and executed like any invoke transaction, but is not charged for; see
+/// [crate::context::SystemTransactionConfig].
+#[derive(Clone, Debug)]
+pub struct SystemTransaction {
+    pub tx: InvokeTransaction,
+}
+
+impl SystemTransaction {
+    pub fn new(tx: InvokeTransaction) -> Self {
+        Self { tx }
+    }
+}
+
+impl TransactionInfoCreator for SystemTransaction {
+    fn create_tx_info(&self) -> TransactionInfo {
+        self.tx.create_tx_info()
+    }
+}
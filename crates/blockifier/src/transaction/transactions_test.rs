@@ -3,33 +3,43 @@ use std::sync::Arc;
 
 use assert_matches::assert_matches;
 use cairo_felt::Felt252;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
 use cairo_vm::vm::runners::builtin_runner::{HASH_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME};
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use num_traits::Pow;
 use once_cell::sync::Lazy;
 use pretty_assertions::assert_eq;
 use rstest::{fixture, rstest};
-use starknet_api::core::{ChainId, ClassHash, ContractAddress, EthAddress, Nonce, PatriciaKey};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{
+    ChainId, ClassHash, CompiledClassHash, ContractAddress, EthAddress, Nonce, PatriciaKey,
+};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{
-    Calldata, EventContent, EventData, EventKey, Fee, L2ToL1Payload, TransactionSignature,
-    TransactionVersion,
+    Calldata, DeployTransaction, EventContent, EventData, EventKey, Fee, L2ToL1Payload,
+    Transaction as StarknetApiTransaction, TransactionSignature, TransactionVersion,
 };
 use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
 use strum::IntoEnumIterator;
 
 use crate::abi::abi_utils::{
-    get_fee_token_var_address, get_storage_var_address, selector_from_name,
+    get_fee_token_allowance_var_address, get_fee_token_var_address, get_storage_var_address,
+    selector_from_name,
 };
 use crate::abi::constants as abi_constants;
 use crate::abi::sierra_types::next_storage_key;
-use crate::context::{BlockContext, ChainInfo, FeeTokenAddresses, TransactionContext};
+use crate::context::{
+    BlockContext, ChainInfo, FeeExemptions, FeeFloor, FeePaymentMode, FeeTokenAddresses,
+    SystemTransactionConfig, TransactionContext,
+};
 use crate::execution::call_info::{
     CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
 };
-use crate::execution::entry_point::{CallEntryPoint, CallType};
+use crate::execution::contract_class::{ContractClass, ContractClassV1};
+use crate::execution::entry_point::{CallEntryPoint, CallType, ExecutionInfoOverrides};
 use crate::execution::errors::{ConstructorEntryPointExecutionError, EntryPointExecutionError};
 use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
 use crate::execution::syscalls::hint_processor::EmitEventError;
@@ -45,15 +55,15 @@ use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::declare::declare_tx;
 use crate::test_utils::deploy_account::deploy_account_tx;
 use crate::test_utils::dict_state_reader::DictStateReader;
-use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::initial_test_state::{fund_account, test_state};
 use crate::test_utils::invoke::invoke_tx;
-use crate::test_utils::prices::Prices;
+use crate::test_utils::prices::{fee_transfer_syscall_counter, Prices};
 use crate::test_utils::{
-    create_calldata, create_trivial_calldata, get_syscall_resources, get_tx_resources,
-    test_erc20_sequencer_balance_key, CairoVersion, NonceManager, SaltManager, BALANCE,
-    CHAIN_ID_NAME, CURRENT_BLOCK_NUMBER, CURRENT_BLOCK_NUMBER_FOR_VALIDATE,
+    create_calldata, create_trivial_calldata, get_raw_contract_class, get_syscall_resources,
+    get_tx_resources, test_erc20_sequencer_balance_key, CairoVersion, NonceManager, SaltManager,
+    BALANCE, CHAIN_ID_NAME, CURRENT_BLOCK_NUMBER, CURRENT_BLOCK_NUMBER_FOR_VALIDATE,
     CURRENT_BLOCK_TIMESTAMP, CURRENT_BLOCK_TIMESTAMP_FOR_VALIDATE, MAX_FEE, MAX_L1_GAS_AMOUNT,
-    MAX_L1_GAS_PRICE, TEST_SEQUENCER_ADDRESS,
+    MAX_L1_GAS_PRICE, MINIMAL_CONTRACT_SIERRA_PATH, TEST_SEQUENCER_ADDRESS,
 };
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::constants;
@@ -61,8 +71,8 @@ use crate::transaction::errors::{
     TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
 };
 use crate::transaction::objects::{
-    FeeType, GasVector, HasRelatedFeeType, StarknetResources, TransactionExecutionInfo,
-    TransactionInfo, TransactionResources,
+    FeeType, GasVector, HasRelatedFeeType, L1HandlerFeeInfo, StarknetResources,
+    TransactionExecutionInfo, TransactionInfo, TransactionInfoCreator, TransactionResources,
 };
 use crate::transaction::test_utils::{
     account_invoke_tx, block_context, calculate_class_info_for_testing,
@@ -70,8 +80,12 @@ use crate::transaction::test_utils::{
     CALL_CONTRACT, GET_BLOCK_HASH, GET_BLOCK_NUMBER, GET_BLOCK_TIMESTAMP, GET_EXECUTION_INFO,
     GET_SEQUENCER_ADDRESS, INVALID, VALID,
 };
+use crate::transaction::transaction_execution::Transaction;
 use crate::transaction::transaction_types::TransactionType;
-use crate::transaction::transactions::{ExecutableTransaction, L1HandlerTransaction};
+use crate::transaction::transaction_utils::to_query_version;
+use crate::transaction::transactions::{
+    DeployAccountTransaction, ExecutableTransaction, L1HandlerTransaction, SystemTransaction,
+};
 use crate::versioned_constants::VersionedConstants;
 use crate::{
     check_transaction_execution_error_for_custom_hint,
@@ -208,6 +222,7 @@ fn expected_fee_transfer_call_info(
     let sequencer_balance_key_low = get_fee_token_var_address(expected_sequencer_address);
     let sequencer_balance_key_high = next_storage_key(&sequencer_balance_key_low)
         .expect("Cannot get sequencer balance high key.");
+    let resources: ExecutionResources = Prices::FeeTransfer(account_address, *fee_type).into();
     Some(CallInfo {
         call: expected_fee_transfer_call,
         execution: CallExecution {
@@ -215,7 +230,9 @@ fn expected_fee_transfer_call_info(
             events: vec![expected_fee_transfer_event],
             ..Default::default()
         },
-        resources: Prices::FeeTransfer(account_address, *fee_type).into(),
+        // The transfer has no inner calls, and its syscalls have no memory holes.
+        vm_memory_holes: resources.n_memory_holes,
+        resources,
         // We read sender and recipient balance - Uint256(BALANCE, 0) then Uint256(0, 0).
         storage_read_values: vec![
             stark_felt!(BALANCE),
@@ -229,6 +246,7 @@ fn expected_fee_transfer_call_info(
             sequencer_balance_key_low,
             sequencer_balance_key_high,
         ]),
+        syscall_counter: fee_transfer_syscall_counter(account_address, *fee_type),
         ..Default::default()
     })
 }
@@ -415,6 +433,14 @@ fn test_invoke_tx(
         ..expected_validate_call_info.as_ref().unwrap().call.clone()
     };
     let expected_return_result_retdata = Retdata(expected_return_result_calldata);
+    // The Cairo 1 account also validates its caller.
+    let expected_execute_syscall_counter = match account_cairo_version {
+        CairoVersion::Cairo0 => HashMap::from([(SyscallSelector::CallContract, 1)]),
+        CairoVersion::Cairo1 => HashMap::from([
+            (SyscallSelector::GetExecutionInfo, 1),
+            (SyscallSelector::CallContract, 1),
+        ]),
+    };
     let expected_execute_call_info = Some(CallInfo {
         call: expected_execute_call,
         execution: CallExecution {
@@ -427,8 +453,11 @@ fn test_invoke_tx(
             call: expected_return_result_call,
             execution: CallExecution::from_retdata(expected_return_result_retdata),
             resources: ExecutionResources { n_steps: 23, n_memory_holes: 0, ..Default::default() },
+            call_index: 1,
+            parent_call_index: Some(0),
             ..Default::default()
         }],
+        syscall_counter: expected_execute_syscall_counter,
         ..Default::default()
     });
 
@@ -462,9 +491,13 @@ fn test_invoke_tx(
         execute_call_info: expected_execute_call_info,
         fee_transfer_call_info: expected_fee_transfer_call_info,
         actual_fee: expected_actual_fee,
+        gas: GasVector::default(),
         da_gas,
         actual_resources: expected_actual_resources,
         revert_error: None,
+        reverted_effects: None,
+        l1_handler_fee_info: None,
+        metadata: None,
     };
 
     add_kzg_da_resources_to_resources_mapping(
@@ -473,9 +506,15 @@ fn test_invoke_tx(
         versioned_constants,
         use_kzg_da,
     );
+    expected_execution_info.gas = expected_execution_info
+        .actual_resources
+        .to_gas_vector(versioned_constants, use_kzg_da)
+        .unwrap();
 
     // Test execution info result.
     assert_eq!(actual_execution_info, expected_execution_info);
+    // L1 data gas is only charged for blob-DA state diffs.
+    assert_eq!(actual_execution_info.gas.l1_data_gas, da_gas.l1_data_gas);
 
     // Test nonce update.
     let nonce_from_state = state.get_nonce_at(sender_address).unwrap();
@@ -930,6 +969,84 @@ fn test_insufficient_resource_bounds(
     );
 }
 
+#[rstest]
+fn test_fee_floor(mut block_context: BlockContext) {
+    let account_contract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state = &mut test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(account_contract, 1), (test_contract, 1)],
+    );
+    let v1_tx_args = invoke_tx_args! {
+        sender_address: account_contract.get_instance_address(0),
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        max_fee: Fee(MAX_FEE),
+        version: TransactionVersion::ONE,
+    };
+    let v3_tx_args = invoke_tx_args! {
+        resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE),
+        version: TransactionVersion::THREE,
+        ..v1_tx_args.clone()
+    };
+    let committed_fee = Fee(u128::from(MAX_L1_GAS_AMOUNT) * MAX_L1_GAS_PRICE);
+
+    // Fee below the floor.
+    let min_fee = Fee(MAX_FEE + 1);
+    block_context.fee_policy_mut().fee_floor = FeeFloor { min_fee, ..Default::default() };
+    let execution_error = account_invoke_tx(v1_tx_args.clone())
+        .execute(state, &block_context, true, true)
+        .unwrap_err();
+    assert_matches!(
+        execution_error,
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::FeeBelowFloor { min_fee: floor, offered_fee }))
+        if floor == min_fee && offered_fee == Fee(MAX_FEE)
+    );
+    let min_fee = Fee(committed_fee.0 + 1);
+    block_context.fee_policy_mut().fee_floor = FeeFloor { min_fee, ..Default::default() };
+    let execution_error = account_invoke_tx(v3_tx_args.clone())
+        .execute(state, &block_context, true, true)
+        .unwrap_err();
+    assert_matches!(
+        execution_error,
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::FeeBelowFloor { min_fee: floor, offered_fee }))
+        if floor == min_fee && offered_fee == committed_fee
+    );
+
+    // L1 gas price below the floor.
+    let min_l1_gas_price = MAX_L1_GAS_PRICE + 1;
+    block_context.fee_policy_mut().fee_floor = FeeFloor { min_l1_gas_price, ..Default::default() };
+    let execution_error = account_invoke_tx(v3_tx_args.clone())
+        .execute(state, &block_context, true, true)
+        .unwrap_err();
+    assert_matches!(
+        execution_error,
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::L1GasPriceBelowFloor {
+                    min_l1_gas_price: floor, max_l1_gas_price }))
+        if floor == min_l1_gas_price && max_l1_gas_price == MAX_L1_GAS_PRICE
+    );
+
+    // Transactions priced at the floor are accepted.
+    block_context.fee_policy_mut().fee_floor =
+        FeeFloor { min_fee: Fee(MAX_FEE), ..Default::default() };
+    let tx_execution_info =
+        account_invoke_tx(v1_tx_args).execute(state, &block_context, true, true).unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    block_context.fee_policy_mut().fee_floor =
+        FeeFloor { min_fee: committed_fee, min_l1_gas_price: MAX_L1_GAS_PRICE };
+    let tx_execution_info =
+        account_invoke_tx(invoke_tx_args! { nonce: nonce!(1_u8), ..v3_tx_args })
+            .execute(state, &block_context, true, true)
+            .unwrap();
+    assert!(!tx_execution_info.is_reverted());
+}
+
 // TODO(Aner, 21/01/24) modify test for 4844.
 #[rstest]
 fn test_actual_fee_gt_resource_bounds(
@@ -957,7 +1074,7 @@ fn test_actual_fee_gt_resource_bounds(
     let invalid_tx = account_invoke_tx(invoke_tx_args! { max_fee: minimal_fee, ..invoke_tx_args });
 
     let execution_result = invalid_tx.execute(state, block_context, true, true).unwrap();
-    let execution_error = execution_result.revert_error.unwrap();
+    let execution_error = execution_result.revert_error.unwrap().to_string();
     // Test error.
     assert!(execution_error.starts_with("Insufficient max fee:"));
     // Test that fee was charged.
@@ -1163,8 +1280,12 @@ fn test_declare_tx(
         execute_call_info: None,
         fee_transfer_call_info: expected_fee_transfer_call_info,
         actual_fee: expected_actual_fee,
+        gas: GasVector::default(),
         da_gas,
         revert_error: None,
+        reverted_effects: None,
+        l1_handler_fee_info: None,
+        metadata: None,
         actual_resources: expected_actual_resources,
     };
 
@@ -1174,6 +1295,10 @@ fn test_declare_tx(
         versioned_constants,
         use_kzg_da,
     );
+    expected_execution_info.gas = expected_execution_info
+        .actual_resources
+        .to_gas_vector(versioned_constants, use_kzg_da)
+        .unwrap();
 
     // Test execution info result.
     assert_eq!(actual_execution_info, expected_execution_info);
@@ -1199,6 +1324,232 @@ fn test_declare_tx(
     assert_eq!(contract_class_from_state, class_info.contract_class());
 }
 
+#[cfg(feature = "batch_declare")]
+#[rstest]
+fn test_batch_declare_tx(block_context: BlockContext) {
+    use crate::compiled_class_hash;
+    use crate::transaction::transactions::{batch_declare_tx_hash, ClassDeclaration};
+
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let chain_info = &block_context.chain_info;
+    let state = &mut test_state(chain_info, BALANCE, &[(account, 1)]);
+    let sender_address = account.get_instance_address(0);
+    let class_declaration = |contract: FeatureContract, compiled_class_hash| ClassDeclaration {
+        class_hash: contract.get_class_hash(),
+        compiled_class_hash,
+        class_info: calculate_class_info_for_testing(contract.get_class()),
+    };
+    let batch_declare_tx = |main_contract: FeatureContract, nonce| {
+        let class_info = calculate_class_info_for_testing(main_contract.get_class());
+        let AccountTransaction::Declare(declare_tx) = declare_tx(
+            declare_tx_args! {
+                sender_address,
+                version: TransactionVersion::THREE,
+                resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE),
+                class_hash: main_contract.get_class_hash(),
+                compiled_class_hash: compiled_class_hash!(1_u8),
+                nonce,
+            },
+            class_info,
+        ) else {
+            panic!("Expected a declare transaction.");
+        };
+        declare_tx
+    };
+
+    // A class may not be declared twice in the same transaction.
+    let empty_contract = FeatureContract::Empty(CairoVersion::Cairo1);
+    let duplicate_class = class_declaration(empty_contract, compiled_class_hash!(2_u8));
+    let error = batch_declare_tx(empty_contract, nonce!(0_u8))
+        .with_additional_classes(vec![duplicate_class])
+        .unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::DuplicateDeclaredClass { class_hash }
+        if class_hash == empty_contract.get_class_hash()
+    );
+
+    // The additional classes are bound to the signed hash and passed to the account's validation.
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let additional_class = class_declaration(test_contract, compiled_class_hash!(2_u8));
+    let base_tx = batch_declare_tx(empty_contract, nonce!(0_u8));
+    let base_tx_hash = base_tx.tx_hash();
+    let tx = base_tx.with_additional_classes(vec![additional_class.clone()]).unwrap();
+    assert_eq!(tx.tx_hash(), batch_declare_tx_hash(base_tx_hash, &[additional_class.clone()]));
+    assert_ne!(tx.tx_hash(), base_tx_hash);
+    let tampered_class = class_declaration(test_contract, compiled_class_hash!(3_u8));
+    assert_ne!(tx.tx_hash(), batch_declare_tx_hash(base_tx_hash, &[tampered_class]));
+    assert_eq!(
+        tx.validate_calldata(),
+        calldata![
+            empty_contract.get_class_hash().0,
+            stark_felt!(1_u8),
+            test_contract.get_class_hash().0
+        ]
+    );
+
+    // Accounts validating only the main class hash reject batch declares.
+    let error = AccountTransaction::Declare(
+        batch_declare_tx(empty_contract, nonce!(0_u8))
+            .with_additional_classes(vec![additional_class.clone()])
+            .unwrap(),
+    )
+    .execute(state, &block_context, true, true)
+    .unwrap_err();
+    assert_matches!(error, TransactionExecutionError::ValidateTransactionError { .. });
+
+    // All classes are declared, and charged for, by a single transaction.
+    let main_class_code_size = tx.class_info.code_size();
+    assert_eq!(tx.code_size(), main_class_code_size + additional_class.class_info.code_size());
+    let tx_execution_info =
+        AccountTransaction::Declare(tx).execute(state, &block_context, true, false).unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    assert_eq!(
+        tx_execution_info.actual_resources.starknet_resources.state_changes_for_fee,
+        StateChangesCount {
+            n_compiled_class_hash_updates: 2,
+            ..declare_expected_state_changes_count(TransactionVersion::THREE)
+        }
+    );
+    for (class_hash, compiled_class_hash) in [
+        (empty_contract.get_class_hash(), compiled_class_hash!(1_u8)),
+        (test_contract.get_class_hash(), compiled_class_hash!(2_u8)),
+    ] {
+        assert_eq!(state.get_compiled_class_hash(class_hash).unwrap(), compiled_class_hash);
+    }
+
+    // The declaration is atomic; if any class is already declared, none are.
+    let faulty_account = FeatureContract::FaultyAccount(CairoVersion::Cairo1);
+    let tx = batch_declare_tx(faulty_account, nonce!(1_u8))
+        .with_additional_classes(vec![additional_class])
+        .unwrap();
+    let error = AccountTransaction::Declare(tx).execute(state, &block_context, true, false);
+    assert_matches!(
+        error.unwrap_err(),
+        TransactionExecutionError::DeclareTransactionError { class_hash }
+        if class_hash == test_contract.get_class_hash()
+    );
+    assert_matches!(
+        state.get_compiled_contract_class(faulty_account.get_class_hash()).unwrap_err(),
+        StateError::UndeclaredClassHash(_)
+    );
+}
+
+#[rstest]
+#[case::deprecated_declare(TransactionVersion::ONE, CairoVersion::Cairo0)]
+#[case::declare_v2(TransactionVersion::TWO, CairoVersion::Cairo1)]
+#[case::declare_v3(TransactionVersion::THREE, CairoVersion::Cairo1)]
+fn test_declare_sierra_compilation_check(
+    #[case] tx_version: TransactionVersion,
+    #[case] empty_contract_version: CairoVersion,
+) {
+    let empty_contract = FeatureContract::Empty(empty_contract_version);
+    let class_info = calculate_class_info_for_testing(empty_contract.get_class());
+    let AccountTransaction::Declare(declare_tx) = declare_tx(
+        declare_tx_args! { version: tx_version, class_hash: empty_contract.get_class_hash() },
+        class_info,
+    ) else {
+        panic!("Expected a declare transaction.");
+    };
+
+    // An empty Sierra program cannot be compiled.
+    let sierra_contract_class = serde_json::from_str(
+        r#"{
+            "sierra_program": [],
+            "contract_class_version": "0.1.0",
+            "entry_points_by_type": { "EXTERNAL": [], "L1_HANDLER": [], "CONSTRUCTOR": [] },
+            "abi": null
+        }"#,
+    )
+    .unwrap();
+    let error = declare_tx.verify_sierra_compilation(sierra_contract_class).unwrap_err();
+    if tx_version == TransactionVersion::ONE {
+        assert_matches!(
+            error,
+            TransactionExecutionError::InvalidVersion { version, .. } if version == tx_version
+        );
+    } else {
+        assert_matches!(
+            error,
+            TransactionExecutionError::SierraCompilationError { class_hash, .. } if
+            class_hash == empty_contract.get_class_hash()
+        );
+    }
+}
+
+#[rstest]
+fn test_declare_with_sierra_compilation_verification(
+    #[values(true, false)] matching_compiled_class_hash: bool,
+) {
+    let mut block_context = BlockContext::create_for_account_testing();
+    block_context.execution_policy_mut().verify_sierra_compilation = true;
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let state = &mut test_state(&block_context.chain_info, BALANCE, &[(account, 1)]);
+
+    let sierra_contract_class: SierraContractClass =
+        serde_json::from_str(&get_raw_contract_class(MINIMAL_CONTRACT_SIERRA_PATH)).unwrap();
+    let casm_contract_class =
+        CasmContractClass::from_contract_class(sierra_contract_class.clone(), false, usize::MAX)
+            .unwrap();
+    let recompiled_compiled_class_hash =
+        CompiledClassHash(felt_to_stark_felt(&casm_contract_class.compiled_class_hash()));
+    let compiled_class_hash = if matching_compiled_class_hash {
+        recompiled_compiled_class_hash
+    } else {
+        CompiledClassHash(stark_felt!(0x1234_u16))
+    };
+    let class_info = calculate_class_info_for_testing(ContractClass::V1(
+        ContractClassV1::try_from(casm_contract_class).unwrap(),
+    ));
+    let class_hash = class_hash!("0x1357");
+    let declare = || {
+        let AccountTransaction::Declare(declare_tx) = declare_tx(
+            declare_tx_args! {
+                sender_address: account.get_instance_address(0),
+                version: TransactionVersion::THREE,
+                resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE),
+                class_hash,
+                compiled_class_hash,
+            },
+            class_info.clone(),
+        ) else {
+            panic!("Expected a declare transaction.");
+        };
+        declare_tx
+    };
+
+    // Once verification is on, the Sierra class must be attached.
+    let error = AccountTransaction::Declare(declare()).execute(state, &block_context, true, true);
+    assert_matches!(
+        error.unwrap_err(),
+        TransactionExecutionError::MissingSierraContractClass { class_hash: missing_class_hash }
+        if missing_class_hash == class_hash
+    );
+
+    let account_tx =
+        AccountTransaction::Declare(declare().with_sierra_contract_class(sierra_contract_class));
+    let result = account_tx.execute(state, &block_context, true, true);
+    if matching_compiled_class_hash {
+        assert!(!result.unwrap().is_reverted());
+        assert_eq!(state.get_compiled_class_hash(class_hash).unwrap(), compiled_class_hash);
+    } else {
+        assert_matches!(
+            result.unwrap_err(),
+            TransactionExecutionError::CompiledClassHashMismatch {
+                class_hash: mismatched_class_hash,
+                declared_compiled_class_hash,
+                recompiled_compiled_class_hash: recompiled_hash,
+            } if mismatched_class_hash == class_hash
+                && declared_compiled_class_hash == compiled_class_hash
+                && recompiled_hash == recompiled_compiled_class_hash
+        );
+        assert_matches!(
+            state.get_compiled_contract_class(class_hash).unwrap_err(),
+            StateError::UndeclaredClassHash(_)
+        );
+    }
+}
+
 #[rstest]
 fn test_deploy_account_tx(
     #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
@@ -1304,8 +1655,12 @@ fn test_deploy_account_tx(
         execute_call_info: expected_execute_call_info,
         fee_transfer_call_info: expected_fee_transfer_call_info,
         actual_fee: expected_actual_fee,
+        gas: GasVector::default(),
         da_gas,
         revert_error: None,
+        reverted_effects: None,
+        l1_handler_fee_info: None,
+        metadata: None,
         actual_resources,
     };
 
@@ -1315,6 +1670,10 @@ fn test_deploy_account_tx(
         versioned_constants,
         use_kzg_da,
     );
+    expected_execution_info.gas = expected_execution_info
+        .actual_resources
+        .to_gas_vector(versioned_constants, use_kzg_da)
+        .unwrap();
 
     // Test execution info result.
     assert_eq!(actual_execution_info, expected_execution_info);
@@ -1623,6 +1982,189 @@ fn test_valid_flag(
     assert!(actual_execution_info.validate_call_info.is_none());
 }
 
+#[rstest]
+fn test_simulate(block_context: BlockContext) {
+    let block_context = &block_context;
+    let account_contract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state = &mut test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(account_contract, 1), (test_contract, 1)],
+    );
+    let sender_address = account_contract.get_instance_address(0);
+    let create_tx = || {
+        account_invoke_tx(invoke_tx_args! {
+            sender_address,
+            calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+            max_fee: Fee(MAX_FEE)
+        })
+    };
+
+    let simulation_info = create_tx().simulate(state, block_context, true, true).unwrap();
+
+    // The given state is not modified.
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), nonce!(0_u8));
+    assert_eq!(
+        simulation_info.state_diff.address_to_nonce.get(&sender_address),
+        Some(&nonce!(1_u8))
+    );
+
+    // Executing the transaction yields the simulated execution info.
+    let tx_execution_info = create_tx().execute(state, block_context, true, true).unwrap();
+    assert_eq!(simulation_info.tx_execution_info, tx_execution_info);
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), nonce!(1_u8));
+}
+
+#[rstest]
+fn test_simulate_with_execution_info_overrides(block_context: BlockContext) {
+    let account_contract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state = &mut test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(account_contract, 1), (test_contract, 1)],
+    );
+    let overridden_block_number = CURRENT_BLOCK_NUMBER + 1000;
+    let tx = account_invoke_tx(invoke_tx_args! {
+        sender_address: account_contract.get_instance_address(0),
+        calldata: create_calldata(
+            test_contract.get_instance_address(0),
+            "test_get_block_number",
+            &[stark_felt!(overridden_block_number)],
+        ),
+        max_fee: Fee(MAX_FEE),
+    });
+
+    // The called contract asserts it sees the overridden block number.
+    let simulation_info = tx.simulate(state, &block_context, true, true).unwrap();
+    assert!(simulation_info.tx_execution_info.is_reverted());
+
+    let mut simulation_block_context = block_context.clone();
+    simulation_block_context.execution_policy_mut().execution_info_overrides =
+        ExecutionInfoOverrides {
+            block_number: Some(BlockNumber(overridden_block_number)),
+            ..Default::default()
+        };
+    let simulation_info = tx.simulate(state, &simulation_block_context, true, true).unwrap();
+    assert!(!simulation_info.tx_execution_info.is_reverted());
+}
+
+#[rstest]
+fn test_transaction_from_api_with_signed_version(
+    block_context: BlockContext,
+    #[values(false, true)] only_query: bool,
+) {
+    let account_contract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state = &mut test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(account_contract, 1), (test_contract, 1)],
+    );
+    let invoke = invoke_tx(invoke_tx_args! {
+        sender_address: account_contract.get_instance_address(0),
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        max_fee: Fee(MAX_FEE),
+    });
+    let from_api = |signed_version| {
+        Transaction::from_api_with_signed_version(
+            StarknetApiTransaction::Invoke(invoke.tx.clone()),
+            invoke.tx_hash,
+            None,
+            None,
+            None,
+            signed_version,
+        )
+    };
+
+    // The version must match the transaction's, regardless of the query bit.
+    let error = from_api(TransactionVersion::THREE).unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::InvalidVersion { version, .. }
+        if version == TransactionVersion::THREE
+    );
+
+    let signed_version = match only_query {
+        true => to_query_version(TransactionVersion::ONE),
+        false => TransactionVersion::ONE,
+    };
+    let tx = from_api(signed_version).unwrap();
+    let tx_info = tx.create_tx_info();
+    assert_eq!(tx_info.only_query(), only_query);
+    assert_eq!(tx_info.version(), TransactionVersion::ONE);
+    assert_eq!(tx_info.signed_version(), signed_version);
+    let tx_execution_info = tx.execute(state, &block_context, true, true).unwrap();
+    assert!(!tx_execution_info.is_reverted());
+
+    // Deploy transactions are not supported.
+    let error = Transaction::from_api_with_signed_version(
+        StarknetApiTransaction::Deploy(DeployTransaction::default()),
+        invoke.tx_hash,
+        None,
+        None,
+        None,
+        signed_version,
+    )
+    .unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::UnsupportedTransactionType { tx_type } if tx_type == "Deploy"
+    );
+}
+
+#[rstest]
+fn test_counterfactual_deploy_account_estimation(
+    block_context: BlockContext,
+    #[values(TransactionVersion::ONE, TransactionVersion::THREE)] version: TransactionVersion,
+    #[values(true, false)] is_funded: bool,
+) {
+    let chain_info = &block_context.chain_info;
+    let account = FeatureContract::FaultyAccount(CairoVersion::Cairo0);
+    let state = &mut test_state(chain_info, BALANCE, &[(account, 0)]);
+    // The signature fails the account validation.
+    let deploy_account = deploy_account_tx(
+        deploy_account_tx_args! {
+            class_hash: account.get_class_hash(),
+            constructor_calldata: calldata![stark_felt!(constants::FELT_FALSE)],
+            signature: TransactionSignature(vec![stark_felt!(INVALID)]),
+            version,
+        },
+        &mut NonceManager::default(),
+    );
+    let contract_address = deploy_account.contract_address;
+    if is_funded {
+        fund_account(chain_info, contract_address, BALANCE, &mut state.state);
+    }
+
+    let counterfactual_deploy_account = DeployAccountTransaction::new_counterfactual(
+        deploy_account.tx.clone(),
+        deploy_account.tx_hash,
+    )
+    .unwrap();
+    assert_eq!(counterfactual_deploy_account.contract_address, contract_address);
+    assert!(counterfactual_deploy_account.only_query);
+
+    let estimation = counterfactual_deploy_account.estimate(state, &block_context).unwrap();
+    assert_eq!(estimation.contract_address, contract_address);
+    assert_eq!(estimation.is_funded, is_funded);
+    let tx_execution_info = &estimation.simulation_info.tx_execution_info;
+    assert!(tx_execution_info.validate_call_info.is_none());
+    assert!(tx_execution_info.fee_transfer_call_info.is_none());
+    assert!(tx_execution_info.actual_fee > Fee(0));
+    // The account is not deployed.
+    assert_eq!(state.get_class_hash_at(contract_address).unwrap(), ClassHash::default());
+
+    // The signature fails a validating execution.
+    assert_matches!(
+        AccountTransaction::DeployAccount(deploy_account)
+            .execute(state, &block_context, false, true)
+            .unwrap_err(),
+        TransactionExecutionError::ValidateTransactionError { .. }
+    );
+}
+
 // TODO(Noa,01/12/2023): Consider moving it to syscall_test.
 #[rstest]
 fn test_only_query_flag(block_context: BlockContext, #[values(true, false)] only_query: bool) {
@@ -1739,6 +2281,7 @@ fn test_l1_handler(#[values(false, true)] use_kzg_da: bool) {
             builtin_instance_counter: HashMap::from([(RANGE_CHECK_BUILTIN_NAME.to_string(), 6)]),
         },
         accessed_storage_keys: HashSet::from_iter(vec![accessed_storage_key]),
+        syscall_counter: HashMap::from([(SyscallSelector::StorageWrite, 1)]),
         ..Default::default()
     };
 
@@ -1746,7 +2289,7 @@ fn test_l1_handler(#[values(false, true)] use_kzg_da: bool) {
     // TODO(Nimrod, 1/5/2024): Change these hard coded values to match to the transaction resources
     // (currently matches only starknet resources).
     let expected_gas = match use_kzg_da {
-        true => GasVector { l1_gas: 16023, l1_data_gas: 128 },
+        true => GasVector { l1_gas: 16023, l1_data_gas: 128, ..Default::default() },
         false => GasVector::from_l1_gas(17675),
     };
     let expected_da_gas = match use_kzg_da {
@@ -1795,14 +2338,26 @@ fn test_l1_handler(#[values(false, true)] use_kzg_da: bool) {
             .to_gas_vector(versioned_constants, use_kzg_da)
     );
     // Build the expected execution info.
+    let expected_actual_fee =
+        calculate_tx_fee(&expected_tx_resources, block_context, &FeeType::Eth).unwrap();
+    let expected_total_gas =
+        expected_tx_resources.to_gas_vector(versioned_constants, use_kzg_da).unwrap();
     let expected_execution_info = TransactionExecutionInfo {
         validate_call_info: None,
         execute_call_info: Some(expected_call_info),
         fee_transfer_call_info: None,
         actual_fee: Fee(0),
+        gas: expected_total_gas,
         da_gas: expected_da_gas,
         actual_resources: expected_tx_resources,
         revert_error: None,
+        reverted_effects: None,
+        l1_handler_fee_info: Some(L1HandlerFeeInfo {
+            paid_fee_on_l1: Fee(1),
+            actual_fee: expected_actual_fee,
+            refundable_fee: Fee(0),
+        }),
+        metadata: None,
     };
 
     // Check the actual returned execution info.
@@ -1828,15 +2383,186 @@ fn test_l1_handler(#[values(false, true)] use_kzg_da: bool) {
     let tx_no_fee = L1HandlerTransaction::create_for_testing(Fee(0), contract_address);
     let error = tx_no_fee.execute(state, block_context, true, true).unwrap_err();
     // Today, we check that the paid_fee is positive, no matter what was the actual fee.
-    let expected_actual_fee =
-        calculate_tx_fee(&expected_execution_info.actual_resources, block_context, &FeeType::Eth)
-            .unwrap();
     assert_matches!(
         error,
         TransactionExecutionError::TransactionFeeError(
             TransactionFeeError::InsufficientL1Fee { paid_fee, actual_fee, })
             if paid_fee == Fee(0) && actual_fee == expected_actual_fee
     );
+
+    // Overpaid fee on L1 is reported as refundable.
+    let overpaid_fee = Fee(expected_actual_fee.0 + 10);
+    let tx_overpaid = L1HandlerTransaction::create_for_testing(overpaid_fee, contract_address);
+    let execution_info = tx_overpaid.execute(state, block_context, true, true).unwrap();
+    assert_eq!(
+        execution_info.l1_handler_fee_info,
+        Some(L1HandlerFeeInfo {
+            paid_fee_on_l1: overpaid_fee,
+            actual_fee: expected_actual_fee,
+            refundable_fee: Fee(10),
+        })
+    );
+}
+
+#[rstest]
+fn test_system_transaction(mut block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let chain_info = block_context.chain_info.clone();
+    let state = &mut test_state(&chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let sender_address = account.get_instance_address(0);
+    let system_tx = SystemTransaction::new(invoke_tx(invoke_tx_args! {
+        sender_address,
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        resource_bounds: l1_resource_bounds(0, 0),
+        version: TransactionVersion::THREE,
+    }));
+
+    // Only the operator may send system transactions.
+    assert_matches!(
+        system_tx.execute(state, &block_context, true, true).unwrap_err(),
+        TransactionExecutionError::UnauthorizedSystemTransaction { sender_address: address }
+        if address == sender_address
+    );
+
+    block_context.execution_policy_mut().system_tx_config =
+        SystemTransactionConfig { operator_address: Some(sender_address), ..Default::default() };
+    let tx_execution_info = system_tx.execute(state, &block_context, true, true).unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    assert!(tx_execution_info.execute_call_info.is_some());
+    assert_eq!(tx_execution_info.actual_fee, Fee(0));
+    assert!(tx_execution_info.fee_transfer_call_info.is_none());
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), nonce!(1_u8));
+    let fee_token_address = chain_info.fee_token_address(&FeeType::Strk);
+    assert_eq!(
+        state.get_fee_token_balance(sender_address, fee_token_address).unwrap(),
+        (stark_felt!(BALANCE), StarkFelt::ZERO)
+    );
+}
+
+#[rstest]
+fn test_fee_exemptions(mut block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    // Only the Cairo 1 test contract has an L1 handler.
+    let l1_handler_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = block_context.chain_info.clone();
+    let state = &mut test_state(
+        &chain_info,
+        BALANCE,
+        &[(account, 1), (test_contract, 1), (l1_handler_contract, 1)],
+    );
+    let sender_address = account.get_instance_address(0);
+    let contract_address = l1_handler_contract.get_instance_address(0);
+    let tx_args = invoke_tx_args! {
+        sender_address,
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        resource_bounds: l1_resource_bounds(1, MAX_L1_GAS_PRICE),
+        version: TransactionVersion::THREE,
+    };
+    let l1_handler_tx = L1HandlerTransaction::create_for_testing(Fee(0), contract_address);
+
+    // Without exemptions, the fee bounds are checked, and L1 handlers must pay a fee on L1.
+    assert_matches!(
+        account_invoke_tx(tx_args.clone()).execute(state, &block_context, true, true).unwrap_err(),
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::MaxL1GasAmountTooLow { .. }
+            )
+        )
+    );
+    assert_matches!(
+        l1_handler_tx.execute(state, &block_context, true, true).unwrap_err(),
+        TransactionExecutionError::TransactionFeeError(
+            TransactionFeeError::InsufficientL1Fee { .. }
+        )
+    );
+
+    block_context.fee_policy_mut().fee_exemptions = FeeExemptions {
+        addresses: HashSet::from([sender_address, contract_address]),
+        ..Default::default()
+    };
+    let tx_execution_info =
+        account_invoke_tx(tx_args).execute(state, &block_context, true, true).unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    assert_eq!(tx_execution_info.actual_fee, Fee(0));
+    assert!(tx_execution_info.fee_transfer_call_info.is_none());
+    let fee_token_address = chain_info.fee_token_address(&FeeType::Strk);
+    assert_eq!(
+        state.get_fee_token_balance(sender_address, fee_token_address).unwrap(),
+        (stark_felt!(BALANCE), StarkFelt::ZERO)
+    );
+    assert!(l1_handler_tx.execute(state, &block_context, true, true).is_ok());
+
+    // All L1 handlers may be exempt.
+    block_context.fee_policy_mut().fee_exemptions =
+        FeeExemptions { l1_handlers: true, ..Default::default() };
+    assert!(l1_handler_tx.execute(state, &block_context, true, true).is_ok());
+}
+
+#[rstest]
+fn test_fee_sponsorship(mut block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let chain_info = block_context.chain_info.clone();
+    let state = &mut test_state(&chain_info, BALANCE, &[(account, 2), (test_contract, 1)]);
+    let (sponsored_address, sponsor_address) =
+        (account.get_instance_address(0), account.get_instance_address(1));
+    let sequencer_address = block_context.block_info.sequencer_address;
+    let fee_token_address = chain_info.fee_token_address(&FeeType::Eth);
+    // The sponsored account does not hold the fee token.
+    state
+        .set_storage_at(
+            fee_token_address,
+            get_fee_token_var_address(sponsored_address),
+            StarkFelt::ZERO,
+        )
+        .unwrap();
+    block_context.fee_policy_mut().fee_payment_mode = FeePaymentMode::TransferFrom {
+        sponsors: HashMap::from([(sponsored_address, sponsor_address)]),
+    };
+    let tx_args = invoke_tx_args! {
+        sender_address: sponsored_address,
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        max_fee: Fee(MAX_FEE),
+    };
+
+    // The sponsor must grant the fee recipient an allowance covering the max fee.
+    assert_matches!(
+        account_invoke_tx(tx_args.clone()).execute(state, &block_context, true, true).unwrap_err(),
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::FeeExceedsAllowance { sponsor, .. }
+            )
+        ) if sponsor == sponsor_address
+    );
+
+    let allowance_key = get_fee_token_allowance_var_address(sponsor_address, sequencer_address);
+    state.set_storage_at(fee_token_address, allowance_key, stark_felt!(MAX_FEE)).unwrap();
+    let tx_execution_info =
+        account_invoke_tx(tx_args).execute(state, &block_context, true, true).unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    let actual_fee = tx_execution_info.actual_fee.0;
+    assert_eq!(
+        tx_execution_info.fee_transfer_call_info.unwrap().call.entry_point_selector,
+        selector_from_name(constants::TRANSFER_FROM_ENTRY_POINT_NAME)
+    );
+
+    // The fee is transferred from the sponsor, against its allowance.
+    assert_eq!(
+        state.get_fee_token_balance(sponsor_address, fee_token_address).unwrap(),
+        (stark_felt!(BALANCE - actual_fee), StarkFelt::ZERO)
+    );
+    assert_eq!(
+        state.get_fee_token_balance(sponsored_address, fee_token_address).unwrap(),
+        (StarkFelt::ZERO, StarkFelt::ZERO)
+    );
+    assert_eq!(
+        state
+            .get_fee_token_allowance(sponsor_address, sequencer_address, fee_token_address)
+            .unwrap(),
+        (stark_felt!(MAX_FEE - actual_fee), StarkFelt::ZERO)
+    );
 }
 
 #[rstest]
@@ -1864,6 +2590,7 @@ fn test_execute_tx_with_invalid_transaction_version(block_context: BlockContext)
         execution_info
             .revert_error
             .unwrap()
+            .to_string()
             .contains(format!("ASSERT_EQ instruction failed: {} != 1.", invalid_version).as_str())
     );
 }
@@ -1965,11 +2692,20 @@ fn test_emit_event_exceeds_limit(
     let execution_info = account_tx.execute(state, block_context, true, true).unwrap();
     match &expected_error {
         Some(expected_error) => {
-            let error_string = execution_info.revert_error.unwrap();
+            let error_string = execution_info.revert_error.unwrap().to_string();
             assert!(error_string.contains(&format!("{}", expected_error)));
+
+            // Events emitted before the limit was exceeded are reported as reverted.
+            let reverted_events = execution_info.reverted_effects.unwrap().events;
+            let expected_n_reverted_events =
+                if n_emitted_events > max_n_emitted_events() { max_n_emitted_events() } else { 0 };
+            assert_eq!(reverted_events.len(), expected_n_reverted_events);
+            assert!(reverted_events.iter().all(|reverted_event| reverted_event.from_address
+                == test_contract.get_instance_address(0)));
         }
         None => {
             assert!(!execution_info.is_reverted());
+            assert_eq!(execution_info.reverted_effects, None);
         }
     }
 }
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use std::num::NonZeroU128;
 
+use starknet_api::hash::StarkFelt;
+
 use crate::transaction::errors::NumericConversionError;
 
 #[cfg(test)]
@@ -61,6 +63,11 @@ pub fn u128_from_usize(val: usize) -> u128 {
     val.try_into().expect("Conversion from usize to u128 should not fail.")
 }
 
+/// Conversion from usize to a felt; e.g., for encoding lengths.
+pub fn usize_to_felt(val: usize) -> StarkFelt {
+    StarkFelt::from(u128_from_usize(val))
+}
+
 /// Returns the ceiling of the division of two u128 numbers.
 pub fn u128_div_ceil(a: u128, b: NonZeroU128) -> u128 {
     let mut result = a / b;
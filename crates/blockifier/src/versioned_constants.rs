@@ -9,11 +9,12 @@ use indexmap::{IndexMap, IndexSet};
 use num_rational::Ratio;
 use once_cell::sync::Lazy;
 use serde::de::Error as DeserializationError;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::{Map, Number, Value};
 use strum::IntoEnumIterator;
 use thiserror::Error;
 
+use crate::abi::constants;
 use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
 use crate::execution::errors::PostExecutionError;
 use crate::execution::execution_utils::poseidon_hash_many_cost;
@@ -39,15 +40,27 @@ pub type ResourceCost = Ratio<u128>;
 /// Additional constants in the JSON file, not used by Blockifier but included for transparency, are
 /// automatically ignored during deserialization.
 #[derive(Clone, Debug, Default, Deserialize)]
+// Serde trick for adding validations via a customr deserializer, without forgoing the derive.
+// See: https://github.com/serde-rs/serde/issues/1220.
+#[serde(remote = "Self")]
 pub struct VersionedConstants {
     // Limits.
     #[serde(default = "EventLimits::max")]
     pub tx_event_limits: EventLimits,
+    #[serde(default)]
+    pub entry_point_limits: EntryPointLimits,
     pub invoke_tx_max_n_steps: u32,
     #[serde(default)]
     pub l2_resource_gas_costs: L2ResourceGasCosts,
     pub max_recursion_depth: usize,
     pub validate_max_n_steps: u32,
+    // Whether the steps of the segment arena usage of the validation are deducted from the
+    // execution step budget; absent from the constants of versions preceding this charge.
+    #[serde(default)]
+    pub charge_validate_segment_arena_steps: bool,
+    // Syscalls that may not be invoked in validate mode.
+    #[serde(default = "default_validate_forbidden_syscalls")]
+    pub validate_forbidden_syscalls: HashSet<SyscallSelector>,
 
     // Cairo OS constants.
     // Note: if loaded from a json file, there are some assumptions made on its structure.
@@ -57,6 +70,11 @@ pub struct VersionedConstants {
     // Resources.
     os_resources: Arc<OsResources>,
 
+    // Sierra-to-CASM compilation; [None] for versions whose compiler is not pinned, in which case
+    // classes may not be compiled by the Blockifier.
+    #[serde(default)]
+    pub sierra_compiler: Option<SierraCompilerConstants>,
+
     // Fee related.
     // TODO: Consider making this a struct, this will require change the way we access these
     // values.
@@ -80,6 +98,61 @@ impl VersionedConstants {
         &self.vm_resource_fee_cost
     }
 
+    /// Returns the constants with the fee costs of the given builtins overridden.
+    /// Only builtins priced by these constants may be overridden; the cost of a Cairo step is not
+    /// a builtin cost, and may not be overridden.
+    pub fn with_builtin_gas_cost_overrides(
+        &self,
+        overrides: &HashMap<String, ResourceCost>,
+    ) -> Result<Self, VersionedConstantsError> {
+        let mut vm_resource_fee_cost = self.vm_resource_fee_cost.as_ref().clone();
+        for (builtin_name, cost) in overrides {
+            match vm_resource_fee_cost.get_mut(builtin_name) {
+                Some(base_cost) if builtin_name != constants::N_STEPS_RESOURCE => {
+                    *base_cost = *cost;
+                }
+                _ => {
+                    return Err(VersionedConstantsError::UnknownBuiltin(builtin_name.clone()));
+                }
+            }
+        }
+
+        Ok(Self { vm_resource_fee_cost: Arc::new(vm_resource_fee_cost), ..self.clone() })
+    }
+
+    /// Returns the constants with the given gas costs (e.g., of syscalls) overridden, by their
+    /// names in [`GasCosts`]. The overrides apply to the final costs; e.g., overriding the cost of
+    /// a step does not affect the syscall costs derived from it.
+    pub fn with_gas_cost_overrides(
+        &self,
+        overrides: &HashMap<String, u64>,
+    ) -> Result<Self, VersionedConstantsError> {
+        let mut gas_costs = serde_json::to_value(&self.os_constants.gas_costs)?;
+        let gas_costs_by_name =
+            gas_costs.as_object_mut().expect("Gas costs must be serialized into a JSON object.");
+        for (gas_cost_name, cost) in overrides {
+            let Some(gas_cost) = gas_costs_by_name.get_mut(gas_cost_name) else {
+                return Err(VersionedConstantsError::UnknownGasCost(gas_cost_name.clone()));
+            };
+            *gas_cost = Value::from(*cost);
+        }
+
+        let os_constants = OsConstants {
+            gas_costs: serde_json::from_value(gas_costs)?,
+            ..self.os_constants.as_ref().clone()
+        };
+        Ok(Self { os_constants: Arc::new(os_constants), ..self.clone() })
+    }
+
+    /// Converts an L1 gas price to an L2 (Sierra) gas price, such that a Cairo step costs the same
+    /// in both; rounded up.
+    pub fn convert_l1_to_l2_gas_price_round_up(&self, l1_gas_price: u128) -> u128 {
+        let l1_gas_per_step =
+            self.vm_resource_fee_cost.get(constants::N_STEPS_RESOURCE).copied().unwrap_or_default();
+        let l2_gas_per_step = u128::from(self.os_constants.gas_costs.step_gas_cost);
+        (l1_gas_per_step * l1_gas_price / l2_gas_per_step).ceil().to_integer()
+    }
+
     pub fn os_resources_for_tx_type(
         &self,
         tx_type: &TransactionType,
@@ -202,6 +275,44 @@ impl VersionedConstants {
     ) -> Self {
         Self { validate_max_n_steps, max_recursion_depth, ..Self::latest_constants().clone() }
     }
+
+    pub fn validate<'de, D: Deserializer<'de>>(
+        &self,
+    ) -> Result<(), <D as Deserializer<'de>>::Error> {
+        let gas_costs = &self.os_constants.gas_costs;
+        for syscall_handler in SyscallSelector::iter() {
+            // Syscalls introduced in later versions are not priced by the constants of earlier
+            // versions, and are rejected when invoked under them.
+            let is_supported = match syscall_handler {
+                SyscallSelector::GetClassHashAt => gas_costs.get_class_hash_at_gas_cost.is_some(),
+                _ => true,
+            };
+            if is_supported && !self.os_resources.execute_syscalls.contains_key(&syscall_handler) {
+                return Err(DeserializationError::custom(format!(
+                    "ValidationError: os_resources.execute_syscalls are missing syscall handler: \
+                     {syscall_handler:?}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionedConstants {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let versioned_constants = Self::deserialize(deserializer)?;
+
+        // Validations.
+
+        #[cfg(not(test))]
+        versioned_constants.validate::<D>()?;
+
+        Ok(versioned_constants)
+    }
 }
 
 impl TryFrom<&Path> for VersionedConstants {
@@ -229,6 +340,34 @@ pub struct EventLimits {
     pub max_data_length: usize,
     pub max_keys_length: usize,
     pub max_n_emitted_events: usize,
+    /// The maximal total size, in bytes, of the keys and data of the events emitted by a
+    /// transaction, where every felt accounts for 32 bytes.
+    #[serde(default = "default_max_total_payload_size")]
+    pub max_total_payload_size: usize,
+}
+
+/// Limits on the return data of every entry point call; [None] limits are not enforced.
+/// The calldata of entry point calls is limited by the size limits of the block context.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct EntryPointLimits {
+    pub max_retdata_length: Option<usize>,
+}
+
+/// The compiler that Sierra classes are compiled to CASM by.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct SierraCompilerConstants {
+    /// The version of the compiler; must match that of the compiler linked into the Blockifier.
+    pub compiler_version: String,
+    /// The maximal size of the compiled bytecode, in felts.
+    pub max_bytecode_size: usize,
+}
+
+fn default_max_total_payload_size() -> usize {
+    usize::MAX
+}
+
+fn default_validate_forbidden_syscalls() -> HashSet<SyscallSelector> {
+    HashSet::from([SyscallSelector::GetBlockHash, SyscallSelector::GetSequencerAddress])
 }
 
 impl EventLimits {
@@ -237,6 +376,7 @@ impl EventLimits {
             max_data_length: usize::MAX,
             max_keys_length: usize::MAX,
             max_n_emitted_events: usize::MAX,
+            max_total_payload_size: usize::MAX,
         }
     }
 }
@@ -275,15 +415,6 @@ impl OsResources {
             }
         }
 
-        for syscall_handler in SyscallSelector::iter() {
-            if !self.execute_syscalls.contains_key(&syscall_handler) {
-                return Err(DeserializationError::custom(format!(
-                    "ValidationError: os_resources.execute_syscalls are missing syscall handler: \
-                     {syscall_handler:?}"
-                )));
-            }
-        }
-
         let known_builtin_names: HashSet<&str> = HashSet::from([
             builtin_runner::OUTPUT_BUILTIN_NAME,
             builtin_runner::HASH_BUILTIN_NAME,
@@ -397,7 +528,7 @@ impl<'de> Deserialize<'de> for OsResources {
 }
 
 /// Gas cost constants. For more documentation see in core/os/constants.cairo.
-#[derive(Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct GasCosts {
     pub step_gas_cost: u64,
     pub range_check_gas_cost: u64,
@@ -416,6 +547,9 @@ pub struct GasCosts {
     pub call_contract_gas_cost: u64,
     pub deploy_gas_cost: u64,
     pub get_block_hash_gas_cost: u64,
+    // Absent from the constants of versions preceding the syscall.
+    #[serde(default)]
+    pub get_class_hash_at_gas_cost: Option<u64>,
     pub get_execution_info_gas_cost: u64,
     pub library_call_gas_cost: u64,
     pub replace_class_gas_cost: u64,
@@ -442,7 +576,7 @@ pub struct GasCosts {
 // conversion into actual values.
 // TODO: consider encoding the * and + operations inside the json file, instead of hardcoded below
 // in the `try_from`.
-#[derive(Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize)]
 #[serde(try_from = "OsConstantsRawJson")]
 pub struct OsConstants {
     pub gas_costs: GasCosts,
@@ -599,6 +733,10 @@ pub enum VersionedConstantsError {
     IoError(#[from] io::Error),
     #[error("JSON file cannot be serialized into VersionedConstants: {0}")]
     ParseError(#[from] serde_json::Error),
+    #[error("Cannot override the cost of {0}: not a builtin priced by the versioned constants.")]
+    UnknownBuiltin(String),
+    #[error("Cannot override the gas cost {0}: not a gas cost of the versioned constants.")]
+    UnknownGasCost(String),
 }
 
 #[derive(Debug, Error)]
@@ -97,7 +97,14 @@ fn test_default_values() {
     assert_eq!(versioned_constants.get_validate_timestamp_rounding(), 1);
 
     assert_eq!(versioned_constants.tx_event_limits, EventLimits::max());
+    assert_eq!(versioned_constants.entry_point_limits, EntryPointLimits::default());
+    assert_eq!(
+        versioned_constants.validate_forbidden_syscalls,
+        default_validate_forbidden_syscalls()
+    );
     assert_eq!(versioned_constants.l2_resource_gas_costs, L2ResourceGasCosts::default());
+    assert_eq!(versioned_constants.sierra_compiler, None);
+    assert!(!versioned_constants.charge_validate_segment_arena_steps);
 
     // Calldata factor was initialized as 0, and did not affect the expected result, even if
     // calldata length is nonzero.
@@ -201,3 +208,75 @@ fn test_old_json_parsing() {
             .unwrap_or_else(|_| panic!("Versioned constants JSON file {file:#?} is malformed"));
     }
 }
+
+#[test]
+fn test_syscall_resources_validation() {
+    let without_get_class_hash_at_resources = |versioned_constants: &VersionedConstants| {
+        let mut versioned_constants = versioned_constants.clone();
+        Arc::make_mut(&mut versioned_constants.os_resources)
+            .execute_syscalls
+            .remove(&SyscallSelector::GetClassHashAt);
+        versioned_constants
+    };
+
+    // The latest constants price the syscall, and must give its resources.
+    let latest_constants = VersionedConstants::latest_constants();
+    assert!(latest_constants.os_constants.gas_costs.get_class_hash_at_gas_cost.is_some());
+    latest_constants.validate::<Value>().unwrap();
+    let error =
+        without_get_class_hash_at_resources(latest_constants).validate::<Value>().unwrap_err();
+    assert!(error.to_string().contains("missing syscall handler: GetClassHashAt"));
+
+    // Constants of versions preceding the syscall do not price it.
+    let path = format!("{}/resources/versioned_constants_13_1.json", env!("CARGO_MANIFEST_DIR"));
+    let old_constants = VersionedConstants::try_from(Path::new(&path)).unwrap();
+    assert_eq!(old_constants.os_constants.gas_costs.get_class_hash_at_gas_cost, None);
+    without_get_class_hash_at_resources(&old_constants).validate::<Value>().unwrap();
+}
+
+#[test]
+fn test_builtin_gas_cost_overrides() {
+    let base_constants = VersionedConstants::create_for_account_testing();
+    let overridden_cost = ResourceCost::new(3, 2);
+    let overridden_constants = base_constants
+        .with_builtin_gas_cost_overrides(&HashMap::from([(
+            builtin_runner::POSEIDON_BUILTIN_NAME.to_string(),
+            overridden_cost,
+        )]))
+        .unwrap();
+
+    // Only the overridden cost changes.
+    let mut expected_costs = base_constants.vm_resource_fee_cost().clone();
+    expected_costs.insert(builtin_runner::POSEIDON_BUILTIN_NAME.to_string(), overridden_cost);
+    assert_eq!(overridden_constants.vm_resource_fee_cost(), &expected_costs);
+
+    // Unknown builtins and the cost of a Cairo step cannot be overridden.
+    for resource_name in ["unknown_builtin", constants::N_STEPS_RESOURCE] {
+        let overrides = HashMap::from([(resource_name.to_string(), overridden_cost)]);
+        assert!(matches!(
+            base_constants.with_builtin_gas_cost_overrides(&overrides),
+            Err(VersionedConstantsError::UnknownBuiltin(name)) if name == resource_name
+        ));
+    }
+}
+
+#[test]
+fn test_gas_cost_overrides() {
+    let base_constants = VersionedConstants::create_for_testing();
+    let overridden_constants = base_constants
+        .with_gas_cost_overrides(&HashMap::from([("storage_read_gas_cost".to_string(), 7)]))
+        .unwrap();
+
+    // Only the overridden cost changes.
+    let (base_gas_costs, overridden_gas_costs) =
+        (&base_constants.os_constants.gas_costs, &overridden_constants.os_constants.gas_costs);
+    assert_eq!(overridden_gas_costs.storage_read_gas_cost, 7);
+    assert_eq!(overridden_gas_costs.storage_write_gas_cost, base_gas_costs.storage_write_gas_cost);
+    assert_eq!(overridden_gas_costs.step_gas_cost, base_gas_costs.step_gas_cost);
+
+    let overrides = HashMap::from([("unknown_gas_cost".to_string(), 7)]);
+    assert!(matches!(
+        base_constants.with_gas_cost_overrides(&overrides),
+        Err(VersionedConstantsError::UnknownGasCost(name)) if name == "unknown_gas_cost"
+    ));
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::num::NonZeroU128;
 
 use blockifier::blockifier::block::{
     pre_process_block as pre_process_block_blockifier, BlockInfo, BlockNumberHashPair, GasPrices,
@@ -53,6 +54,7 @@ pub(crate) struct ThinTransactionExecutionInfo {
     pub execute_call_info: Option<CallInfo>,
     pub fee_transfer_call_info: Option<CallInfo>,
     pub actual_fee: Fee,
+    pub gas: GasVector,
     pub da_gas: GasVector,
     pub actual_resources: ResourcesMapping,
     pub revert_error: Option<String>,
@@ -68,13 +70,14 @@ impl ThinTransactionExecutionInfo {
             execute_call_info: tx_execution_info.execute_call_info,
             fee_transfer_call_info: tx_execution_info.fee_transfer_call_info,
             actual_fee: tx_execution_info.actual_fee,
+            gas: tx_execution_info.gas,
             da_gas: tx_execution_info.da_gas,
             actual_resources: tx_execution_info.actual_resources.to_resources_mapping(
                 block_context.versioned_constants(),
                 block_context.block_info().use_kzg_da,
                 true,
             ),
-            revert_error: tx_execution_info.revert_error,
+            revert_error: tx_execution_info.revert_error.map(|error| error.to_string()),
         }
     }
 }
@@ -388,6 +391,7 @@ impl PyBlockExecutor {
                     state_diff_size: max_state_diff_size,
                     ..BouncerWeights::max(true)
                 },
+                min_tx_weights: BouncerWeights::default(),
             },
             tx_executor_config: TransactionExecutorConfig {
                 concurrency_config: concurrency_config.into(),
@@ -463,6 +467,8 @@ impl TryFrom<PyOsConfig> for ChainInfo {
                     py_os_config.fee_token_address.0,
                 )?,
             },
+            allowed_custom_hints: Default::default(),
+            allow_deploy_from_zero: true,
         })
     }
 }
@@ -482,25 +488,29 @@ pub fn into_block_context_args(
     block_info: &PyBlockInfo,
 ) -> NativeBlockifierResult<(BlockInfo, ChainInfo)> {
     let chain_info: ChainInfo = general_config.starknet_os_config.clone().try_into()?;
+    let eth_l1_gas_price: NonZeroU128 =
+        block_info.l1_gas_price.price_in_wei.try_into().map_err(|_| {
+            NativeBlockifierInputError::InvalidNativeBlockifierInputError(
+                InvalidNativeBlockifierInputError::InvalidGasPriceWei(
+                    block_info.l1_gas_price.price_in_wei,
+                ),
+            )
+        })?;
+    let strk_l1_gas_price: NonZeroU128 =
+        block_info.l1_gas_price.price_in_fri.try_into().map_err(|_| {
+            NativeBlockifierInputError::InvalidNativeBlockifierInputError(
+                InvalidNativeBlockifierInputError::InvalidGasPriceFri(
+                    block_info.l1_gas_price.price_in_fri,
+                ),
+            )
+        })?;
     let block_info = BlockInfo {
         block_number: BlockNumber(block_info.block_number),
         block_timestamp: BlockTimestamp(block_info.block_timestamp),
         sequencer_address: ContractAddress::try_from(block_info.sequencer_address.0)?,
         gas_prices: GasPrices {
-            eth_l1_gas_price: block_info.l1_gas_price.price_in_wei.try_into().map_err(|_| {
-                NativeBlockifierInputError::InvalidNativeBlockifierInputError(
-                    InvalidNativeBlockifierInputError::InvalidGasPriceWei(
-                        block_info.l1_gas_price.price_in_wei,
-                    ),
-                )
-            })?,
-            strk_l1_gas_price: block_info.l1_gas_price.price_in_fri.try_into().map_err(|_| {
-                NativeBlockifierInputError::InvalidNativeBlockifierInputError(
-                    InvalidNativeBlockifierInputError::InvalidGasPriceFri(
-                        block_info.l1_gas_price.price_in_fri,
-                    ),
-                )
-            })?,
+            eth_l1_gas_price,
+            strk_l1_gas_price,
             eth_l1_data_gas_price: block_info.l1_data_gas_price.price_in_wei.try_into().map_err(
                 |_| {
                     NativeBlockifierInputError::InvalidNativeBlockifierInputError(
@@ -519,6 +529,8 @@ pub fn into_block_context_args(
                     )
                 },
             )?,
+            eth_l2_gas_price: l2_gas_price(eth_l1_gas_price),
+            strk_l2_gas_price: l2_gas_price(strk_l1_gas_price),
         },
         use_kzg_da: block_info.use_kzg_da,
     };
@@ -526,6 +538,13 @@ pub fn into_block_context_args(
     Ok((block_info, chain_info))
 }
 
+/// Derives the L2 gas price from the L1 gas price, as the former is not provided by Python.
+fn l2_gas_price(l1_gas_price: NonZeroU128) -> NonZeroU128 {
+    let l2_gas_price = VersionedConstants::latest_constants()
+        .convert_l1_to_l2_gas_price_round_up(l1_gas_price.into());
+    NonZeroU128::new(l2_gas_price).unwrap_or(NonZeroU128::MIN)
+}
+
 // Executes block pre-processing; see `blockifier::blockifier::block::pre_process_block`
 // documentation.
 fn pre_process_block(
@@ -558,6 +577,7 @@ fn pre_process_block(
         chain_info,
         versioned_constants.clone(),
         concurrency_mode,
+        None,
     )?;
 
     Ok(block_context)
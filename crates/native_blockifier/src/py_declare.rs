@@ -11,7 +11,9 @@ use starknet_api::transaction::{
 };
 
 use crate::errors::{NativeBlockifierInputError, NativeBlockifierResult};
-use crate::py_transaction::{PyClassInfo, PyDataAvailabilityMode, PyResourceBoundsMapping};
+use crate::py_transaction::{
+    get_py_tx_version, PyClassInfo, PyDataAvailabilityMode, PyResourceBoundsMapping,
+};
 use crate::py_utils::{from_py_felts, py_attr, PyFelt};
 
 #[derive(FromPyObject)]
@@ -102,7 +104,7 @@ pub fn py_declare(
     py_tx: &PyAny,
     py_class_info: PyClassInfo,
 ) -> NativeBlockifierResult<DeclareTransaction> {
-    let version = usize::try_from(py_attr::<PyFelt>(py_tx, "version")?.0)?;
+    let (version, only_query) = get_py_tx_version(py_tx)?;
     let tx = match version {
         0 => {
             let py_declare_tx: PyDeclareTransactionV0V1 = py_tx.extract()?;
@@ -131,5 +133,9 @@ pub fn py_declare(
     }?;
     let tx_hash = TransactionHash(py_attr::<PyFelt>(py_tx, "hash_value")?.0);
     let class_info = PyClassInfo::try_from(py_class_info, &tx)?;
-    Ok(DeclareTransaction::new(tx, tx_hash, class_info)?)
+    let declare_tx = match only_query {
+        true => DeclareTransaction::new_for_query(tx, tx_hash, class_info),
+        false => DeclareTransaction::new(tx, tx_hash, class_info),
+    };
+    Ok(declare_tx?)
 }
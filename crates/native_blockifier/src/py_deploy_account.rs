@@ -11,7 +11,7 @@ use starknet_api::transaction::{
 };
 
 use crate::errors::{NativeBlockifierInputError, NativeBlockifierResult};
-use crate::py_transaction::{PyDataAvailabilityMode, PyResourceBoundsMapping};
+use crate::py_transaction::{get_py_tx_version, PyDataAvailabilityMode, PyResourceBoundsMapping};
 use crate::py_utils::{from_py_felts, py_attr, PyFelt};
 
 #[derive(FromPyObject)]
@@ -72,7 +72,7 @@ impl TryFrom<PyDeployAccountTransactionV3> for DeployAccountTransactionV3 {
 }
 
 pub fn py_deploy_account(py_tx: &PyAny) -> NativeBlockifierResult<DeployAccountTransaction> {
-    let version = usize::try_from(py_attr::<PyFelt>(py_tx, "version")?.0)?;
+    let (version, only_query) = get_py_tx_version(py_tx)?;
     let tx = match version {
         1 => {
             let py_deploy_account_tx: PyDeployAccountTransactionV1 = py_tx.extract()?;
@@ -93,5 +93,8 @@ pub fn py_deploy_account(py_tx: &PyAny) -> NativeBlockifierResult<DeployAccountT
     let tx_hash = TransactionHash(py_attr::<PyFelt>(py_tx, "hash_value")?.0);
     let contract_address =
         ContractAddress::try_from(py_attr::<PyFelt>(py_tx, "sender_address")?.0)?;
-    Ok(DeployAccountTransaction::new(tx, tx_hash, contract_address))
+    Ok(match only_query {
+        true => DeployAccountTransaction::new_for_query(tx, tx_hash, contract_address),
+        false => DeployAccountTransaction::new(tx, tx_hash, contract_address),
+    })
 }
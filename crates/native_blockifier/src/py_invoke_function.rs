@@ -13,7 +13,7 @@ use starknet_api::transaction::{
 };
 
 use crate::errors::{NativeBlockifierInputError, NativeBlockifierResult};
-use crate::py_transaction::{PyDataAvailabilityMode, PyResourceBoundsMapping};
+use crate::py_transaction::{get_py_tx_version, PyDataAvailabilityMode, PyResourceBoundsMapping};
 use crate::py_utils::{from_py_felts, py_attr, PyFelt};
 
 #[derive(FromPyObject)]
@@ -97,7 +97,7 @@ impl TryFrom<PyInvokeTransactionV3> for InvokeTransactionV3 {
 }
 
 pub fn py_invoke_function(py_tx: &PyAny) -> NativeBlockifierResult<InvokeTransaction> {
-    let version = usize::try_from(py_attr::<PyFelt>(py_tx, "version")?.0)?;
+    let (version, only_query) = get_py_tx_version(py_tx)?;
     let tx = match version {
         0 => {
             let py_invoke_tx: PyInvokeTransactionV0 = py_tx.extract()?;
@@ -121,5 +121,8 @@ pub fn py_invoke_function(py_tx: &PyAny) -> NativeBlockifierResult<InvokeTransac
     }?;
 
     let tx_hash = TransactionHash(py_attr::<PyFelt>(py_tx, "hash_value")?.0);
-    Ok(InvokeTransaction::new(tx, tx_hash))
+    Ok(match only_query {
+        true => InvokeTransaction::new_for_query(tx, tx_hash),
+        false => InvokeTransaction::new(tx, tx_hash),
+    })
 }
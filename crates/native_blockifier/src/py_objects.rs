@@ -46,6 +46,7 @@ impl From<PyBouncerConfig> for BouncerConfig {
             block_max_capacity_with_keccak: hash_map_into_bouncer_weights(
                 py_bouncer_config.full_total_weights_with_keccak.clone(),
             ),
+            min_tx_weights: BouncerWeights::default(),
         }
     }
 }
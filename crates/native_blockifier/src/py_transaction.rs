@@ -6,9 +6,10 @@ use blockifier::execution::contract_class::{
 use blockifier::transaction::account_transaction::AccountTransaction;
 use blockifier::transaction::transaction_execution::Transaction;
 use blockifier::transaction::transaction_types::TransactionType;
+use blockifier::transaction::transaction_utils::split_query_version;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use starknet_api::transaction::{Resource, ResourceBounds};
+use starknet_api::transaction::{Resource, ResourceBounds, TransactionVersion};
 use starknet_api::StarknetApiError;
 
 use crate::errors::{NativeBlockifierInputError, NativeBlockifierResult};
@@ -16,6 +17,7 @@ use crate::py_declare::py_declare;
 use crate::py_deploy_account::py_deploy_account;
 use crate::py_invoke_function::py_invoke_function;
 use crate::py_l1_handler::py_l1_handler;
+use crate::py_utils::{py_attr, PyFelt};
 
 pub(crate) const PY_TX_PARSING_ERR: &str = "Failed parsing Py transaction.";
 
@@ -144,6 +146,14 @@ pub fn py_tx(
     })
 }
 
+/// Returns the version of the given transaction, and whether it is a query-only transaction, i.e.,
+/// whether its version has the query bit set.
+pub fn get_py_tx_version(tx: &PyAny) -> NativeBlockifierResult<(usize, bool)> {
+    let (version, only_query) =
+        split_query_version(TransactionVersion(py_attr::<PyFelt>(tx, "version")?.0));
+    Ok((usize::try_from(version.0)?, only_query))
+}
+
 pub fn get_py_tx_type(tx: &PyAny) -> NativeBlockifierResult<&str> {
     Ok(tx.getattr("tx_type")?.getattr("name")?.extract()?)
 }
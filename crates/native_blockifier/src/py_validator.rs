@@ -22,7 +22,7 @@ pub struct PyValidator {
 #[pymethods]
 impl PyValidator {
     #[new]
-    #[pyo3(signature = (general_config, state_reader_proxy, next_block_info, validate_max_n_steps, max_recursion_depth, max_nonce_for_validation_skip))]
+    #[pyo3(signature = (general_config, state_reader_proxy, next_block_info, validate_max_n_steps, max_recursion_depth, max_nonce_for_validation_skip, max_future_nonce_gap=None))]
     pub fn create(
         general_config: PyGeneralConfig,
         state_reader_proxy: &PyAny,
@@ -30,6 +30,7 @@ impl PyValidator {
         validate_max_n_steps: u32,
         max_recursion_depth: usize,
         max_nonce_for_validation_skip: PyFelt,
+        max_future_nonce_gap: Option<u64>,
     ) -> NativeBlockifierResult<Self> {
         // Create the state.
         let state_reader = PyStateReader::new(state_reader_proxy);
@@ -50,6 +51,7 @@ impl PyValidator {
             state,
             block_context,
             max_nonce_for_validation_skip,
+            max_future_nonce_gap,
             BouncerConfig::max(),
         );
 